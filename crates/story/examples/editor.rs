@@ -13,13 +13,14 @@ use gpui_component::{
     ActiveTheme, IconName, Sizable, WindowExt,
     button::{Button, ButtonVariants as _},
     h_flex,
-    highlighter::{Diagnostic, DiagnosticSeverity, Language, LanguageConfig, LanguageRegistry},
+    highlighter::{Diagnostic, DiagnosticSeverity, Language, LanguageConfig, register_language},
     input::{
         self, CodeActionProvider, CompletionProvider, DefinitionProvider, DocumentColorProvider,
         HoverProvider, Input, InputEvent, InputState, Position, Rope, RopeExt, TabSize,
     },
     list::ListItem,
     resizable::{h_resizable, resizable_panel},
+    status_bar::{InputStatus, StatusBar},
     tree::{TreeItem, TreeState, tree},
     v_flex,
 };
@@ -54,16 +55,16 @@ impl Lang {
 }
 
 fn init() {
-    LanguageRegistry::singleton().register(
-        "navi",
-        &LanguageConfig::new(
+    register_language(
+        LanguageConfig::new(
             "navi",
             tree_sitter_navi::LANGUAGE.into(),
             vec![],
             tree_sitter_navi::HIGHLIGHTS_QUERY,
             "",
             "",
-        ),
+        )
+        .extensions(["nv"]),
     );
 }
 
@@ -75,6 +76,8 @@ pub struct Example {
     line_number: bool,
     indent_guides: bool,
     soft_wrap: bool,
+    minimap: bool,
+    vim_mode: bool,
     lsp_store: ExampleLspStore,
     _subscriptions: Vec<Subscription>,
     _lint_task: Task<()>,
@@ -700,11 +703,14 @@ impl Example {
                 .code_editor(default_language.name().to_string())
                 .line_number(true)
                 .indent_guides(true)
+                .auto_close_brackets(true)
+                .auto_indent(true)
                 .tab_size(TabSize {
                     tab_size: 4,
                     hard_tabs: false,
                 })
                 .soft_wrap(false)
+                .minimap(true)
                 .default_value(include_str!("./fixtures/test.rs"))
                 .placeholder("Enter your code here...");
 
@@ -734,6 +740,8 @@ impl Example {
             line_number: true,
             indent_guides: true,
             soft_wrap: false,
+            minimap: true,
+            vim_mode: false,
             lsp_store,
             _subscriptions,
             _lint_task: Task::ready(()),
@@ -994,6 +1002,36 @@ impl Example {
             }))
     }
 
+    fn render_minimap_button(&self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        Button::new("minimap")
+            .ghost()
+            .xsmall()
+            .when(self.minimap, |this| this.icon(IconName::Check))
+            .label("Minimap")
+            .on_click(cx.listener(|this, _, window, cx| {
+                this.minimap = !this.minimap;
+                this.editor.update(cx, |state, cx| {
+                    state.set_minimap(this.minimap, window, cx);
+                });
+                cx.notify();
+            }))
+    }
+
+    fn render_vim_mode_button(&self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        Button::new("vim-mode")
+            .ghost()
+            .xsmall()
+            .when(self.vim_mode, |this| this.icon(IconName::Check))
+            .label("Vim")
+            .on_click(cx.listener(|this, _, window, cx| {
+                this.vim_mode = !this.vim_mode;
+                this.editor.update(cx, |state, cx| {
+                    state.set_vim_mode(this.vim_mode, window, cx);
+                });
+                cx.notify();
+            }))
+    }
+
     fn render_indent_guides_button(
         &self,
         _: &mut Window,
@@ -1014,18 +1052,10 @@ impl Example {
     }
 
     fn render_go_to_line_button(&self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let position = self.editor.read(cx).cursor_position();
-        let cursor = self.editor.read(cx).cursor();
-
         Button::new("line-column")
             .ghost()
             .xsmall()
-            .label(format!(
-                "{}:{} ({} byte)",
-                position.line + 1,
-                position.character + 1,
-                cursor
-            ))
+            .label("Go to Line")
             .on_click(cx.listener(Self::go_to_line))
     }
 }
@@ -1036,11 +1066,7 @@ impl Render for Example {
         if self.lsp_store.is_dirty() {
             let diagnostics = self.lsp_store.diagnostics();
             self.editor.update(cx, |state, cx| {
-                state.diagnostics_mut().map(|set| {
-                    set.clear();
-                    set.extend(diagnostics);
-                });
-                cx.notify();
+                state.set_diagnostics(diagnostics, cx);
             });
         }
 
@@ -1066,29 +1092,22 @@ impl Render for Example {
                                     .p_0()
                                     .h_full()
                                     .font_family(cx.theme().mono_font_family.clone())
-                                    .text_size(cx.theme().mono_font_size)
+                                    .text_size(cx.theme().scaled_mono_font_size())
                                     .focus_bordered(false)
                                     .into_any_element(),
                             ),
                     )
                     .child(
-                        h_flex()
-                            .justify_between()
-                            .text_sm()
-                            .bg(cx.theme().background)
-                            .py_1p5()
+                        StatusBar::new()
+                            .h(px(32.))
                             .px_4()
-                            .border_t_1()
-                            .border_color(cx.theme().border)
-                            .text_color(cx.theme().muted_foreground)
-                            .child(
-                                h_flex()
-                                    .gap_3()
-                                    .child(self.render_line_number_button(window, cx))
-                                    .child(self.render_soft_wrap_button(window, cx))
-                                    .child(self.render_indent_guides_button(window, cx)),
-                            )
-                            .child(self.render_go_to_line_button(window, cx)),
+                            .left(self.render_line_number_button(window, cx))
+                            .left(self.render_soft_wrap_button(window, cx))
+                            .left(self.render_indent_guides_button(window, cx))
+                            .left(self.render_minimap_button(window, cx))
+                            .left(self.render_vim_mode_button(window, cx))
+                            .right(InputStatus::new(self.editor.read(cx)))
+                            .right(self.render_go_to_line_button(window, cx)),
                     ),
             )
     }
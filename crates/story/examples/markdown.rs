@@ -88,7 +88,7 @@ impl Render for Example {
                                 .id("source")
                                 .size_full()
                                 .font_family(cx.theme().mono_font_family.clone())
-                                .text_size(cx.theme().mono_font_size)
+                                .text_size(cx.theme().scaled_mono_font_size())
                                 .child(
                                     Input::new(&self.input_state)
                                         .h_full()
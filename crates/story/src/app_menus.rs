@@ -1,5 +1,7 @@
 use gpui::{App, Entity, Menu, MenuItem, SharedString};
-use gpui_component::{ActiveTheme as _, Theme, ThemeMode, ThemeRegistry, menu::AppMenuBar};
+use gpui_component::{
+    ActiveTheme as _, Theme, ThemeModePreference, ThemeRegistry, menu::AppMenuBar,
+};
 
 use crate::{
     About, Open, Quit, SelectLocale, ToggleSearch,
@@ -34,7 +36,7 @@ pub fn init(title: impl Into<SharedString>, cx: &mut App) -> Entity<AppMenuBar>
 }
 
 fn update_app_menu(title: impl Into<SharedString>, app_menu_bar: Entity<AppMenuBar>, cx: &mut App) {
-    let mode = cx.theme().mode;
+    let mode = cx.theme().preferred_mode();
     cx.set_menus(vec![
         Menu {
             name: title.into(),
@@ -47,10 +49,12 @@ fn update_app_menu(title: impl Into<SharedString>, app_menu_bar: Entity<AppMenuB
                 MenuItem::Submenu(Menu {
                     name: "Appearance".into(),
                     items: vec![
-                        MenuItem::action("Light", SwitchThemeMode(ThemeMode::Light))
-                            .checked(!mode.is_dark()),
-                        MenuItem::action("Dark", SwitchThemeMode(ThemeMode::Dark))
-                            .checked(mode.is_dark()),
+                        MenuItem::action("Light", SwitchThemeMode(ThemeModePreference::Light))
+                            .checked(mode == ThemeModePreference::Light),
+                        MenuItem::action("Dark", SwitchThemeMode(ThemeModePreference::Dark))
+                            .checked(mode == ThemeModePreference::Dark),
+                        MenuItem::action("System", SwitchThemeMode(ThemeModePreference::System))
+                            .checked(mode == ThemeModePreference::System),
                     ],
                 }),
                 language_menu(cx),
@@ -1,12 +1,13 @@
 use gpui::{
     App, AppContext, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, Render,
-    Styled, Window,
+    Styled, Window, prelude::FluentBuilder as _, px,
 };
 use gpui_component::{
     IconName, Selectable as _, Sizable as _, Size,
-    alert::Alert,
+    alert::{Alert, ErrorBanner},
     button::{Button, ButtonGroup},
     dock::PanelControl,
+    input::{Input, InputEvent, InputState},
     text::markdown,
     v_flex,
 };
@@ -16,14 +17,32 @@ use crate::section;
 pub struct AlertStory {
     size: Size,
     banner_visible: bool,
+    json_input: Entity<InputState>,
+    error_visible: bool,
     focus_handle: gpui::FocusHandle,
 }
 
 impl AlertStory {
-    fn new(_: &mut Window, cx: &mut Context<Self>) -> Self {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let json_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .code_editor("json")
+                .line_number(true)
+                .default_value("{\n  \"name\": \"GPUI\",\n  \"version\": 1\n  \"tags\": []\n}")
+        });
+        cx.subscribe(&json_input, |this: &mut Self, _, ev: &InputEvent, cx| {
+            if let InputEvent::Change = ev {
+                this.error_visible = true;
+                cx.notify();
+            }
+        })
+        .detach();
+
         Self {
             size: Size::default(),
             banner_visible: true,
+            json_input,
+            error_visible: true,
             focus_handle: cx.focus_handle(),
         }
     }
@@ -217,6 +236,33 @@ impl Render for AlertStory {
                         ),
                 ),
             )
+            .child({
+                let json_input = self.json_input.clone();
+                let error = serde_json::from_str::<serde_json::Value>(&json_input.read(cx).value())
+                    .err()
+                    .filter(|_| self.error_visible);
+
+                section("Error Banner").w_2_3().child(
+                    v_flex()
+                        .w_full()
+                        .child(Input::new(&self.json_input).h(px(150.)))
+                        .when_some(error, |this, err| {
+                            this.child(
+                                ErrorBanner::new("json-error", err.to_string())
+                                    .location(err.line(), err.column())
+                                    .on_jump(move |position, window, cx| {
+                                        json_input.update(cx, |input, cx| {
+                                            input.set_cursor_position(position, window, cx);
+                                        });
+                                    })
+                                    .on_close(cx.listener(|this, _, _, cx| {
+                                        this.error_visible = false;
+                                        cx.notify();
+                                    })),
+                            )
+                        }),
+                )
+            })
             .child(
                 section("Custom Icon").w_2_3().child(
                     Alert::new(
@@ -1,11 +1,12 @@
 use gpui::{
-    Action, App, AppContext as _, Axis, ClickEvent, Context, Entity, Focusable, InteractiveElement,
-    IntoElement, ParentElement as _, Render, Styled as _, Window, prelude::FluentBuilder, px,
+    Action, App, AppContext as _, Axis, ClickEvent, Context, ElementId, Entity, Focusable,
+    InteractiveElement, IntoElement, Keystroke, ParentElement as _, Render, Styled as _, Window,
+    prelude::FluentBuilder, px,
 };
 
 use gpui_component::{
     ActiveTheme, Disableable as _, Icon, IconName, Selectable as _, Sizable as _, Theme,
-    button::{Button, ButtonCustomVariant, ButtonGroup, ButtonVariants as _},
+    button::{Button, ButtonCustomVariant, ButtonGroup, ButtonGroupEvent, ButtonVariants as _},
     checkbox::Checkbox,
     h_flex, v_flex,
 };
@@ -29,6 +30,7 @@ pub struct ButtonStory {
     selected: bool,
     compact: bool,
     toggle_multiple: bool,
+    format: ElementId,
 }
 
 impl ButtonStory {
@@ -40,6 +42,7 @@ impl ButtonStory {
             selected: false,
             compact: false,
             toggle_multiple: false,
+            format: "pretty".into(),
         })
     }
 
@@ -172,6 +175,7 @@ impl Render for ButtonStory {
                             .disabled(disabled)
                             .selected(selected)
                             .loading(loading)
+                            .loading_label("Loading…")
                             .when(compact, |this| this.compact())
                             .on_click(Self::on_click)
                             .on_hover(Self::on_hover),
@@ -671,6 +675,21 @@ impl Render for ButtonStory {
                         ),
                 ),
             )
+            .child(
+                section("Segmented Button Group").child(
+                    ButtonGroup::new("format-group")
+                        .outline()
+                        .child(Button::new("pretty").label("Pretty"))
+                        .child(Button::new("minified").label("Minified"))
+                        .child(Button::new("compact").label("Compact"))
+                        .selected(self.format.clone())
+                        .on_change(cx.listener(|view, event: &ButtonGroupEvent, _, cx| {
+                            let ButtonGroupEvent::SelectionChanged(id) = event;
+                            view.format = id.clone();
+                            cx.notify();
+                        })),
+                ),
+            )
             .child(
                 section("Button Group (Vertical)").child(
                     ButtonGroup::new("button-group-vertical")
@@ -749,6 +768,38 @@ impl Render for ButtonStory {
                             })),
                     ),
             )
+            .child(
+                section("Key Binding")
+                    .child(
+                        Button::new("open-file")
+                            .outline()
+                            .label("Open File")
+                            .key_binding(Keystroke::parse("secondary-o").unwrap())
+                            .on_click(Self::on_click),
+                    )
+                    .child(
+                        Button::new("expand")
+                            .outline()
+                            .label("Expand")
+                            .key_binding(Keystroke::parse("secondary-e").unwrap())
+                            .on_click(Self::on_click),
+                    )
+                    .child(
+                        Button::new("open-file-icon")
+                            .ghost()
+                            .icon(IconName::File)
+                            .tooltip("Open File")
+                            .key_binding(Keystroke::parse("secondary-o").unwrap())
+                            .on_click(Self::on_click),
+                    )
+                    .child(
+                        Button::new("save-file-icon")
+                            .ghost()
+                            .icon(IconName::Inbox)
+                            .key_binding(Keystroke::parse("secondary-s").unwrap())
+                            .on_click(Self::on_click),
+                    ),
+            )
             .child(
                 section("Icon Button")
                     .child(
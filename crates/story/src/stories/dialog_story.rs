@@ -7,6 +7,7 @@ use gpui_component::{
     ActiveTheme, Icon, IconName, WindowExt as _,
     button::{Button, ButtonVariant, ButtonVariants as _},
     checkbox::Checkbox,
+    command_palette::{Command, CommandPalette},
     date_picker::{DatePicker, DatePickerState},
     dialog::DialogButtonProps,
     h_flex,
@@ -17,7 +18,7 @@ use gpui_component::{
     v_flex,
 };
 
-use crate::{TestAction, section};
+use crate::{About, TestAction, ToggleSearch, section};
 
 pub struct DialogStory {
     focus_handle: FocusHandle,
@@ -213,6 +214,20 @@ impl DialogStory {
         self.input1.focus_handle(cx).focus(window);
     }
 
+    fn show_command_palette(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        // Focus the story to receive the actions from the palette.
+        self.focus_handle.focus(window);
+        CommandPalette::open(
+            vec![
+                Command::from_action(TestAction).icon(IconName::Bell),
+                Command::new("toggle-search", "Toggle Search", ToggleSearch).icon(IconName::Search),
+                Command::new("about", "About", About).icon(IconName::Info),
+            ],
+            window,
+            cx,
+        );
+    }
+
     fn on_action_test_action(
         &mut self,
         _: &TestAction,
@@ -453,6 +468,16 @@ impl Render for DialogStory {
                                     })),
                             )
                     )
+                    .child(
+                        section("Command Palette").child(
+                            Button::new("show-command-palette")
+                                .outline()
+                                .label("Command Palette")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.show_command_palette(window, cx)
+                                })),
+                        ),
+                    )
                     .child(
                         section("Custom Paddings").child(
                             Button::new("custom-dialog-paddings")
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use gpui::{
     App, AppContext as _, ClickEvent, Context, Entity, InteractiveElement, IntoElement,
     ParentElement as _, Render, Styled, Subscription, Window, div,
@@ -53,6 +55,7 @@ impl InputStory {
         let input1 = cx.new(|cx| {
             InputState::new(window, cx)
                 .default_value("Hello 世界，this is GPUI component, this is a long text.")
+                .change_debounce(Duration::from_millis(500))
         });
 
         let input2 = cx.new(|cx| InputState::new(window, cx).placeholder("Enter text here..."));
@@ -149,9 +152,23 @@ impl InputStory {
                     println!("Change: {}", text)
                 }
             }
+            InputEvent::ChangeDebounced => println!("ChangeDebounced"),
             InputEvent::PressEnter { secondary } => println!("PressEnter secondary: {}", secondary),
             InputEvent::Focus => println!("Focus"),
             InputEvent::Blur => println!("Blur"),
+            InputEvent::Undo => println!("Undo"),
+            InputEvent::Redo => println!("Redo"),
+            InputEvent::SearchMatchesChanged { count, active } => {
+                println!("SearchMatchesChanged: {:?} of {}", active, count)
+            }
+            InputEvent::SelectionChanged { .. } => {}
+            InputEvent::FilesDropped { paths } => println!("FilesDropped: {:?}", paths),
+            InputEvent::Copy { text } => println!("Copy: {}", text),
+            InputEvent::Cut { text } => println!("Cut: {}", text),
+            InputEvent::Paste { text } => println!("Paste: {}", text),
+            InputEvent::Rejected { text } => println!("Rejected: {}", text),
+            InputEvent::VimModeChanged { mode } => println!("VimModeChanged: {:?}", mode),
+            InputEvent::LineAnnotationClick { line } => println!("LineAnnotationClick: {}", line),
         };
     }
 
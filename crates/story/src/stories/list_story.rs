@@ -8,12 +8,12 @@ use gpui::{
 };
 
 use gpui_component::{
-    ActiveTheme, Icon, IconName, IndexPath, Selectable, Sizable,
+    ActiveTheme, IndexPath, Selectable, Sizable,
     button::Button,
     checkbox::Checkbox,
     h_flex,
     label::Label,
-    list::{List, ListDelegate, ListEvent, ListItem, ListState},
+    list::{List, ListDelegate, ListEvent, ListItem, ListSectionHeader, ListState},
     v_flex,
 };
 
@@ -228,22 +228,13 @@ impl ListDelegate for CompanyListDelegate {
         &mut self,
         section: usize,
         _: &mut Window,
-        cx: &mut Context<ListState<Self>>,
+        _: &mut Context<ListState<Self>>,
     ) -> Option<impl IntoElement> {
         let Some(industry) = self.industries.get(section) else {
             return None;
         };
 
-        Some(
-            h_flex()
-                .pb_1()
-                .px_2()
-                .gap_2()
-                .text_sm()
-                .text_color(cx.theme().muted_foreground)
-                .child(Icon::new(IconName::Folder))
-                .child(industry.clone()),
-        )
+        Some(ListSectionHeader::new(industry.clone()))
     }
 
     fn render_section_footer(
@@ -575,6 +566,7 @@ impl Render for ListStory {
             )
             .child(
                 List::new(&self.company_list)
+                    .sticky_section_headers(true)
                     .p(px(8.))
                     .flex_1()
                     .w_full()
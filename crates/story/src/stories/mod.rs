@@ -47,6 +47,7 @@ mod tabs_story;
 mod tag_story;
 mod textarea_story;
 mod toggle_story;
+mod toolbar_story;
 mod tooltip_story;
 mod tree_story;
 mod virtual_list_story;
@@ -98,6 +99,7 @@ pub use tabs_story::TabsStory;
 pub use tag_story::TagStory;
 pub use textarea_story::TextareaStory;
 pub use toggle_story::ToggleStory;
+pub use toolbar_story::ToolbarStory;
 pub use tooltip_story::TooltipStory;
 pub use tree_story::TreeStory;
 pub use virtual_list_story::VirtualListStory;
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use gpui::{
     App, AppContext, Context, Entity, FocusHandle, Focusable, InteractiveElement as _, IntoElement,
     ParentElement, Render, Styled, Window,
@@ -131,6 +133,18 @@ impl Render for NotificationStory {
                                     cx,
                                 )
                             })),
+                    )
+                    .child(
+                        Button::new("show-notify-short")
+                            .outline()
+                            .label("Short Timeout")
+                            .on_click(cx.listener(|_, _, window, cx| {
+                                window.push_notification(
+                                    Notification::info("Copied to clipboard.")
+                                        .timeout(Duration::from_secs(2)),
+                                    cx,
+                                )
+                            })),
                     ),
             )
             .child(
@@ -146,6 +146,18 @@ impl NumberInputStory {
             }
             InputEvent::Focus => println!("Focus"),
             InputEvent::Blur => println!("Blur"),
+            InputEvent::ChangeDebounced
+            | InputEvent::Undo
+            | InputEvent::Redo
+            | InputEvent::SearchMatchesChanged { .. }
+            | InputEvent::SelectionChanged { .. }
+            | InputEvent::FilesDropped { .. }
+            | InputEvent::Copy { .. }
+            | InputEvent::Cut { .. }
+            | InputEvent::Paste { .. }
+            | InputEvent::Rejected { .. }
+            | InputEvent::VimModeChanged { .. }
+            | InputEvent::LineAnnotationClick { .. } => {}
         }
     }
 
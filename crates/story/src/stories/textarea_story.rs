@@ -1,6 +1,6 @@
 use gpui::{
     App, AppContext as _, ClickEvent, Context, Entity, Focusable, InteractiveElement, IntoElement,
-    ParentElement as _, Render, Styled, Window, px,
+    ParentElement as _, Render, Styled, Subscription, Window, px,
 };
 
 use crate::section;
@@ -8,7 +8,7 @@ use gpui_component::{
     Sizable,
     button::Button,
     h_flex,
-    input::{Input, InputState},
+    input::{Input, InputEvent, InputState},
     v_flex,
 };
 
@@ -19,6 +19,7 @@ pub struct TextareaStory {
     textarea_auto_grow: Entity<InputState>,
     textarea_no_wrap: Entity<InputState>,
     textarea_auto_grow_no_wrap: Entity<InputState>,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl super::Story for TextareaStory {
@@ -51,6 +52,7 @@ impl TextareaStory {
                 .rows(10)
                 .placeholder("Enter text here...")
                 .searchable(true)
+                .accept_file_drop(true)
                 .default_value(
                     unindent::unindent(
                         r#"Hello 世界，this is GPUI component.
@@ -108,11 +110,28 @@ impl TextareaStory {
                 .default_value("Hello 世界，this is GPUI component.")
         });
 
+        let _subscriptions = vec![cx.subscribe_in(
+            &textarea,
+            window,
+            |_, state, event: &InputEvent, window, cx| {
+                if let InputEvent::FilesDropped { paths } = event {
+                    let Some(text) = paths
+                        .first()
+                        .and_then(|path| std::fs::read_to_string(path).ok())
+                    else {
+                        return;
+                    };
+                    state.update(cx, |state, cx| state.set_value(text, window, cx));
+                }
+            },
+        )];
+
         Self {
             textarea,
             textarea_auto_grow,
             textarea_no_wrap,
             textarea_auto_grow_no_wrap,
+            _subscriptions,
         }
     }
 
@@ -153,39 +172,41 @@ impl Render for TextareaStory {
             .id("textarea-story")
             .gap_3()
             .child(
-                section("Textarea").child(
-                    v_flex()
-                        .gap_2()
-                        .w_full()
-                        .child(Input::new(&self.textarea).h(px(320.)))
-                        .child(
-                            h_flex()
-                                .justify_between()
-                                .child(
-                                    h_flex()
-                                        .gap_2()
-                                        .child(
-                                            Button::new("btn-insert-text")
-                                                .outline()
-                                                .xsmall()
-                                                .label("Insert Text")
-                                                .on_click(
-                                                    cx.listener(Self::on_insert_text_to_textarea),
-                                                ),
-                                        )
-                                        .child(
-                                            Button::new("btn-replace-text")
-                                                .outline()
-                                                .xsmall()
-                                                .label("Replace Text")
-                                                .on_click(
-                                                    cx.listener(Self::on_replace_text_to_textarea),
-                                                ),
-                                        ),
-                                )
-                                .child(format!("{}:{}", loc.line, loc.character)),
-                        ),
-                ),
+                section("Textarea")
+                    .sub_title("Drop a text file to load it.")
+                    .child(
+                        v_flex()
+                            .gap_2()
+                            .w_full()
+                            .child(Input::new(&self.textarea).h(px(320.)))
+                            .child(
+                                h_flex()
+                                    .justify_between()
+                                    .child(
+                                        h_flex()
+                                            .gap_2()
+                                            .child(
+                                                Button::new("btn-insert-text")
+                                                    .outline()
+                                                    .xsmall()
+                                                    .label("Insert Text")
+                                                    .on_click(cx.listener(
+                                                        Self::on_insert_text_to_textarea,
+                                                    )),
+                                            )
+                                            .child(
+                                                Button::new("btn-replace-text")
+                                                    .outline()
+                                                    .xsmall()
+                                                    .label("Replace Text")
+                                                    .on_click(cx.listener(
+                                                        Self::on_replace_text_to_textarea,
+                                                    )),
+                                            ),
+                                    )
+                                    .child(format!("{}:{}", loc.line, loc.character)),
+                            ),
+                    ),
             )
             .child(
                 section("No Wrap")
@@ -0,0 +1,158 @@
+use gpui::{
+    App, AppContext, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, Render,
+    SharedString, Styled, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme as _, IconName, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    menu::PopupMenuItem,
+    toolbar::{Toolbar, ToolbarGroup},
+    v_flex,
+};
+
+use crate::section;
+
+pub struct ToolbarStory {
+    focus_handle: FocusHandle,
+    last_action: SharedString,
+}
+
+impl super::Story for ToolbarStory {
+    fn title() -> &'static str {
+        "Toolbar"
+    }
+
+    fn description() -> &'static str {
+        "A bar of the actions, the extra items are collapsed into a menu when it's too narrow."
+    }
+
+    fn new_view(window: &mut Window, cx: &mut App) -> Entity<impl Render> {
+        Self::view(window, cx)
+    }
+}
+
+impl ToolbarStory {
+    pub(crate) fn new(_: &mut Window, cx: &mut App) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            last_action: "None".into(),
+        }
+    }
+
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn render_toolbar(&self, id: &'static str, cx: &mut Context<Self>) -> Toolbar {
+        let view = cx.entity();
+        let action_button = |name: &'static str, icon: IconName| {
+            Button::new(name)
+                .ghost()
+                .small()
+                .icon(icon)
+                .label(name)
+                .on_click({
+                    let view = view.clone();
+                    move |_, _, cx| {
+                        view.update(cx, |this, cx| {
+                            this.last_action = name.into();
+                            cx.notify();
+                        })
+                    }
+                })
+        };
+        let menu_item = |name: &'static str, icon: IconName| {
+            let view = view.clone();
+            move |_: &mut Window, _: &mut App| {
+                let view = view.clone();
+                PopupMenuItem::new(name)
+                    .icon(icon.clone())
+                    .on_click(move |_, _, cx| {
+                        view.update(cx, |this, cx| {
+                            this.last_action = name.into();
+                            cx.notify();
+                        })
+                    })
+            }
+        };
+
+        Toolbar::new(id)
+            .item(action_button("Open", IconName::FolderOpen))
+            .separator()
+            .collapsible_item(
+                action_button("Format", IconName::ALargeSmall),
+                menu_item("Format", IconName::ALargeSmall),
+            )
+            .collapsible_item(
+                action_button("Copy", IconName::Copy),
+                menu_item("Copy", IconName::Copy),
+            )
+            .separator()
+            .collapsible_item(
+                action_button("Undo", IconName::Undo),
+                menu_item("Undo", IconName::Undo),
+            )
+            .collapsible_item(
+                action_button("Redo", IconName::Redo),
+                menu_item("Redo", IconName::Redo),
+            )
+            .group(ToolbarGroup::Center)
+            .item(div().text_sm().child("untitled.json"))
+            .group(ToolbarGroup::Right)
+            .item(
+                Button::new("settings")
+                    .ghost()
+                    .small()
+                    .icon(IconName::Settings),
+            )
+    }
+}
+
+impl Focusable for ToolbarStory {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for ToolbarStory {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_6()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(format!("Last action: {}", self.last_action)),
+            )
+            .child(
+                section("Toolbar").child(
+                    v_flex()
+                        .w_full()
+                        .border_1()
+                        .border_color(cx.theme().border)
+                        .child(self.render_toolbar("toolbar", cx))
+                        .child(div().h(px(80.))),
+                ),
+            )
+            .child(
+                section("Collapsed into More menu").child(
+                    v_flex()
+                        .w(px(360.))
+                        .border_1()
+                        .border_color(cx.theme().border)
+                        .child(self.render_toolbar("narrow-toolbar", cx))
+                        .child(div().h(px(80.))),
+                ),
+            )
+            .child(
+                section("Custom height and padding").child(
+                    v_flex()
+                        .w_full()
+                        .border_1()
+                        .border_color(cx.theme().border)
+                        .child(self.render_toolbar("small-toolbar", cx).h_8().px_1())
+                        .child(div().h(px(80.))),
+                ),
+            )
+    }
+}
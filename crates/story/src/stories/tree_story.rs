@@ -2,37 +2,48 @@ use std::path::PathBuf;
 
 use autocorrect::ignorer::Ignorer;
 use gpui::{
-    App, AppContext, Context, Entity, InteractiveElement, KeyBinding, ParentElement, Render,
-    Styled, Window, actions, px,
+    App, AppContext, Context, DismissEvent, Entity, Focusable as _, InteractiveElement, KeyBinding,
+    ParentElement, Pixels, Point, Render, Styled, Subscription, Window, actions, anchored,
+    deferred, prelude::FluentBuilder as _, px,
 };
 
 use gpui_component::{
-    ActiveTheme as _, IconName, StyledExt as _,
+    ActiveTheme as _, IconName, Sizable as _, StyledExt as _, WindowExt as _,
+    breadcrumb::{Breadcrumb, BreadcrumbItem},
+    button::{Button, ButtonVariants as _},
     dock::PanelControl,
     h_flex,
+    input::Input,
     label::Label,
     list::ListItem,
-    tree::{TreeItem, TreeState, tree},
+    menu::PopupMenu,
+    tree::{SortBy, TreeEvent, TreeItem, TreeState, tree},
     v_flex,
 };
 
 use crate::{Story, section};
 
-actions!(story, [Rename, SelectItem]);
+actions!(story, [Rename, SelectItem, DeleteItem, NewFile]);
 
 const CONTEXT: &str = "TreeStory";
 pub(crate) fn init(cx: &mut App) {
     cx.bind_keys([
-        KeyBinding::new("enter", Rename, Some(CONTEXT)),
+        KeyBinding::new("f2", Rename, Some(CONTEXT)),
         KeyBinding::new("space", SelectItem, Some(CONTEXT)),
+        KeyBinding::new("delete", DeleteItem, Some(CONTEXT)),
     ]);
 }
 
 pub struct TreeStory {
     tree_state: Entity<TreeState>,
+    checkable_state: Entity<TreeState>,
+    large_state: Entity<TreeState>,
     selected_item: Option<TreeItem>,
+    context_menu: Option<(Entity<PopupMenu>, Point<Pixels>, Subscription)>,
+    _subscriptions: Vec<Subscription>,
 }
 
+/// Build the items of the directory, the sub directories are lazy loaded when expanded.
 fn build_file_items(ignorer: &Ignorer, root: &PathBuf, path: &PathBuf) -> Vec<TreeItem> {
     let mut items = Vec::new();
     if let Ok(entries) = std::fs::read_dir(path) {
@@ -50,28 +61,48 @@ fn build_file_items(ignorer: &Ignorer, root: &PathBuf, path: &PathBuf) -> Vec<Tr
                 .unwrap_or("Unknown")
                 .to_string();
             let id = path.to_string_lossy().to_string();
-            if path.is_dir() {
-                let children = build_file_items(ignorer, &root, &path);
-                items.push(TreeItem::new(id, file_name).children(children));
-            } else {
-                items.push(TreeItem::new(id, file_name));
-            }
+            items.push(TreeItem::new(id, file_name).lazy(path.is_dir()));
         }
     }
-    items.sort_by(|a, b| {
-        b.is_folder()
-            .cmp(&a.is_folder())
-            .then(a.label.cmp(&b.label))
-    });
     items
 }
 
+/// Build 100 folders with 500 files in each, to test the scrolling performance.
+fn build_large_items() -> Vec<TreeItem> {
+    (0..100)
+        .map(|i| {
+            let folder = format!("folder-{}", i);
+            let children = (0..500).map(|j| {
+                TreeItem::new(
+                    format!("{}/file-{}.rs", folder, j),
+                    format!("file-{}.rs", j),
+                )
+            });
+            TreeItem::new(folder.clone(), folder)
+                .expanded(true)
+                .children(children)
+        })
+        .collect()
+}
+
 impl TreeStory {
     pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
         cx.new(|cx| Self::new(window, cx))
     }
 
     fn load_files(state: Entity<TreeState>, path: PathBuf, cx: &mut App) {
+        state.update(cx, |state, _| {
+            let root = path.clone();
+            state.set_children_loader(move |item, cx| {
+                let root = root.clone();
+                let path = PathBuf::from(item.id.as_ref());
+                cx.spawn(async move |_| {
+                    let ignorer = Ignorer::new(&root.to_string_lossy());
+                    Ok(build_file_items(&ignorer, &root, &path))
+                })
+            });
+        });
+
         cx.spawn(async move |cx| {
             let ignorer = Ignorer::new(&path.to_string_lossy());
             let items = build_file_items(&ignorer, &path, &path);
@@ -83,13 +114,71 @@ impl TreeStory {
     }
 
     fn new(_: &mut Window, cx: &mut Context<Self>) -> Self {
-        let tree_state = cx.new(|cx| TreeState::new(cx));
+        let tree_state = cx.new(|cx| {
+            let mut state = TreeState::new(cx)
+                .preserve_scroll_on_update(true)
+                .animations(true);
+            state.set_draggable(true, cx);
+            state.set_sort_by(SortBy::FoldersFirst, cx);
+            state
+        });
 
         Self::load_files(tree_state.clone(), PathBuf::from("./"), cx);
 
+        let checkable_state = cx.new(|cx| {
+            TreeState::new(cx).items(vec![
+                TreeItem::new("src", "src")
+                    .expanded(true)
+                    .child(TreeItem::new("src/main.rs", "main.rs"))
+                    .child(
+                        TreeItem::new("src/ui", "ui")
+                            .child(TreeItem::new("src/ui/button.rs", "button.rs"))
+                            .child(TreeItem::new("src/ui/icon.rs", "icon.rs")),
+                    ),
+                TreeItem::new("Cargo.toml", "Cargo.toml"),
+                TreeItem::new("README.md", "README.md"),
+            ])
+        });
+
+        let large_state = cx.new(|cx| TreeState::new(cx).items(build_large_items()));
+
+        let mut _subscriptions = vec![cx.subscribe(&tree_state, |_, _, event: &TreeEvent, _| {
+            if let TreeEvent::Activated { id } = event {
+                println!("Activated item: {}", id);
+            }
+            if let TreeEvent::Renamed {
+                id,
+                old_label,
+                new_label,
+            } = event
+            {
+                println!("Renamed item: {} -> {} ({})", old_label, new_label, id);
+            }
+            if let TreeEvent::Moved {
+                dragged_id,
+                target_id,
+                position,
+            } = event
+            {
+                println!("Moved item: {} {:?} {}", dragged_id, position, target_id);
+            }
+        })];
+        _subscriptions.push(
+            cx.subscribe(&checkable_state, |_, _, event: &TreeEvent, cx| {
+                if let TreeEvent::CheckChanged { id, checked } = event {
+                    println!("Checked item: {} {}", id, checked);
+                    cx.notify();
+                }
+            }),
+        );
+
         Self {
             tree_state,
+            checkable_state,
+            large_state,
             selected_item: None,
+            context_menu: None,
+            _subscriptions,
         }
     }
 
@@ -105,13 +194,88 @@ impl TreeStory {
         }
     }
 
-    fn on_action_rename(&mut self, _: &Rename, _: &mut Window, cx: &mut gpui::Context<Self>) {
+    fn on_action_rename(&mut self, _: &Rename, window: &mut Window, cx: &mut gpui::Context<Self>) {
+        let Some(id) = self
+            .tree_state
+            .read(cx)
+            .selected_entry()
+            .map(|entry| entry.item().id.clone())
+        else {
+            return;
+        };
+
+        self.tree_state.update(cx, |state, cx| {
+            state.begin_rename(&id, window, cx);
+        });
+    }
+
+    fn on_action_delete_item(
+        &mut self,
+        _: &DeleteItem,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ids: Vec<_> = self
+            .tree_state
+            .read(cx)
+            .selected_entries()
+            .iter()
+            .map(|entry| entry.item().id.clone())
+            .collect();
+        if ids.is_empty() {
+            return;
+        }
+
+        let message = format!("Are you sure to delete {} item(s)?", ids.len());
+        let confirmed = window.open_confirm_dialog(cx, move |dialog, _, _| {
+            dialog.confirm().title("Delete").child(message.clone())
+        });
+
+        let tree_state = self.tree_state.clone();
+        cx.spawn(async move |_, cx| {
+            if !confirmed.await {
+                return;
+            }
+
+            _ = tree_state.update(cx, |state, cx| {
+                for id in ids {
+                    println!("Delete item: {}", id);
+                    state.remove_item(&id, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn on_action_new_file(&mut self, _: &NewFile, _: &mut Window, cx: &mut Context<Self>) {
         if let Some(entry) = self.tree_state.read(cx).selected_entry() {
-            let item = entry.item();
-            println!("Renaming item: {} ({})", item.label, item.id);
-            // Here you could implement actual renaming logic
+            println!("New file in: {}", entry.item().id);
         }
     }
+
+    fn show_context_menu(
+        &mut self,
+        position: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let action_context = self.tree_state.focus_handle(cx);
+        let menu = PopupMenu::build(window, cx, |menu, _, _| {
+            menu.action_context(action_context)
+                .menu("Rename", Box::new(Rename))
+                .menu("New File", Box::new(NewFile))
+                .separator()
+                .menu("Delete", Box::new(DeleteItem))
+        });
+        menu.focus_handle(cx).focus(window);
+
+        let subscription = cx.subscribe(&menu, |this, _, _: &DismissEvent, cx| {
+            this.context_menu = None;
+            cx.notify();
+        });
+        self.context_menu = Some((menu, position, subscription));
+        cx.notify();
+    }
 }
 
 impl Story for TreeStory {
@@ -128,6 +292,38 @@ impl Story for TreeStory {
     }
 }
 
+impl TreeStory {
+    /// The path of the selected item, click on a segment to reveal it in the tree.
+    fn render_breadcrumb(&self, cx: &mut Context<Self>) -> Breadcrumb {
+        let tree_state = self.tree_state.clone();
+        let path = self
+            .tree_state
+            .read(cx)
+            .selected_entry()
+            .map(|entry| self.tree_state.read(cx).item_path(&entry.item().id))
+            .unwrap_or_default();
+        let items = path.iter().map(|item| {
+            let icon = if item.is_folder {
+                IconName::Folder
+            } else {
+                IconName::File
+            };
+            BreadcrumbItem::from(item).icon(icon)
+        });
+
+        Breadcrumb::new()
+            .id("tree-breadcrumb")
+            .h_6()
+            .max_items(4)
+            .children(items)
+            .on_click(move |id, window, cx| {
+                tree_state.update(cx, |state, cx| {
+                    state.reveal(id, window, cx);
+                })
+            })
+    }
+}
+
 impl Render for TreeStory {
     fn render(
         &mut self,
@@ -135,18 +331,26 @@ impl Render for TreeStory {
         cx: &mut gpui::Context<Self>,
     ) -> impl gpui::IntoElement {
         let view = cx.entity();
+        let menu_view = cx.entity();
         v_flex()
             .id("tree-story")
             .key_context(CONTEXT)
             .on_action(cx.listener(Self::on_action_rename))
             .on_action(cx.listener(Self::on_action_select_item))
+            .on_action(cx.listener(Self::on_action_delete_item))
+            .on_action(cx.listener(Self::on_action_new_file))
             .gap_5()
             .size_full()
             .child(
                 section("File tree")
-                    .sub_title("Press `space` to select, `enter` to rename.")
+                    .sub_title(
+                        "Press `space` to select, `enter` or double-click to activate, `f2` to rename, \
+                        Ctrl/Cmd-click or Shift-click to select multiple items, drag to move items, \
+                        right-click to open the context menu.",
+                    )
                     .v_flex()
                     .max_w_md()
+                    .child(self.render_breadcrumb(cx))
                     .child(
                         tree(
                             &self.tree_state,
@@ -166,9 +370,17 @@ impl Render for TreeStory {
                                         .rounded(cx.theme().radius)
                                         .px_3()
                                         .pl(px(16.) * entry.depth() + px(12.))
-                                        .child(
-                                            h_flex().gap_2().child(icon).child(item.label.clone()),
-                                        )
+                                        .child(h_flex().w_full().gap_2().child(icon).map(|this| {
+                                            match entry.rename_input() {
+                                                Some(input) => {
+                                                    this.child(Input::new(input).xsmall())
+                                                }
+                                                None => this.child(
+                                                    Label::new(item.label.clone())
+                                                        .highlight(entry.match_ranges().to_vec()),
+                                                ),
+                                            }
+                                        }))
                                         .on_click(cx.listener({
                                             let item = item.clone();
                                             move |this, _, _window, cx| {
@@ -179,6 +391,12 @@ impl Render for TreeStory {
                                 })
                             },
                         )
+                        .animated_chevron(true)
+                        .on_context_menu(move |_, _, position, window, cx| {
+                            menu_view.update(cx, |this, cx| {
+                                this.show_context_menu(position, window, cx);
+                            });
+                        })
                         .p_1()
                         .border_1()
                         .border_color(cx.theme().border)
@@ -196,12 +414,152 @@ impl Render for TreeStory {
                                     .selected_index()
                                     .map(|ix| format!("Selected Index: {}", ix)),
                             )
+                            .child(format!(
+                                "Selected Count: {}",
+                                self.tree_state.read(cx).selected_indices().len()
+                            ))
                             .children(
                                 self.selected_item
                                     .as_ref()
-                                    .map(|item| Label::new("Selected:").secondary(item.id.clone())),
+                                    .map(|item| {
+                                        h_flex().min_w_0().gap_1().child("Selected:").child(
+                                            Label::new(item.id.clone())
+                                                .truncate_middle()
+                                                .copyable("selected-item-id"),
+                                        )
+                                    }),
                             ),
                     ),
             )
+            .child(
+                section("Checkable")
+                    .sub_title(
+                        "Check a folder to check all its descendants, \
+                        the folder is indeterminate if only some are checked.",
+                    )
+                    .v_flex()
+                    .max_w_md()
+                    .child(
+                        tree(&self.checkable_state, |ix, entry, _selected, _window, _cx| {
+                            ListItem::new(ix)
+                                .px_3()
+                                .pl(px(16.) * entry.depth() + px(12.))
+                                .child(entry.item().label.clone())
+                        })
+                        .checkable(true)
+                        .chevron_icons(IconName::ChevronRight, IconName::ChevronDown)
+                        .p_1()
+                        .border_1()
+                        .border_color(cx.theme().border)
+                        .rounded(cx.theme().radius)
+                        .h(px(200.)),
+                    )
+                    .child(format!(
+                        "Checked Count: {}",
+                        self.checkable_state.read(cx).checked_ids().len()
+                    )),
+            )
+            .child(
+                section("Large Tree")
+                    .sub_title(
+                        "50,000 files in 100 folders, only the rows in the viewport are rendered.",
+                    )
+                    .v_flex()
+                    .max_w_md()
+                    .child(
+                        tree(&self.large_state, |ix, entry, _selected, _window, _cx| {
+                            let icon = if entry.is_folder() {
+                                IconName::Folder
+                            } else {
+                                IconName::File
+                            };
+
+                            ListItem::new(ix)
+                                .px_3()
+                                .pl(px(16.) * entry.depth() + px(12.))
+                                .child(
+                                    h_flex().gap_2().child(icon).child(
+                                        Label::new(entry.item().label.clone())
+                                            .highlight(entry.match_ranges().to_vec()),
+                                    ),
+                                )
+                        })
+                        .p_1()
+                        .border_1()
+                        .border_color(cx.theme().border)
+                        .rounded(cx.theme().radius)
+                        .h(px(320.)),
+                    )
+                    .child({
+                        let state = self.large_state.read(cx);
+                        let search_status = format!(
+                            "{} of {}",
+                            state.active_search_match().map_or(0, |ix| ix + 1),
+                            state.search_match_count()
+                        );
+
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                Button::new("search-file-42")
+                                    .outline()
+                                    .small()
+                                    .label("Search \"file-42.\"")
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.large_state.update(cx, |state, cx| {
+                                            state.search("file-42.", cx);
+                                        });
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                Button::new("search-prev")
+                                    .outline()
+                                    .small()
+                                    .icon(IconName::ChevronUp)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.large_state.update(cx, |state, cx| {
+                                            state.search_prev(window, cx);
+                                        });
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                Button::new("search-next")
+                                    .outline()
+                                    .small()
+                                    .icon(IconName::ChevronDown)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.large_state.update(cx, |state, cx| {
+                                            state.search_next(window, cx);
+                                        });
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(Label::new(search_status).text_sm())
+                    })
+                    .child(
+                        Button::new("reveal-last-file")
+                            .outline()
+                            .small()
+                            .label("Reveal folder-99/file-499.rs")
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.large_state.update(cx, |state, cx| {
+                                    state.reveal("folder-99/file-499.rs", window, cx);
+                                });
+                            })),
+                    ),
+            )
+            .when_some(self.context_menu.as_ref(), |this, (menu, position, _)| {
+                this.child(
+                    deferred(
+                        anchored()
+                            .position(*position)
+                            .snap_to_window_with_margin(px(8.))
+                            .child(menu.clone()),
+                    )
+                    .with_priority(1),
+                )
+            })
     }
 }
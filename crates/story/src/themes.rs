@@ -1,7 +1,9 @@
 use std::path::PathBuf;
 
 use gpui::{Action, App, SharedString};
-use gpui_component::{ActiveTheme, Theme, ThemeMode, ThemeRegistry, scroll::ScrollbarShow};
+use gpui_component::{
+    ActiveTheme, Theme, ThemeModePreference, ThemeRegistry, scroll::ScrollbarShow,
+};
 use serde::{Deserialize, Serialize};
 
 const STATE_FILE: &str = "target/state.json";
@@ -10,6 +12,7 @@ const STATE_FILE: &str = "target/state.json";
 struct State {
     theme: SharedString,
     scrollbar_show: Option<ScrollbarShow>,
+    ui_scale: Option<f32>,
 }
 
 impl Default for State {
@@ -17,6 +20,7 @@ impl Default for State {
         Self {
             theme: "Default Light".into(),
             scrollbar_show: None,
+            ui_scale: None,
         }
     }
 }
@@ -41,12 +45,16 @@ pub fn init(cx: &mut App) {
     if let Some(scrollbar_show) = state.scrollbar_show {
         Theme::global_mut(cx).scrollbar_show = scrollbar_show;
     }
+    if let Some(ui_scale) = state.ui_scale {
+        gpui_component::set_ui_scale(ui_scale, cx);
+    }
     cx.refresh_windows();
 
     cx.observe_global::<Theme>(|cx| {
         let state = State {
             theme: cx.theme().theme_name().clone(),
             scrollbar_show: Some(cx.theme().scrollbar_show),
+            ui_scale: Some(cx.theme().ui_scale),
         };
 
         if let Ok(json) = serde_json::to_string_pretty(&state) {
@@ -76,4 +84,4 @@ pub(crate) struct SwitchTheme(pub(crate) SharedString);
 
 #[derive(Action, Clone, PartialEq)]
 #[action(namespace = themes, no_json)]
-pub(crate) struct SwitchThemeMode(pub(crate) ThemeMode);
+pub(crate) struct SwitchThemeMode(pub(crate) ThemeModePreference);
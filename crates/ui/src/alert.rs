@@ -7,9 +7,12 @@ use gpui::{
 };
 
 use crate::{
+    clipboard::Clipboard,
     h_flex,
-    text::{Text, TextViewStyle},
-    ActiveTheme as _, Icon, IconName, Sizable, Size, StyledExt,
+    input::Position,
+    link::Link,
+    text::{Text, TextView, TextViewStyle},
+    v_flex, ActiveTheme as _, Icon, IconName, Sizable, Size, StyledExt,
 };
 
 /// The variant of the [`Alert`].
@@ -44,6 +47,15 @@ impl AlertVariant {
         }
     }
 
+    fn icon(&self) -> IconName {
+        match self {
+            AlertVariant::Secondary | AlertVariant::Info => IconName::Info,
+            AlertVariant::Success => IconName::CircleCheck,
+            AlertVariant::Warning => IconName::TriangleAlert,
+            AlertVariant::Error => IconName::CircleX,
+        }
+    }
+
     fn border_color(&self, cx: &App) -> Hsla {
         match self {
             AlertVariant::Secondary => cx.theme().border,
@@ -253,3 +265,198 @@ impl RenderOnce for Alert {
             .into_any_element()
     }
 }
+
+/// A banner to display an error, e.g. the parse error of the text in an editor.
+///
+/// Unlike the [`Alert`], the message is plain text that can be selected and copied,
+/// and the location of the error is rendered as a link to jump to.
+///
+/// ```ignore
+/// if let Err(err) = serde_json::from_str::<serde_json::Value>(&text) {
+///     ErrorBanner::new("json-error", err.to_string())
+///         .location(err.line(), err.column())
+///         .on_jump(move |position, window, cx| {
+///             editor.update(cx, |editor, cx| {
+///                 editor.set_cursor_position(position, window, cx);
+///             });
+///         })
+/// }
+/// ```
+#[derive(IntoElement)]
+pub struct ErrorBanner {
+    id: ElementId,
+    style: StyleRefinement,
+    variant: AlertVariant,
+    message: SharedString,
+    location: Option<Position>,
+    on_jump: Option<Rc<dyn Fn(Position, &mut Window, &mut App) + 'static>>,
+    on_close: Option<Rc<dyn Fn(&ClickEvent, &mut Window, &mut App) + 'static>>,
+}
+
+impl ErrorBanner {
+    /// Create a new error banner with the given message.
+    pub fn new(id: impl Into<ElementId>, message: impl Into<SharedString>) -> Self {
+        Self {
+            id: id.into(),
+            style: StyleRefinement::default(),
+            variant: AlertVariant::Error,
+            message: message.into(),
+            location: None,
+            on_jump: None,
+            on_close: None,
+        }
+    }
+
+    /// Sets the [`AlertVariant`] for the severity of the banner, default is [`AlertVariant::Error`].
+    pub fn with_variant(mut self, variant: AlertVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Set the (1-based) line and column of the error, as reported by the most parsers,
+    /// e.g. `serde_json::Error::line` and `serde_json::Error::column`.
+    ///
+    /// The location is ignored if the line is 0, that means the error has no location.
+    pub fn location(mut self, line: usize, column: usize) -> Self {
+        self.location = (line > 0).then(|| {
+            Position::new(
+                line.saturating_sub(1) as u32,
+                column.saturating_sub(1) as u32,
+            )
+        });
+        self
+    }
+
+    /// Set the handler to jump to the location of the error, the location is rendered as a link
+    /// if this is set.
+    ///
+    /// The handler receives the (0-based) [`Position`], e.g. to pass to
+    /// [`crate::input::InputState::set_cursor_position`].
+    pub fn on_jump(mut self, on_jump: impl Fn(Position, &mut Window, &mut App) + 'static) -> Self {
+        self.on_jump = Some(Rc::new(on_jump));
+        self
+    }
+
+    /// Set the handler of the dismiss button, the button is shown if this is set.
+    pub fn on_close(
+        mut self,
+        on_close: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_close = Some(Rc::new(on_close));
+        self
+    }
+}
+
+impl Styled for ErrorBanner {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for ErrorBanner {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let color = self.variant.color(cx);
+        let fg = self.variant.fg(cx);
+        let border_color = self.variant.border_color(cx);
+        let message = self.message.clone();
+
+        h_flex()
+            .id(self.id)
+            .w_full()
+            .items_start()
+            .gap_2()
+            .px_3()
+            .py_2()
+            .text_sm()
+            .text_color(fg)
+            .bg(color.opacity(0.08))
+            .border_b_1()
+            .border_color(border_color)
+            .refine_style(&self.style)
+            .child(
+                div()
+                    .mt(px(3.))
+                    .child(Icon::new(self.variant.icon()).flex_shrink_0()),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .overflow_hidden()
+                    .gap_1()
+                    .child(
+                        TextView::markdown("message", escape_markdown(&self.message))
+                            .style(TextViewStyle::default().paragraph_gap(rems(0.2)))
+                            .selectable(true),
+                    )
+                    .when_some(self.location, |this, position| {
+                        let label = format!(
+                            "Line {}, Column {}",
+                            position.line + 1,
+                            position.character + 1
+                        );
+
+                        this.child(match self.on_jump {
+                            Some(on_jump) => Link::new("jump")
+                                .text_xs()
+                                .on_click(move |_, window, cx| on_jump(position, window, cx))
+                                .child(label)
+                                .into_any_element(),
+                            None => div().text_xs().child(label).into_any_element(),
+                        })
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .flex_shrink_0()
+                    .child(Clipboard::new("copy").value(message))
+                    .when_some(self.on_close, |this, on_close| {
+                        this.child(
+                            div()
+                                .id("close")
+                                .p_0p5()
+                                .rounded(cx.theme().radius)
+                                .hover(|this| this.bg(color.opacity(0.1)))
+                                .active(|this| this.bg(color.opacity(0.2)))
+                                .on_click(move |ev, window, cx| {
+                                    on_close(ev, window, cx);
+                                })
+                                .child(Icon::new(IconName::Close).flex_shrink_0()),
+                        )
+                    }),
+            )
+    }
+}
+
+/// Escape the text to render it as is in Markdown, the line breaks are kept.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\n' => escaped.push_str("  \n"),
+            c if c.is_ascii_punctuation() => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::escape_markdown;
+
+    #[test]
+    fn test_escape_markdown() {
+        assert_eq!(escape_markdown("hello world"), "hello world");
+        assert_eq!(
+            escape_markdown("expected `,` or `}` at line 1"),
+            "expected \\`\\,\\` or \\`\\}\\` at line 1"
+        );
+        assert_eq!(escape_markdown("# **a**"), "\\# \\*\\*a\\*\\*");
+        assert_eq!(escape_markdown("a\nb"), "a  \nb");
+        assert_eq!(escape_markdown("中文"), "中文");
+    }
+}
@@ -1,3 +1,7 @@
+use gpui::{Animation, AnimationExt as _, AnyElement, App, ElementId, IntoElement};
+
+use crate::ActiveTheme as _;
+
 /// A cubic bezier function like CSS `cubic-bezier`.
 ///
 /// Builder:
@@ -17,3 +21,25 @@ pub fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32) -> impl Fn(f32) -> f32 {
         y
     }
 }
+
+/// Extension trait to respect the [`crate::Theme::reduced_motion`] setting in animations.
+pub trait MotionExt: IntoElement + 'static {
+    /// Same as [`gpui::AnimationExt::with_animation`], but when the reduced motion is enabled,
+    /// the animation is skipped and the element is rendered at the end state (`delta` is 1.0).
+    fn with_motion(
+        self,
+        id: impl Into<ElementId>,
+        animation: Animation,
+        animator: impl Fn(Self, f32) -> Self + 'static,
+        cx: &App,
+    ) -> AnyElement {
+        if cx.theme().reduced_motion {
+            animator(self, 1.).into_any_element()
+        } else {
+            self.with_animation(id, animation, animator)
+                .into_any_element()
+        }
+    }
+}
+
+impl<E: IntoElement + 'static> MotionExt for E {}
@@ -1,44 +1,75 @@
-use std::rc::Rc;
+use std::{ops::Range, rc::Rc};
 
 use gpui::{
-    div, prelude::FluentBuilder as _, App, ClickEvent, ElementId, InteractiveElement as _,
+    prelude::FluentBuilder as _, AnyElement, App, ClickEvent, ElementId, InteractiveElement as _,
     IntoElement, ParentElement, RenderOnce, SharedString, StatefulInteractiveElement,
     StyleRefinement, Styled, Window,
 };
 
-use crate::{h_flex, ActiveTheme, Icon, IconName, StyledExt};
+use crate::{
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    menu::{DropdownMenu as _, PopupMenuItem},
+    tree::TreePathItem,
+    ActiveTheme, Icon, IconName, Sizable as _, StyledExt,
+};
+
+type OnClick = Rc<dyn Fn(&ClickEvent, &mut Window, &mut App)>;
+type OnSelect = Rc<dyn Fn(&SharedString, &mut Window, &mut App)>;
 
 /// A breadcrumb navigation element.
 #[derive(IntoElement)]
 pub struct Breadcrumb {
+    id: ElementId,
     style: StyleRefinement,
     items: Vec<BreadcrumbItem>,
+    max_items: Option<usize>,
+    on_click: Option<OnSelect>,
 }
 
 /// Item for the [`Breadcrumb`].
 #[derive(IntoElement)]
 pub struct BreadcrumbItem {
-    id: ElementId,
+    ix: usize,
+    id: Option<SharedString>,
     style: StyleRefinement,
     label: SharedString,
-    on_click: Option<Rc<dyn Fn(&ClickEvent, &mut Window, &mut App)>>,
+    icon: Option<Icon>,
+    on_click: Option<OnClick>,
+    on_select: Option<OnSelect>,
     disabled: bool,
     is_last: bool,
 }
 
 impl BreadcrumbItem {
-    /// Create a new BreadcrumbItem with the given id and label.
+    /// Create a new BreadcrumbItem with the given label.
     pub fn new(label: impl Into<SharedString>) -> Self {
         Self {
-            id: ElementId::Integer(0),
+            ix: 0,
+            id: None,
             style: StyleRefinement::default(),
             label: label.into(),
+            icon: None,
             on_click: None,
+            on_select: None,
             disabled: false,
             is_last: false,
         }
     }
 
+    /// Set the id of the item, it's passed to the [`Breadcrumb::on_click`] when clicked,
+    /// default is the label.
+    pub fn id(mut self, id: impl Into<SharedString>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Set the icon before the label.
+    pub fn icon(mut self, icon: impl Into<Icon>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
     pub fn disabled(mut self, disabled: bool) -> Self {
         self.disabled = disabled;
         self
@@ -52,15 +83,36 @@ impl BreadcrumbItem {
         self
     }
 
-    fn id(mut self, id: impl Into<ElementId>) -> Self {
-        self.id = id.into();
-        self
+    fn value(&self) -> SharedString {
+        self.id.clone().unwrap_or_else(|| self.label.clone())
     }
 
-    /// For internal use only.
-    fn is_last(mut self, is_last: bool) -> Self {
-        self.is_last = is_last;
-        self
+    /// Returns the menu item to show in the overflow menu of the [`Breadcrumb`].
+    fn menu_item(&self) -> impl Fn(&mut Window, &mut App) -> PopupMenuItem + 'static {
+        let label = self.label.clone();
+        let icon = self.icon.clone();
+        let value = self.value();
+        let disabled = self.disabled;
+        let on_click = self.on_click.clone();
+        let on_select = self.on_select.clone();
+
+        move |_, _| {
+            let on_click = on_click.clone();
+            let on_select = on_select.clone();
+            let value = value.clone();
+            let mut item = PopupMenuItem::new(label.clone()).disabled(disabled);
+            if let Some(icon) = icon.clone() {
+                item = item.icon(icon);
+            }
+            item.on_click(move |event, window, cx| {
+                if let Some(on_click) = on_click.as_ref() {
+                    on_click(event, window, cx);
+                }
+                if let Some(on_select) = on_select.as_ref() {
+                    on_select(&value, window, cx);
+                }
+            })
+        }
     }
 }
 
@@ -88,10 +140,27 @@ impl From<SharedString> for BreadcrumbItem {
     }
 }
 
+impl From<&TreePathItem> for BreadcrumbItem {
+    fn from(item: &TreePathItem) -> Self {
+        Self::new(item.label.clone()).id(item.id.clone())
+    }
+}
+
+impl From<TreePathItem> for BreadcrumbItem {
+    fn from(item: TreePathItem) -> Self {
+        Self::new(item.label).id(item.id)
+    }
+}
+
 impl RenderOnce for BreadcrumbItem {
     fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
-        div()
-            .id(self.id)
+        let value = self.value();
+        let clickable = self.on_click.is_some() || self.on_select.is_some();
+
+        h_flex()
+            .id(self.ix)
+            .gap_1()
+            .when_some(self.icon, |this, icon| this.child(icon.size_3p5()))
             .child(self.label)
             .text_color(cx.theme().muted_foreground)
             .when(self.is_last, |this| this.text_color(cx.theme().foreground))
@@ -99,11 +168,16 @@ impl RenderOnce for BreadcrumbItem {
                 this.text_color(cx.theme().muted_foreground)
             })
             .refine_style(&self.style)
-            .when(!self.disabled, |this| {
-                this.when_some(self.on_click, |this, on_click| {
-                    this.cursor_pointer().on_click(move |event, window, cx| {
+            .when(!self.disabled && clickable, |this| {
+                let on_click = self.on_click;
+                let on_select = self.on_select;
+                this.cursor_pointer().on_click(move |event, window, cx| {
+                    if let Some(on_click) = on_click.as_ref() {
                         on_click(event, window, cx);
-                    })
+                    }
+                    if let Some(on_select) = on_select.as_ref() {
+                        on_select(&value, window, cx);
+                    }
                 })
             })
     }
@@ -113,11 +187,22 @@ impl Breadcrumb {
     /// Create a new breadcrumb.
     pub fn new() -> Self {
         Self {
+            id: "breadcrumb".into(),
             items: Vec::new(),
             style: StyleRefinement::default(),
+            max_items: None,
+            on_click: None,
         }
     }
 
+    /// Set the id of the breadcrumb, default is `breadcrumb`.
+    ///
+    /// Use a unique id when there are multiple breadcrumbs with the overflow menu in the same view.
+    pub fn id(mut self, id: impl Into<ElementId>) -> Self {
+        self.id = id.into();
+        self
+    }
+
     /// Add an [`BreadcrumbItem`] to the breadcrumb.
     pub fn child(mut self, item: impl Into<BreadcrumbItem>) -> Self {
         self.items.push(item.into());
@@ -129,6 +214,35 @@ impl Breadcrumb {
         self.items.extend(items.into_iter().map(Into::into));
         self
     }
+
+    /// Set the maximum number of the visible items, default is unlimited.
+    ///
+    /// If there are more items, the first item and the last `max_items - 1` items are kept,
+    /// the items in the middle are collapsed into an overflow menu.
+    pub fn max_items(mut self, max_items: usize) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+
+    /// Set the handler when an item is clicked, include the items in the overflow menu,
+    /// the argument is the [`BreadcrumbItem::id`] of the item.
+    ///
+    /// ```ignore
+    /// Breadcrumb::new()
+    ///     .children(tree_state.read(cx).item_path(&id).iter().map(BreadcrumbItem::from))
+    ///     .on_click(move |id, window, cx| {
+    ///         tree_state.update(cx, |state, cx| {
+    ///             state.reveal(id, window, cx);
+    ///         })
+    ///     })
+    /// ```
+    pub fn on_click(
+        mut self,
+        on_click: impl Fn(&SharedString, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_click = Some(Rc::new(on_click));
+        self
+    }
 }
 
 #[derive(IntoElement)]
@@ -151,19 +265,50 @@ impl Styled for Breadcrumb {
 impl RenderOnce for Breadcrumb {
     fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
         let items_count = self.items.len();
+        let collapsed = self
+            .max_items
+            .and_then(|max_items| collapsed_range(items_count, max_items));
 
-        let mut children = vec![];
-        for (ix, item) in self.items.into_iter().enumerate() {
+        let mut menu_items = vec![];
+        let mut children: Vec<AnyElement> = vec![];
+        for (ix, mut item) in self.items.into_iter().enumerate() {
             let is_last = ix == items_count - 1;
+            item.ix = ix;
+            item.is_last = is_last;
+            item.on_select = self.on_click.clone();
+
+            if let Some(collapsed) = collapsed.as_ref()
+                && collapsed.contains(&ix)
+            {
+                menu_items.push(item.menu_item());
+                if ix + 1 == collapsed.end {
+                    children.push(
+                        Button::new("more")
+                            .ghost()
+                            .xsmall()
+                            .icon(IconName::Ellipsis)
+                            .dropdown_menu(move |mut menu, window, cx| {
+                                for menu_item in menu_items.iter() {
+                                    menu = menu.item(menu_item(window, cx));
+                                }
+                                menu
+                            })
+                            .into_any_element(),
+                    );
+                    menu_items = vec![];
+                    children.push(BreadcrumbSeparator.into_any_element());
+                }
+                continue;
+            }
 
-            let item = item.id(ix);
-            children.push(item.is_last(is_last).into_any_element());
+            children.push(item.into_any_element());
             if !is_last {
                 children.push(BreadcrumbSeparator.into_any_element());
             }
         }
 
         h_flex()
+            .id(self.id)
             .gap_1p5()
             .text_sm()
             .text_color(cx.theme().muted_foreground)
@@ -171,3 +316,29 @@ impl RenderOnce for Breadcrumb {
             .children(children)
     }
 }
+
+/// Returns the range of the items to collapse into the overflow menu,
+/// keep the first item and the last `max_items - 1` items.
+fn collapsed_range(count: usize, max_items: usize) -> Option<Range<usize>> {
+    let max_items = max_items.max(2);
+    if count <= max_items {
+        return None;
+    }
+
+    Some(1..count - (max_items - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::collapsed_range;
+
+    #[test]
+    fn test_collapsed_range() {
+        assert_eq!(collapsed_range(3, 4), None);
+        assert_eq!(collapsed_range(4, 4), None);
+        assert_eq!(collapsed_range(5, 4), Some(1..2));
+        assert_eq!(collapsed_range(8, 3), Some(1..6));
+        // Keep the first and the last item at least.
+        assert_eq!(collapsed_range(5, 0), Some(1..4));
+    }
+}
@@ -1,15 +1,33 @@
-use std::rc::Rc;
+use std::{ops::Range, rc::Rc};
 
 use crate::{
-    ActiveTheme, Colorize as _, Disableable, FocusableExt as _, Icon, IconName, Selectable,
-    Sizable, Size, StyleSized, StyledExt, h_flex, spinner::Spinner, tooltip::Tooltip,
+    ActiveTheme, Colorize as _, Disableable, FocusableExt as _, Icon, IconName, Root, Selectable,
+    Sizable, Size, StyleSized, StyledExt, h_flex, kbd::Kbd, spinner::Spinner, tooltip::Tooltip,
 };
 use gpui::{
-    Action, AnyElement, App, ClickEvent, Corners, Div, Edges, ElementId, Hsla, InteractiveElement,
-    Interactivity, IntoElement, MouseButton, ParentElement, Pixels, RenderOnce, SharedString,
-    Stateful, StatefulInteractiveElement as _, StyleRefinement, Styled, Window, div,
-    prelude::FluentBuilder as _, px, relative,
+    Action, AnyElement, AnyView, App, ClickEvent, Corners, Div, Edges, ElementId, FocusHandle,
+    HighlightStyle, Hsla, InteractiveElement, Interactivity, IntoElement, MouseButton,
+    ParentElement, Pixels, RenderOnce, SharedString, Stateful, StatefulInteractiveElement as _,
+    StyleRefinement, Styled, StyledText, Subscription, UnderlineStyle, Window, anchored, deferred,
+    div, prelude::FluentBuilder as _, px, relative,
 };
+use serde::Deserialize;
+
+/// Trigger the click of the [`Button`] that has the mnemonic in the focused container,
+/// see [`Button::mnemonic`].
+#[derive(Action, Clone, PartialEq, Eq, Deserialize)]
+#[action(namespace = button, no_json)]
+pub struct Mnemonic {
+    /// The mnemonic char in lowercase.
+    pub key: char,
+}
+
+/// The key binding to show on the [`Button`], see [`Button::key_binding`].
+#[derive(Clone)]
+enum ButtonKeyBinding {
+    Kbd(Kbd),
+    Action(Rc<Box<dyn Action>>, Option<SharedString>),
+}
 
 #[derive(Default, Clone, Copy)]
 pub enum ButtonRounded {
@@ -173,10 +191,30 @@ impl ButtonVariant {
     }
 }
 
+/// The interaction state of the [`Button`], see [`Button::render_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ButtonState {
+    /// The mouse is over the button.
+    pub hovered: bool,
+    /// The left mouse button is pressed down on the button.
+    pub pressed: bool,
+    /// The button is focused.
+    pub focused: bool,
+    pub disabled: bool,
+    pub selected: bool,
+}
+
+/// The hovered and pressed state of the [`Button`] kept between renders.
+#[derive(Default)]
+struct ButtonInteraction {
+    hovered: bool,
+    pressed: bool,
+}
+
 /// A Button element.
 #[derive(IntoElement)]
 pub struct Button {
-    id: ElementId,
+    pub(super) id: ElementId,
     base: Stateful<Div>,
     style: StyleRefinement,
     icon: Option<Icon>,
@@ -200,6 +238,10 @@ pub struct Button {
     on_hover: Option<Rc<dyn Fn(&bool, &mut Window, &mut App)>>,
     loading: bool,
     loading_icon: Option<Icon>,
+    loading_label: Option<SharedString>,
+    key_binding: Option<ButtonKeyBinding>,
+    mnemonic: Option<char>,
+    render_with: Option<Rc<dyn Fn(ButtonState, &mut Window, &mut App) -> AnyElement>>,
 
     tab_index: isize,
     tab_stop: bool,
@@ -238,7 +280,11 @@ impl Button {
             outline: false,
             children: Vec::new(),
             loading_icon: None,
+            loading_label: None,
+            key_binding: None,
             dropdown_caret: false,
+            mnemonic: None,
+            render_with: None,
             tab_index: 0,
             tab_stop: true,
         }
@@ -281,6 +327,9 @@ impl Button {
     }
 
     /// Set the tooltip of the button.
+    ///
+    /// The tooltip is shown on hover after a short delay, or below the button when it's focused by
+    /// keyboard, and dismissed on click.
     pub fn tooltip(mut self, tooltip: impl Into<SharedString>) -> Self {
         self.tooltip = Some((tooltip.into(), None));
         self
@@ -303,12 +352,37 @@ impl Button {
         self
     }
 
-    /// Set true to show the loading indicator.
+    /// Set true to show the loading indicator, the clicks are ignored while loading.
+    ///
+    /// The spinner replaces the icon, or overlays the label if there is no icon,
+    /// so the button keeps the same width.
     pub fn loading(mut self, loading: bool) -> Self {
         self.loading = loading;
         self
     }
 
+    /// Set the key binding to show as a shortcut hint after the label, e.g. `⌘O`.
+    ///
+    /// The keystroke is formatted for the platform, and it's shown in the tooltip instead
+    /// for the icon-only button.
+    ///
+    /// NOTE: This is only for display, the key binding is not bound to the button.
+    pub fn key_binding(mut self, kbd: impl Into<Kbd>) -> Self {
+        self.key_binding = Some(ButtonKeyBinding::Kbd(kbd.into()));
+        self
+    }
+
+    /// Set the action to show its bound keystroke as a shortcut hint, see [`Self::key_binding`].
+    ///
+    /// The keystroke is looked up from the key bindings of the action in the `context`.
+    pub fn key_binding_for_action(mut self, action: &dyn Action, context: Option<&str>) -> Self {
+        self.key_binding = Some(ButtonKeyBinding::Action(
+            Rc::new(action.boxed_clone()),
+            context.map(|c| c.to_string().into()),
+        ));
+        self
+    }
+
     /// Set the button to compact mode, then padding will be reduced.
     pub fn compact(mut self) -> Self {
         self.compact = true;
@@ -316,6 +390,9 @@ impl Button {
     }
 
     /// Add click handler.
+    ///
+    /// The [`ClickEvent`] contains the click position and the modifier keys,
+    /// use `event.position()` and `event.modifiers()` to get them.
     pub fn on_click(
         mut self,
         handler: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
@@ -338,6 +415,14 @@ impl Button {
         self
     }
 
+    /// Set the label to show when loading, e.g. "Opening…", the spinner is shown before it.
+    ///
+    /// NOTE: The button width may change if the loading label is different in length.
+    pub fn loading_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.loading_label = Some(label.into());
+        self
+    }
+
     /// Set the tab index of the button, it will be used to focus the button by tab key.
     ///
     /// Default is 0.
@@ -360,6 +445,42 @@ impl Button {
         self
     }
 
+    /// Set the mnemonic (access key) of the button, like the `F` of `&File` in menu bar.
+    ///
+    /// The first matched char (case-insensitive) in the label will be underlined,
+    /// and press `Alt` + the char (`a-z` and `0-9`) will trigger the `on_click` handler,
+    /// if the Button is in the focused Dialog or Sheet, or in the window without them focused.
+    pub fn mnemonic(mut self, mnemonic: char) -> Self {
+        self.mnemonic = Some(mnemonic.to_ascii_lowercase());
+        self
+    }
+
+    /// Render a custom content of the Button by the [`ButtonState`], instead of the built-in visuals.
+    ///
+    /// This is an escape hatch for the custom appearances that react to the interaction state,
+    /// the icon, label, children, key binding and the variant styles (padding, border, colors)
+    /// are not rendered, only the click, focus and keyboard handling are still provided by the Button.
+    ///
+    /// ```ignore
+    /// Button::new("custom")
+    ///     .render_with(|state, _, cx| {
+    ///         div()
+    ///             .when(state.pressed, |this| this.bg(cx.theme().accent))
+    ///             .when(state.hovered, |this| this.underline())
+    ///             .child("Custom")
+    ///     })
+    /// ```
+    pub fn render_with<F, E>(mut self, f: F) -> Self
+    where
+        F: Fn(ButtonState, &mut Window, &mut App) -> E + 'static,
+        E: IntoElement,
+    {
+        self.render_with = Some(Rc::new(move |state, window, cx| {
+            f(state, window, cx).into_any_element()
+        }));
+        self
+    }
+
     #[inline]
     fn clickable(&self) -> bool {
         !(self.disabled || self.loading) && self.on_click.is_some()
@@ -427,6 +548,29 @@ impl RenderOnce for Button {
         let clickable = self.clickable();
         let is_disabled = self.disabled;
         let hoverable = self.hoverable();
+        let is_loading = self.loading;
+        let is_custom = self.render_with.is_some();
+        // Overlay the spinner on the content to keep the width, if there is no icon to replace.
+        let loading_overlay =
+            !is_custom && is_loading && self.icon.is_none() && self.loading_label.is_none();
+        let label = match self.loading_label {
+            Some(loading_label) if is_loading => Some(loading_label),
+            _ => self.label,
+        };
+        let kbd = self
+            .key_binding
+            .as_ref()
+            .and_then(|key_binding| match key_binding {
+                ButtonKeyBinding::Kbd(kbd) => Some(kbd.clone()),
+                ButtonKeyBinding::Action(action, context) => {
+                    Kbd::binding_for_action((**action).as_ref(), context.as_deref(), window)
+                }
+            });
+        let icon_only = label.is_none() && self.children.is_empty();
+        let (tooltip_kbd, kbd) = match icon_only {
+            true => (kbd, None),
+            false => (None, kbd),
+        };
         let normal_style = style.normal(self.outline, cx);
         let icon_size = match self.size {
             Size::Size(v) => Size::Size(v * 0.75),
@@ -439,6 +583,71 @@ impl RenderOnce for Button {
             .clone();
         let is_focused = focus_handle.is_focused(window);
 
+        // The icon-only button shows the key binding in a tooltip, even without the tooltip text.
+        let tooltip = self.tooltip.clone().or_else(|| {
+            tooltip_kbd
+                .as_ref()
+                .map(|_| (SharedString::default(), None))
+        });
+
+        // Show the tooltip when focused by keyboard, until the button is clicked.
+        let focus_tooltip_state = tooltip.as_ref().map(|_| {
+            window.use_keyed_state(
+                SharedString::from(format!("{}-focus-tooltip", self.id)),
+                cx,
+                |window, cx| FocusTooltipState::new(&focus_handle, window, cx),
+            )
+        });
+        let focus_tooltip = tooltip
+            .clone()
+            .filter(|_| is_focused && !is_disabled && window.last_input_was_keyboard())
+            .filter(|_| {
+                !focus_tooltip_state
+                    .as_ref()
+                    .is_some_and(|state| state.read(cx).dismissed)
+            })
+            .map(|(tooltip, action)| {
+                build_tooltip(tooltip, action.as_ref(), tooltip_kbd.clone(), window, cx)
+            });
+
+        // Track the hovered and pressed state only for `render_with`.
+        let interaction = self.render_with.as_ref().map(|_| {
+            window.use_keyed_state(
+                SharedString::from(format!("{}-interaction", self.id)),
+                cx,
+                |_, _| ButtonInteraction::default(),
+            )
+        });
+        let custom_content =
+            self.render_with
+                .clone()
+                .zip(interaction.as_ref())
+                .map(|(render_with, interaction)| {
+                    let interaction = interaction.read(cx);
+                    let state = ButtonState {
+                        hovered: interaction.hovered && !is_disabled,
+                        pressed: interaction.pressed && !is_disabled,
+                        focused: is_focused,
+                        disabled: is_disabled,
+                        selected: self.selected,
+                    };
+                    render_with(state, window, cx)
+                });
+
+        if let Some(mnemonic) = self.mnemonic {
+            let on_click = self.on_click.clone().filter(|_| clickable);
+            window
+                .use_keyed_state(
+                    SharedString::from(format!("{}-mnemonic", self.id)),
+                    cx,
+                    |window, cx| MnemonicState::new(&focus_handle, window, cx),
+                )
+                .update(cx, |state, _| {
+                    state.mnemonic = mnemonic;
+                    state.on_click = on_click;
+                });
+        }
+
         let rounding = match self.rounded {
             ButtonRounded::Small => cx.theme().radius * 0.5,
             ButtonRounded::Medium => cx.theme().radius,
@@ -462,80 +671,84 @@ impl RenderOnce for Button {
             .justify_center()
             .cursor_default()
             .when(self.variant.is_link(), |this| this.cursor_pointer())
-            .when(cx.theme().shadow && normal_style.shadow, |this| {
-                this.shadow_xs()
-            })
-            .when(!style.no_padding(), |this| {
-                if self.label.is_none() && self.children.is_empty() {
-                    // Icon Button
-                    match self.size {
-                        Size::Size(px) => this.size(px),
-                        Size::XSmall => this.size_5(),
-                        Size::Small => this.size_6(),
-                        Size::Large | Size::Medium => this.size_8(),
-                    }
-                } else {
-                    // Normal Button
-                    match self.size {
-                        Size::Size(size) => this.px(size * 0.2),
-                        Size::XSmall => this.h_5().px_1(),
-                        Size::Small => this.h_6().px_3().when(self.compact, |this| this.px_1p5()),
-                        _ => this.h_8().px_4().when(self.compact, |this| this.px_2()),
+            .when(!is_custom, |this| {
+                this.when(cx.theme().shadow && normal_style.shadow, |this| {
+                    this.shadow_xs()
+                })
+                .when(!style.no_padding(), |this| {
+                    if label.is_none() && self.children.is_empty() {
+                        // Icon Button
+                        match self.size {
+                            Size::Size(px) => this.size(px),
+                            Size::XSmall => this.size_5(),
+                            Size::Small => this.size_6(),
+                            Size::Large | Size::Medium => this.size_8(),
+                        }
+                    } else {
+                        // Normal Button
+                        match self.size {
+                            Size::Size(size) => this.px(size * 0.2),
+                            Size::XSmall => this.h_5().px_1(),
+                            Size::Small => {
+                                this.h_6().px_3().when(self.compact, |this| this.px_1p5())
+                            }
+                            _ => this.h_8().px_4().when(self.compact, |this| this.px_2()),
+                        }
                     }
-                }
-            })
-            .when(self.border_corners.top_left, |this| {
-                this.rounded_tl(rounding)
-            })
-            .when(self.border_corners.top_right, |this| {
-                this.rounded_tr(rounding)
-            })
-            .when(self.border_corners.bottom_left, |this| {
-                this.rounded_bl(rounding)
-            })
-            .when(self.border_corners.bottom_right, |this| {
-                this.rounded_br(rounding)
-            })
-            .when(self.border_edges.left, |this| this.border_l_1())
-            .when(self.border_edges.right, |this| this.border_r_1())
-            .when(self.border_edges.top, |this| this.border_t_1())
-            .when(self.border_edges.bottom, |this| this.border_b_1())
-            .text_color(normal_style.fg)
-            .when(self.selected, |this| {
-                let selected_style = style.selected(self.outline, cx);
-                this.bg(selected_style.bg)
-                    .border_color(selected_style.border)
-                    .text_color(selected_style.fg)
-            })
-            .when(!self.disabled && !self.selected, |this| {
-                this.border_color(normal_style.border)
-                    .bg(normal_style.bg)
-                    .when(normal_style.underline, |this| this.text_decoration_1())
-                    .hover(|this| {
-                        let hover_style = style.hovered(self.outline, cx);
-                        this.bg(hover_style.bg)
-                            .border_color(hover_style.border)
-                            .text_color(crate::red_400())
-                    })
-                    .active(|this| {
-                        let active_style = style.active(self.outline, cx);
-                        this.bg(active_style.bg)
-                            .border_color(active_style.border)
-                            .text_color(active_style.fg)
-                    })
-            })
-            .when(self.disabled, |this| {
-                let disabled_style = style.disabled(self.outline, cx);
-                this.bg(disabled_style.bg)
-                    .text_color(disabled_style.fg)
-                    .border_color(disabled_style.border)
-                    .shadow_none()
+                })
+                .when(self.border_corners.top_left, |this| {
+                    this.rounded_tl(rounding)
+                })
+                .when(self.border_corners.top_right, |this| {
+                    this.rounded_tr(rounding)
+                })
+                .when(self.border_corners.bottom_left, |this| {
+                    this.rounded_bl(rounding)
+                })
+                .when(self.border_corners.bottom_right, |this| {
+                    this.rounded_br(rounding)
+                })
+                .when(self.border_edges.left, |this| this.border_l_1())
+                .when(self.border_edges.right, |this| this.border_r_1())
+                .when(self.border_edges.top, |this| this.border_t_1())
+                .when(self.border_edges.bottom, |this| this.border_b_1())
+                .text_color(normal_style.fg)
+                .when(self.selected, |this| {
+                    let selected_style = style.selected(self.outline, cx);
+                    this.bg(selected_style.bg)
+                        .border_color(selected_style.border)
+                        .text_color(selected_style.fg)
+                })
+                .when(!self.disabled && !self.selected, |this| {
+                    this.border_color(normal_style.border)
+                        .bg(normal_style.bg)
+                        .when(normal_style.underline, |this| this.text_decoration_1())
+                        .hover(|this| {
+                            let hover_style = style.hovered(self.outline, cx);
+                            this.bg(hover_style.bg)
+                                .border_color(hover_style.border)
+                                .text_color(crate::red_400())
+                        })
+                        .active(|this| {
+                            let active_style = style.active(self.outline, cx);
+                            this.bg(active_style.bg)
+                                .border_color(active_style.border)
+                                .text_color(active_style.fg)
+                        })
+                })
+                .when(self.disabled, |this| {
+                    let disabled_style = style.disabled(self.outline, cx);
+                    this.bg(disabled_style.bg)
+                        .text_color(disabled_style.fg)
+                        .border_color(disabled_style.border)
+                        .shadow_none()
+                })
             })
             .refine_style(&self.style)
             .on_mouse_down(MouseButton::Left, move |_, window, cx| {
-                // Stop handle any click event when disabled.
+                // Stop handle any click event when disabled or loading.
                 // To avoid handle dropdown menu open when button is disabled.
-                if is_disabled {
+                if is_disabled || is_loading {
                     cx.stop_propagation();
                     return;
                 }
@@ -555,71 +768,251 @@ impl RenderOnce for Button {
                     (on_click)(event, window, cx);
                 })
             })
-            .when_some(self.on_hover.filter(|_| hoverable), |this, on_hover| {
+            .when_some(interaction.clone(), |this, interaction| {
+                this.on_mouse_down(MouseButton::Left, {
+                    let interaction = interaction.clone();
+                    move |_, _, cx| {
+                        interaction.update(cx, |state, cx| {
+                            state.pressed = true;
+                            cx.notify();
+                        });
+                    }
+                })
+                .on_mouse_up(MouseButton::Left, {
+                    let interaction = interaction.clone();
+                    move |_, _, cx| {
+                        interaction.update(cx, |state, cx| {
+                            state.pressed = false;
+                            cx.notify();
+                        });
+                    }
+                })
+                .on_mouse_up_out(MouseButton::Left, move |_, _, cx| {
+                    interaction.update(cx, |state, cx| {
+                        state.pressed = false;
+                        cx.notify();
+                    });
+                })
+            })
+            .map(|this| {
+                let on_hover = self.on_hover.filter(|_| hoverable);
+                if on_hover.is_none() && interaction.is_none() {
+                    return this;
+                }
+
                 this.on_hover(move |hovered, window, cx| {
-                    (on_hover)(hovered, window, cx);
+                    if let Some(interaction) = interaction.as_ref() {
+                        interaction.update(cx, |state, cx| {
+                            state.hovered = *hovered;
+                            cx.notify();
+                        });
+                    }
+                    if let Some(on_hover) = on_hover.as_ref() {
+                        (on_hover)(hovered, window, cx);
+                    }
                 })
             })
-            .child({
-                h_flex()
-                    .id("label")
-                    .w_full()
-                    .items_center()
-                    .justify_center()
-                    .button_text_size(self.size)
-                    .map(|this| match self.size {
-                        Size::XSmall => this.gap_1(),
-                        Size::Small => this.gap_1(),
-                        _ => this.gap_2(),
-                    })
-                    .when(!self.loading, |this| {
-                        this.when_some(self.icon, |this, icon| {
-                            this.child(icon.with_size(icon_size))
+            .when_some(custom_content, |this, content| this.child(content))
+            .when(!is_custom, |this| {
+                this.child(
+                    h_flex()
+                        .id("label")
+                        .w_full()
+                        .items_center()
+                        .justify_center()
+                        .button_text_size(self.size)
+                        .map(|this| match self.size {
+                            Size::XSmall => this.gap_1(),
+                            Size::Small => this.gap_1(),
+                            _ => this.gap_2(),
+                        })
+                        .when(!self.loading, |this| {
+                            this.when_some(self.icon, |this, icon| {
+                                this.child(icon.with_size(icon_size))
+                            })
+                        })
+                        .when(self.loading && !loading_overlay, |this| {
+                            this.child(
+                                Spinner::new()
+                                    .with_size(self.size)
+                                    .when_some(self.loading_icon.clone(), |this, icon| {
+                                        this.icon(icon)
+                                    }),
+                            )
+                        })
+                        .when(loading_overlay, |this| this.invisible())
+                        .when_some(label, |this, label| {
+                            let highlights = self
+                                .mnemonic
+                                .and_then(|mnemonic| mnemonic_range(&label, mnemonic))
+                                .map(|range| {
+                                    vec![(
+                                        range,
+                                        HighlightStyle {
+                                            underline: Some(UnderlineStyle {
+                                                thickness: px(1.),
+                                                ..Default::default()
+                                            }),
+                                            ..Default::default()
+                                        },
+                                    )]
+                                });
+
+                            this.child(div().flex_none().line_height(relative(1.)).child(
+                                StyledText::new(label).when_some(highlights, |this, highlights| {
+                                    this.with_highlights(highlights)
+                                }),
+                            ))
                         })
-                    })
-                    .when(self.loading, |this| {
-                        this.child(
+                        .children(self.children)
+                        .when_some(kbd, |this, kbd| {
+                            this.child(
+                                div()
+                                    .ml_auto()
+                                    .flex_shrink_0()
+                                    .text_xs()
+                                    .text_color(normal_style.fg.opacity(0.6))
+                                    .child(kbd.appearance(false)),
+                            )
+                        })
+                        .when(self.dropdown_caret, |this| {
+                            this.justify_between().child(
+                                Icon::new(IconName::ChevronDown).xsmall().text_color(
+                                    match self.disabled {
+                                        true => normal_style.fg.opacity(0.3),
+                                        false => normal_style.fg.opacity(0.5),
+                                    },
+                                ),
+                            )
+                        }),
+                )
+            })
+            .when(loading_overlay, |this| {
+                this.relative().child(
+                    h_flex()
+                        .absolute()
+                        .inset_0()
+                        .items_center()
+                        .justify_center()
+                        .child(
                             Spinner::new()
                                 .with_size(self.size)
                                 .when_some(self.loading_icon, |this, icon| this.icon(icon)),
-                        )
-                    })
-                    .when_some(self.label, |this, label| {
-                        this.child(div().flex_none().line_height(relative(1.)).child(label))
-                    })
-                    .children(self.children)
-                    .when(self.dropdown_caret, |this| {
-                        this.justify_between().child(
-                            Icon::new(IconName::ChevronDown).xsmall().text_color(
-                                match self.disabled {
-                                    true => normal_style.fg.opacity(0.3),
-                                    false => normal_style.fg.opacity(0.5),
-                                },
-                            ),
-                        )
-                    })
+                        ),
+                )
             })
-            .when(self.loading && !self.disabled, |this| {
+            .when(self.loading && !self.disabled && !is_custom, |this| {
                 this.bg(normal_style.bg.opacity(0.8))
                     .border_color(normal_style.border.opacity(0.8))
                     .text_color(normal_style.fg.opacity(0.8))
             })
-            .when_some(self.tooltip, |this, (tooltip, action)| {
+            .when_some(focus_tooltip_state, |this, state| {
+                this.on_click(move |_, _, cx| {
+                    state.update(cx, |state, cx| {
+                        state.dismissed = true;
+                        cx.notify();
+                    });
+                })
+            })
+            .when_some(focus_tooltip, |this, tooltip| {
+                this.child(
+                    div().absolute().top_full().left_0().child(
+                        deferred(anchored().snap_to_window_with_margin(px(8.)).child(tooltip))
+                            .with_priority(1),
+                    ),
+                )
+            })
+            .when_some(tooltip, |this, (tooltip, action)| {
                 this.tooltip(move |window, cx| {
-                    Tooltip::new(tooltip.clone())
-                        .when_some(action.clone(), |this, (action, context)| {
-                            this.action(
-                                action.boxed_clone().as_ref(),
-                                context.as_ref().map(|c| c.as_ref()),
-                            )
-                        })
-                        .build(window, cx)
+                    build_tooltip(
+                        tooltip.clone(),
+                        action.as_ref(),
+                        tooltip_kbd.clone(),
+                        window,
+                        cx,
+                    )
                 })
             })
             .focus_ring(is_focused, px(0.), window, cx)
     }
 }
 
+/// Build the tooltip of the Button, only the key binding is shown if the `text` is empty.
+fn build_tooltip(
+    text: SharedString,
+    action: Option<&(Rc<Box<dyn Action>>, Option<SharedString>)>,
+    kbd: Option<Kbd>,
+    window: &mut Window,
+    cx: &mut App,
+) -> AnyView {
+    let tooltip = match kbd.clone().filter(|_| text.is_empty()) {
+        Some(kbd) => Tooltip::element(move |_, _| kbd.clone().appearance(false)),
+        None => Tooltip::new(text).key_binding(kbd),
+    };
+
+    tooltip
+        .when_some(action, |this, (action, context)| {
+            this.action(action.boxed_clone().as_ref(), context.as_deref())
+        })
+        .build(window, cx)
+}
+
+/// The state of the tooltip shown when the Button is focused by keyboard.
+struct FocusTooltipState {
+    /// The tooltip is dismissed by the click, until the Button is blurred.
+    dismissed: bool,
+    _subscription: Subscription,
+}
+
+impl FocusTooltipState {
+    fn new(focus_handle: &FocusHandle, window: &mut Window, cx: &mut gpui::Context<Self>) -> Self {
+        let _subscription = cx.on_blur(focus_handle, window, |state, _, cx| {
+            state.dismissed = false;
+            cx.notify();
+        });
+
+        Self {
+            dismissed: false,
+            _subscription,
+        }
+    }
+}
+
+/// The state to trigger the Button click by the [`Mnemonic`] action, see [`Root`].
+pub(crate) struct MnemonicState {
+    pub(crate) mnemonic: char,
+    pub(crate) focus_handle: FocusHandle,
+    pub(crate) on_click: Option<Rc<dyn Fn(&ClickEvent, &mut Window, &mut App)>>,
+}
+
+impl MnemonicState {
+    fn new(focus_handle: &FocusHandle, window: &mut Window, cx: &mut gpui::Context<Self>) -> Self {
+        // Register to the Root of the window, it will be dropped when the Button is not rendered.
+        if let Some(root) = window.root::<Root>().flatten() {
+            let state = cx.weak_entity();
+            root.update(cx, |root, _| {
+                root.mnemonic_buttons
+                    .retain(|state| state.upgrade().is_some());
+                root.mnemonic_buttons.push(state);
+            });
+        }
+
+        Self {
+            mnemonic: '\0',
+            focus_handle: focus_handle.clone(),
+            on_click: None,
+        }
+    }
+}
+
+/// Return the byte range of the first char in the label that matches the mnemonic (case-insensitive).
+fn mnemonic_range(label: &str, mnemonic: char) -> Option<Range<usize>> {
+    label
+        .char_indices()
+        .find(|(_, c)| c.eq_ignore_ascii_case(&mnemonic))
+        .map(|(ix, c)| ix..ix + c.len_utf8())
+}
+
 struct ButtonVariantStyle {
     bg: Hsla,
     border: Hsla,
@@ -10,7 +10,16 @@ use crate::{
     Disableable, Sizable, Size, StyledExt,
 };
 
+/// The event of the [`ButtonGroup`], see [`ButtonGroup::on_change`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ButtonGroupEvent {
+    /// The button is selected (or deselected in `multiple` mode) by click, with the button id.
+    SelectionChanged(ElementId),
+}
+
 /// A ButtonGroup element, to wrap multiple buttons in a group.
+///
+/// The buttons are rendered as connected segments, only the outer corners are rounded.
 #[derive(IntoElement)]
 pub struct ButtonGroup {
     id: ElementId,
@@ -25,8 +34,10 @@ pub struct ButtonGroup {
     pub(super) outline: bool,
     pub(super) variant: Option<ButtonVariant>,
     pub(super) size: Option<Size>,
+    selected_ids: Vec<ElementId>,
 
     on_click: Option<Box<dyn Fn(&Vec<usize>, &mut Window, &mut App) + 'static>>,
+    on_change: Option<Box<dyn Fn(&ButtonGroupEvent, &mut Window, &mut App) + 'static>>,
 }
 
 impl Disableable for ButtonGroup {
@@ -50,7 +61,9 @@ impl ButtonGroup {
             multiple: false,
             disabled: false,
             layout: Axis::Horizontal,
+            selected_ids: Vec::new(),
             on_click: None,
+            on_change: None,
         }
     }
 
@@ -72,6 +85,14 @@ impl ButtonGroup {
         self
     }
 
+    /// Select the button by its id, call it multiple times to select more in `multiple` mode.
+    ///
+    /// This is the same as [`Button::selected`] on the button.
+    pub fn selected(mut self, id: impl Into<ElementId>) -> Self {
+        self.selected_ids.push(id.into());
+        self
+    }
+
     /// Set the layout of the button group. Default is `Axis::Horizontal`.
     pub fn layout(mut self, layout: Axis) -> Self {
         self.layout = layout;
@@ -124,6 +145,29 @@ impl ButtonGroup {
         self.on_click = Some(Box::new(handler));
         self
     }
+
+    /// Sets the handler to be called when the selection is changed by click.
+    ///
+    /// In single selection mode, clicking the selected button does not change the selection.
+    ///
+    /// ```ignore
+    /// ButtonGroup::new("format")
+    ///    .child(Button::new("pretty").label("Pretty"))
+    ///    .child(Button::new("minified").label("Minified"))
+    ///    .selected(self.format.clone())
+    ///    .on_change(cx.listener(|view, event: &ButtonGroupEvent, _, cx| {
+    ///        let ButtonGroupEvent::SelectionChanged(id) = event;
+    ///        view.format = id.clone();
+    ///        cx.notify();
+    ///    }))
+    /// ```
+    pub fn on_change(
+        mut self,
+        handler: impl Fn(&ButtonGroupEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_change = Some(Box::new(handler));
+        self
+    }
 }
 
 impl Sizable for ButtonGroup {
@@ -151,8 +195,18 @@ impl RenderOnce for ButtonGroup {
         let children_len = self.children.len();
         let mut selected_ixs: Vec<usize> = Vec::new();
         let state = Rc::new(Cell::new(None));
+        let has_handler = self.on_click.is_some() || self.on_change.is_some();
 
-        for (ix, child) in self.children.iter().enumerate() {
+        let children: Vec<Button> = self
+            .children
+            .into_iter()
+            .map(|mut child| {
+                child.selected |= self.selected_ids.contains(&child.id);
+                child
+            })
+            .collect();
+        let ids: Vec<ElementId> = children.iter().map(|child| child.id.clone()).collect();
+        for (ix, child) in children.iter().enumerate() {
             if child.selected {
                 selected_ixs.push(ix);
             }
@@ -167,7 +221,7 @@ impl RenderOnce for ButtonGroup {
             .when(!vertical, |this| this.items_center())
             .refine_style(&self.style)
             .children(
-                self.children
+                children
                     .into_iter()
                     .enumerate()
                     .map(|(child_index, child)| {
@@ -219,7 +273,7 @@ impl RenderOnce for ButtonGroup {
                         .when_some(self.variant, |this, variant| this.with_variant(variant))
                         .when(self.compact, |this| this.compact())
                         .when(self.outline, |this| this.outline())
-                        .when(self.on_click.is_some(), |this| {
+                        .when(has_handler, |this| {
                             this.on_click(move |_, _, _| {
                                 state.set(Some(child_index));
                             })
@@ -228,27 +282,38 @@ impl RenderOnce for ButtonGroup {
                         child
                     }),
             )
-            .when_some(
-                self.on_click.filter(|_| !self.disabled),
-                move |this, on_click| {
-                    this.on_click(move |_, window, cx| {
-                        let mut selected_ixs = selected_ixs.clone();
-                        if let Some(ix) = state.get() {
-                            if self.multiple {
-                                if let Some(pos) = selected_ixs.iter().position(|&i| i == ix) {
-                                    selected_ixs.remove(pos);
-                                } else {
-                                    selected_ixs.push(ix);
-                                }
+            .when(has_handler && !self.disabled, move |this| {
+                let multiple = self.multiple;
+                let on_click = self.on_click;
+                let on_change = self.on_change;
+                this.on_click(move |_, window, cx| {
+                    let mut selected_ixs = selected_ixs.clone();
+                    let mut changed = false;
+                    if let Some(ix) = state.get() {
+                        if multiple {
+                            if let Some(pos) = selected_ixs.iter().position(|&i| i == ix) {
+                                selected_ixs.remove(pos);
                             } else {
-                                selected_ixs.clear();
                                 selected_ixs.push(ix);
                             }
+                            changed = true;
+                        } else {
+                            changed = selected_ixs != [ix];
+                            selected_ixs.clear();
+                            selected_ixs.push(ix);
                         }
+                    }
 
+                    if let Some(on_click) = on_click.as_ref() {
                         on_click(&selected_ixs, window, cx);
-                    })
-                },
-            )
+                    }
+                    if let Some((on_change, ix)) = on_change.as_ref().zip(state.get()) {
+                        if changed {
+                            let event = ButtonGroupEvent::SelectionChanged(ids[ix].clone());
+                            on_change(&event, window, cx);
+                        }
+                    }
+                })
+            })
     }
 }
@@ -1,12 +1,12 @@
 use std::{rc::Rc, time::Duration};
 
 use crate::{
-    icon::IconNamed, text::Text, v_flex, ActiveTheme, Disableable, FocusableExt, IconName,
-    Selectable, Sizable, Size, StyledExt as _,
+    animation::MotionExt as _, icon::IconNamed, text::Text, v_flex, ActiveTheme, Disableable,
+    FocusableExt, IconName, Selectable, Sizable, Size, StyledExt as _,
 };
 use gpui::{
-    div, prelude::FluentBuilder as _, px, relative, rems, svg, Animation, AnimationExt, AnyElement,
-    App, Div, ElementId, InteractiveElement, IntoElement, ParentElement, RenderOnce,
+    div, prelude::FluentBuilder as _, px, relative, rems, svg, Animation, AnyElement, App, Div,
+    ElementId, InteractiveElement, IntoElement, ParentElement, RenderOnce,
     StatefulInteractiveElement, StyleRefinement, Styled, Window,
 };
 
@@ -19,6 +19,7 @@ pub struct Checkbox {
     label: Option<Text>,
     children: Vec<AnyElement>,
     checked: bool,
+    indeterminate: bool,
     disabled: bool,
     size: Size,
     tab_stop: bool,
@@ -36,6 +37,7 @@ impl Checkbox {
             label: None,
             children: Vec::new(),
             checked: false,
+            indeterminate: false,
             disabled: false,
             size: Size::default(),
             on_click: None,
@@ -56,6 +58,14 @@ impl Checkbox {
         self
     }
 
+    /// Set the indeterminate state for the checkbox, e.g. only some of the children are checked.
+    ///
+    /// It's rendered as a dash instead of the check mark, and will be checked after the click.
+    pub fn indeterminate(mut self, indeterminate: bool) -> Self {
+        self.indeterminate = indeterminate;
+        self
+    }
+
     /// Set the click handler for the checkbox.
     ///
     /// The `&bool` parameter indicates the new checked state after the click.
@@ -79,10 +89,11 @@ impl Checkbox {
     fn handle_click(
         on_click: &Option<Rc<dyn Fn(&bool, &mut Window, &mut App) + 'static>>,
         checked: bool,
+        indeterminate: bool,
         window: &mut Window,
         cx: &mut App,
     ) {
-        let new_checked = !checked;
+        let new_checked = indeterminate || !checked;
         if let Some(f) = on_click {
             (f)(&new_checked, window, cx);
         }
@@ -175,23 +186,46 @@ pub(crate) fn checkbox_check_icon(
                 })
                 .detach();
 
-                this.with_animation(
+                this.with_motion(
                     ElementId::NamedInteger("toggle".into(), checked as u64),
                     Animation::new(Duration::from_secs_f64(0.25)),
                     move |this, delta| {
                         this.opacity(if checked { 1.0 * delta } else { 1.0 - delta })
                     },
+                    cx,
                 )
-                .into_any_element()
             } else {
                 this.into_any_element()
             }
         })
 }
 
+fn checkbox_indeterminate_icon(size: Size, disabled: bool, cx: &App) -> impl IntoElement {
+    let color = if disabled {
+        cx.theme().accent_foreground.opacity(0.5)
+    } else {
+        cx.theme().accent_foreground
+    };
+
+    svg()
+        .absolute()
+        .top_px()
+        .left_px()
+        .map(|this| match size {
+            Size::XSmall => this.size_2(),
+            Size::Small => this.size_2p5(),
+            Size::Medium => this.size_3(),
+            Size::Large => this.size_3p5(),
+            _ => this.size_3(),
+        })
+        .text_color(color)
+        .path(IconName::Minus.path())
+}
+
 impl RenderOnce for Checkbox {
     fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
         let checked = self.checked;
+        let indeterminate = self.indeterminate;
 
         let focus_handle = window
             .use_keyed_state(self.id.clone(), cx, |_, cx| cx.focus_handle())
@@ -199,7 +233,7 @@ impl RenderOnce for Checkbox {
             .clone();
         let is_focused = focus_handle.is_focused(window);
 
-        let border_color = if checked {
+        let border_color = if checked || indeterminate {
             cx.theme().primary
         } else {
             cx.theme().input
@@ -254,18 +288,29 @@ impl RenderOnce for Checkbox {
                         .border_color(color)
                         .rounded(radius)
                         .when(cx.theme().shadow && !self.disabled, |this| this.shadow_xs())
-                        .map(|this| match checked {
-                            false => this.bg(cx.theme().background),
+                        .map(|this| match (checked, indeterminate) {
+                            (_, true) => this.bg(cx.theme().accent),
+                            (false, _) => this.bg(cx.theme().background),
                             _ => this.bg(color),
                         })
-                        .child(checkbox_check_icon(
-                            self.id,
-                            self.size,
-                            checked,
-                            self.disabled,
-                            window,
-                            cx,
-                        )),
+                        .map(|this| {
+                            if indeterminate {
+                                this.child(checkbox_indeterminate_icon(
+                                    self.size,
+                                    self.disabled,
+                                    cx,
+                                ))
+                            } else {
+                                this.child(checkbox_check_icon(
+                                    self.id,
+                                    self.size,
+                                    checked,
+                                    self.disabled,
+                                    window,
+                                    cx,
+                                ))
+                            }
+                        }),
                 )
                 .when(self.label.is_some() || !self.children.is_empty(), |this| {
                     this.child(
@@ -301,7 +346,7 @@ impl RenderOnce for Checkbox {
                         let on_click = self.on_click.clone();
                         move |_, window, cx| {
                             window.prevent_default();
-                            Self::handle_click(&on_click, checked, window, cx);
+                            Self::handle_click(&on_click, checked, indeterminate, window, cx);
                         }
                     })
                 }),
@@ -0,0 +1,385 @@
+use std::{ops::Range, rc::Rc};
+
+use gpui::{
+    Action, App, AppContext as _, Context, DismissEvent, Edges, Entity, EventEmitter, FocusHandle,
+    Focusable, IntoElement, Keystroke, ParentElement as _, Render, SharedString, Styled as _,
+    Subscription, Task, Window, px, rems,
+};
+use rust_i18n::t;
+
+use crate::{
+    Icon, IndexPath, Selectable as _, StyledExt as _, WindowExt as _, h_flex,
+    kbd::Kbd,
+    label::Label,
+    list::{List, ListDelegate, ListEvent, ListItem, ListState},
+    tree::fuzzy_match,
+    v_flex,
+};
+
+/// A command in the [`CommandPalette`], that dispatches the action when executed.
+pub struct Command {
+    id: SharedString,
+    label: SharedString,
+    action: Box<dyn Action>,
+    icon: Option<Icon>,
+    key_binding: Option<Keystroke>,
+}
+
+impl Command {
+    /// Create a new command with the id, label and the action to dispatch.
+    pub fn new(
+        id: impl Into<SharedString>,
+        label: impl Into<SharedString>,
+        action: impl Action,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            action: Box::new(action),
+            icon: None,
+            key_binding: None,
+        }
+    }
+
+    /// Create a command from an action declared by `actions!`.
+    ///
+    /// The id is the action name, and the label is humanized from it,
+    /// e.g.: `story::ToggleSearch` to `Toggle Search`.
+    pub fn from_action(action: impl Action) -> Self {
+        let id = action.name();
+        Self::new(id, humanize_action_name(id), action)
+    }
+
+    /// Set the icon of the command.
+    pub fn icon(mut self, icon: impl Into<Icon>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Set the key binding to show, default is the first key binding of the action in the keymap.
+    pub fn key_binding(mut self, key_binding: Keystroke) -> Self {
+        self.key_binding = Some(key_binding);
+        self
+    }
+
+    /// Returns the id of the command.
+    pub fn id(&self) -> &SharedString {
+        &self.id
+    }
+
+    /// Returns the label of the command.
+    pub fn label(&self) -> &SharedString {
+        &self.label
+    }
+
+    /// Returns the action of the command.
+    pub fn action(&self) -> &dyn Action {
+        self.action.as_ref()
+    }
+}
+
+/// Convert the action name to a label, e.g.: `story::ToggleSearch` to `Toggle Search`.
+fn humanize_action_name(name: &str) -> String {
+    let name = name.rsplit("::").next().unwrap_or(name);
+
+    let mut label = String::with_capacity(name.len() + 4);
+    let mut prev: Option<char> = None;
+    for c in name.chars() {
+        if c == '_' {
+            label.push(' ');
+        } else {
+            if c.is_uppercase() && prev.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit()) {
+                label.push(' ');
+            }
+            label.push(c);
+        }
+        prev = Some(c);
+    }
+
+    label
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CommandMatch {
+    /// The index of the command.
+    ix: usize,
+    /// The matched byte ranges in the label.
+    ranges: Vec<Range<usize>>,
+}
+
+/// Fuzzy filter the labels by the query, the best matches first.
+///
+/// All the labels are matched in order if the query is empty.
+fn filter_commands<'a>(
+    labels: impl IntoIterator<Item = &'a str>,
+    query: &str,
+) -> Vec<CommandMatch> {
+    let query = query.trim();
+    if query.is_empty() {
+        return labels
+            .into_iter()
+            .enumerate()
+            .map(|(ix, _)| CommandMatch { ix, ranges: vec![] })
+            .collect();
+    }
+
+    let mut matches = labels
+        .into_iter()
+        .enumerate()
+        .filter_map(|(ix, label)| {
+            fuzzy_match(query, label).map(|m| {
+                (
+                    m.score,
+                    CommandMatch {
+                        ix,
+                        ranges: m.ranges,
+                    },
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by(|a, b| b.0.cmp(&a.0));
+    matches.into_iter().map(|(_, m)| m).collect()
+}
+
+struct CommandPaletteDelegate {
+    commands: Vec<Rc<Command>>,
+    matches: Vec<CommandMatch>,
+    selected_index: Option<IndexPath>,
+    /// The focus handle to dispatch the actions to.
+    target: Option<FocusHandle>,
+}
+
+impl CommandPaletteDelegate {
+    fn command(&self, ix: IndexPath) -> Option<Rc<Command>> {
+        let m = self.matches.get(ix.row)?;
+        self.commands.get(m.ix).cloned()
+    }
+}
+
+impl ListDelegate for CommandPaletteDelegate {
+    type Item = ListItem;
+
+    fn items_count(&self, _: usize, _: &App) -> usize {
+        self.matches.len()
+    }
+
+    fn perform_search(
+        &mut self,
+        query: &str,
+        _: &mut Window,
+        _: &mut Context<ListState<Self>>,
+    ) -> Task<()> {
+        self.matches = filter_commands(self.commands.iter().map(|c| c.label.as_ref()), query);
+        Task::ready(())
+    }
+
+    fn render_item(
+        &mut self,
+        ix: IndexPath,
+        window: &mut Window,
+        _: &mut Context<ListState<Self>>,
+    ) -> Option<Self::Item> {
+        let m = self.matches.get(ix.row)?;
+        let command = self.commands.get(m.ix)?;
+        let selected = Some(ix) == self.selected_index;
+
+        let kbd = match (&command.key_binding, &self.target) {
+            (Some(key_binding), _) => Some(Kbd::new(key_binding.clone())),
+            (None, Some(target)) => Kbd::binding_for_action_in(command.action(), target, window),
+            (None, None) => Kbd::binding_for_action(command.action(), None, window),
+        };
+
+        Some(
+            ListItem::new(("command", ix.row)).selected(selected).child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .children(command.icon.clone())
+                    .child(
+                        Label::new(command.label.clone())
+                            .highlight(m.ranges.clone())
+                            .flex_1()
+                            .truncate(),
+                    )
+                    .children(kbd),
+            ),
+        )
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: Option<IndexPath>,
+        _: &mut Window,
+        cx: &mut Context<ListState<Self>>,
+    ) {
+        self.selected_index = ix;
+        cx.notify();
+    }
+}
+
+/// A command palette to search and execute the commands, like the `Cmd-Shift-P` in the editors.
+///
+/// The commands are fuzzy filtered by the label as typing, use `up` and `down` to select,
+/// and `enter` to dispatch the action of the selected command to the element that was focused
+/// when the palette was created.
+///
+/// ```ignore
+/// CommandPalette::open(
+///     vec![
+///         Command::new("open-file", "Open File", OpenFile).icon(IconName::Folder),
+///         Command::from_action(ToggleSearch),
+///     ],
+///     window,
+///     cx,
+/// );
+/// ```
+pub struct CommandPalette {
+    list: Entity<ListState<CommandPaletteDelegate>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl CommandPalette {
+    /// Create a new command palette with the commands.
+    ///
+    /// The actions are dispatched to the current focused element of the window.
+    pub fn new(
+        commands: impl IntoIterator<Item = Command>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let commands: Vec<Rc<Command>> = commands.into_iter().map(Rc::new).collect();
+        let delegate = CommandPaletteDelegate {
+            matches: filter_commands(commands.iter().map(|c| c.label.as_ref()), ""),
+            commands,
+            selected_index: Some(IndexPath::default()),
+            target: window.focused(cx),
+        };
+        let list = cx.new(|cx| {
+            let mut list = ListState::new(delegate, window, cx).searchable(true);
+            list.set_selected_index(Some(IndexPath::default()), window, cx);
+            list
+        });
+
+        let _subscriptions = vec![cx.subscribe_in(&list, window, Self::on_list_event)];
+
+        Self {
+            list,
+            _subscriptions,
+        }
+    }
+
+    /// Open the command palette in a dialog, it will be closed after a command is executed
+    /// or pressed `escape`.
+    pub fn open(
+        commands: impl IntoIterator<Item = Command>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        let palette = cx.new(|cx| Self::new(commands, window, cx));
+        window
+            .subscribe(&palette, cx, |_, _: &DismissEvent, window, cx| {
+                window.close_dialog(cx);
+            })
+            .detach();
+
+        window.open_dialog(cx, {
+            let palette = palette.clone();
+            move |dialog, _, _| {
+                dialog
+                    .w(px(560.))
+                    .p_0()
+                    .close_button(false)
+                    .keyboard(false)
+                    .child(palette.clone())
+            }
+        });
+        palette.focus_handle(cx).focus(window);
+
+        palette
+    }
+
+    fn on_list_event(
+        &mut self,
+        _: &Entity<ListState<CommandPaletteDelegate>>,
+        event: &ListEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            ListEvent::Confirm(ix) => {
+                let delegate = self.list.read(cx).delegate();
+                let Some(command) = delegate.command(*ix) else {
+                    return;
+                };
+                if let Some(target) = &delegate.target {
+                    target.focus(window);
+                }
+
+                window.dispatch_action(command.action.boxed_clone(), cx);
+                cx.emit(DismissEvent);
+            }
+            ListEvent::Cancel => cx.emit(DismissEvent),
+            ListEvent::Select(_) => {}
+        }
+    }
+}
+
+impl EventEmitter<DismissEvent> for CommandPalette {}
+
+impl Focusable for CommandPalette {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.list.focus_handle(cx)
+    }
+}
+
+impl Render for CommandPalette {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w_full().child(
+            List::new(&self.list)
+                .search_placeholder(t!("CommandPalette.placeholder"))
+                .max_h(rems(24.))
+                .paddings(Edges::all(px(4.))),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommandMatch, filter_commands, humanize_action_name};
+
+    #[test]
+    fn test_humanize_action_name() {
+        assert_eq!(humanize_action_name("story::ToggleSearch"), "Toggle Search");
+        assert_eq!(humanize_action_name("OpenFile"), "Open File");
+        assert_eq!(humanize_action_name("editor::go_to_line"), "go to line");
+        assert_eq!(humanize_action_name("Clear"), "Clear");
+        assert_eq!(humanize_action_name("Tab2Prev"), "Tab2 Prev");
+    }
+
+    #[test]
+    fn test_filter_commands() {
+        let labels = ["Open File", "Toggle Compression", "Clear", "Close File"];
+
+        let all = filter_commands(labels, "");
+        assert_eq!(
+            all.iter().map(|m| m.ix).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert!(all.iter().all(|m| m.ranges.is_empty()));
+
+        assert_eq!(
+            filter_commands(labels, "tc"),
+            vec![CommandMatch {
+                ix: 1,
+                ranges: vec![0..1, 7..8],
+            }]
+        );
+
+        let matches = filter_commands(labels, "cl");
+        assert_eq!(matches.iter().map(|m| m.ix).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(matches[0].ranges, vec![0..2]);
+
+        assert!(filter_commands(labels, "xyz").is_empty());
+    }
+}
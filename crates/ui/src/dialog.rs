@@ -1,17 +1,17 @@
 use std::{rc::Rc, time::Duration};
 
 use gpui::{
-    Animation, AnimationExt as _, AnyElement, App, Bounds, BoxShadow, ClickEvent, Edges,
-    FocusHandle, Hsla, InteractiveElement, IntoElement, KeyBinding, MouseButton, ParentElement,
-    Pixels, Point, RenderOnce, SharedString, StyleRefinement, Styled, Window, anchored, div, hsla,
-    point, prelude::FluentBuilder, px, relative,
+    Animation, AnyElement, App, Bounds, BoxShadow, ClickEvent, Edges, FocusHandle, Hsla,
+    InteractiveElement, IntoElement, KeyBinding, MouseButton, ParentElement, Pixels, Point,
+    RenderOnce, SharedString, StyleRefinement, Styled, Window, anchored, div, hsla, point,
+    prelude::FluentBuilder, px, relative,
 };
 use rust_i18n::t;
 
 use crate::{
     ActiveTheme as _, IconName, Root, Sizable as _, StyledExt, TITLE_BAR_HEIGHT, WindowExt as _,
     actions::{Cancel, Confirm},
-    animation::cubic_bezier,
+    animation::{MotionExt as _, cubic_bezier},
     button::{Button, ButtonVariant, ButtonVariants as _},
     h_flex,
     scroll::ScrollableElement as _,
@@ -222,6 +222,31 @@ impl Dialog {
         self
     }
 
+    /// Send the choice to the `tx` when the dialog is closed by ok or cancel, `true` for ok.
+    pub(crate) fn result_sender(mut self, tx: smol::channel::Sender<bool>) -> Self {
+        let on_ok = self.on_ok.take();
+        self.on_ok = Some(Rc::new({
+            let tx = tx.clone();
+            move |event, window, cx| {
+                let close = on_ok.as_ref().map_or(true, |f| f(event, window, cx));
+                if close {
+                    _ = tx.try_send(true);
+                }
+                close
+            }
+        }));
+
+        let on_cancel = self.on_cancel.clone();
+        self.on_cancel = Rc::new(move |event, window, cx| {
+            let close = on_cancel(event, window, cx);
+            if close {
+                _ = tx.try_send(false);
+            }
+            close
+        });
+        self
+    }
+
     /// Sets the false to hide close icon, default: true
     pub fn close_button(mut self, close_button: bool) -> Self {
         self.close_button = close_button;
@@ -545,27 +570,37 @@ impl RenderOnce for Dialog {
                                         .children(footer(render_ok, render_cancel, window, cx)),
                                 )
                             })
-                            .with_animation("slide-down", animation.clone(), move |this, delta| {
-                                let y_offset = px(0.) + delta * px(30.);
-                                // This is equivalent to `shadow_xl` with an extra opacity.
-                                let shadow = vec![
-                                    BoxShadow {
-                                        color: hsla(0., 0., 0., 0.1 * delta),
-                                        offset: point(px(0.), px(20.)),
-                                        blur_radius: px(25.),
-                                        spread_radius: px(-5.),
-                                    },
-                                    BoxShadow {
-                                        color: hsla(0., 0., 0., 0.1 * delta),
-                                        offset: point(px(0.), px(8.)),
-                                        blur_radius: px(10.),
-                                        spread_radius: px(-6.),
-                                    },
-                                ];
-                                this.top(y + y_offset).shadow(shadow)
-                            }),
+                            .with_motion(
+                                "slide-down",
+                                animation.clone(),
+                                move |this, delta| {
+                                    let y_offset = px(0.) + delta * px(30.);
+                                    // This is equivalent to `shadow_xl` with an extra opacity.
+                                    let shadow = vec![
+                                        BoxShadow {
+                                            color: hsla(0., 0., 0., 0.1 * delta),
+                                            offset: point(px(0.), px(20.)),
+                                            blur_radius: px(25.),
+                                            spread_radius: px(-5.),
+                                        },
+                                        BoxShadow {
+                                            color: hsla(0., 0., 0., 0.1 * delta),
+                                            offset: point(px(0.), px(8.)),
+                                            blur_radius: px(10.),
+                                            spread_radius: px(-6.),
+                                        },
+                                    ];
+                                    this.top(y + y_offset).shadow(shadow)
+                                },
+                                cx,
+                            ),
                     )
-                    .with_animation("fade-in", animation, move |this, delta| this.opacity(delta)),
+                    .with_motion(
+                        "fade-in",
+                        animation,
+                        move |this, delta| this.opacity(delta),
+                        cx,
+                    ),
             )
     }
 }
@@ -0,0 +1,385 @@
+use std::ops::Range;
+
+/// A changed region between the old and new lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// The range of the old lines.
+    pub old: Range<usize>,
+    /// The range of the new lines.
+    pub new: Range<usize>,
+}
+
+impl DiffHunk {
+    /// Return true if this hunk only inserts new lines.
+    #[inline]
+    pub fn is_insert(&self) -> bool {
+        self.old.is_empty()
+    }
+
+    /// Return true if this hunk only deletes old lines.
+    #[inline]
+    pub fn is_delete(&self) -> bool {
+        self.new.is_empty()
+    }
+}
+
+/// Split the text into lines, each line includes the line ending.
+pub fn split_lines(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+/// Diff the lines of two texts, returns the changed hunks.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffHunk> {
+    let old = split_lines(old);
+    let new = split_lines(new);
+    diff(&old, &new)
+}
+
+/// Diff two texts by lines, returns the changed hunks with the byte ranges of the texts.
+///
+/// The common prefix and suffix of each changed lines are trimmed, so the hunk only
+/// covers the changed chars, this is useful to apply the diff as the minimal edits.
+pub fn diff_text(old: &str, new: &str) -> Vec<DiffHunk> {
+    let old_lines = split_lines(old);
+    let new_lines = split_lines(new);
+    let line_offsets = |lines: &[&str]| {
+        let mut offsets = Vec::with_capacity(lines.len() + 1);
+        offsets.push(0);
+        for line in lines {
+            offsets.push(offsets[offsets.len() - 1] + line.len());
+        }
+        offsets
+    };
+    let old_offsets = line_offsets(&old_lines);
+    let new_offsets = line_offsets(&new_lines);
+
+    diff(&old_lines, &new_lines)
+        .into_iter()
+        .map(|hunk| {
+            let mut old_range = old_offsets[hunk.old.start]..old_offsets[hunk.old.end];
+            let mut new_range = new_offsets[hunk.new.start]..new_offsets[hunk.new.end];
+
+            let prefix: usize = old[old_range.clone()]
+                .chars()
+                .zip(new[new_range.clone()].chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(c, _)| c.len_utf8())
+                .sum();
+            old_range.start += prefix;
+            new_range.start += prefix;
+
+            let suffix: usize = old[old_range.clone()]
+                .chars()
+                .rev()
+                .zip(new[new_range.clone()].chars().rev())
+                .take_while(|(a, b)| a == b)
+                .map(|(c, _)| c.len_utf8())
+                .sum();
+            old_range.end -= suffix;
+            new_range.end -= suffix;
+
+            DiffHunk {
+                old: old_range,
+                new: new_range,
+            }
+        })
+        .collect()
+}
+
+/// Diff two sequences, returns the changed hunks.
+pub fn diff<T: PartialEq>(old: &[T], new: &[T]) -> Vec<DiffHunk> {
+    let mut hunks = vec![];
+    let (mut old_ix, mut new_ix) = (0, 0);
+    for (old_match, new_match) in matches(old, new)
+        .into_iter()
+        .chain(std::iter::once((old.len(), new.len())))
+    {
+        if old_match > old_ix || new_match > new_ix {
+            hunks.push(DiffHunk {
+                old: old_ix..old_match,
+                new: new_ix..new_match,
+            });
+        }
+        old_ix = old_match + 1;
+        new_ix = new_match + 1;
+    }
+
+    hunks
+}
+
+/// Returns the matched `(old_ix, new_ix)` pairs of the longest common subsequence.
+pub(crate) fn matches<T: PartialEq>(old: &[T], new: &[T]) -> Vec<(usize, usize)> {
+    myers(old, new)
+}
+
+/// The maximum edit cost to search the middle snake, the larger regions are treated as replaced,
+/// to keep the time of the unrelated inputs bounded.
+const MAX_EDIT_COST: isize = 2048;
+
+/// The Myers' O(ND) diff algorithm in linear space, returns the matched pairs.
+///
+/// http://www.xmailserver.org/diff2.pdf
+fn myers<T: PartialEq>(a: &[T], b: &[T]) -> Vec<(usize, usize)> {
+    let mut vf = V::new(a.len() + b.len());
+    let mut vb = V::new(a.len() + b.len());
+    let mut matches = vec![];
+    conquer(a, 0, b, 0, &mut vf, &mut vb, &mut matches);
+    matches
+}
+
+/// The furthest reaching x of each diagonal `k`, indexed from `-max` to `max`.
+struct V {
+    offset: isize,
+    v: Vec<isize>,
+}
+
+impl V {
+    fn new(max: usize) -> Self {
+        Self {
+            offset: max as isize + 1,
+            v: vec![0; 2 * max + 3],
+        }
+    }
+}
+
+impl std::ops::Index<isize> for V {
+    type Output = isize;
+
+    fn index(&self, k: isize) -> &isize {
+        &self.v[(k + self.offset) as usize]
+    }
+}
+
+impl std::ops::IndexMut<isize> for V {
+    fn index_mut(&mut self, k: isize) -> &mut isize {
+        &mut self.v[(k + self.offset) as usize]
+    }
+}
+
+/// Divide the inputs by the middle snake recursively, and push the matched pairs in order.
+fn conquer<T: PartialEq>(
+    a: &[T],
+    a_start: usize,
+    b: &[T],
+    b_start: usize,
+    vf: &mut V,
+    vb: &mut V,
+    matches: &mut Vec<(usize, usize)>,
+) {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    matches.extend((0..prefix).map(|ix| (a_start + ix, b_start + ix)));
+    let (a, b) = (&a[prefix..], &b[prefix..]);
+    let (a_start, b_start) = (a_start + prefix, b_start + prefix);
+
+    let suffix = a
+        .iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a, b) = (&a[..a.len() - suffix], &b[..b.len() - suffix]);
+
+    if !a.is_empty()
+        && !b.is_empty()
+        && let Some((x, y)) = middle_snake(a, b, vf, vb)
+    {
+        conquer(&a[..x], a_start, &b[..y], b_start, vf, vb, matches);
+        conquer(&a[x..], a_start + x, &b[y..], b_start + y, vf, vb, matches);
+    }
+
+    matches.extend((0..suffix).map(|ix| (a_start + a.len() + ix, b_start + b.len() + ix)));
+}
+
+/// Find the start of the middle snake of the shortest edit script to split the inputs,
+/// the inputs must be not empty, and have no common prefix or suffix.
+///
+/// Returns `None` if the edit cost is over the [`MAX_EDIT_COST`].
+fn middle_snake<T: PartialEq>(a: &[T], b: &[T], vf: &mut V, vb: &mut V) -> Option<(usize, usize)> {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let delta = n - m;
+    let odd = delta & 1 == 1;
+    let max_d = ((n + m + 1) / 2).min(MAX_EDIT_COST);
+
+    vf[1] = 0;
+    vb[1] = 0;
+    for d in 0..=max_d {
+        // Forward from the top left.
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && vf[k - 1] < vf[k + 1]) {
+                vf[k + 1]
+            } else {
+                vf[k - 1] + 1
+            };
+            let (x0, y0) = (x, x - k);
+            let mut y = y0;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            vf[k] = x;
+
+            if odd && (k - delta).abs() < d && vf[k] + vb[-(k - delta)] >= n {
+                return Some((x0 as usize, y0 as usize));
+            }
+        }
+
+        // Backward from the bottom right, the x is counted from the end.
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && vb[k - 1] < vb[k + 1]) {
+                vb[k + 1]
+            } else {
+                vb[k - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[(n - x - 1) as usize] == b[(m - y - 1) as usize] {
+                x += 1;
+                y += 1;
+            }
+            vb[k] = x;
+
+            if !odd && (k - delta).abs() <= d && vb[k] + vf[-(k - delta)] >= n {
+                return Some(((n - x) as usize, (m - y) as usize));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        assert_eq!(diff_lines("a\nb\nc\n", "a\nb\nc\n"), vec![]);
+        assert_eq!(
+            diff_lines("a\nb\nc\n", "a\nB\nc\n"),
+            vec![DiffHunk {
+                old: 1..2,
+                new: 1..2
+            }]
+        );
+        assert_eq!(
+            diff_lines("a\nc\n", "a\nb\nc\nd\n"),
+            vec![
+                DiffHunk {
+                    old: 1..1,
+                    new: 1..2
+                },
+                DiffHunk {
+                    old: 2..2,
+                    new: 3..4
+                }
+            ]
+        );
+        assert_eq!(
+            diff_lines("a\nb\nc\nd\n", "b\nd\n"),
+            vec![
+                DiffHunk {
+                    old: 0..1,
+                    new: 0..0
+                },
+                DiffHunk {
+                    old: 2..3,
+                    new: 1..1
+                }
+            ]
+        );
+        assert_eq!(
+            diff_lines("", "a\n"),
+            vec![DiffHunk {
+                old: 0..0,
+                new: 0..1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_text() {
+        assert_eq!(diff_text("a\nb\n", "a\nb\n"), vec![]);
+        assert_eq!(
+            diff_text("let a = 1;\nlet b = 2;\n", "let a = 1;\nlet b = 20;\n"),
+            vec![DiffHunk {
+                old: 20..20,
+                new: 20..21
+            }]
+        );
+        assert_eq!(
+            diff_text("{\"a\":1}", "{\n  \"a\": 1\n}"),
+            vec![DiffHunk {
+                old: 1..6,
+                new: 1..11
+            }]
+        );
+        // Multibyte chars
+        assert_eq!(
+            diff_text("你好\n世界\n", "你好\n世间\n"),
+            vec![DiffHunk {
+                old: 10..13,
+                new: 10..13
+            }]
+        );
+        assert_eq!(
+            diff_text("a\nb\nc\n", "a\nc\n"),
+            vec![DiffHunk {
+                old: 2..4,
+                new: 2..2
+            }]
+        );
+    }
+
+    #[test]
+    fn test_matches() {
+        let old: Vec<char> = "ABCABBA".chars().collect();
+        let new: Vec<char> = "CBABAC".chars().collect();
+        let matches = matches(&old, &new);
+        // The length of the LCS is 4.
+        assert_eq!(matches.len(), 4);
+        for (a, b) in &matches {
+            assert_eq!(old[*a], new[*b]);
+        }
+        for pair in matches.windows(2) {
+            assert!(pair[0].0 < pair[1].0 && pair[0].1 < pair[1].1);
+        }
+
+        let old: Vec<char> = "the quick brown fox jumps over the lazy dog"
+            .chars()
+            .collect();
+        let new: Vec<char> = "a quick red fox leaps over lazy dogs".chars().collect();
+        let matches = super::matches(&old, &new);
+        assert_eq!(matches.len(), 29);
+        for (a, b) in &matches {
+            assert_eq!(old[*a], new[*b]);
+        }
+    }
+
+    #[test]
+    fn test_diff_large_unrelated() {
+        let old = (0..10_000)
+            .map(|ix| format!("old {}\n", ix))
+            .collect::<String>();
+        let new = (0..10_000)
+            .map(|ix| format!("new {}\n", ix))
+            .collect::<String>();
+        assert_eq!(
+            diff_lines(&old, &new),
+            vec![DiffHunk {
+                old: 0..10_000,
+                new: 0..10_000
+            }]
+        );
+
+        // The common lines are still matched around the unrelated region.
+        let old = format!("a\n{}z\n", old);
+        let new = format!("a\n{}z\n", new);
+        assert_eq!(
+            diff_lines(&old, &new),
+            vec![DiffHunk {
+                old: 1..10_001,
+                new: 1..10_001
+            }]
+        );
+    }
+}
@@ -0,0 +1,738 @@
+use std::{ops::Range, time::Duration};
+
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, HighlightStyle, Hsla,
+    InteractiveElement as _, IntoElement, ListHorizontalSizingBehavior, ListSizingBehavior,
+    ParentElement, Pixels, Render, SharedString, Styled, StyledText, Subscription, Task, TextRun,
+    UniformListScrollHandle, Window, div, prelude::FluentBuilder as _, px, rems, uniform_list,
+};
+
+use super::{DiffHunk, diff, split_lines};
+use crate::{
+    ActiveTheme, h_flex,
+    input::{InputEvent, InputState},
+};
+
+/// The delay to diff the texts again after the followed [`InputState`]s changed.
+const DIFF_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// The layout of the [`DiffView`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiffLayout {
+    /// The old and new texts are side by side, the lines are aligned.
+    #[default]
+    Split,
+    /// The removed lines are followed by the added lines in a single pane.
+    Unified,
+}
+
+/// Events emitted by the [`DiffView`].
+pub enum DiffViewEvent {
+    /// The diff has been updated.
+    Change,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffLineKind {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+/// A line of the old or new text in the [`DiffView`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DiffLine {
+    /// The line number (zero-based) in the old or new text.
+    line: usize,
+    /// The line text without the line ending.
+    text: SharedString,
+    kind: DiffLineKind,
+    /// The changed byte ranges of the `text`, only for the changed line that is paired
+    /// with a line of the other side.
+    changes: Vec<Range<usize>>,
+}
+
+/// A row of the [`DiffView`], the old and new lines are aligned in a row.
+///
+/// One side is `None` if the line is only added or removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DiffRow {
+    old: Option<DiffLine>,
+    new: Option<DiffLine>,
+}
+
+impl DiffRow {
+    #[inline]
+    fn is_unchanged(&self) -> bool {
+        self.old
+            .as_ref()
+            .is_some_and(|line| line.kind == DiffLineKind::Unchanged)
+    }
+}
+
+#[inline]
+fn trim_line_end(line: &str) -> &str {
+    line.trim_end_matches(['\n', '\r'])
+}
+
+/// Diff the chars of two lines, returns the changed byte ranges of the old and new line.
+fn diff_chars(old: &str, new: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let old_chars = old.chars().collect::<Vec<_>>();
+    let new_chars = new.chars().collect::<Vec<_>>();
+    let offsets = |text: &str| {
+        text.char_indices()
+            .map(|(ix, _)| ix)
+            .chain(std::iter::once(text.len()))
+            .collect::<Vec<_>>()
+    };
+    let old_offsets = offsets(old);
+    let new_offsets = offsets(new);
+
+    diff(&old_chars, &new_chars)
+        .into_iter()
+        .map(|DiffHunk { old, new }| {
+            (
+                old_offsets[old.start]..old_offsets[old.end],
+                new_offsets[new.start]..new_offsets[new.end],
+            )
+        })
+        .unzip()
+}
+
+/// The diff of the texts, computed in the background.
+struct DiffResult {
+    hunks: Vec<DiffHunk>,
+    /// The rows of the [`DiffLayout::Split`].
+    rows: Vec<DiffRow>,
+    /// The max number of lines of the old and new texts.
+    max_lines: usize,
+    /// The line with the most chars, to measure the width of the text column.
+    longest_line: SharedString,
+}
+
+/// Diff two texts by lines, the hunks are computed once and used to build the rows.
+fn diff_texts(old: &str, new: &str) -> DiffResult {
+    let old_lines = split_lines(old);
+    let new_lines = split_lines(new);
+    let hunks = diff(&old_lines, &new_lines);
+    let rows = diff_rows(&old_lines, &new_lines, &hunks);
+    let longest_line = old_lines
+        .iter()
+        .chain(new_lines.iter())
+        .map(|line| trim_line_end(line))
+        .max_by_key(|line| line.chars().count())
+        .unwrap_or_default();
+
+    DiffResult {
+        max_lines: old_lines.len().max(new_lines.len()),
+        longest_line: longest_line.to_string().into(),
+        hunks,
+        rows,
+    }
+}
+
+/// Returns the aligned rows of the line hunks for the [`DiffLayout::Split`].
+///
+/// The removed and added lines of a hunk are paired in order, the paired lines are
+/// diffed by chars to highlight the changes.
+fn diff_rows(old_lines: &[&str], new_lines: &[&str], hunks: &[DiffHunk]) -> Vec<DiffRow> {
+    let line =
+        |lines: &[&str], ix: usize, kind: DiffLineKind, changes: Vec<Range<usize>>| DiffLine {
+            line: ix,
+            text: trim_line_end(lines[ix]).to_string().into(),
+            kind,
+            changes,
+        };
+
+    let mut rows = vec![];
+    let (mut old_ix, mut new_ix) = (0, 0);
+    let end = DiffHunk {
+        old: old_lines.len()..old_lines.len(),
+        new: new_lines.len()..new_lines.len(),
+    };
+    for hunk in hunks.iter().chain([&end]) {
+        while old_ix < hunk.old.start {
+            rows.push(DiffRow {
+                old: Some(line(old_lines, old_ix, DiffLineKind::Unchanged, vec![])),
+                new: Some(line(new_lines, new_ix, DiffLineKind::Unchanged, vec![])),
+            });
+            old_ix += 1;
+            new_ix += 1;
+        }
+
+        for ix in 0..hunk.old.len().max(hunk.new.len()) {
+            let old_ix = (ix < hunk.old.len()).then_some(hunk.old.start + ix);
+            let new_ix = (ix < hunk.new.len()).then_some(hunk.new.start + ix);
+            let (old_changes, new_changes) = match (old_ix, new_ix) {
+                (Some(old_ix), Some(new_ix)) => diff_chars(
+                    trim_line_end(old_lines[old_ix]),
+                    trim_line_end(new_lines[new_ix]),
+                ),
+                _ => (vec![], vec![]),
+            };
+
+            rows.push(DiffRow {
+                old: old_ix.map(|ix| line(old_lines, ix, DiffLineKind::Removed, old_changes)),
+                new: new_ix.map(|ix| line(new_lines, ix, DiffLineKind::Added, new_changes)),
+            });
+        }
+
+        old_ix = hunk.old.end;
+        new_ix = hunk.new.end;
+    }
+
+    rows
+}
+
+/// Convert the split rows to the rows of [`DiffLayout::Unified`].
+///
+/// The unchanged rows are kept, and the changed rows are split into the removed rows
+/// followed by the added rows, each of them only has one side.
+fn unified_rows(rows: Vec<DiffRow>) -> Vec<DiffRow> {
+    let mut unified = Vec::with_capacity(rows.len());
+    let mut added = vec![];
+    for row in rows {
+        if row.is_unchanged() {
+            unified.append(&mut added);
+            unified.push(row);
+            continue;
+        }
+
+        if let Some(old) = row.old {
+            unified.push(DiffRow {
+                old: Some(old),
+                new: None,
+            });
+        }
+        if let Some(new) = row.new {
+            added.push(DiffRow {
+                old: None,
+                new: Some(new),
+            });
+        }
+    }
+    unified.append(&mut added);
+
+    unified
+}
+
+/// A view to show the line based diff of two texts.
+///
+/// The added, removed and changed lines are highlighted, with the changed chars of the
+/// changed lines. In [`DiffLayout::Split`] the old and new texts are aligned side by side
+/// in the same rows, so they are always scrolled together.
+///
+/// The diff is computed in the background, and [`DiffViewEvent::Change`] is emitted when done.
+///
+/// ```ignore
+/// let diff_view = cx.new(|cx| {
+///     let mut view = DiffView::new(window, cx).layout(DiffLayout::Unified);
+///     view.set_texts(raw, formatted, cx);
+///     view
+/// });
+///
+/// // Or follow the changes of two editors.
+/// diff_view.update(cx, |view, cx| view.set_inputs(&input, &output, cx));
+/// ```
+pub struct DiffView {
+    focus_handle: FocusHandle,
+    layout: DiffLayout,
+    old_text: SharedString,
+    new_text: SharedString,
+    hunks: Vec<DiffHunk>,
+    /// The rows of the [`DiffLayout::Split`], to switch the layout without diffing again.
+    split_rows: Vec<DiffRow>,
+    /// The rows of the current layout.
+    rows: Vec<DiffRow>,
+    /// The max number of lines of the old and new texts.
+    max_lines: usize,
+    longest_line: SharedString,
+    scroll_handle: UniformListScrollHandle,
+    _diff_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DiffView {
+    /// Create a new empty diff view.
+    pub fn new(_: &mut Window, cx: &mut Context<Self>) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            layout: DiffLayout::default(),
+            old_text: SharedString::default(),
+            new_text: SharedString::default(),
+            hunks: vec![],
+            split_rows: vec![],
+            rows: vec![],
+            max_lines: 0,
+            longest_line: SharedString::default(),
+            scroll_handle: UniformListScrollHandle::default(),
+            _diff_task: Task::ready(()),
+            _subscriptions: vec![],
+        }
+    }
+
+    /// Set the layout, default is [`DiffLayout::Split`].
+    pub fn layout(mut self, layout: DiffLayout) -> Self {
+        self.layout = layout;
+        self.layout_rows();
+        self
+    }
+
+    /// Set the layout, see [`DiffView::layout`].
+    pub fn set_layout(&mut self, layout: DiffLayout, cx: &mut Context<Self>) {
+        if self.layout == layout {
+            return;
+        }
+
+        self.layout = layout;
+        self.layout_rows();
+        cx.notify();
+    }
+
+    /// Returns the layout of the diff view.
+    pub fn get_layout(&self) -> DiffLayout {
+        self.layout
+    }
+
+    /// Set the old and new texts to diff.
+    ///
+    /// This stops following the [`InputState`]s set by [`DiffView::set_inputs`].
+    pub fn set_texts(
+        &mut self,
+        old: impl Into<SharedString>,
+        new: impl Into<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        self._subscriptions.clear();
+        self.update_texts(old.into(), new.into(), None, cx);
+    }
+
+    /// Diff the texts of two [`InputState`]s, and update the diff when they changed.
+    ///
+    /// The changes are debounced, the diff is updated after the typing is paused.
+    pub fn set_inputs(
+        &mut self,
+        old: &Entity<InputState>,
+        new: &Entity<InputState>,
+        cx: &mut Context<Self>,
+    ) {
+        let update = |old: Entity<InputState>, new: Entity<InputState>| {
+            move |this: &mut Self,
+                  _: Entity<InputState>,
+                  event: &InputEvent,
+                  cx: &mut Context<Self>| {
+                if let InputEvent::Change = event {
+                    let old_text = old.read(cx).value();
+                    let new_text = new.read(cx).value();
+                    this.update_texts(old_text, new_text, Some(DIFF_DEBOUNCE), cx);
+                }
+            }
+        };
+
+        self._subscriptions = vec![
+            cx.subscribe(old, update(old.clone(), new.clone())),
+            cx.subscribe(new, update(old.clone(), new.clone())),
+        ];
+        self.update_texts(old.read(cx).value(), new.read(cx).value(), None, cx);
+    }
+
+    /// Returns the changed hunks of the lines.
+    pub fn hunks(&self) -> &[DiffHunk] {
+        &self.hunks
+    }
+
+    fn update_texts(
+        &mut self,
+        old: SharedString,
+        new: SharedString,
+        debounce: Option<Duration>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.old_text == old && self.new_text == new {
+            return;
+        }
+
+        self.old_text = old.clone();
+        self.new_text = new.clone();
+        // Replace the pending diff, only the latest texts are diffed.
+        self._diff_task = cx.spawn(async move |this, cx| {
+            if let Some(debounce) = debounce {
+                cx.background_executor().timer(debounce).await;
+            }
+            let result = cx
+                .background_spawn(async move { diff_texts(&old, &new) })
+                .await;
+            _ = this.update(cx, |this, cx| {
+                this.hunks = result.hunks;
+                this.split_rows = result.rows;
+                this.max_lines = result.max_lines;
+                this.longest_line = result.longest_line;
+                this.layout_rows();
+                cx.emit(DiffViewEvent::Change);
+                cx.notify();
+            });
+        });
+    }
+
+    fn layout_rows(&mut self) {
+        self.rows = match self.layout {
+            DiffLayout::Split => self.split_rows.clone(),
+            DiffLayout::Unified => unified_rows(self.split_rows.clone()),
+        };
+    }
+
+    /// Returns the width of the line numbers to fit the max line number.
+    fn line_number_width(&self) -> Pixels {
+        let digits = self.max_lines.max(1).to_string().len().max(2);
+        px(8. * digits as f32 + 8.)
+    }
+
+    /// Returns the width of the text column to fit the longest line.
+    fn text_width(&self, window: &mut Window, cx: &App) -> Pixels {
+        if self.longest_line.is_empty() {
+            return px(0.);
+        }
+
+        let font_size = rems(0.875).to_pixels(window.rem_size());
+        window
+            .text_system()
+            .shape_line(
+                self.longest_line.clone(),
+                font_size,
+                &[TextRun {
+                    len: self.longest_line.len(),
+                    font: gpui::font(cx.theme().mono_font_family.clone()),
+                    color: gpui::black(),
+                    background_color: None,
+                    underline: None,
+                    strikethrough: None,
+                }],
+                None,
+            )
+            .width
+    }
+}
+
+/// Returns the background and the changed chars background of the line kind.
+fn line_colors(kind: DiffLineKind, cx: &App) -> (Option<Hsla>, Hsla) {
+    match kind {
+        DiffLineKind::Unchanged => (None, cx.theme().transparent),
+        DiffLineKind::Added => (
+            Some(cx.theme().success.opacity(0.15)),
+            cx.theme().success.opacity(0.35),
+        ),
+        DiffLineKind::Removed => (
+            Some(cx.theme().danger.opacity(0.15)),
+            cx.theme().danger.opacity(0.35),
+        ),
+    }
+}
+
+fn render_line_number(line: Option<usize>, width: Pixels, cx: &App) -> impl IntoElement {
+    div()
+        .flex_shrink_0()
+        .w(width)
+        .pr_2()
+        .text_right()
+        .text_color(cx.theme().muted_foreground)
+        .when_some(line, |this, line| this.child((line + 1).to_string()))
+}
+
+fn render_text(line: &DiffLine, text_width: Pixels, cx: &App) -> impl IntoElement {
+    let (_, change_bg) = line_colors(line.kind, cx);
+    let highlights = line
+        .changes
+        .iter()
+        .map(|range| {
+            let style = HighlightStyle {
+                background_color: Some(change_bg),
+                ..Default::default()
+            };
+            (range.clone(), style)
+        })
+        .collect::<Vec<_>>();
+
+    div()
+        .flex_1()
+        .min_w(text_width)
+        .whitespace_nowrap()
+        .child(StyledText::new(line.text.clone()).with_highlights(highlights))
+}
+
+fn render_marker(kind: Option<DiffLineKind>, cx: &App) -> impl IntoElement {
+    let (marker, color) = match kind {
+        Some(DiffLineKind::Added) => ("+", cx.theme().success),
+        Some(DiffLineKind::Removed) => ("-", cx.theme().danger),
+        _ => ("", cx.theme().muted_foreground),
+    };
+
+    div()
+        .flex_shrink_0()
+        .w_4()
+        .text_center()
+        .text_color(color)
+        .child(marker)
+}
+
+/// Render a side of the [`DiffLayout::Split`] row, the empty side is filled with the muted color.
+fn render_split_line(
+    line: Option<&DiffLine>,
+    number_width: Pixels,
+    text_width: Pixels,
+    cx: &App,
+) -> AnyElement {
+    let Some(line) = line else {
+        return div()
+            .flex_1()
+            .h_full()
+            .bg(cx.theme().muted.opacity(0.5))
+            .into_any_element();
+    };
+
+    let (bg, _) = line_colors(line.kind, cx);
+    h_flex()
+        .flex_1()
+        .min_w_0()
+        .h_full()
+        .overflow_hidden()
+        .when_some(bg, |this, bg| this.bg(bg))
+        .child(render_line_number(Some(line.line), number_width, cx))
+        .child(render_marker(Some(line.kind), cx))
+        .child(render_text(line, text_width, cx))
+        .into_any_element()
+}
+
+/// Render a row of the [`DiffLayout::Unified`], with the old and new line numbers.
+fn render_unified_line(
+    row: &DiffRow,
+    number_width: Pixels,
+    text_width: Pixels,
+    cx: &App,
+) -> AnyElement {
+    let Some(line) = row.new.as_ref().or(row.old.as_ref()) else {
+        return div().into_any_element();
+    };
+
+    let (bg, _) = line_colors(line.kind, cx);
+    h_flex()
+        .flex_1()
+        .min_w_0()
+        .h_full()
+        .overflow_hidden()
+        .when_some(bg, |this, bg| this.bg(bg))
+        .child(render_line_number(
+            row.old.as_ref().map(|line| line.line),
+            number_width,
+            cx,
+        ))
+        .child(render_line_number(
+            row.new.as_ref().map(|line| line.line),
+            number_width,
+            cx,
+        ))
+        .child(render_marker(Some(line.kind), cx))
+        .child(render_text(line, text_width, cx))
+        .into_any_element()
+}
+
+impl EventEmitter<DiffViewEvent> for DiffView {}
+
+impl Focusable for DiffView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DiffView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let number_width = self.line_number_width();
+        let text_width = self.text_width(window, cx);
+        // The long lines are scrolled horizontally instead of clipped, so the rows are at
+        // least wide enough to fit the longest line.
+        let marker_width = rems(1.).to_pixels(window.rem_size());
+        let row_width = match self.layout {
+            DiffLayout::Split => (number_width + marker_width + text_width) * 2. + px(1.),
+            DiffLayout::Unified => number_width * 2. + marker_width + text_width,
+        };
+
+        div()
+            .id("diff-view")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .font_family(cx.theme().mono_font_family.clone())
+            .text_sm()
+            .child(
+                uniform_list("rows", self.rows.len(), {
+                    cx.processor(move |this, visible_range: Range<usize>, _, cx| {
+                        visible_range
+                            .map(|ix| {
+                                let row = &this.rows[ix];
+                                h_flex()
+                                    .id(ix)
+                                    .w_full()
+                                    .min_w(row_width)
+                                    .h(rems(1.25))
+                                    .map(|el| match this.layout {
+                                        DiffLayout::Split => el
+                                            .gap(px(1.))
+                                            .child(render_split_line(
+                                                row.old.as_ref(),
+                                                number_width,
+                                                text_width,
+                                                cx,
+                                            ))
+                                            .child(render_split_line(
+                                                row.new.as_ref(),
+                                                number_width,
+                                                text_width,
+                                                cx,
+                                            )),
+                                        DiffLayout::Unified => el.child(render_unified_line(
+                                            row,
+                                            number_width,
+                                            text_width,
+                                            cx,
+                                        )),
+                                    })
+                            })
+                            .collect()
+                    })
+                })
+                .size_full()
+                .track_scroll(&self.scroll_handle)
+                .with_sizing_behavior(ListSizingBehavior::Auto)
+                .with_horizontal_sizing_behavior(ListHorizontalSizingBehavior::Unconstrained)
+                .with_width_from_item((!self.rows.is_empty()).then_some(0)),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DiffLineKind, DiffRow, diff_chars, diff_texts, unified_rows};
+
+    fn diff_rows(old: &str, new: &str) -> Vec<DiffRow> {
+        diff_texts(old, new).rows
+    }
+
+    fn describe(rows: &[DiffRow]) -> Vec<String> {
+        let side = |line: Option<&super::DiffLine>| match line {
+            Some(line) => {
+                let marker = match line.kind {
+                    DiffLineKind::Unchanged => " ",
+                    DiffLineKind::Added => "+",
+                    DiffLineKind::Removed => "-",
+                };
+                format!("{}{}:{}", marker, line.line, line.text)
+            }
+            None => "_".to_string(),
+        };
+
+        rows.iter()
+            .map(|row| format!("{} | {}", side(row.old.as_ref()), side(row.new.as_ref())))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_chars() {
+        assert_eq!(
+            diff_chars("\"a\": 1,", "\"a\": 10,"),
+            (vec![6..6], vec![6..7])
+        );
+        assert_eq!(diff_chars("你好", "你们好"), (vec![3..3], vec![3..6]));
+        assert_eq!(diff_chars("abc", "abc"), (vec![], vec![]));
+    }
+
+    #[test]
+    fn test_diff_rows() {
+        let rows = diff_rows("a\nb\nc\nd\n", "a\nB\nc\nx\ny\nd\n");
+        assert_eq!(
+            describe(&rows),
+            vec![
+                " 0:a |  0:a",
+                "-1:b | +1:B",
+                " 2:c |  2:c",
+                "_ | +3:x",
+                "_ | +4:y",
+                " 3:d |  5:d",
+            ]
+        );
+        assert_eq!(rows[1].old.as_ref().unwrap().changes, vec![0..1]);
+        assert_eq!(rows[1].new.as_ref().unwrap().changes, vec![0..1]);
+        assert!(rows[3].new.as_ref().unwrap().changes.is_empty());
+
+        let rows = diff_rows("a\nb\nc", "a\n");
+        assert_eq!(describe(&rows), vec![" 0:a |  0:a", "-1:b | _", "-2:c | _"]);
+    }
+
+    #[test]
+    fn test_unified_rows() {
+        let rows = unified_rows(diff_rows("a\nb\nc\nd\n", "a\nB\nC\nx\nd\n"));
+        assert_eq!(
+            describe(&rows),
+            vec![
+                " 0:a |  0:a",
+                "-1:b | _",
+                "-2:c | _",
+                "_ | +1:B",
+                "_ | +2:C",
+                "_ | +3:x",
+                " 3:d |  4:d",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_texts() {
+        let result = diff_texts(
+            "a
+b
+c
+",
+            "a
+longer line
+",
+        );
+        assert_eq!(result.hunks.len(), 1);
+        assert_eq!(result.max_lines, 3);
+        assert_eq!(result.longest_line.as_ref(), "longer line");
+    }
+
+    #[gpui::test]
+    fn test_diff_in_background(cx: &mut gpui::TestAppContext) {
+        use gpui::AppContext as _;
+
+        use super::{DiffLayout, DiffView};
+
+        cx.update(|cx| crate::init(cx));
+        let window = cx.add_empty_window();
+        let view = window.update(|window, cx| {
+            cx.new(|cx| {
+                let mut view = DiffView::new(window, cx);
+                view.set_texts(
+                    "a
+b
+c
+", "a
+B
+c
+", cx,
+                );
+                view
+            })
+        });
+        window.update(|_, cx| assert!(view.read(cx).hunks().is_empty()));
+
+        window.run_until_parked();
+        window.update(|_, cx| {
+            assert_eq!(view.read(cx).hunks().len(), 1);
+            assert_eq!(view.read(cx).rows.len(), 3);
+        });
+
+        // Switch the layout without diffing again.
+        window.update(|_, cx| {
+            view.update(cx, |view, cx| view.set_layout(DiffLayout::Unified, cx));
+            assert_eq!(view.read(cx).rows.len(), 4);
+        });
+    }
+}
@@ -0,0 +1,282 @@
+use std::ops::Range;
+
+use super::diff::{matches, split_lines};
+
+/// A conflict region of the three-way merge, both local and remote changed the base.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    pub base: String,
+    pub local: String,
+    pub remote: String,
+    /// The (0-based) line range of the conflict in the base text.
+    pub base_lines: Range<usize>,
+    /// The (0-based) line range of the conflict in the local text.
+    pub local_lines: Range<usize>,
+    /// The (0-based) line range of the conflict in the remote text.
+    pub remote_lines: Range<usize>,
+}
+
+impl MergeConflict {
+    /// Returns the resolved text of this conflict.
+    pub fn resolve(&self, resolution: ConflictResolution) -> String {
+        match resolution {
+            ConflictResolution::Base => self.base.clone(),
+            ConflictResolution::Local => self.local.clone(),
+            ConflictResolution::Remote => self.remote.clone(),
+            ConflictResolution::Both => {
+                let mut text = self.local.clone();
+                push_line(&mut text, &self.remote);
+                text
+            }
+        }
+    }
+
+    /// Returns the unresolved text of this conflict with the conflict markers.
+    pub fn marked_text(&self) -> String {
+        let mut text = String::from("<<<<<<< local\n");
+        push_line(&mut text, &self.local);
+        push_line(&mut text, "=======\n");
+        push_line(&mut text, &self.remote);
+        push_line(&mut text, ">>>>>>> remote\n");
+        text
+    }
+}
+
+/// How to resolve a [`MergeConflict`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Keep the base text.
+    Base,
+    /// Accept the local (ours) text.
+    Local,
+    /// Accept the remote (theirs) text.
+    Remote,
+    /// Accept both the local and remote text, local first.
+    Both,
+}
+
+/// A chunk of the three-way merge result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeChunk {
+    /// Text that unchanged, or changed only by one side, or changed the same by both sides.
+    Resolved(String),
+    Conflict(MergeConflict),
+}
+
+/// The result of [`merge3`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeResult {
+    pub chunks: Vec<MergeChunk>,
+}
+
+impl MergeResult {
+    /// Returns all the conflicts in order.
+    pub fn conflicts(&self) -> impl Iterator<Item = &MergeConflict> {
+        self.chunks.iter().filter_map(|chunk| match chunk {
+            MergeChunk::Conflict(conflict) => Some(conflict),
+            _ => None,
+        })
+    }
+
+    /// Returns true if there are any conflicts.
+    pub fn has_conflicts(&self) -> bool {
+        self.conflicts().next().is_some()
+    }
+
+    /// Build the merged text, the `resolutions` is indexed by the conflict index.
+    ///
+    /// The unresolved conflicts are written with the conflict markers:
+    ///
+    /// ```text
+    /// <<<<<<< local
+    /// local text
+    /// =======
+    /// remote text
+    /// >>>>>>> remote
+    /// ```
+    pub fn text(&self, resolutions: &[Option<ConflictResolution>]) -> String {
+        self.text_with_ranges(resolutions).0
+    }
+
+    /// Build the merged text like [`Self::text`], and returns the byte ranges of the conflicts in it.
+    pub fn text_with_ranges(
+        &self,
+        resolutions: &[Option<ConflictResolution>],
+    ) -> (String, Vec<Range<usize>>) {
+        let mut text = String::new();
+        let mut ranges = vec![];
+        for chunk in &self.chunks {
+            match chunk {
+                MergeChunk::Resolved(resolved) => text.push_str(resolved),
+                MergeChunk::Conflict(conflict) => {
+                    match resolutions.get(ranges.len()).copied().flatten() {
+                        Some(resolution) => {
+                            let start = text.len();
+                            text.push_str(&conflict.resolve(resolution));
+                            ranges.push(start..text.len());
+                        }
+                        None => {
+                            let marked_text = conflict.marked_text();
+                            push_line(&mut text, &marked_text);
+                            ranges.push(text.len() - marked_text.len()..text.len());
+                        }
+                    }
+                }
+            }
+        }
+
+        (text, ranges)
+    }
+}
+
+/// Push the text to a new line.
+fn push_line(text: &mut String, line: &str) {
+    if !text.is_empty() && !text.ends_with('\n') && !line.is_empty() {
+        text.push('\n');
+    }
+    text.push_str(line);
+}
+
+/// Three-way merge the local and remote changes of the base text by lines, like `diff3`.
+pub fn merge3(base: &str, local: &str, remote: &str) -> MergeResult {
+    let base = split_lines(base);
+    let local = split_lines(local);
+    let remote = split_lines(remote);
+
+    // The matched line in local and remote of each base line.
+    let mut local_matches = vec![None; base.len()];
+    for (base_ix, local_ix) in matches(&base, &local) {
+        local_matches[base_ix] = Some(local_ix);
+    }
+    let mut remote_matches = vec![None; base.len()];
+    for (base_ix, remote_ix) in matches(&base, &remote) {
+        remote_matches[base_ix] = Some(remote_ix);
+    }
+
+    // The stable lines are unchanged in both local and remote.
+    let stables = (0..base.len())
+        .filter_map(|ix| Some((ix, local_matches[ix]?, remote_matches[ix]?)))
+        .chain(std::iter::once((base.len(), local.len(), remote.len())));
+
+    let mut result = MergeResult::default();
+    let (mut base_ix, mut local_ix, mut remote_ix) = (0, 0, 0);
+    for (base_end, local_end, remote_end) in stables {
+        let base_chunk = &base[base_ix..base_end];
+        let local_chunk = &local[local_ix..local_end];
+        let remote_chunk = &remote[remote_ix..remote_end];
+
+        if local_chunk == base_chunk {
+            push_resolved(&mut result, remote_chunk.concat());
+        } else if remote_chunk == base_chunk || local_chunk == remote_chunk {
+            push_resolved(&mut result, local_chunk.concat());
+        } else {
+            result.chunks.push(MergeChunk::Conflict(MergeConflict {
+                base: base_chunk.concat(),
+                local: local_chunk.concat(),
+                remote: remote_chunk.concat(),
+                base_lines: base_ix..base_end,
+                local_lines: local_ix..local_end,
+                remote_lines: remote_ix..remote_end,
+            }));
+        }
+
+        if base_end < base.len() {
+            push_resolved(&mut result, base[base_end].to_string());
+        }
+
+        base_ix = base_end + 1;
+        local_ix = local_end + 1;
+        remote_ix = remote_end + 1;
+    }
+
+    result
+}
+
+fn push_resolved(result: &mut MergeResult, text: String) {
+    if text.is_empty() {
+        return;
+    }
+
+    if let Some(MergeChunk::Resolved(last)) = result.chunks.last_mut() {
+        last.push_str(&text);
+    } else {
+        result.chunks.push(MergeChunk::Resolved(text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge3() {
+        let base = "a\nb\nc\nd\n";
+
+        // Changes in different lines are merged.
+        let result = merge3(base, "a\nB\nc\nd\n", "a\nb\nc\nD\n");
+        assert!(!result.has_conflicts());
+        assert_eq!(result.text(&[]), "a\nB\nc\nD\n");
+
+        // The same changes are merged.
+        let result = merge3(base, "a\nB\nc\nd\n", "a\nB\nc\nd\n");
+        assert!(!result.has_conflicts());
+        assert_eq!(result.text(&[]), "a\nB\nc\nd\n");
+
+        // Insert and delete.
+        let result = merge3(base, "a\nb\nc\nd\ne\n", "b\nc\nd\n");
+        assert_eq!(result.text(&[]), "b\nc\nd\ne\n");
+
+        // Conflict
+        let result = merge3(base, "a\nB1\nc\nd\n", "a\nB2\nc\nD\n");
+        let conflicts = result.conflicts().collect::<Vec<_>>();
+        assert_eq!(
+            conflicts,
+            vec![&MergeConflict {
+                base: "b\n".into(),
+                local: "B1\n".into(),
+                remote: "B2\n".into(),
+                base_lines: 1..2,
+                local_lines: 1..2,
+                remote_lines: 1..2,
+            }]
+        );
+        let (text, ranges) = result.text_with_ranges(&[]);
+        assert_eq!(&text[ranges[0].clone()], conflicts[0].marked_text());
+        assert_eq!(
+            result.text(&[]),
+            "a\n<<<<<<< local\nB1\n=======\nB2\n>>>>>>> remote\nc\nD\n"
+        );
+        assert_eq!(
+            result.text(&[Some(ConflictResolution::Local)]),
+            "a\nB1\nc\nD\n"
+        );
+        assert_eq!(
+            result.text(&[Some(ConflictResolution::Remote)]),
+            "a\nB2\nc\nD\n"
+        );
+        assert_eq!(
+            result.text(&[Some(ConflictResolution::Both)]),
+            "a\nB1\nB2\nc\nD\n"
+        );
+        assert_eq!(
+            result.text(&[Some(ConflictResolution::Base)]),
+            "a\nb\nc\nD\n"
+        );
+    }
+
+    #[test]
+    fn test_merge3_without_trailing_newline() {
+        let result = merge3("{\n  \"a\": 1\n}", "{\n  \"a\": 2\n}", "{\n  \"a\": 3\n}");
+        assert_eq!(
+            result.text(&[]),
+            "{\n<<<<<<< local\n  \"a\": 2\n=======\n  \"a\": 3\n>>>>>>> remote\n}"
+        );
+
+        let result = merge3("a", "b", "c");
+        assert_eq!(
+            result.text(&[]),
+            "<<<<<<< local\nb\n=======\nc\n>>>>>>> remote\n"
+        );
+        assert_eq!(result.text(&[Some(ConflictResolution::Both)]), "b\nc");
+    }
+}
@@ -0,0 +1,585 @@
+use std::ops::Range;
+
+use gpui::{
+    App, AppContext as _, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement as _, IntoElement, ParentElement, Render, SharedString, Styled,
+    Subscription, Window, div,
+};
+use rust_i18n::t;
+
+use super::{ConflictResolution, MergeConflict, MergeResult, merge3};
+use crate::{
+    ActiveTheme, IconName, h_flex,
+    input::{GutterMarker, Input, InputEvent, InputState, LineAnnotation, RopeExt as _},
+    v_flex,
+};
+
+/// Events emitted by the [`MergeView`].
+pub enum MergeViewEvent {
+    /// The merged result text has changed.
+    Change,
+}
+
+/// A three-pane merge view to resolve the conflicts of the local and remote changes of a base text.
+///
+/// The base, local and remote texts are shown in the top panes, and the result editor in the bottom,
+/// the conflict regions are marked in the gutter of each pane, with the buttons to accept the local,
+/// remote or both next to them.
+///
+/// ```ignore
+/// let merge_view = cx.new(|cx| {
+///     let mut view = MergeView::new("json", window, cx);
+///     view.set_texts(base, local, remote, window, cx);
+///     view
+/// });
+///
+/// // Get the merged text.
+/// let text = merge_view.read(cx).merged_text(cx);
+/// ```
+pub struct MergeView {
+    focus_handle: FocusHandle,
+    base: Entity<InputState>,
+    local: Entity<InputState>,
+    remote: Entity<InputState>,
+    result: Entity<InputState>,
+    merge: MergeResult,
+    resolutions: Vec<Option<ConflictResolution>>,
+    /// The byte ranges of the conflicts in the result text, moved by the edits.
+    ranges: Vec<Range<usize>>,
+    /// The result text of the last change, to find the edited range.
+    last_result: String,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl MergeView {
+    /// Create a new merge view with the code editor language for the texts.
+    pub fn new(
+        language: impl Into<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let language: SharedString = language.into();
+        let readonly_editor = |window: &mut Window, cx: &mut App| {
+            cx.new(|cx| {
+                InputState::new(window, cx)
+                    .code_editor(language.clone())
+                    .read_only(true)
+            })
+        };
+
+        let base = readonly_editor(window, cx);
+        let local = readonly_editor(window, cx);
+        let remote = readonly_editor(window, cx);
+        let result = cx.new(|cx| InputState::new(window, cx).code_editor(language.clone()));
+
+        let _subscriptions = vec![
+            cx.subscribe_in(&result, window, Self::on_result_event),
+            cx.subscribe_in(&local, window, |this, _, event: &InputEvent, window, cx| {
+                if let InputEvent::LineAnnotationClick { line } = event {
+                    let ix = this.conflict_at_line(*line, |conflict| &conflict.local_lines);
+                    this.toggle_resolution(ix, ConflictResolution::Local, window, cx);
+                }
+            }),
+            cx.subscribe_in(
+                &remote,
+                window,
+                |this, _, event: &InputEvent, window, cx| {
+                    if let InputEvent::LineAnnotationClick { line } = event {
+                        let ix = this.conflict_at_line(*line, |conflict| &conflict.remote_lines);
+                        this.toggle_resolution(ix, ConflictResolution::Remote, window, cx);
+                    }
+                },
+            ),
+        ];
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            base,
+            local,
+            remote,
+            result,
+            merge: MergeResult::default(),
+            resolutions: vec![],
+            ranges: vec![],
+            last_result: String::new(),
+            _subscriptions,
+        }
+    }
+
+    /// Set the base, local (ours) and remote (theirs) texts to merge.
+    ///
+    /// The result will be reset to the merged text, with the conflict markers for the conflicts.
+    pub fn set_texts(
+        &mut self,
+        base: impl Into<SharedString>,
+        local: impl Into<SharedString>,
+        remote: impl Into<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let base: SharedString = base.into();
+        let local: SharedString = local.into();
+        let remote: SharedString = remote.into();
+
+        self.merge = merge3(&base, &local, &remote);
+        self.resolutions = vec![None; self.merge.conflicts().count()];
+        let (text, ranges) = self.merge.text_with_ranges(&self.resolutions);
+        self.ranges = ranges;
+        self.last_result = text.clone();
+
+        self.base.update(cx, |state, cx| {
+            state.set_value_without_history(base, window, cx)
+        });
+        self.local.update(cx, |state, cx| {
+            state.set_value_without_history(local, window, cx)
+        });
+        self.remote.update(cx, |state, cx| {
+            state.set_value_without_history(remote, window, cx)
+        });
+        self.result
+            .update(cx, |state, cx| state.set_value(text, window, cx));
+        self.update_decorations(cx);
+        cx.notify();
+    }
+
+    /// Returns the conflicts of the merge in order.
+    pub fn conflicts(&self) -> Vec<&MergeConflict> {
+        self.merge.conflicts().collect()
+    }
+
+    /// Returns the resolution of the conflict at the given index.
+    pub fn resolution(&self, ix: usize) -> Option<ConflictResolution> {
+        self.resolutions.get(ix).copied().flatten()
+    }
+
+    /// Returns the number of the unresolved conflicts.
+    pub fn unresolved_count(&self) -> usize {
+        self.resolutions.iter().filter(|r| r.is_none()).count()
+    }
+
+    /// Resolve the conflict at the given index, `None` to reset to unresolved.
+    ///
+    /// Only the text of the conflict region in the result editor is replaced, as a single undo step,
+    /// so the other edits in the result editor are kept.
+    pub fn resolve(
+        &mut self,
+        ix: usize,
+        resolution: Option<ConflictResolution>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(conflict) = self.merge.conflicts().nth(ix) else {
+            return;
+        };
+
+        let new_text = match resolution {
+            Some(resolution) => conflict.resolve(resolution),
+            None => conflict.marked_text(),
+        };
+        let range = self.ranges[ix].clone();
+        self.result.update(cx, |state, cx| {
+            state.set_selection(range.clone(), window, cx);
+            state.insert_at_cursor(new_text.clone(), window, cx);
+        });
+
+        for (other_ix, other) in self.ranges.iter_mut().enumerate() {
+            if other_ix != ix {
+                *other = map_range(other, &range, new_text.len());
+            }
+        }
+        self.ranges[ix] = range.start..range.start + new_text.len();
+        self.resolutions[ix] = resolution;
+        self.last_result = self.result.read(cx).value().to_string();
+        self.update_decorations(cx);
+        cx.notify();
+    }
+
+    /// Returns the merged text in the result editor.
+    pub fn merged_text(&self, cx: &App) -> SharedString {
+        self.result.read(cx).value()
+    }
+
+    fn on_result_event(
+        &mut self,
+        _: &Entity<InputState>,
+        event: &InputEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            InputEvent::Change => {
+                self.track_result_edit(cx);
+                cx.emit(MergeViewEvent::Change);
+            }
+            InputEvent::LineAnnotationClick { line } => {
+                let text = self.result.read(cx).text().clone();
+                let ix = self
+                    .ranges
+                    .iter()
+                    .position(|range| text.offset_to_point(range.start).row == *line);
+                self.toggle_resolution(ix, ConflictResolution::Both, window, cx);
+            }
+            _ => {}
+        }
+    }
+
+    /// Move the conflict ranges by the edit in the result editor, e.g. typing or undo,
+    /// and update the resolutions by the text of the conflict regions.
+    fn track_result_edit(&mut self, cx: &mut Context<Self>) {
+        let new_text = self.result.read(cx).value().to_string();
+        if new_text == self.last_result {
+            return;
+        }
+
+        let (edit, new_len) = edited_range(&self.last_result, &new_text);
+        for (ix, conflict) in self.merge.conflicts().enumerate() {
+            let range = map_range(&self.ranges[ix], &edit, new_len);
+            let region = &new_text[range.clone()];
+            if region == conflict.marked_text() {
+                self.resolutions[ix] = None;
+            } else if let Some(resolution) = [
+                ConflictResolution::Local,
+                ConflictResolution::Remote,
+                ConflictResolution::Both,
+                ConflictResolution::Base,
+            ]
+            .into_iter()
+            .find(|resolution| region == conflict.resolve(*resolution))
+            {
+                self.resolutions[ix] = Some(resolution);
+            }
+            self.ranges[ix] = range;
+        }
+        self.last_result = new_text;
+        self.update_decorations(cx);
+        cx.notify();
+    }
+
+    /// Returns the index of the conflict that starts at the line of a pane.
+    fn conflict_at_line(
+        &self,
+        line: usize,
+        lines: impl Fn(&MergeConflict) -> &Range<usize>,
+    ) -> Option<usize> {
+        self.merge
+            .conflicts()
+            .position(|conflict| lines(conflict).start == line)
+    }
+
+    fn toggle_resolution(
+        &mut self,
+        ix: Option<usize>,
+        resolution: ConflictResolution,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ix) = ix else {
+            return;
+        };
+
+        let resolution = if self.resolution(ix) == Some(resolution) {
+            None
+        } else {
+            Some(resolution)
+        };
+        self.resolve(ix, resolution, window, cx);
+    }
+
+    /// Mark the conflict regions in the gutter of the panes, and add the accept buttons
+    /// as the line annotations at the start of the regions.
+    fn update_decorations(&mut self, cx: &mut Context<Self>) {
+        let danger = cx.theme().danger;
+        let success = cx.theme().success;
+        let marker = |resolved: bool| {
+            GutterMarker::new(IconName::Dash).color(if resolved { success } else { danger })
+        };
+        let annotation = |line: usize, label: SharedString, selected: bool| {
+            let annotation = LineAnnotation::new(line, label);
+            if selected {
+                annotation.color(success)
+            } else {
+                annotation
+            }
+        };
+
+        let conflicts = self
+            .merge
+            .conflicts()
+            .cloned()
+            .zip(self.resolutions.iter().copied())
+            .collect::<Vec<_>>();
+        let decorate = |state: &mut InputState,
+                        label: Option<SharedString>,
+                        lines: &dyn Fn(&MergeConflict) -> Range<usize>,
+                        value: ConflictResolution,
+                        cx: &mut Context<InputState>| {
+            let last_line = state.text().lines_len().saturating_sub(1);
+            let mut markers = vec![];
+            let mut annotations = vec![];
+            for (conflict, resolution) in conflicts.iter() {
+                let line_range = lines(conflict);
+                let start = line_range.start.min(last_line);
+                for line in start..line_range.end.max(start + 1) {
+                    markers.push((line, marker(resolution.is_some())));
+                }
+                if let Some(label) = label.clone() {
+                    annotations.push(annotation(start, label, *resolution == Some(value)));
+                }
+            }
+            state.set_gutter_markers(markers, cx);
+            state.set_line_annotations(annotations, cx);
+        };
+
+        self.base.update(cx, |state, cx| {
+            decorate(
+                state,
+                None,
+                &|conflict| conflict.base_lines.clone(),
+                ConflictResolution::Base,
+                cx,
+            )
+        });
+        self.local.update(cx, |state, cx| {
+            decorate(
+                state,
+                Some(t!("Merge.Accept Local").into()),
+                &|conflict| conflict.local_lines.clone(),
+                ConflictResolution::Local,
+                cx,
+            )
+        });
+        self.remote.update(cx, |state, cx| {
+            decorate(
+                state,
+                Some(t!("Merge.Accept Remote").into()),
+                &|conflict| conflict.remote_lines.clone(),
+                ConflictResolution::Remote,
+                cx,
+            )
+        });
+
+        let ranges = self.ranges.clone();
+        self.result.update(cx, |state, cx| {
+            let text = state.text().clone();
+            // The line range of the conflict region, the last line is excluded if the region
+            // ends at the start of it.
+            let lines = |range: &Range<usize>| {
+                let start = text.offset_to_point(range.start).row;
+                let end = text.offset_to_point(range.end);
+                let end = if end.column == 0 {
+                    end.row
+                } else {
+                    end.row + 1
+                };
+                start..end
+            };
+            let mut markers = vec![];
+            let mut annotations = vec![];
+            for (range, (_, resolution)) in ranges.iter().zip(conflicts.iter()) {
+                let line_range = lines(range);
+                for line in line_range.start..line_range.end.max(line_range.start + 1) {
+                    markers.push((line, marker(resolution.is_some())));
+                }
+                annotations.push(annotation(
+                    line_range.start,
+                    t!("Merge.Accept Both").into(),
+                    *resolution == Some(ConflictResolution::Both),
+                ));
+            }
+            state.set_gutter_markers(markers, cx);
+            state.set_line_annotations(annotations, cx);
+        });
+    }
+
+    fn render_pane(
+        &self,
+        title: impl Into<SharedString>,
+        state: &Entity<InputState>,
+        cx: &App,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .overflow_hidden()
+            .h_full()
+            .gap_1()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(title.into()),
+            )
+            .child(Input::new(state).h_full())
+    }
+}
+
+/// Returns the byte range replaced in the old text and the length of the new text,
+/// by the common prefix and suffix of the texts.
+fn edited_range(old_text: &str, new_text: &str) -> (Range<usize>, usize) {
+    let mut prefix = old_text
+        .bytes()
+        .zip(new_text.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old_text.is_char_boundary(prefix) || !new_text.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+
+    let max_suffix = old_text.len().min(new_text.len()) - prefix;
+    let mut suffix = old_text
+        .bytes()
+        .rev()
+        .zip(new_text.bytes().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old_text.is_char_boundary(old_text.len() - suffix)
+        || !new_text.is_char_boundary(new_text.len() - suffix)
+    {
+        suffix -= 1;
+    }
+
+    (
+        prefix..old_text.len() - suffix,
+        new_text.len() - suffix - prefix,
+    )
+}
+
+/// Map the range by the edit that replaced the `edit` range with `new_len` bytes.
+///
+/// The text inserted at the start or end of the range is not included in the range.
+fn map_range(range: &Range<usize>, edit: &Range<usize>, new_len: usize) -> Range<usize> {
+    let shift = |offset: usize| offset + new_len - edit.len();
+    let end = if range.end <= edit.start {
+        range.end
+    } else if range.end >= edit.end {
+        shift(range.end)
+    } else {
+        edit.start + new_len
+    };
+    let start = if range.start >= edit.end {
+        shift(range.start)
+    } else if range.start <= edit.start {
+        range.start
+    } else {
+        edit.start
+    };
+    start.min(end)..end
+}
+
+impl EventEmitter<MergeViewEvent> for MergeView {}
+
+impl Focusable for MergeView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for MergeView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .gap_2()
+            .child(
+                h_flex()
+                    .flex_1()
+                    .overflow_hidden()
+                    .gap_2()
+                    .child(self.render_pane(t!("Merge.Local"), &self.local, cx))
+                    .child(self.render_pane(t!("Merge.Base"), &self.base, cx))
+                    .child(self.render_pane(t!("Merge.Remote"), &self.remote, cx)),
+            )
+            .child(v_flex().flex_1().overflow_hidden().child(self.render_pane(
+                t!("Merge.Result"),
+                &self.result,
+                cx,
+            )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::AppContext as _;
+
+    use super::{ConflictResolution, MergeView, edited_range, map_range};
+
+    #[gpui::test]
+    fn test_resolve(cx: &mut gpui::TestAppContext) {
+        cx.update(|cx| crate::init(cx));
+        let window = cx.add_empty_window();
+        let view = window.update(|window, cx| {
+            cx.new(|cx| {
+                let mut view = MergeView::new("text", window, cx);
+                view.set_texts("a\nb\nc\n", "a\nb1\nc\n", "a\nb2\nc\n", window, cx);
+                view
+            })
+        });
+        let marked_text = view.read_with(window, |view, cx| view.merged_text(cx));
+
+        // The manual edit is kept after resolving a conflict.
+        window.update(|window, cx| {
+            view.update(cx, |view, cx| {
+                view.result.update(cx, |state, cx| {
+                    state.set_selection(0..1, window, cx);
+                    state.insert_at_cursor("x", window, cx);
+                });
+            })
+        });
+        window.run_until_parked();
+        window.update(|window, cx| {
+            view.update(cx, |view, cx| {
+                view.resolve(0, Some(ConflictResolution::Local), window, cx);
+                assert_eq!(view.merged_text(cx), "x\nb1\nc\n");
+                assert_eq!(view.unresolved_count(), 0);
+            })
+        });
+        window.run_until_parked();
+
+        // Undo the resolution only.
+        window.update(|window, cx| {
+            view.update(cx, |view, cx| {
+                view.result.update(cx, |state, cx| state.undo(window, cx));
+            })
+        });
+        window.run_until_parked();
+        view.read_with(window, |view, cx| {
+            assert_eq!(
+                view.merged_text(cx).to_string(),
+                format!("x{}", &marked_text[1..])
+            );
+            assert_eq!(view.resolution(0), None);
+        });
+
+        // Resolve again after the undo, the tracked range is still the conflict region.
+        window.update(|window, cx| {
+            view.update(cx, |view, cx| {
+                view.resolve(0, Some(ConflictResolution::Both), window, cx);
+                assert_eq!(view.merged_text(cx), "x\nb1\nb2\nc\n");
+            })
+        });
+    }
+
+    #[test]
+    fn test_edited_range() {
+        assert_eq!(edited_range("abc", "abc"), (3..3, 0));
+        assert_eq!(edited_range("abc", "aXc"), (1..2, 1));
+        assert_eq!(edited_range("abc", "abXc"), (2..2, 1));
+        assert_eq!(edited_range("aaa", "aa"), (2..3, 0));
+        // On the char boundary.
+        assert_eq!(edited_range("a你b", "a好b"), (1..4, 3));
+    }
+
+    #[test]
+    fn test_map_range() {
+        // Before, after and inside the range.
+        assert_eq!(map_range(&(5..10), &(0..2), 4), 7..12);
+        assert_eq!(map_range(&(5..10), &(12..14), 0), 5..10);
+        assert_eq!(map_range(&(5..10), &(6..8), 1), 5..9);
+        // The insertion at the start or end is not included.
+        assert_eq!(map_range(&(5..10), &(5..5), 2), 7..12);
+        assert_eq!(map_range(&(5..10), &(10..10), 2), 5..10);
+        // Overlapped.
+        assert_eq!(map_range(&(5..10), &(3..7), 0), 3..6);
+        assert_eq!(map_range(&(5..10), &(8..12), 0), 5..8);
+    }
+}
@@ -0,0 +1,9 @@
+mod diff;
+mod diff_view;
+mod merge;
+mod merge_view;
+
+pub use diff::*;
+pub use diff_view::*;
+pub use merge::*;
+pub use merge_view::*;
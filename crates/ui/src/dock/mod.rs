@@ -895,13 +895,16 @@ impl DockArea {
 
     /// Load the state of the DockArea from the DockAreaState.
     ///
+    /// The panels that are not registered in the [`PanelRegistry`] are skipped.
+    ///
     /// See also [DockeArea::dump].
     pub fn load(
         &mut self,
-        state: DockAreaState,
+        mut state: DockAreaState,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Result<()> {
+        state.retain_panels(|panel| PanelRegistry::is_registered(&panel.panel_name, cx));
         self.version = state.version;
         let weak_self = cx.entity().downgrade();
 
@@ -326,6 +326,12 @@ impl PanelRegistry {
         cx.global_mut::<PanelRegistry>()
     }
 
+    /// Return true if the panel name is registered by [`register_panel`].
+    pub fn is_registered(panel_name: &str, cx: &App) -> bool {
+        cx.try_global::<PanelRegistry>()
+            .is_some_and(|registry| registry.items.contains_key(panel_name))
+    }
+
     /// Build a panel by name.
     ///
     /// If not registered, return InvalidPanel.
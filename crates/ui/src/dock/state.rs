@@ -21,6 +21,29 @@ pub struct DockAreaState {
     pub bottom_dock: Option<DockState>,
 }
 
+impl DockAreaState {
+    /// Only keep the panels that `f` returns true, the containers that become empty are removed,
+    /// and the docks without panels are dropped.
+    ///
+    /// This is used to skip the panels that no longer exist before loading the state,
+    /// see [`DockArea::load`].
+    pub fn retain_panels(&mut self, f: impl Fn(&PanelState) -> bool) {
+        self.center.retain_panels(&f);
+        for dock in [
+            &mut self.left_dock,
+            &mut self.right_dock,
+            &mut self.bottom_dock,
+        ] {
+            if dock
+                .as_mut()
+                .is_some_and(|dock| !dock.panel.retain_panels(&f))
+            {
+                *dock = None;
+            }
+        }
+    }
+}
+
 /// Used to serialize and deserialize the Dock
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DockState {
@@ -104,7 +127,12 @@ pub enum PanelInfo {
         axis: usize, // 0 for horizontal, 1 for vertical
     },
     #[serde(rename = "tabs")]
-    Tabs { active_index: usize },
+    Tabs {
+        active_index: usize,
+        /// Whether the tab panel is zoomed in.
+        #[serde(default, skip_serializing_if = "is_false")]
+        zoomed: bool,
+    },
     #[serde(rename = "panel")]
     Panel(serde_json::Value),
     #[serde(rename = "tiles")]
@@ -120,7 +148,10 @@ impl PanelInfo {
     }
 
     pub fn tabs(active_index: usize) -> Self {
-        Self::Tabs { active_index }
+        Self::Tabs {
+            active_index,
+            zoomed: false,
+        }
     }
 
     pub fn panel(info: serde_json::Value) -> Self {
@@ -151,10 +182,28 @@ impl PanelInfo {
 
     pub fn active_index(&self) -> Option<usize> {
         match self {
-            Self::Tabs { active_index } => Some(*active_index),
+            Self::Tabs { active_index, .. } => Some(*active_index),
             _ => None,
         }
     }
+
+    /// Return true if the tab panel is zoomed in.
+    pub fn zoomed(&self) -> bool {
+        matches!(self, Self::Tabs { zoomed: true, .. })
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Only keep the items that the `keeps` at the same index is true.
+fn retain_by_index<T>(items: &mut Vec<T>, keeps: &[bool]) {
+    let mut ix = 0;
+    items.retain(|_| {
+        ix += 1;
+        keeps.get(ix - 1).copied().unwrap_or(false)
+    });
 }
 
 impl Default for PanelState {
@@ -179,6 +228,41 @@ impl PanelState {
         self.children.push(panel);
     }
 
+    /// Only keep the panels that `f` returns true, the sizes of the stack, the active index
+    /// of the tabs and the metas of the tiles are updated to match the kept children.
+    ///
+    /// Returns false if this panel is removed, or it's an empty container after removing.
+    fn retain_panels(&mut self, f: &dyn Fn(&PanelState) -> bool) -> bool {
+        if let PanelInfo::Panel(_) = self.info {
+            return f(self);
+        }
+
+        let keeps = self
+            .children
+            .iter_mut()
+            .map(|child| child.retain_panels(f))
+            .collect_vec();
+        retain_by_index(&mut self.children, &keeps);
+
+        match &mut self.info {
+            PanelInfo::Stack { sizes, .. } => retain_by_index(sizes, &keeps),
+            PanelInfo::Tiles { metas } => retain_by_index(metas, &keeps),
+            PanelInfo::Tabs { active_index, .. } => {
+                let removed = keeps
+                    .iter()
+                    .take(*active_index)
+                    .filter(|keep| !**keep)
+                    .count();
+                *active_index = active_index
+                    .saturating_sub(removed)
+                    .min(self.children.len().saturating_sub(1));
+            }
+            PanelInfo::Panel(_) => {}
+        }
+
+        !self.children.is_empty()
+    }
+
     pub fn to_item(
         &self,
         dock_area: WeakEntity<DockArea>,
@@ -203,23 +287,36 @@ impl PanelState {
                 let sizes = sizes.iter().map(|s| Some(*s)).collect_vec();
                 DockItem::split_with_sizes(axis, items, sizes, &dock_area, window, cx)
             }
-            PanelInfo::Tabs { active_index } => {
-                if items.len() == 1 {
-                    return items[0].clone();
+            PanelInfo::Tabs {
+                active_index,
+                zoomed,
+            } => {
+                let item = if items.len() == 1 {
+                    items[0].clone()
+                } else {
+                    let items = items
+                        .iter()
+                        .flat_map(|item| match item {
+                            DockItem::Tabs { items, .. } => items.clone(),
+                            _ => {
+                                // ignore invalid panels in tabs
+                                vec![]
+                            }
+                        })
+                        .collect_vec();
+
+                    DockItem::tabs(items, &dock_area, window, cx).active_index(active_index)
+                };
+
+                // Zoom in after the tab panel is subscribed by the dock area.
+                if let (true, DockItem::Tabs { view, .. }) = (zoomed, &item) {
+                    let view = view.clone();
+                    window.defer(cx, move |window, cx| {
+                        view.update(cx, |view, cx| view.zoom_in(window, cx));
+                    });
                 }
 
-                let items = items
-                    .iter()
-                    .flat_map(|item| match item {
-                        DockItem::Tabs { items, .. } => items.clone(),
-                        _ => {
-                            // ignore invalid panels in tabs
-                            vec![]
-                        }
-                    })
-                    .collect_vec();
-
-                DockItem::tabs(items, &dock_area, window, cx).active_index(active_index)
+                item
             }
             PanelInfo::Panel(_) => {
                 let view = PanelRegistry::build_panel(
@@ -279,4 +376,55 @@ mod tests {
         assert_eq!(right_dock.panel.children.len(), 1);
         assert_eq!(right_dock.panel.children[0].panel_name, "StoryContainer");
     }
+
+    #[test]
+    fn test_retain_panels() {
+        fn leaf(name: &str) -> serde_json::Value {
+            serde_json::json!({ "panel_name": name, "children": [], "info": { "panel": null } })
+        }
+
+        let json = serde_json::json!({
+            "center": {
+                "panel_name": "StackPanel",
+                "children": [
+                    {
+                        "panel_name": "TabPanel",
+                        "children": [leaf("Foo"), leaf("Bar"), leaf("Foo")],
+                        "info": { "tabs": { "active_index": 2 } }
+                    },
+                    {
+                        "panel_name": "TabPanel",
+                        "children": [leaf("Bar")],
+                        "info": { "tabs": { "active_index": 0, "zoomed": true } }
+                    }
+                ],
+                "info": { "stack": { "sizes": [100.0, 200.0], "axis": 0 } }
+            },
+            "left_dock": {
+                "panel": {
+                    "panel_name": "TabPanel",
+                    "children": [leaf("Bar")],
+                    "info": { "tabs": { "active_index": 0 } }
+                },
+                "placement": "left",
+                "size": 300.0,
+                "open": false
+            }
+        });
+        let mut state: DockAreaState = serde_json::from_value(json).unwrap();
+        assert!(state.center.children[1].info.zoomed());
+        assert!(!state.center.children[0].info.zoomed());
+
+        state.retain_panels(|panel| panel.panel_name != "Bar");
+        assert_eq!(state.center.children.len(), 1);
+        assert_eq!(state.center.info.sizes(), Some(&vec![px(100.)]));
+        let tabs = &state.center.children[0];
+        assert_eq!(tabs.children.len(), 2);
+        assert_eq!(tabs.info.active_index(), Some(1));
+        assert!(state.left_dock.is_none());
+
+        // The zoomed is only serialized if true.
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(!json.contains("zoomed"));
+    }
 }
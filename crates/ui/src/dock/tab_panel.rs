@@ -147,7 +147,10 @@ impl Panel for TabPanel {
         let mut state = PanelState::new(self);
         for panel in self.panels.iter() {
             state.add_child(panel.dump(cx));
-            state.info = PanelInfo::tabs(self.active_ix);
+            state.info = PanelInfo::Tabs {
+                active_index: self.active_ix,
+                zoomed: self.zoomed,
+            };
         }
         state
     }
@@ -1096,6 +1099,13 @@ impl TabPanel {
         }
     }
 
+    /// Zoom in the tab panel if it's not zoomed.
+    pub(super) fn zoom_in(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.zoomed {
+            self.on_action_toggle_zoom(&ToggleZoom, window, cx);
+        }
+    }
+
     fn on_action_toggle_zoom(
         &mut self,
         _: &ToggleZoom,
@@ -4,8 +4,10 @@ use std::{
     usize,
 };
 
-use gpui::{px, App, HighlightStyle, Hsla, SharedString, UnderlineStyle};
+use gpui::{px, rgb, App, HighlightStyle, Hsla, SharedString, UnderlineStyle};
 use ropey::Rope;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use sum_tree::{Bias, SeekTarget, SumTree};
 
 use crate::{
@@ -98,10 +100,61 @@ impl From<lsp_types::DiagnosticSeverity> for DiagnosticSeverity {
     }
 }
 
+/// The style to render the diagnostics, see [`crate::Theme::set_diagnostic_style`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticStyle {
+    /// Use the status colors of the highlight theme, with wavy underline for all severities.
+    #[default]
+    Default,
+    /// Use a color-blind friendly palette (Okabe-Ito), and distinguish the severities
+    /// by the underline shapes, not by color alone:
+    ///
+    /// - Error: wavy underline
+    /// - Warning: thick underline
+    /// - Info: thin underline
+    /// - Hint: thin underline with low opacity
+    ColorBlind,
+}
+
+impl DiagnosticStyle {
+    #[inline]
+    pub fn is_color_blind(&self) -> bool {
+        matches!(self, Self::ColorBlind)
+    }
+}
+
 impl DiagnosticSeverity {
+    /// The color-blind friendly color of the severity.
+    fn color_blind_color(&self, cx: &App) -> Hsla {
+        match self {
+            // Vermillion
+            Self::Error => rgb(0xd55e00).into(),
+            // Orange
+            Self::Warning => rgb(0xe69f00).into(),
+            // Blue / Sky Blue
+            Self::Info => {
+                if cx.theme().is_dark() {
+                    rgb(0x56b4e9).into()
+                } else {
+                    rgb(0x0072b2).into()
+                }
+            }
+            // Bluish Green
+            Self::Hint => rgb(0x009e73).into(),
+        }
+    }
+
     pub(crate) fn bg(&self, cx: &App) -> Hsla {
         let theme = &cx.theme().highlight_theme;
 
+        if cx.theme().diagnostic_style.is_color_blind() {
+            return cx
+                .theme()
+                .background
+                .blend(self.color_blind_color(cx).alpha(0.2));
+        }
+
         match self {
             Self::Error => theme.style.status.error_background(cx),
             Self::Warning => theme.style.status.warning_background(cx),
@@ -113,6 +166,10 @@ impl DiagnosticSeverity {
     pub(crate) fn fg(&self, cx: &App) -> Hsla {
         let theme = &cx.theme().highlight_theme;
 
+        if cx.theme().diagnostic_style.is_color_blind() {
+            return self.color_blind_color(cx);
+        }
+
         match self {
             Self::Error => theme.style.status.error(cx),
             Self::Warning => theme.style.status.warning(cx),
@@ -123,6 +180,11 @@ impl DiagnosticSeverity {
 
     pub(crate) fn border(&self, cx: &App) -> Hsla {
         let theme = &cx.theme().highlight_theme;
+
+        if cx.theme().diagnostic_style.is_color_blind() {
+            return self.color_blind_color(cx);
+        }
+
         match self {
             Self::Error => theme.style.status.error_border(cx),
             Self::Warning => theme.style.status.warning_border(cx),
@@ -134,6 +196,24 @@ impl DiagnosticSeverity {
     pub(crate) fn highlight_style(&self, cx: &App) -> HighlightStyle {
         let theme = &cx.theme().highlight_theme;
 
+        if cx.theme().diagnostic_style.is_color_blind() {
+            let color = self.color_blind_color(cx);
+            let (color, thickness, wavy) = match self {
+                Self::Error => (color, px(1.), true),
+                Self::Warning => (color, px(2.), false),
+                Self::Info => (color, px(1.), false),
+                Self::Hint => (color.opacity(0.6), px(1.), false),
+            };
+
+            let mut style = HighlightStyle::default();
+            style.underline = Some(UnderlineStyle {
+                color: Some(color),
+                thickness,
+                wavy,
+            });
+            return style;
+        }
+
         let color = match self {
             Self::Error => Some(theme.style.status.error(cx)),
             Self::Warning => Some(theme.style.status.warning(cx)),
@@ -309,6 +309,12 @@ impl SyntaxHighlighter {
         self.text.len() == 0
     }
 
+    /// Returns the last parsed syntax tree.
+    #[inline]
+    pub(crate) fn tree(&self) -> Option<&Tree> {
+        self.tree.as_ref()
+    }
+
     /// Highlight the given text, returning a map from byte ranges to highlight captures.
     ///
     /// Uses incremental parsing by `edit` to efficiently update the highlighter's state.
@@ -368,6 +368,49 @@ impl Language {
             injection,
             locals,
         )
+        .bracket_pairs(self.bracket_pairs())
+        .auto_close_pairs(self.auto_close_pairs())
+    }
+
+    /// The bracket pairs for matching.
+    fn bracket_pairs(&self) -> Vec<(&'static str, &'static str)> {
+        #[cfg(not(feature = "tree-sitter-languages"))]
+        return vec![("{", "}"), ("[", "]")];
+
+        #[cfg(feature = "tree-sitter-languages")]
+        match self {
+            Self::Json => vec![("{", "}"), ("[", "]")],
+            Self::Html | Self::Tsx | Self::Erb | Self::Ejs => {
+                vec![("(", ")"), ("[", "]"), ("{", "}"), ("<", ">")]
+            }
+            _ => vec![("(", ")"), ("[", "]"), ("{", "}")],
+        }
+    }
+
+    /// The pairs to auto close when typing the start.
+    fn auto_close_pairs(&self) -> Vec<(&'static str, &'static str)> {
+        #[cfg(not(feature = "tree-sitter-languages"))]
+        return vec![("{", "}"), ("[", "]"), ("\"", "\"")];
+
+        #[cfg(feature = "tree-sitter-languages")]
+        match self {
+            Self::Plain => vec![],
+            Self::Json => vec![("{", "}"), ("[", "]"), ("\"", "\"")],
+            Self::Markdown | Self::MarkdownInline => {
+                vec![("(", ")"), ("[", "]"), ("{", "}"), ("`", "`")]
+            }
+            // The `'` is used for lifetime in Rust.
+            Self::Rust => vec![("(", ")"), ("[", "]"), ("{", "}"), ("\"", "\"")],
+            Self::JavaScript | Self::TypeScript | Self::Tsx => vec![
+                ("(", ")"),
+                ("[", "]"),
+                ("{", "}"),
+                ("\"", "\""),
+                ("'", "'"),
+                ("`", "`"),
+            ],
+            _ => vec![("(", ")"), ("[", "]"), ("{", "}"), ("\"", "\""), ("'", "'")],
+        }
     }
 }
 
@@ -397,4 +440,24 @@ mod tests {
         assert_eq!(Language::Erb.name(), "erb");
         assert_eq!(Language::Ejs.name(), "ejs");
     }
+
+    #[test]
+    fn test_bracket_pairs() {
+        use super::*;
+        use crate::highlighter::BracketPair;
+
+        let config = Language::Json.config();
+        assert_eq!(
+            config.bracket_pairs,
+            vec![BracketPair::new("{", "}"), BracketPair::new("[", "]")]
+        );
+        assert_eq!(
+            config.auto_close_pairs,
+            vec![
+                BracketPair::new("{", "}"),
+                BracketPair::new("[", "]"),
+                BracketPair::new("\"", "\"")
+            ]
+        );
+    }
 }
@@ -13,7 +13,11 @@ use crate::{
     ActiveTheme, ThemeMode, DEFAULT_THEME_COLORS,
 };
 
-pub(super) const HIGHLIGHT_NAMES: [&str; 40] = [
+/// The highlight names (the tree-sitter capture names) that can be styled by the [`SyntaxColors`] of the theme.
+///
+/// The highlights query of a custom language should use these capture names,
+/// a dotted name that is not in the list, e.g. `keyword.modifier`, falls back to its prefix `keyword`.
+pub const HIGHLIGHT_NAMES: [&str; 40] = [
     "attribute",
     "boolean",
     "comment",
@@ -56,6 +60,28 @@ pub(super) const HIGHLIGHT_NAMES: [&str; 40] = [
     "variant",
 ];
 
+/// A pair of brackets or quotes in a language, e.g. `{` and `}`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BracketPair {
+    pub start: SharedString,
+    pub end: SharedString,
+}
+
+impl BracketPair {
+    pub fn new(start: impl Into<SharedString>, end: impl Into<SharedString>) -> Self {
+        Self {
+            start: start.into(),
+            end: end.into(),
+        }
+    }
+}
+
+impl<S: Into<SharedString>> From<(S, S)> for BracketPair {
+    fn from((start, end): (S, S)) -> Self {
+        Self::new(start, end)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LanguageConfig {
     pub name: SharedString,
@@ -64,6 +90,14 @@ pub struct LanguageConfig {
     pub highlights: SharedString,
     pub injections: SharedString,
     pub locals: SharedString,
+    /// The file extensions of the language (without the leading dot), e.g. `["rs"]`.
+    ///
+    /// The language can also be found by these extensions in the [`LanguageRegistry`].
+    pub extensions: Vec<SharedString>,
+    /// The bracket pairs for matching, default is `()`, `[]` and `{}`.
+    pub bracket_pairs: Vec<BracketPair>,
+    /// The pairs to auto close when typing the start, default is `()`, `[]`, `{}` and `""`.
+    pub auto_close_pairs: Vec<BracketPair>,
 }
 
 impl LanguageConfig {
@@ -82,8 +116,92 @@ impl LanguageConfig {
             highlights: SharedString::from(highlights.to_string()),
             injections: SharedString::from(injections.to_string()),
             locals: SharedString::from(locals.to_string()),
+            extensions: vec![],
+            bracket_pairs: vec![("(", ")").into(), ("[", "]").into(), ("{", "}").into()],
+            auto_close_pairs: vec![
+                ("(", ")").into(),
+                ("[", "]").into(),
+                ("{", "}").into(),
+                ("\"", "\"").into(),
+            ],
         }
     }
+
+    /// Set the file extensions (without the leading dot) of the language.
+    pub fn extensions(
+        mut self,
+        extensions: impl IntoIterator<Item = impl Into<SharedString>>,
+    ) -> Self {
+        self.extensions = extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the bracket pairs for matching.
+    pub fn bracket_pairs(
+        mut self,
+        pairs: impl IntoIterator<Item = impl Into<BracketPair>>,
+    ) -> Self {
+        self.bracket_pairs = pairs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the pairs to auto close when typing the start.
+    pub fn auto_close_pairs(
+        mut self,
+        pairs: impl IntoIterator<Item = impl Into<BracketPair>>,
+    ) -> Self {
+        self.auto_close_pairs = pairs.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// Match the highlight name to the field of the [`SyntaxColors`], use `&` or `&mut` as the `$ref`.
+macro_rules! syntax_field {
+    ($self:ident, $name:expr, $($ref:tt)*) => {
+        match $name {
+            "attribute" => Some($($ref)* $self.attribute),
+            "boolean" => Some($($ref)* $self.boolean),
+            "comment" => Some($($ref)* $self.comment),
+            "comment.doc" => Some($($ref)* $self.comment_doc),
+            "constant" => Some($($ref)* $self.constant),
+            "constructor" => Some($($ref)* $self.constructor),
+            "embedded" => Some($($ref)* $self.embedded),
+            "emphasis" => Some($($ref)* $self.emphasis),
+            "emphasis.strong" => Some($($ref)* $self.emphasis_strong),
+            "enum" => Some($($ref)* $self.enum_),
+            "function" => Some($($ref)* $self.function),
+            "hint" => Some($($ref)* $self.hint),
+            "keyword" => Some($($ref)* $self.keyword),
+            "label" => Some($($ref)* $self.label),
+            "link_text" => Some($($ref)* $self.link_text),
+            "link_uri" => Some($($ref)* $self.link_uri),
+            "number" => Some($($ref)* $self.number),
+            "operator" => Some($($ref)* $self.operator),
+            "predictive" => Some($($ref)* $self.predictive),
+            "preproc" => Some($($ref)* $self.preproc),
+            "primary" => Some($($ref)* $self.primary),
+            "property" => Some($($ref)* $self.property),
+            "punctuation" => Some($($ref)* $self.punctuation),
+            "punctuation.bracket" => Some($($ref)* $self.punctuation_bracket),
+            "punctuation.delimiter" => Some($($ref)* $self.punctuation_delimiter),
+            "punctuation.list_marker" => Some($($ref)* $self.punctuation_list_marker),
+            "punctuation.special" => Some($($ref)* $self.punctuation_special),
+            "string" => Some($($ref)* $self.string),
+            "string.escape" => Some($($ref)* $self.string_escape),
+            "string.regex" => Some($($ref)* $self.string_regex),
+            "string.special" => Some($($ref)* $self.string_special),
+            "string.special.symbol" => Some($($ref)* $self.string_special_symbol),
+            "tag" => Some($($ref)* $self.tag),
+            "tag.doctype" => Some($($ref)* $self.tag_doctype),
+            "text.literal" => Some($($ref)* $self.text_literal),
+            "title" => Some($($ref)* $self.title),
+            "type" => Some($($ref)* $self.type_),
+            "variable" => Some($($ref)* $self.variable),
+            "variable.special" => Some($($ref)* $self.variable_special),
+            "variant" => Some($($ref)* $self.variant),
+            _ => None,
+        }
+    };
 }
 
 /// Theme for Tree-sitter Highlight
@@ -204,6 +322,34 @@ pub struct ThemeStyle {
     font_weight: Option<FontWeightContent>,
 }
 
+impl ThemeStyle {
+    /// Create a style with the foreground color.
+    pub fn new(color: impl Into<Hsla>) -> Self {
+        Self {
+            color: Some(color.into()),
+            font_style: None,
+            font_weight: None,
+        }
+    }
+
+    /// Set the font weight to bold.
+    pub fn bold(mut self) -> Self {
+        self.font_weight = Some(FontWeightContent::Bold);
+        self
+    }
+
+    /// Set the font style to italic.
+    pub fn italic(mut self) -> Self {
+        self.font_style = Some(FontStyle::Italic);
+        self
+    }
+
+    /// Returns the foreground color.
+    pub fn color(&self) -> Option<Hsla> {
+        self.color
+    }
+}
+
 impl From<ThemeStyle> for HighlightStyle {
     fn from(style: ThemeStyle) -> Self {
         HighlightStyle {
@@ -221,50 +367,10 @@ impl SyntaxColors {
             return None;
         }
 
-        let style = match name {
-            "attribute" => self.attribute,
-            "boolean" => self.boolean,
-            "comment" => self.comment,
-            "comment.doc" => self.comment_doc,
-            "constant" => self.constant,
-            "constructor" => self.constructor,
-            "embedded" => self.embedded,
-            "emphasis" => self.emphasis,
-            "emphasis.strong" => self.emphasis_strong,
-            "enum" => self.enum_,
-            "function" => self.function,
-            "hint" => self.hint,
-            "keyword" => self.keyword,
-            "label" => self.label,
-            "link_text" => self.link_text,
-            "link_uri" => self.link_uri,
-            "number" => self.number,
-            "operator" => self.operator,
-            "predictive" => self.predictive,
-            "preproc" => self.preproc,
-            "primary" => self.primary,
-            "property" => self.property,
-            "punctuation" => self.punctuation,
-            "punctuation.bracket" => self.punctuation_bracket,
-            "punctuation.delimiter" => self.punctuation_delimiter,
-            "punctuation.list_marker" => self.punctuation_list_marker,
-            "punctuation.special" => self.punctuation_special,
-            "string" => self.string,
-            "string.escape" => self.string_escape,
-            "string.regex" => self.string_regex,
-            "string.special" => self.string_special,
-            "string.special.symbol" => self.string_special_symbol,
-            "tag" => self.tag,
-            "tag.doctype" => self.tag_doctype,
-            "text.literal" => self.text_literal,
-            "title" => self.title,
-            "type" => self.type_,
-            "variable" => self.variable,
-            "variable.special" => self.variable_special,
-            "variant" => self.variant,
-            _ => None,
-        }
-        .map(|s| s.into());
+        let style = syntax_field!(self, name, &)
+            .copied()
+            .flatten()
+            .map(|s| s.into());
 
         if style.is_some() {
             style
@@ -286,6 +392,20 @@ impl SyntaxColors {
     pub fn style_for_index(&self, index: usize) -> Option<HighlightStyle> {
         HIGHLIGHT_NAMES.get(index).and_then(|name| self.style(name))
     }
+
+    /// Set the style of the highlight name in [`HIGHLIGHT_NAMES`], e.g. `string`, `property`,
+    /// pass `None` to fall back to the prefix of a dotted name, or the editor foreground color.
+    ///
+    /// Returns false if the name is not in [`HIGHLIGHT_NAMES`].
+    pub fn set_style(&mut self, name: &str, style: Option<ThemeStyle>) -> bool {
+        match syntax_field!(self, name, &mut) {
+            Some(field) => {
+                *field = style;
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash, JsonSchema, Serialize, Deserialize)]
@@ -423,7 +543,7 @@ pub struct HighlightThemeStyle {
     pub editor_active_line_number: Option<Hsla>,
     #[serde(flatten)]
     pub status: StatusColors,
-    #[serde(rename = "syntax")]
+    #[serde(rename = "syntax", default)]
     pub syntax: SyntaxColors,
 }
 
@@ -489,22 +609,84 @@ impl LanguageRegistry {
         self.languages.lock().unwrap().keys().cloned().collect()
     }
 
-    /// Returns the language configuration for the given language name.
+    /// Returns the language configuration for the given language name or file extension.
+    ///
+    /// The unknown languages fall back to the plain text.
     pub fn language(&self, name: &str) -> Option<LanguageConfig> {
         // Try to get by name first, there may have a custom language registered
+        // Then try the extensions of the custom languages,
         // Then try to get built-in language to support short language names, e.g. "js" for "javascript"
         let languages = self.languages.lock().unwrap();
         languages
             .get(name)
+            .or_else(|| {
+                languages
+                    .values()
+                    .find(|config| config.extensions.iter().any(|ext| ext == name))
+            })
             .or_else(|| languages.get(Language::from_str(name).name()))
             .cloned()
     }
 }
 
+/// Register a custom language to the [`LanguageRegistry`] by the [`LanguageConfig::name`].
+///
+/// Then the language can be used by the name or extensions, e.g.: `InputState::code_editor("mylang")`.
+///
+/// ```ignore
+/// register_language(
+///     LanguageConfig::new(
+///         "navi",
+///         tree_sitter_navi::LANGUAGE.into(),
+///         vec![],
+///         tree_sitter_navi::HIGHLIGHTS_QUERY,
+///         "",
+///         "",
+///     )
+///     .extensions(["nv"]),
+/// );
+/// ```
+pub fn register_language(config: LanguageConfig) {
+    LanguageRegistry::singleton().register(&config.name.clone(), &config);
+}
+
 #[cfg(test)]
 mod tests {
+    use gpui::{red, FontWeight};
+
+    use super::{SyntaxColors, ThemeStyle};
     use crate::highlighter::LanguageConfig;
 
+    #[test]
+    fn test_syntax_colors() {
+        let mut syntax: SyntaxColors = serde_json::from_str(
+            r##"{
+                "string": { "color": "#00aa00" },
+                "property": { "color": "#0055ff", "font_weight": 700 }
+            }"##,
+        )
+        .unwrap();
+
+        let string = syntax.style("string").unwrap();
+        assert_eq!(string.color, syntax.string.unwrap().color());
+        assert_eq!(
+            syntax.style("property").unwrap().font_weight,
+            Some(FontWeight::BOLD)
+        );
+        // Fallback to the prefix, and the unspecified names have no style.
+        assert_eq!(syntax.style("string.special"), Some(string));
+        assert_eq!(syntax.style("keyword"), None);
+
+        assert!(syntax.set_style("keyword", Some(ThemeStyle::new(red()).italic())));
+        let keyword = syntax.style("keyword").unwrap();
+        assert_eq!(keyword.color, Some(red()));
+        assert_eq!(keyword.font_style, Some(gpui::FontStyle::Italic));
+
+        assert!(syntax.set_style("string", None));
+        assert_eq!(syntax.style("string"), None);
+        assert!(!syntax.set_style("unknown", Some(ThemeStyle::new(red()))));
+    }
+
     #[test]
     fn test_registry() {
         use super::LanguageRegistry;
@@ -521,4 +703,27 @@ mod tests {
         assert!(registry.language("javascript").is_some());
         assert!(registry.language("js").is_some());
     }
+
+    #[test]
+    fn test_register_language_with_extensions() {
+        use super::{register_language, LanguageRegistry};
+
+        register_language(
+            LanguageConfig::new(
+                "mylang",
+                tree_sitter_json::LANGUAGE.into(),
+                vec![],
+                "",
+                "",
+                "",
+            )
+            .extensions(["ml1", "ml2"]),
+        );
+
+        let registry = LanguageRegistry::singleton();
+        assert_eq!(registry.language("mylang").unwrap().name, "mylang");
+        assert_eq!(registry.language("ml2").unwrap().name, "mylang");
+        // Unknown language fallback to plain text.
+        assert!(registry.language("unknown-lang").is_some());
+    }
 }
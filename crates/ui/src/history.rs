@@ -59,6 +59,8 @@ where
 
     /// Set the history to be unique, defaults to false.
     /// If set to true, the history will only keep unique changes.
+    ///
+    /// The redo stack is kept on push in this mode, e.g. for the tab history.
     pub fn unique(mut self) -> Self {
         self.unique = true;
         self
@@ -80,6 +82,20 @@ where
         self.grouping = false;
     }
 
+    /// Start a new version, so the next change will not be grouped with the previous changes,
+    /// even if it is made within the group interval.
+    ///
+    /// This is ignored between `start_grouping` and `end_grouping`.
+    pub fn break_group(&mut self) {
+        if self.grouping {
+            return;
+        }
+
+        if self.undos.last().map(|c| c.version()) == Some(self.version) {
+            self.version += 1;
+        }
+    }
+
     /// Increment the version number if the last change was made more than `GROUP_INTERVAL` milliseconds ago.
     fn inc_version(&mut self) -> usize {
         let t = Instant::now();
@@ -97,21 +113,38 @@ where
     }
 
     /// Push a new change to the history.
+    ///
+    /// The redo stack is cleared, because the changes in it are based on the previous state,
+    /// unless the history is [`Self::unique`].
     pub fn push(&mut self, item: I) {
         let version = self.inc_version();
 
-        if self.undos.len() >= self.max_undos {
-            self.undos.remove(0);
-        }
-
         if self.unique {
             self.undos.retain(|c| *c != item);
             self.redos.retain(|c| *c != item);
+        } else {
+            self.redos.clear();
         }
 
         let mut item = item;
         item.set_version(version);
         self.undos.push(item);
+
+        // Evict the whole oldest undo steps, to never undo a part of the grouped changes.
+        let mut steps = self
+            .undos
+            .chunk_by(|a, b| a.version() == b.version())
+            .count();
+        while steps > self.max_undos.max(1) {
+            let version = self.undos[0].version();
+            let len = self
+                .undos
+                .iter()
+                .take_while(|c| c.version() == version)
+                .count();
+            self.undos.drain(..len);
+            steps -= 1;
+        }
     }
 
     /// Get the undo stack.
@@ -227,30 +260,85 @@ mod tests {
         assert_eq!(changes.len(), 1);
         assert_eq!(changes[0].tab_index, 2);
 
+        // The undone changes can't be redone after a new change.
         history.push(5.into());
+        assert!(history.redo().is_none());
 
-        let changes = history.redo().unwrap();
-        assert_eq!(changes[0].tab_index, 2);
+        let changes = history.undo().unwrap();
+        assert_eq!(changes[0].tab_index, 5);
 
-        let changes = history.redo().unwrap();
-        assert_eq!(changes[0].tab_index, 1);
+        let changes = history.undo().unwrap();
+        assert_eq!(changes[0].tab_index, 3);
 
         let changes = history.undo().unwrap();
-        assert_eq!(changes[0].tab_index, 1);
+        assert_eq!(changes[0].tab_index, 0);
+
+        assert_eq!(history.undo().is_none(), true);
+    }
 
+    #[test]
+    fn test_break_group() {
+        let mut history: History<TabIndex> = History::new().group_interval(Duration::from_secs(60));
+        history.push(0.into());
+        history.push(1.into());
+        history.break_group();
+        history.break_group();
+        history.push(2.into());
+        history.break_group();
+        history.push(3.into());
+
+        assert_eq!(history.version(), 2);
         let changes = history.undo().unwrap();
-        assert_eq!(changes[0].tab_index, 2);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].tab_index, 3);
 
         let changes = history.undo().unwrap();
-        assert_eq!(changes[0].tab_index, 5);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].tab_index, 2);
 
         let changes = history.undo().unwrap();
-        assert_eq!(changes[0].tab_index, 3);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].tab_index, 1);
+        assert_eq!(changes[1].tab_index, 0);
 
+        // Ignored in grouping.
+        history.start_grouping();
+        history.push(4.into());
+        history.break_group();
+        history.push(5.into());
+        history.end_grouping();
         let changes = history.undo().unwrap();
-        assert_eq!(changes[0].tab_index, 0);
+        assert_eq!(changes.len(), 2);
+    }
 
-        assert_eq!(history.undo().is_none(), true);
+    #[test]
+    fn test_max_undos() {
+        let mut history: History<TabIndex> = History::new().max_undos(2);
+        history.push(0.into());
+        history.break_group();
+        history.start_grouping();
+        history.push(1.into());
+        history.push(2.into());
+        history.push(3.into());
+        history.end_grouping();
+        history.break_group();
+        history.push(4.into());
+
+        // The max undos is the number of the steps, the grouped changes are kept together.
+        assert_eq!(history.undos().len(), 4);
+        assert_eq!(history.undo().unwrap().len(), 1);
+        assert_eq!(history.undo().unwrap().len(), 3);
+        assert!(history.undo().is_none());
+
+        // The oldest step is evicted as a whole.
+        history.redo();
+        history.redo();
+        history.break_group();
+        history.push(5.into());
+        assert_eq!(history.undos().len(), 2);
+        assert_eq!(history.undo().unwrap()[0].tab_index, 5);
+        assert_eq!(history.undo().unwrap()[0].tab_index, 4);
+        assert!(history.undo().is_none());
     }
 
     #[test]
@@ -0,0 +1,243 @@
+use std::{ops::Range, rc::Rc};
+
+use gpui::{Context, Window};
+use ropey::Rope;
+
+use crate::{
+    highlighter::{BracketPair, LanguageConfig, LanguageRegistry},
+    input::{InputState, RopeExt as _, mode::InputMode},
+};
+
+/// The max bytes to scan for the matching bracket, to avoid scanning the entire large text.
+const MAX_SCAN_LEN: usize = 100_000;
+
+impl InputState {
+    /// Returns the [`LanguageConfig`] of the code editor.
+    ///
+    /// This is cached when the language is changed, to avoid locking the [`LanguageRegistry`]
+    /// on every layout.
+    pub(super) fn language_config(&self) -> Option<Rc<LanguageConfig>> {
+        self.language_config.clone()
+    }
+
+    /// Update the cached [`LanguageConfig`] by the language of the code editor.
+    pub(super) fn update_language_config(&mut self) {
+        self.language_config = match &self.mode {
+            InputMode::CodeEditor { language, .. } => LanguageRegistry::singleton()
+                .language(language)
+                .map(Rc::new),
+            _ => None,
+        };
+    }
+
+    /// Return true if the offset is inside a string literal of the syntax tree.
+    fn is_in_string(&self, offset: usize) -> bool {
+        let InputMode::CodeEditor { highlighter, .. } = &self.mode else {
+            return false;
+        };
+        let highlighter = highlighter.borrow();
+        let Some(tree) = highlighter.as_ref().and_then(|h| h.tree()) else {
+            return false;
+        };
+
+        let mut node = tree.root_node().descendant_for_byte_range(offset, offset);
+        while let Some(n) = node {
+            if n.start_byte() < offset && offset < n.end_byte() && n.kind().contains("string") {
+                return true;
+            }
+            node = n.parent();
+        }
+
+        false
+    }
+
+    /// Handle the auto close pairs of the typed text.
+    ///
+    /// - Typing the start of a pair inserts the end of it, the cursor is placed between them,
+    ///   except inside a string literal.
+    /// - Typing the end of a pair skips over it if the next text is the same.
+    ///
+    /// Returns true if the typing is handled.
+    pub(super) fn handle_auto_close(
+        &mut self,
+        new_text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if !self.auto_close_brackets || !self.mode.is_code_editor() || new_text.is_empty() {
+            return false;
+        }
+        if !self.selected_range.is_empty() {
+            return false;
+        }
+        let Some(config) = self.language_config() else {
+            return false;
+        };
+
+        let offset = self.cursor();
+        let pairs = &config.auto_close_pairs;
+
+        // Skip over the end of the pair.
+        if pairs.iter().any(|pair| pair.end.as_ref() == new_text) {
+            let end = offset + new_text.len();
+            if end <= self.text.len() && self.text.slice(offset..end) == new_text {
+                self.move_to(end, None, cx);
+                return true;
+            }
+        }
+
+        let Some(pair) = pairs.iter().find(|pair| pair.start.as_ref() == new_text) else {
+            return false;
+        };
+        if self.is_in_string(offset) {
+            return false;
+        }
+        // Avoid to close the quotes after a word, e.g.: `don't`.
+        if pair.start == pair.end
+            && offset > 0
+            && self
+                .text
+                .chars_at(offset)
+                .reversed()
+                .next()
+                .is_some_and(|c| c.is_alphanumeric())
+        {
+            return false;
+        }
+
+        // Insert the pair as a single change, so a single undo removes both.
+        let text = format!("{}{}", pair.start, pair.end);
+        let new_offset = offset + pair.start.len();
+        self.replace_text_in_range_silent(None, &text, window, cx);
+        self.selected_range = (new_offset..new_offset).into();
+        self.update_preferred_column();
+        cx.notify();
+        true
+    }
+
+    /// Returns the byte ranges of the bracket adjacent to the cursor and its matching bracket.
+    ///
+    /// The bracket after the cursor takes precedence over the one before.
+    pub(super) fn matching_brackets(&self) -> Option<(Range<usize>, Range<usize>)> {
+        if !self.auto_close_brackets || !self.selected_range.is_empty() {
+            return None;
+        }
+        let config = self.language_config()?;
+
+        let offset = self.cursor();
+        let after = self
+            .text
+            .char_at(offset)
+            .map(|c| offset..offset + c.len_utf8());
+        let before = self
+            .text
+            .chars_at(offset)
+            .reversed()
+            .next()
+            .map(|c| offset - c.len_utf8()..offset);
+
+        [after, before].into_iter().flatten().find_map(|range| {
+            find_matching_bracket(&self.text, range.clone(), &config.bracket_pairs)
+                .map(|matched| (range, matched))
+        })
+    }
+}
+
+/// Find the matching bracket of the bracket at the given range.
+///
+/// Only the single char pairs with different start and end are matched.
+fn find_matching_bracket(
+    text: &Rope,
+    range: Range<usize>,
+    pairs: &[BracketPair],
+) -> Option<Range<usize>> {
+    let c = text.char_at(range.start)?;
+    let is_bracket = |s: &str| s.chars().count() == 1 && s.starts_with(c);
+
+    let pair = pairs.iter().find(|pair| {
+        pair.start != pair.end && (is_bracket(&pair.start) || is_bracket(&pair.end))
+    })?;
+    let start = pair.start.chars().next()?;
+    let end = pair.end.chars().next()?;
+
+    let mut depth = 0;
+    if c == start {
+        let mut offset = range.end;
+        for ch in text.chars_at(range.end) {
+            if offset > range.end + MAX_SCAN_LEN {
+                break;
+            }
+            if ch == end {
+                if depth == 0 {
+                    return Some(offset..offset + ch.len_utf8());
+                }
+                depth -= 1;
+            } else if ch == start {
+                depth += 1;
+            }
+            offset += ch.len_utf8();
+        }
+    } else {
+        let mut offset = range.start;
+        for ch in text.chars_at(range.start).reversed() {
+            if offset + MAX_SCAN_LEN < range.start {
+                break;
+            }
+            offset -= ch.len_utf8();
+            if ch == start {
+                if depth == 0 {
+                    return Some(offset..offset + ch.len_utf8());
+                }
+                depth -= 1;
+            } else if ch == end {
+                depth += 1;
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use ropey::Rope;
+
+    use super::find_matching_bracket;
+    use crate::highlighter::BracketPair;
+
+    #[test]
+    fn test_find_matching_bracket() {
+        let pairs: Vec<BracketPair> = vec![
+            ("(", ")").into(),
+            ("[", "]").into(),
+            ("{", "}").into(),
+            ("\"", "\"").into(),
+        ];
+        let text = Rope::from(r#"{"a": [1, (2)], "中文": {}}"#);
+        let len = text.len();
+
+        assert_eq!(
+            find_matching_bracket(&text, 0..1, &pairs),
+            Some(len - 1..len)
+        );
+        assert_eq!(
+            find_matching_bracket(&text, len - 1..len, &pairs),
+            Some(0..1)
+        );
+        assert_eq!(find_matching_bracket(&text, 6..7, &pairs), Some(13..14));
+        assert_eq!(find_matching_bracket(&text, 13..14, &pairs), Some(6..7));
+        assert_eq!(find_matching_bracket(&text, 10..11, &pairs), Some(12..13));
+        // Multibyte chars before the bracket.
+        let ix = text.to_string().rfind("{}").unwrap();
+        assert_eq!(
+            find_matching_bracket(&text, ix..ix + 1, &pairs),
+            Some(ix + 1..ix + 2)
+        );
+        // Quotes and non-brackets are not matched.
+        assert_eq!(find_matching_bracket(&text, 1..2, &pairs), None);
+        assert_eq!(find_matching_bracket(&text, 2..3, &pairs), None);
+        // Unbalanced
+        let text = Rope::from("{[}");
+        assert_eq!(find_matching_bracket(&text, 1..2, &pairs), None);
+    }
+}
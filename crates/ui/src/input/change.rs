@@ -26,6 +26,14 @@ impl Change {
             version: 0,
         }
     }
+
+    /// Return true if this change is a single char typed, not include the newline.
+    pub(crate) fn is_typing(&self) -> bool {
+        let mut chars = self.new_text.chars();
+        self.old_text.is_empty()
+            && chars.next().is_some_and(|c| c != '\n')
+            && chars.next().is_none()
+    }
 }
 
 impl HistoryItem for Change {
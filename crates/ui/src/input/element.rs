@@ -1,17 +1,20 @@
-use std::{ops::Range, rc::Rc};
+use std::{borrow::Cow, collections::HashSet, ops::Range, rc::Rc};
 
 use gpui::{
-    App, Bounds, Corners, Element, ElementId, ElementInputHandler, Entity, GlobalElementId, Half,
-    HighlightStyle, Hitbox, Hsla, IntoElement, LayoutId, MouseButton, MouseMoveEvent, Path, Pixels,
-    Point, ShapedLine, SharedString, Size, Style, TextRun, TextStyle, UnderlineStyle, Window, fill,
-    point, px, relative, size,
+    AnyElement, App, AvailableSpace, Bounds, Corners, Element, ElementId, ElementInputHandler,
+    Entity, GlobalElementId, Half, HighlightStyle, Hitbox, Hsla, InteractiveElement as _,
+    IntoElement, LayoutId, MouseButton, MouseMoveEvent, ParentElement as _, Path, Pixels, Point,
+    ShapedLine, SharedString, Size, StatefulInteractiveElement as _, Style, Styled as _, TextRun,
+    TextStyle, UnderlineStyle, Window, div, fill, point, prelude::FluentBuilder as _, px, relative,
+    size,
 };
 use ropey::Rope;
 use smallvec::SmallVec;
 
 use crate::{
-    ActiveTheme as _, Colorize, PixelsExt, Root,
-    input::{RopeExt as _, blink_cursor::CURSOR_WIDTH, text_wrapper::LineLayout},
+    ActiveTheme as _, Colorize, Icon, IconName, PixelsExt, Root, Sizable as _,
+    input::{GutterLine, RopeExt as _, blink_cursor::CURSOR_WIDTH, text_wrapper::LineLayout},
+    tooltip::Tooltip,
 };
 
 use super::{InputState, LastLayout, mode::InputMode};
@@ -19,6 +22,16 @@ use super::{InputState, LastLayout, mode::InputMode};
 const BOTTOM_MARGIN_ROWS: usize = 3;
 pub(super) const RIGHT_MARGIN: Pixels = px(10.);
 pub(super) const LINE_NUMBER_RIGHT_MARGIN: Pixels = px(10.);
+/// The width of the markers column in the gutter, see [`InputState::set_gutter_markers`].
+const GUTTER_MARKER_WIDTH: Pixels = px(16.);
+/// The gap between the end of the blank line and its hint.
+const BLANK_LINE_HINT_MARGIN: Pixels = px(4.);
+/// The horizontal padding of the line annotation badge, see [`InputState::set_line_annotations`].
+const LINE_ANNOTATION_PADDING: Pixels = px(4.);
+/// The width of the fold indicators column in the gutter, see [`InputState::folding`].
+pub(super) const FOLD_INDICATOR_WIDTH: Pixels = px(14.);
+/// The gap between the end of the fold header line and its placeholder.
+const FOLD_PLACEHOLDER_MARGIN: Pixels = px(4.);
 
 pub(super) struct TextElement {
     pub(crate) state: Entity<InputState>,
@@ -222,11 +235,7 @@ impl TextElement {
             }
 
             // cursor bounds
-            let cursor_height = match state.size {
-                crate::Size::Large => 1.,
-                crate::Size::Small => 0.75,
-                _ => 0.85,
-            } * line_height;
+            let cursor_height = Self::cursor_height(state.size, line_height);
 
             cursor_bounds = Some(Bounds::new(
                 point(
@@ -386,16 +395,8 @@ impl TextElement {
         bounds: &Bounds<Pixels>,
         cx: &mut App,
     ) -> Vec<(Path<Pixels>, bool)> {
-        let search_panel = self.state.read(cx).search_panel.clone();
-        let Some((ranges, current_match_ix)) = search_panel.and_then(|panel| {
-            if let Some(matcher) = panel.read(cx).matcher() {
-                Some((matcher.matched_ranges.clone(), matcher.current_match_ix))
-            } else {
-                None
-            }
-        }) else {
-            return vec![];
-        };
+        let matcher = &self.state.read(cx).search_matcher;
+        let (ranges, current_match_ix) = (matcher.matched_ranges.clone(), matcher.current_match_ix);
 
         let mut paths = Vec::new();
         for (index, range) in ranges.as_ref().iter().enumerate() {
@@ -422,6 +423,22 @@ impl TextElement {
         Self::layout_match_range(symbol_range, last_layout, bounds)
     }
 
+    fn layout_matching_brackets(
+        &self,
+        last_layout: &LastLayout,
+        bounds: &Bounds<Pixels>,
+        cx: &mut App,
+    ) -> Vec<Path<Pixels>> {
+        let Some((bracket, matched)) = self.state.read(cx).matching_brackets() else {
+            return vec![];
+        };
+
+        [bracket, matched]
+            .into_iter()
+            .filter_map(|range| Self::layout_match_range(range, last_layout, bounds))
+            .collect()
+    }
+
     fn layout_document_colors(
         &self,
         document_colors: &[(Range<usize>, Hsla)],
@@ -438,6 +455,78 @@ impl TextElement {
         paths
     }
 
+    fn cursor_height(size: crate::Size, line_height: Pixels) -> Pixels {
+        match size {
+            crate::Size::Large => 1.,
+            crate::Size::Small => 0.75,
+            _ => 0.85,
+        }
+        *line_height
+    }
+
+    /// Returns the position of the offset in the visible lines, relative to the text origin.
+    fn visible_position_for_offset(
+        offset: usize,
+        last_layout: &LastLayout,
+    ) -> Option<Point<Pixels>> {
+        if offset < last_layout.visible_range_offset.start
+            || offset > last_layout.visible_range_offset.end
+        {
+            return None;
+        }
+
+        let line_height = last_layout.line_height;
+        let mut prev_lines_offset = last_layout.visible_range_offset.start;
+        let mut offset_y = last_layout.visible_top;
+        for line in last_layout.lines.iter() {
+            if let Some(pos) =
+                line.position_for_index(offset.saturating_sub(prev_lines_offset), line_height)
+            {
+                return Some(point(pos.x, offset_y + pos.y));
+            }
+
+            offset_y += line.size(line_height).height;
+            // +1 for the last `\n`
+            prev_lines_offset += line.len() + 1;
+        }
+
+        None
+    }
+
+    /// Layout the selection paths and the cursor bounds of the secondary cursors.
+    fn layout_secondary_cursors(
+        &self,
+        last_layout: &LastLayout,
+        bounds: &Bounds<Pixels>,
+        cx: &App,
+    ) -> (Vec<Path<Pixels>>, Vec<Bounds<Pixels>>) {
+        let state = self.state.read(cx);
+        let line_height = last_layout.line_height;
+        let cursor_height = Self::cursor_height(state.size, line_height);
+
+        let mut selection_paths = vec![];
+        let mut cursor_bounds = vec![];
+        for selection in state.secondary_selections.iter() {
+            let range = selection.start.max(last_layout.visible_range_offset.start)
+                ..selection.end.min(last_layout.visible_range_offset.end);
+            if let Some(path) = Self::layout_match_range(range, last_layout, bounds) {
+                selection_paths.push(path);
+            }
+
+            if let Some(pos) = Self::visible_position_for_offset(selection.end, last_layout) {
+                cursor_bounds.push(Bounds::new(
+                    point(
+                        bounds.left() + last_layout.line_number_width + pos.x,
+                        bounds.top() + pos.y + ((line_height - cursor_height) / 2.),
+                    ),
+                    size(CURSOR_WIDTH, cursor_height),
+                ));
+            }
+        }
+
+        (selection_paths, cursor_bounds)
+    }
+
     fn layout_selections(
         &self,
         last_layout: &LastLayout,
@@ -535,7 +624,14 @@ impl TextElement {
             _ => 8,
         };
 
-        let line_number_width = if state.mode.line_number() {
+        let line_number_width = if !state.mode.line_number() {
+            px(0.)
+        } else if let Some(gutter_width) = state
+            .gutter_width
+            .filter(|_| state.gutter_renderer.is_some())
+        {
+            gutter_width + LINE_NUMBER_RIGHT_MARGIN
+        } else {
             let empty_line_number = window.text_system().shape_line(
                 "+".repeat(line_number_len).into(),
                 font_size,
@@ -551,11 +647,424 @@ impl TextElement {
             );
 
             empty_line_number.width + px(6.) + LINE_NUMBER_RIGHT_MARGIN
+        };
+        let line_number_width = line_number_width
+            + Self::gutter_marker_width(state)
+            + Self::line_annotation_width(state, font_size, style, window)
+            + Self::fold_indicator_width(state);
+
+        (line_number_width, line_number_len)
+    }
+
+    /// Returns the width of the markers column in the gutter, zero if there is no marker.
+    fn gutter_marker_width(state: &InputState) -> Pixels {
+        if state.mode.line_number() && !state.gutter_markers.is_empty() {
+            GUTTER_MARKER_WIDTH
         } else {
             px(0.)
+        }
+    }
+
+    /// Returns the width of the annotations column in the gutter, measured by the longest text,
+    /// zero if there is no annotation.
+    fn line_annotation_width(
+        state: &InputState,
+        font_size: Pixels,
+        style: &TextStyle,
+        window: &mut Window,
+    ) -> Pixels {
+        if !state.mode.line_number() {
+            return px(0.);
+        }
+        let Some(text) = state
+            .line_annotations
+            .values()
+            .map(|annotation| &annotation.text)
+            .max_by_key(|text| text.chars().count())
+        else {
+            return px(0.);
         };
 
-        (line_number_width, line_number_len)
+        let line = window.text_system().shape_line(
+            text.clone(),
+            Self::line_annotation_font_size(font_size),
+            &[TextRun {
+                len: text.len(),
+                font: style.font(),
+                color: gpui::black(),
+                background_color: None,
+                underline: None,
+                strikethrough: None,
+            }],
+            None,
+        );
+        // The paddings of the badge and the gap after it.
+        line.width + LINE_ANNOTATION_PADDING * 3.
+    }
+
+    fn line_annotation_font_size(font_size: Pixels) -> Pixels {
+        font_size * 0.85
+    }
+
+    /// Layout the annotations of the visible lines in the gutter, see [`InputState::set_line_annotations`].
+    #[allow(clippy::too_many_arguments)]
+    fn layout_line_annotations(
+        &self,
+        last_layout: &LastLayout,
+        current_row: Option<usize>,
+        origin: Point<Pixels>,
+        width: Pixels,
+        font_size: Pixels,
+        ghost_lines_height: Pixels,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        let state = self.state.read(cx);
+        if width == px(0.) {
+            return vec![];
+        }
+        let line_height = last_layout.line_height;
+        let default_color = cx.theme().muted_foreground;
+
+        let mut elements = vec![];
+        let mut offset_y = last_layout.visible_top;
+        for (ix, line) in last_layout.lines.iter().enumerate() {
+            let row = last_layout.visible_range.start + ix;
+            // The folded lines are hidden.
+            if line.wrapped_lines.is_empty() {
+                continue;
+            }
+            if let Some(annotation) = state.line_annotations.get(&row) {
+                let color = annotation.color.unwrap_or(default_color);
+                let tooltip = annotation.tooltip.clone();
+                let element = div()
+                    .id(("line-annotation", row))
+                    .flex()
+                    .items_center()
+                    .w(width - LINE_ANNOTATION_PADDING)
+                    .h(line_height)
+                    .cursor_pointer()
+                    .child(
+                        div()
+                            .px(LINE_ANNOTATION_PADDING)
+                            .rounded(cx.theme().radius.half())
+                            .bg(color.opacity(0.15))
+                            .text_color(color)
+                            .text_size(Self::line_annotation_font_size(font_size))
+                            .line_height(line_height)
+                            .whitespace_nowrap()
+                            .overflow_hidden()
+                            .child(annotation.text.clone()),
+                    )
+                    .when_some(tooltip, |this, tooltip| {
+                        this.tooltip(move |window, cx| {
+                            Tooltip::new(tooltip.clone()).build(window, cx)
+                        })
+                    })
+                    .into_any_element();
+                elements.push((element, point(origin.x, origin.y + offset_y)));
+            }
+
+            offset_y += line.size(line_height).height;
+            if Some(row) == current_row {
+                offset_y += ghost_lines_height;
+            }
+        }
+
+        elements
+            .into_iter()
+            .map(|(mut element, origin)| {
+                element.layout_as_root(
+                    size(
+                        AvailableSpace::Definite(width),
+                        AvailableSpace::Definite(line_height),
+                    ),
+                    window,
+                    cx,
+                );
+                element.prepaint_at(origin, window, cx);
+                element
+            })
+            .collect()
+    }
+
+    /// Returns the width of the fold indicators column in the gutter, zero if folding is disabled.
+    fn fold_indicator_width(state: &InputState) -> Pixels {
+        if state.mode.line_number() && state.is_folding_enabled() {
+            FOLD_INDICATOR_WIDTH
+        } else {
+            px(0.)
+        }
+    }
+
+    /// Layout the markers of the visible lines in the gutter, see [`InputState::set_gutter_markers`].
+    fn layout_gutter_markers(
+        &self,
+        last_layout: &LastLayout,
+        current_row: Option<usize>,
+        origin: Point<Pixels>,
+        ghost_lines_height: Pixels,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        let state = self.state.read(cx);
+        if Self::gutter_marker_width(state) == px(0.) {
+            return vec![];
+        }
+        let line_height = last_layout.line_height;
+        let default_color = cx.theme().muted_foreground;
+
+        let mut elements = vec![];
+        let mut offset_y = last_layout.visible_top;
+        for (ix, line) in last_layout.lines.iter().enumerate() {
+            let row = last_layout.visible_range.start + ix;
+            if line.wrapped_lines.is_empty() {
+                continue;
+            }
+            if let Some(marker) = state.gutter_markers.get(&row) {
+                let element = div()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .w(GUTTER_MARKER_WIDTH)
+                    .h(line_height)
+                    .child(
+                        marker
+                            .icon
+                            .clone()
+                            .xsmall()
+                            .text_color(marker.color.unwrap_or(default_color)),
+                    )
+                    .into_any_element();
+                elements.push((element, point(origin.x, origin.y + offset_y)));
+            }
+
+            offset_y += line.size(line_height).height;
+            if Some(row) == current_row {
+                offset_y += ghost_lines_height;
+            }
+        }
+
+        elements
+            .into_iter()
+            .map(|(mut element, origin)| {
+                element.layout_as_root(
+                    size(
+                        AvailableSpace::Definite(GUTTER_MARKER_WIDTH),
+                        AvailableSpace::Definite(line_height),
+                    ),
+                    window,
+                    cx,
+                );
+                element.prepaint_at(origin, window, cx);
+                element
+            })
+            .collect()
+    }
+
+    /// Layout the fold indicators of the visible foldable lines in the gutter.
+    fn layout_fold_indicators(
+        &self,
+        last_layout: &LastLayout,
+        current_row: Option<usize>,
+        origin: Point<Pixels>,
+        ghost_lines_height: Pixels,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        let state = self.state.read(cx);
+        if Self::fold_indicator_width(state) == px(0.) {
+            return vec![];
+        }
+        let line_height = last_layout.line_height;
+        let color = cx.theme().muted_foreground;
+        let foldable_rows = state
+            .foldable_ranges(last_layout.visible_range.clone())
+            .into_iter()
+            .map(|range| state.text.offset_to_point(range.start).row)
+            .collect::<HashSet<_>>();
+
+        let mut elements = vec![];
+        let mut offset_y = last_layout.visible_top;
+        for (ix, line) in last_layout.lines.iter().enumerate() {
+            let row = last_layout.visible_range.start + ix;
+            if line.wrapped_lines.is_empty() {
+                continue;
+            }
+
+            let folded = state.is_folded(row);
+            if folded || foldable_rows.contains(&row) {
+                let icon = if folded {
+                    IconName::ChevronRight
+                } else {
+                    IconName::ChevronDown
+                };
+                let element = div()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .w(FOLD_INDICATOR_WIDTH)
+                    .h(line_height)
+                    .child(Icon::new(icon).xsmall().text_color(color))
+                    .into_any_element();
+                elements.push((element, point(origin.x, origin.y + offset_y)));
+            }
+
+            offset_y += line.size(line_height).height;
+            if Some(row) == current_row {
+                offset_y += ghost_lines_height;
+            }
+        }
+
+        elements
+            .into_iter()
+            .map(|(mut element, origin)| {
+                element.layout_as_root(
+                    size(
+                        AvailableSpace::Definite(FOLD_INDICATOR_WIDTH),
+                        AvailableSpace::Definite(line_height),
+                    ),
+                    window,
+                    cx,
+                );
+                element.prepaint_at(origin, window, cx);
+                element
+            })
+            .collect()
+    }
+
+    /// Layout the placeholders of the visible folded lines, see [`InputState::folded_ranges`].
+    ///
+    /// The placeholder is a `…` followed by the rest of the last folded line, e.g.: `…},`.
+    ///
+    /// Returns the rows of the fold headers and the shaped placeholders.
+    fn layout_fold_placeholders(
+        state: &InputState,
+        last_layout: &LastLayout,
+        font_size: Pixels,
+        window: &mut Window,
+        cx: &App,
+    ) -> Vec<(usize, ShapedLine)> {
+        let text = &state.text;
+        let font = window.text_style().font();
+
+        state
+            .folds
+            .iter()
+            .filter_map(|fold| {
+                let row = text.offset_to_point(fold.start).row;
+                last_layout
+                    .line(row)
+                    .filter(|line| !line.wrapped_lines.is_empty())?;
+
+                let end_row = text.offset_to_point(fold.end).row;
+                let end_char_start = text
+                    .chars_at(fold.end)
+                    .reversed()
+                    .next()
+                    .map_or(fold.end, |c| fold.end - c.len_utf8());
+                let tail_start = end_char_start.max(text.line_start_offset(end_row));
+                let tail = text
+                    .slice(tail_start..text.line_end_offset(end_row))
+                    .to_string();
+
+                let ellipsis = "…";
+                let runs = [
+                    TextRun {
+                        len: ellipsis.len(),
+                        font: font.clone(),
+                        color: cx.theme().muted_foreground,
+                        background_color: Some(cx.theme().muted),
+                        underline: None,
+                        strikethrough: None,
+                    },
+                    TextRun {
+                        len: tail.len(),
+                        font: font.clone(),
+                        color: cx.theme().foreground,
+                        background_color: None,
+                        underline: None,
+                        strikethrough: None,
+                    },
+                ]
+                .into_iter()
+                .filter(|run| run.len > 0)
+                .collect::<Vec<_>>();
+                let line = window.text_system().shape_line(
+                    format!("{}{}", ellipsis, tail).into(),
+                    font_size,
+                    &runs,
+                    None,
+                );
+
+                Some((row, line))
+            })
+            .collect()
+    }
+
+    /// Layout the custom gutter elements of the visible lines, and measure the gutter width.
+    fn layout_gutter(
+        &self,
+        last_layout: &LastLayout,
+        current_row: Option<usize>,
+        origin: Point<Pixels>,
+        ghost_lines_height: Pixels,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        let state = self.state.read(cx);
+        if !state.mode.line_number() {
+            return vec![];
+        }
+        let Some(gutter_renderer) = state.gutter_renderer.clone() else {
+            return vec![];
+        };
+        let total_lines = state.text.lines_len();
+        let line_height = last_layout.line_height;
+
+        let mut elements = Vec::with_capacity(last_layout.lines.len());
+        let mut gutter_width = px(0.);
+        let mut offset_y = last_layout.visible_top;
+        for (ix, line) in last_layout.lines.iter().enumerate() {
+            let row = last_layout.visible_range.start + ix;
+            // Skip the lines hidden in the folds.
+            if line.wrapped_lines.is_empty() {
+                continue;
+            }
+            let gutter_line = GutterLine {
+                line: row,
+                current_line: current_row,
+                total_lines,
+            };
+
+            let mut element = gutter_renderer(&gutter_line, window, cx);
+            let element_size = element.layout_as_root(
+                size(
+                    AvailableSpace::MinContent,
+                    AvailableSpace::Definite(line_height),
+                ),
+                window,
+                cx,
+            );
+            gutter_width = gutter_width.max(element_size.width);
+            element.prepaint_at(point(origin.x, origin.y + offset_y), window, cx);
+            elements.push(element);
+
+            offset_y += line.size(line_height).height;
+            if Some(row) == current_row {
+                offset_y += ghost_lines_height;
+            }
+        }
+
+        // Relayout with the new width on next frame.
+        if self.state.read(cx).gutter_width != Some(gutter_width) {
+            self.state.update(cx, |state, cx| {
+                state.gutter_width = Some(gutter_width);
+                cx.notify();
+            });
+        }
+
+        elements
     }
 
     /// Compute inline completion ghost lines for rendering.
@@ -643,6 +1152,51 @@ impl TextElement {
         (first_line, ghost_lines)
     }
 
+    /// Layout the hint of the blank line at the cursor, see [`InputState::blank_line_hint`].
+    ///
+    /// Returns the row and the shaped hint.
+    fn layout_blank_line_hint(
+        state: &InputState,
+        current_row: Option<usize>,
+        font_size: Pixels,
+        window: &mut Window,
+        cx: &App,
+    ) -> Option<(usize, ShapedLine)> {
+        let blank_line_hint = state.blank_line_hint.as_ref()?;
+        let row = current_row?;
+        if !state.focus_handle.is_focused(window)
+            || state.text.len() == 0
+            || !state.selected_range.is_empty()
+            || state.ime_marked_range.is_some()
+            || state.inline_completion.item.is_some()
+        {
+            return None;
+        }
+        if !state
+            .text
+            .slice_line(row)
+            .chars()
+            .all(|c| c.is_whitespace())
+        {
+            return None;
+        }
+
+        let hint = blank_line_hint(row).filter(|hint| !hint.is_empty())?;
+        let run = TextRun {
+            len: hint.len(),
+            font: window.text_style().font(),
+            color: cx.theme().muted_foreground.opacity(0.5),
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        };
+        let line = window
+            .text_system()
+            .shape_line(hint, font_size, &[run], None);
+
+        Some((row, line))
+    }
+
     fn layout_lines(
         state: &InputState,
         display_text: &Rope,
@@ -658,12 +1212,14 @@ impl TextElement {
         let visible_range_offset = &last_layout.visible_range_offset;
 
         if is_single_line {
-            let shaped_line = window.text_system().shape_line(
-                display_text.to_string().into(),
-                font_size,
-                &runs,
-                None,
-            );
+            let mut line_text = display_text.to_string();
+            if state.render_control_chars {
+                line_text = replace_control_chars(&line_text).into_owned();
+            }
+            let shaped_line =
+                window
+                    .text_system()
+                    .shape_line(line_text.into(), font_size, &runs, None);
 
             return vec![LineLayout::new().lines(smallvec::smallvec![shaped_line])];
         }
@@ -685,9 +1241,12 @@ impl TextElement {
                 .collect();
         }
 
-        let visible_text = display_text
+        let mut visible_text = display_text
             .slice_lines(visible_range.start..visible_range.end)
             .to_string();
+        if state.render_control_chars {
+            visible_text = replace_control_chars(&visible_text).into_owned();
+        }
 
         let mut lines = vec![];
         let mut offset = 0;
@@ -699,6 +1258,13 @@ impl TextElement {
 
             debug_assert_eq!(line_item.len(), line.len());
 
+            if line_item.is_hidden() {
+                lines.push(LineLayout::hidden(line.len()));
+                // +1 for the `\n`
+                offset += line.len() + 1;
+                continue;
+            }
+
             let mut line_layout = LineLayout::new();
             let mut wrapped_lines = SmallVec::with_capacity(1);
 
@@ -797,6 +1363,20 @@ pub(super) struct PrepaintState {
     ///
     /// The child is the soft lines.
     line_numbers: Option<Vec<SmallVec<[ShapedLine; 1]>>>,
+    /// The custom gutter elements of the visible lines, see [`InputState::gutter_renderer`].
+    gutter_elements: Vec<AnyElement>,
+    /// The width of the markers column before the line numbers.
+    gutter_marker_width: Pixels,
+    /// The marker elements of the visible lines, see [`InputState::set_gutter_markers`].
+    gutter_marker_elements: Vec<AnyElement>,
+    /// The width of the annotations column after the line numbers.
+    line_annotation_width: Pixels,
+    /// The annotation elements of the visible lines, see [`InputState::set_line_annotations`].
+    line_annotation_elements: Vec<AnyElement>,
+    /// The fold indicator elements of the visible foldable lines.
+    fold_indicator_elements: Vec<AnyElement>,
+    /// The rows of the visible fold headers and the shaped placeholders.
+    fold_placeholders: Vec<(usize, ShapedLine)>,
     /// Size of the scrollable area by entire lines.
     scroll_size: Size<Pixels>,
     cursor_bounds: Option<Bounds<Pixels>>,
@@ -804,11 +1384,19 @@ pub(super) struct PrepaintState {
     /// row index (zero based), no wrap, same line as the cursor.
     current_row: Option<usize>,
     selection_path: Option<Path<Pixels>>,
+    /// The selection paths of the secondary cursors, see [`InputState::cursors`].
+    secondary_selection_paths: Vec<Path<Pixels>>,
+    /// The bounds of the secondary cursors, the scroll offset is included.
+    secondary_cursor_bounds: Vec<Bounds<Pixels>>,
     hover_highlight_path: Option<Path<Pixels>>,
     search_match_paths: Vec<(Path<Pixels>, bool)>,
+    /// The paths of the bracket adjacent to the cursor and its matching bracket.
+    bracket_paths: Vec<Path<Pixels>>,
     document_color_paths: Vec<(Path<Pixels>, Hsla)>,
     hover_definition_hitbox: Option<Hitbox>,
     indent_guides_path: Option<Path<Pixels>>,
+    ruler_path: Option<Path<Pixels>>,
+    whitespace_path: Option<Path<Pixels>>,
     bounds: Bounds<Pixels>,
     // Inline completion rendering data
     /// Shaped ghost lines to paint after cursor row (completion lines 2+)
@@ -816,6 +1404,8 @@ pub(super) struct PrepaintState {
     /// First line of inline completion (painted after cursor on same line)
     ghost_first_line: Option<ShapedLine>,
     ghost_lines_height: Pixels,
+    /// The row and the shaped hint of the blank line at the cursor.
+    blank_line_hint: Option<(usize, ShapedLine)>,
 }
 
 impl PrepaintState {
@@ -926,10 +1516,26 @@ impl Element for TextElement {
         self.state.update(cx, |state, cx| {
             state.text_wrapper.set_font(font, text_size, cx);
             state.text_wrapper.prepare_if_need(&state.text, cx);
+            state.reveal_cursor_in_folds();
         });
 
-        let state = self.state.read(cx);
         let line_height = window.line_height();
+        if self.state.read(cx).line_source.is_some() {
+            let (visible_range, _) =
+                self.calculate_visible_range(self.state.read(cx), line_height, bounds.size.height);
+            // Load the lines after the paint, and render them in the next frame.
+            if self.state.read(cx).has_unloaded_lines(&visible_range) {
+                let state = self.state.clone();
+                window.defer(cx, move |_, cx| {
+                    state.update(cx, |state, cx| {
+                        state.load_lines(visible_range, cx);
+                        cx.notify();
+                    });
+                });
+            }
+        }
+
+        let state = self.state.read(cx);
 
         let (visible_range, visible_top) =
             self.calculate_visible_range(&state, line_height, bounds.size.height);
@@ -951,6 +1557,7 @@ impl Element for TextElement {
         let is_empty = text.len() == 0;
         let placeholder = self.placeholder.clone();
 
+        let input_bounds = bounds;
         let mut bounds = bounds;
 
         let (display_text, text_color) = if is_empty {
@@ -1058,9 +1665,18 @@ impl Element for TextElement {
             vec![run]
         };
 
-        let document_colors = state
+        let mut document_colors = state
             .lsp
             .document_colors_for_range(&text, &last_layout.visible_range);
+        if state.render_control_chars && !is_empty && !state.masked {
+            let control_color = cx.theme().danger.opacity(0.8);
+            document_colors.extend(
+                control_char_ranges(&text, &last_layout.visible_range_offset)
+                    .into_iter()
+                    .map(|range| (range, control_color)),
+            );
+            document_colors.sort_by_key(|(range, _)| range.start);
+        }
         let lines = Self::layout_lines(
             &state,
             &display_text,
@@ -1164,7 +1780,12 @@ impl Element for TextElement {
 
         let search_match_paths = self.layout_search_matches(&last_layout, &mut bounds, cx);
         let selection_path = self.layout_selections(&last_layout, &mut bounds, cx);
+        let (secondary_selection_paths, secondary_cursor_bounds) =
+            self.layout_secondary_cursors(&last_layout, &bounds, cx);
         let hover_highlight_path = self.layout_hover_highlight(&last_layout, &mut bounds, cx);
+        let bracket_paths = self.layout_matching_brackets(&last_layout, &bounds, cx);
+        let blank_line_hint =
+            Self::layout_blank_line_hint(self.state.read(cx), current_row, text_size, window, cx);
         let document_color_paths =
             self.layout_document_colors(&document_colors, &last_layout, &bounds);
 
@@ -1200,11 +1821,21 @@ impl Element for TextElement {
                 };
 
                 let mut sub_lines: SmallVec<[ShapedLine; 1]> = SmallVec::new();
-                sub_lines.push(
-                    window
-                        .text_system()
-                        .shape_line(line_no, text_size, &runs, None),
-                );
+                // The line is hidden in a fold.
+                if line.wrapped_lines.is_empty() {
+                    line_numbers.push(sub_lines);
+                    continue;
+                }
+                // The custom gutter is rendered by `layout_gutter`.
+                if state.gutter_renderer.is_some() {
+                    sub_lines.push(ShapedLine::default());
+                } else {
+                    sub_lines.push(
+                        window
+                            .text_system()
+                            .shape_line(line_no, text_size, &runs, None),
+                    );
+                }
                 for _ in 0..line.wrapped_lines.len().saturating_sub(1) {
                     sub_lines.push(ShapedLine::default());
                 }
@@ -1218,24 +1849,104 @@ impl Element for TextElement {
         let hover_definition_hitbox = self.layout_hover_definition_hitbox(state, window, cx);
         let indent_guides_path =
             self.layout_indent_guides(state, &bounds, &last_layout, &text_style, window);
+        let ruler_path = self.layout_rulers(
+            state,
+            &bounds,
+            &input_bounds,
+            &last_layout,
+            &text_style,
+            window,
+        );
+        let whitespace_path =
+            self.layout_whitespace(state, &bounds, &last_layout, &text_style, window);
+        let gutter_marker_width = Self::gutter_marker_width(state);
+        let gutter_marker_elements = self.layout_gutter_markers(
+            &last_layout,
+            current_row,
+            point(input_bounds.origin.x, bounds.origin.y),
+            ghost_lines_height,
+            window,
+            cx,
+        );
+        let gutter_elements = self.layout_gutter(
+            &last_layout,
+            current_row,
+            point(input_bounds.origin.x + gutter_marker_width, bounds.origin.y),
+            ghost_lines_height,
+            window,
+            cx,
+        );
+        let fold_indicator_elements = self.layout_fold_indicators(
+            &last_layout,
+            current_row,
+            point(
+                input_bounds.origin.x + line_number_width
+                    - LINE_NUMBER_RIGHT_MARGIN
+                    - FOLD_INDICATOR_WIDTH,
+                bounds.origin.y,
+            ),
+            ghost_lines_height,
+            window,
+            cx,
+        );
+        let state = self.state.read(cx);
+        let line_annotation_width =
+            Self::line_annotation_width(state, text_size, &text_style, window);
+        let fold_indicator_width = Self::fold_indicator_width(state);
+        let line_annotation_elements = self.layout_line_annotations(
+            &last_layout,
+            current_row,
+            point(
+                input_bounds.origin.x + line_number_width
+                    - LINE_NUMBER_RIGHT_MARGIN
+                    - fold_indicator_width
+                    - line_annotation_width,
+                bounds.origin.y,
+            ),
+            line_annotation_width,
+            text_size,
+            ghost_lines_height,
+            window,
+            cx,
+        );
+        let fold_placeholders = Self::layout_fold_placeholders(
+            self.state.read(cx),
+            &last_layout,
+            text_size,
+            window,
+            cx,
+        );
 
         PrepaintState {
             bounds,
             last_layout,
             scroll_size,
             line_numbers,
+            gutter_elements,
+            gutter_marker_width,
+            gutter_marker_elements,
+            line_annotation_width,
+            line_annotation_elements,
+            fold_indicator_elements,
+            fold_placeholders,
             cursor_bounds,
             cursor_scroll_offset,
             current_row,
             selection_path,
+            secondary_selection_paths,
+            secondary_cursor_bounds,
             search_match_paths,
+            bracket_paths,
             hover_highlight_path,
             hover_definition_hitbox,
             document_color_paths,
             indent_guides_path,
+            ruler_path,
+            whitespace_path,
             ghost_first_line,
             ghost_lines,
             ghost_lines_height,
+            blank_line_hint,
         }
     }
 
@@ -1334,7 +2045,28 @@ impl Element for TextElement {
             window.paint_path(path, cx.theme().border.opacity(0.85));
         }
 
+        // Paint rulers and whitespace markers
+        if let Some(path) = prepaint.ruler_path.take() {
+            window.paint_path(path, cx.theme().border);
+        }
+        if let Some(path) = prepaint.whitespace_path.take() {
+            window.paint_path(path, cx.theme().muted_foreground.opacity(0.5));
+        }
+
         // Paint selections
+        let selection_color =
+            if self.state.read(cx).focus_handle.is_focused(window) && window.is_window_active() {
+                cx.theme().selection
+            } else {
+                cx.theme().selection_inactive
+            };
+        if let Some(path) = prepaint.selection_path.take() {
+            window.paint_path(path, selection_color);
+        }
+        for path in prepaint.secondary_selection_paths.drain(..) {
+            window.paint_path(path, selection_color);
+        }
+
         if window.is_window_active() {
             let secondary_selection = cx.theme().selection.saturation(0.1);
             for (path, is_active) in prepaint.search_match_paths.iter() {
@@ -1345,14 +2077,14 @@ impl Element for TextElement {
                 }
             }
 
-            if let Some(path) = prepaint.selection_path.take() {
-                window.paint_path(path, cx.theme().selection);
-            }
-
             // Paint hover highlight
             if let Some(path) = prepaint.hover_highlight_path.take() {
                 window.paint_path(path, secondary_selection);
             }
+
+            for path in prepaint.bracket_paths.drain(..) {
+                window.paint_path(path, secondary_selection);
+            }
         }
 
         // Paint document colors
@@ -1374,6 +2106,25 @@ impl Element for TextElement {
 
             // Paint the actual line
             _ = line.paint(p, line_height, window, cx);
+            if let Some((_, hint)) = prepaint
+                .blank_line_hint
+                .as_ref()
+                .filter(|(hint_row, _)| *hint_row == row)
+            {
+                let hint_x = p.x + line.longest_width + BLANK_LINE_HINT_MARGIN;
+                _ = hint.paint(point(hint_x, p.y), line_height, window, cx);
+            }
+            if let Some((_, placeholder)) = prepaint
+                .fold_placeholders
+                .iter()
+                .find(|(fold_row, _)| *fold_row == row)
+            {
+                if let Some(last_line) = line.wrapped_lines.last() {
+                    let y = p.y + line_height * (line.wrapped_lines.len() - 1) as f32;
+                    let x = p.x + last_line.width + FOLD_PLACEHOLDER_MARGIN;
+                    _ = placeholder.paint(point(x, y), line_height, window, cx);
+                }
+            }
             offset_y += line.size(line_height).height;
 
             // After the cursor row, paint ghost lines (which shifts subsequent content down)
@@ -1405,6 +2156,9 @@ impl Element for TextElement {
             if let Some(cursor_bounds) = prepaint.cursor_bounds_with_scroll() {
                 window.paint_quad(fill(cursor_bounds, cx.theme().caret));
             }
+            for cursor_bounds in prepaint.secondary_cursor_bounds.iter() {
+                window.paint_quad(fill(*cursor_bounds, cx.theme().caret));
+            }
         }
 
         // Paint line numbers
@@ -1441,8 +2195,9 @@ impl Element for TextElement {
                     }
                 }
 
+                let line_number_p = point(p.x + prepaint.gutter_marker_width, p.y);
                 for line in lines {
-                    _ = line.paint(p, line_height, window, cx);
+                    _ = line.paint(line_number_p, line_height, window, cx);
                     offset_y += line_height;
                 }
 
@@ -1451,6 +2206,19 @@ impl Element for TextElement {
                     offset_y += prepaint.ghost_lines_height;
                 }
             }
+
+            for element in prepaint.gutter_elements.iter_mut() {
+                element.paint(window, cx);
+            }
+            for element in prepaint.gutter_marker_elements.iter_mut() {
+                element.paint(window, cx);
+            }
+            for element in prepaint.line_annotation_elements.iter_mut() {
+                element.paint(window, cx);
+            }
+            for element in prepaint.fold_indicator_elements.iter_mut() {
+                element.paint(window, cx);
+            }
         }
 
         self.state.update(cx, |state, cx| {
@@ -1459,6 +2227,7 @@ impl Element for TextElement {
             state.last_cursor = Some(state.cursor());
             state.set_input_bounds(input_bounds, cx);
             state.last_selected_range = Some(selected_range);
+            state.line_annotation_width = prepaint.line_annotation_width;
             state.scroll_size = prepaint.scroll_size;
             state.update_scroll_offset(Some(prepaint.cursor_scroll_offset), cx);
             state.deferred_scroll_offset = None;
@@ -1530,6 +2299,51 @@ pub(super) fn runs_for_range(
     result
 }
 
+/// Return true if the char is a control character should be rendered as a replacement symbol.
+///
+/// The `\t`, `\n`, `\r` are excluded, they are handled by the layout.
+#[inline]
+fn is_hidden_control_char(c: char) -> bool {
+    c.is_control() && !matches!(c, '\t' | '\n' | '\r')
+}
+
+/// Replace the control characters with `?` to make them visible.
+///
+/// The replacement keeps the same UTF-8 length of the original character,
+/// so the byte offsets of the text are still valid for the layout.
+pub(super) fn replace_control_chars(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(is_hidden_control_char) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if is_hidden_control_char(c) {
+            for _ in 0..c.len_utf8() {
+                result.push('?');
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// Get the byte ranges of the control characters in the given range of the text.
+fn control_char_ranges(text: &Rope, range: &Range<usize>) -> Vec<Range<usize>> {
+    let range = range.start.min(text.len())..range.end.min(text.len());
+    let mut ranges = vec![];
+    let mut offset = range.start;
+    for c in text.slice(range).chars() {
+        let len = c.len_utf8();
+        if is_hidden_control_char(c) {
+            ranges.push(offset..offset + len);
+        }
+        offset += len;
+    }
+    ranges
+}
+
 fn split_runs_by_bg_segments(
     start_offset: usize,
     runs: &[TextRun],
@@ -1654,6 +2468,26 @@ mod tests {
         assert_runs(runs_for_range(&runs, 9, &(0..8)), &[1, 7]);
     }
 
+    #[test]
+    fn test_replace_control_chars() {
+        assert_eq!(replace_control_chars("hello"), "hello");
+        assert_eq!(replace_control_chars("a\tb\r\nc"), "a\tb\r\nc");
+        assert_eq!(replace_control_chars("a\u{0}b\u{1b}"), "a?b?");
+        // C1 control character is 2 bytes in UTF-8.
+        assert_eq!(replace_control_chars("中\u{85}文"), "中??文");
+        assert_eq!(
+            replace_control_chars("中\u{85}文").len(),
+            "中\u{85}文".len()
+        );
+
+        let text = Rope::from("{\"a\": \"\u{7}\"}\n\u{0}");
+        assert_eq!(
+            control_char_ranges(&text, &(0..text.len())),
+            vec![7..8, 11..12]
+        );
+        assert_eq!(control_char_ranges(&text, &(9..100)), vec![11..12]);
+    }
+
     #[test]
     fn test_split_runs_by_bg_segments() {
         let run = TextRun {
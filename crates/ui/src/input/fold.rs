@@ -0,0 +1,506 @@
+use std::ops::Range;
+
+use gpui::{App, Context, KeyBinding, Pixels, Point, Window, actions};
+use ropey::Rope;
+use sum_tree::Bias;
+
+use crate::{
+    highlighter::BracketPair,
+    input::{
+        InputState, RopeExt as _,
+        element::{FOLD_INDICATOR_WIDTH, LINE_NUMBER_RIGHT_MARGIN},
+        mode::InputMode,
+        state::{CONTEXT, map_offset_for_edit},
+    },
+};
+
+actions!(input, [Fold, Unfold, FoldAll, UnfoldAll]);
+
+pub(super) fn init(cx: &mut App) {
+    cx.bind_keys(vec![
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-alt-[", Fold, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-[", Fold, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-alt-]", Unfold, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-]", Unfold, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-k cmd-0", FoldAll, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-k ctrl-0", FoldAll, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-k cmd-j", UnfoldAll, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-k ctrl-j", UnfoldAll, Some(CONTEXT)),
+    ]);
+}
+
+impl InputState {
+    /// Set to enable the code folding, default is true.
+    ///
+    /// Only for [`InputMode::CodeEditor`] with multi-line mode, the foldable ranges
+    /// are the brackets of the syntax tree, or the ranges set by [`InputState::set_fold_ranges`].
+    pub fn folding(mut self, folding: bool) -> Self {
+        debug_assert!(self.mode.is_code_editor() && self.mode.is_multi_line());
+        self.folding = folding;
+        self
+    }
+
+    /// Set the foldable byte ranges to replace the ranges from the syntax tree.
+    ///
+    /// The range is folded from the line of the `start` to the line of the `end`, the
+    /// ranges in a single line are ignored. The folded ranges that still exist are kept.
+    pub fn set_fold_ranges(&mut self, ranges: Vec<Range<usize>>, cx: &mut Context<Self>) {
+        let len = self.text.len();
+        let ranges = ranges
+            .into_iter()
+            .map(|range| range.start.min(len)..range.end.min(len))
+            .collect::<Vec<_>>();
+        let ranges = dedup_fold_ranges(&self.text, ranges);
+
+        let text = &self.text;
+        self.folds = self
+            .folds
+            .iter()
+            .filter_map(|fold| {
+                let row = text.offset_to_point(fold.start).row;
+                ranges
+                    .iter()
+                    .find(|range| text.offset_to_point(range.start).row == row)
+                    .cloned()
+            })
+            .collect();
+        self.fold_ranges = Some(ranges);
+        self.sync_folds(cx);
+    }
+
+    /// Returns the folded byte ranges, sorted by the start.
+    pub fn folded_ranges(&self) -> &[Range<usize>] {
+        &self.folds
+    }
+
+    /// Return true if the line (zero-based) is the first line of a folded range.
+    pub fn is_folded(&self, line: usize) -> bool {
+        self.folds
+            .iter()
+            .any(|fold| self.text.offset_to_point(fold.start).row == line)
+    }
+
+    /// Fold or unfold the range starts at the line (zero-based).
+    pub fn toggle_fold(&mut self, line: usize, cx: &mut Context<Self>) {
+        if self.is_folded(line) {
+            self.unfold_line(line, cx);
+        } else if let Some(range) = self.foldable_range(line) {
+            self.fold(range, cx);
+        }
+    }
+
+    /// Fold all the foldable ranges.
+    pub fn fold_all(&mut self, cx: &mut Context<Self>) {
+        if !self.is_folding_enabled() {
+            return;
+        }
+
+        self.folds = self.foldable_ranges(0..self.text.lines_len());
+        self.sync_folds(cx);
+        self.reveal_cursor_in_folds_header(cx);
+    }
+
+    /// Unfold all the folded ranges.
+    pub fn unfold_all(&mut self, cx: &mut Context<Self>) {
+        if self.folds.is_empty() {
+            return;
+        }
+
+        self.folds.clear();
+        self.sync_folds(cx);
+    }
+
+    pub(super) fn on_action_fold(&mut self, _: &Fold, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.is_folding_enabled() {
+            return;
+        }
+
+        // Fold the innermost unfolded range contains the cursor.
+        let row = self.text.offset_to_point(self.cursor()).row;
+        let range = self
+            .foldable_ranges(0..row + 1)
+            .into_iter()
+            .rev()
+            .find(|range| {
+                self.text.offset_to_point(range.end).row >= row
+                    && !self.folds.iter().any(|fold| fold.start == range.start)
+            });
+        if let Some(range) = range {
+            self.fold(range, cx);
+        }
+    }
+
+    pub(super) fn on_action_unfold(&mut self, _: &Unfold, _: &mut Window, cx: &mut Context<Self>) {
+        let row = self.text.offset_to_point(self.cursor()).row;
+        self.unfold_line(row, cx);
+    }
+
+    pub(super) fn on_action_fold_all(
+        &mut self,
+        _: &FoldAll,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.fold_all(cx);
+    }
+
+    pub(super) fn on_action_unfold_all(
+        &mut self,
+        _: &UnfoldAll,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.unfold_all(cx);
+    }
+
+    /// Return true if the folding is enabled and there are foldable ranges source.
+    pub(super) fn is_folding_enabled(&self) -> bool {
+        self.folding
+            && self.mode.is_code_editor()
+            && self.mode.is_multi_line()
+            && (self.fold_ranges.is_some() || self.language_config().is_some())
+    }
+
+    /// Returns the foldable ranges start in the rows, sorted by the start.
+    pub(super) fn foldable_ranges(&self, rows: Range<usize>) -> Vec<Range<usize>> {
+        if !self.is_folding_enabled() {
+            return vec![];
+        }
+
+        let text = &self.text;
+        if let Some(ranges) = &self.fold_ranges {
+            return ranges
+                .iter()
+                .filter(|range| rows.contains(&text.offset_to_point(range.start).row))
+                .cloned()
+                .collect();
+        }
+
+        let InputMode::CodeEditor { highlighter, .. } = &self.mode else {
+            return vec![];
+        };
+        let Some(config) = self.language_config() else {
+            return vec![];
+        };
+        let highlighter = highlighter.borrow();
+        let Some(tree) = highlighter.as_ref().and_then(|h| h.tree()) else {
+            return vec![];
+        };
+
+        let mut ranges = vec![];
+        collect_fold_ranges(tree.root_node(), &rows, &config.bracket_pairs, &mut ranges);
+        dedup_fold_ranges(text, ranges)
+    }
+
+    /// Returns the foldable range starts at the line (zero-based).
+    pub(super) fn foldable_range(&self, line: usize) -> Option<Range<usize>> {
+        self.foldable_ranges(line..line + 1).into_iter().next()
+    }
+
+    fn fold(&mut self, range: Range<usize>, cx: &mut Context<Self>) {
+        let ix = self.folds.partition_point(|fold| fold.start < range.start);
+        self.folds.insert(ix, range);
+        self.sync_folds(cx);
+        self.reveal_cursor_in_folds_header(cx);
+    }
+
+    fn unfold_line(&mut self, line: usize, cx: &mut Context<Self>) {
+        let len = self.folds.len();
+        let text = &self.text;
+        self.folds
+            .retain(|fold| text.offset_to_point(fold.start).row != line);
+        if self.folds.len() != len {
+            self.sync_folds(cx);
+        }
+    }
+
+    /// Move the cursor to the end of the fold header if it's hidden in a fold.
+    fn reveal_cursor_in_folds_header(&mut self, cx: &mut Context<Self>) {
+        let row = self.text.offset_to_point(self.cursor()).row;
+        let Some(header_row) = hidden_rows(&self.text, &self.folds)
+            .into_iter()
+            .find(|rows| rows.contains(&row))
+            .map(|rows| rows.start - 1)
+        else {
+            return;
+        };
+
+        self.move_to(self.text.line_end_offset(header_row), None, cx);
+    }
+
+    /// Unfold the folds that hide the cursor, e.g.: after moving the cursor by the keyboard.
+    pub(super) fn reveal_cursor_in_folds(&mut self) {
+        if self.folds.is_empty() {
+            return;
+        }
+
+        let row = self.text.offset_to_point(self.cursor()).row;
+        let len = self.folds.len();
+        let text = &self.text;
+        self.folds.retain(|fold| {
+            let rows =
+                text.offset_to_point(fold.start).row + 1..text.offset_to_point(fold.end).row + 1;
+            !rows.contains(&row)
+        });
+        if self.folds.len() != len {
+            self.text_wrapper
+                .set_hidden_rows(hidden_rows(&self.text, &self.folds));
+            self.mode.update_auto_grow(&self.text_wrapper);
+        }
+    }
+
+    /// Update the folds after the `range` is replaced with the `new_text`.
+    ///
+    /// The folds overlap the edit are unfolded, the others are moved by the edit.
+    pub(super) fn update_folds_for_edit(&mut self, range: &Range<usize>, new_text: &str) {
+        if let Some(fold_ranges) = self.fold_ranges.as_mut() {
+            for fold_range in fold_ranges.iter_mut() {
+                *fold_range = map_offset_for_edit(fold_range.start, range, new_text)
+                    ..map_offset_for_edit(fold_range.end, range, new_text);
+            }
+        }
+
+        if self.folds.is_empty() {
+            return;
+        }
+        self.folds = map_folds_for_edit(&self.folds, range, new_text);
+        self.text_wrapper
+            .set_hidden_rows(hidden_rows(&self.text, &self.folds));
+    }
+
+    /// Restore the folds, e.g.: after set the same value.
+    pub(super) fn restore_folds(&mut self, folds: Vec<Range<usize>>, cx: &mut Context<Self>) {
+        self.folds = folds;
+        self.sync_folds(cx);
+    }
+
+    /// Restore the folds saved in the view state, the ranges are clamped to the text,
+    /// and the single line ranges are ignored.
+    pub(super) fn restore_fold_ranges(
+        &mut self,
+        ranges: Vec<Range<usize>>,
+        cx: &mut Context<Self>,
+    ) {
+        self.folds = clamp_fold_ranges(&self.text, ranges);
+        self.sync_folds(cx);
+    }
+
+    fn sync_folds(&mut self, cx: &mut Context<Self>) {
+        self.text_wrapper
+            .set_hidden_rows(hidden_rows(&self.text, &self.folds));
+        self.mode.update_auto_grow(&self.text_wrapper);
+        cx.notify();
+    }
+
+    /// Handle the click on the fold indicator in the gutter, returns true if it's handled.
+    pub(super) fn handle_fold_click(
+        &mut self,
+        position: Point<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if !self.mode.line_number() || !self.is_folding_enabled() {
+            return false;
+        }
+        let Some(last_layout) = self.last_layout.as_ref() else {
+            return false;
+        };
+        let x = position.x - self.input_bounds.origin.x;
+        let indicator_x =
+            last_layout.line_number_width - LINE_NUMBER_RIGHT_MARGIN - FOLD_INDICATOR_WIDTH;
+        if x < indicator_x || x >= indicator_x + FOLD_INDICATOR_WIDTH {
+            return false;
+        }
+        if !self.input_bounds.contains(&position) {
+            return false;
+        }
+
+        let offset = self.index_for_mouse_position(position);
+        let line = self.text.offset_to_point(offset).row;
+        if !self.is_folded(line) && self.foldable_range(line).is_none() {
+            return false;
+        }
+
+        self.toggle_fold(line, cx);
+        true
+    }
+}
+
+/// Collect the ranges of the multi-line nodes that are enclosed by a bracket pair,
+/// and start in the `rows`.
+fn collect_fold_ranges(
+    node: tree_sitter::Node,
+    rows: &Range<usize>,
+    pairs: &[BracketPair],
+    ranges: &mut Vec<Range<usize>>,
+) {
+    let start_row = node.start_position().row;
+    let end_row = node.end_position().row;
+    if end_row < rows.start || start_row >= rows.end {
+        return;
+    }
+
+    let mut cursor = node.walk();
+    let children = node.children(&mut cursor).collect::<Vec<_>>();
+    if start_row < end_row && rows.contains(&start_row) && children.len() >= 2 {
+        let first = children[0];
+        let last = children[children.len() - 1];
+        if pairs
+            .iter()
+            .any(|pair| pair.start.as_ref() == first.kind() && pair.end.as_ref() == last.kind())
+        {
+            ranges.push(first.start_byte()..last.end_byte());
+        }
+    }
+
+    for child in children {
+        collect_fold_ranges(child, rows, pairs, ranges);
+    }
+}
+
+/// Sort the fold ranges by the start, and only keep the outermost multi-line range of each line.
+fn dedup_fold_ranges(text: &Rope, mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.retain(|range| {
+        text.offset_to_point(range.start).row < text.offset_to_point(range.end).row
+    });
+    ranges.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+
+    let mut last_row = None;
+    ranges.retain(|range| {
+        let row = text.offset_to_point(range.start).row;
+        if last_row == Some(row) {
+            return false;
+        }
+        last_row = Some(row);
+        true
+    });
+    ranges
+}
+
+/// Clamp the fold ranges to the text, the single line and duplicated ranges are removed.
+fn clamp_fold_ranges(text: &Rope, ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    let ranges = ranges
+        .into_iter()
+        .map(|range| {
+            text.clip_offset(range.start, Bias::Left)..text.clip_offset(range.end, Bias::Left)
+        })
+        .filter(|range| range.start < range.end)
+        .collect();
+    dedup_fold_ranges(text, ranges)
+}
+
+/// Returns the rows hidden by the folds, the first line of a fold is kept as the header.
+fn hidden_rows(text: &Rope, folds: &[Range<usize>]) -> Vec<Range<usize>> {
+    folds
+        .iter()
+        .map(|fold| {
+            text.offset_to_point(fold.start).row + 1..text.offset_to_point(fold.end).row + 1
+        })
+        .filter(|rows| !rows.is_empty())
+        .collect()
+}
+
+/// Move the folds by the edit, the folds overlap the edit are removed.
+fn map_folds_for_edit(
+    folds: &[Range<usize>],
+    range: &Range<usize>,
+    new_text: &str,
+) -> Vec<Range<usize>> {
+    folds
+        .iter()
+        .filter(|fold| range.end <= fold.start || range.start >= fold.end)
+        .map(|fold| {
+            map_offset_for_edit(fold.start, range, new_text)
+                ..map_offset_for_edit(fold.end, range, new_text)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ropey::Rope;
+
+    use super::{clamp_fold_ranges, dedup_fold_ranges, hidden_rows, map_folds_for_edit};
+
+    const TEXT: &str = "{\n  \"a\": [\n    1\n  ],\n  \"b\": { \"c\": 2 }\n}\nend";
+
+    #[test]
+    fn test_clamp_fold_ranges() {
+        let text = Rope::from(TEXT);
+        let a_start = TEXT.find('[').unwrap();
+        let a_end = TEXT.find(']').unwrap() + 1;
+
+        assert_eq!(
+            clamp_fold_ranges(&text, vec![a_start..a_end, 0..TEXT.len()]),
+            vec![0..TEXT.len(), a_start..a_end]
+        );
+        // The text is shorter than the saved folds.
+        let text = Rope::from("{\n  \"a\": [\n    1");
+        assert_eq!(
+            clamp_fold_ranges(&text, vec![0..100, a_start..a_end, 200..300]),
+            vec![0..text.len(), a_start..text.len()]
+        );
+    }
+
+    #[test]
+    fn test_dedup_fold_ranges() {
+        let text = Rope::from(TEXT);
+        let a_start = TEXT.find('[').unwrap();
+        let a_end = TEXT.find(']').unwrap() + 1;
+        let b_start = TEXT.find("{ \"c\"").unwrap();
+        let b_end = TEXT.find(" }").unwrap() + 2;
+
+        assert_eq!(
+            dedup_fold_ranges(
+                &text,
+                vec![a_start..a_end, b_start..b_end, 0..TEXT.len() - 4, 0..2]
+            ),
+            vec![0..TEXT.len() - 4, a_start..a_end]
+        );
+        assert!(dedup_fold_ranges(&text, vec![b_start..b_end]).is_empty());
+    }
+
+    #[test]
+    fn test_hidden_rows() {
+        let text = Rope::from(TEXT);
+        let a_start = TEXT.find('[').unwrap();
+        let a_end = TEXT.find(']').unwrap() + 1;
+
+        assert_eq!(hidden_rows(&text, &[a_start..a_end]), vec![2..4]);
+        assert_eq!(
+            hidden_rows(&text, &[0..TEXT.len() - 4, a_start..a_end]),
+            vec![1..6, 2..4]
+        );
+        assert!(hidden_rows(&text, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_map_folds_for_edit() {
+        let folds = vec![0..10, 20..30];
+
+        // Insert before the folds.
+        assert_eq!(
+            map_folds_for_edit(&folds, &(0..0), "ab"),
+            vec![2..12, 22..32]
+        );
+        // Edit between the folds.
+        assert_eq!(
+            map_folds_for_edit(&folds, &(12..15), ""),
+            vec![0..10, 17..27]
+        );
+        // Edit inside a fold removes it.
+        assert_eq!(map_folds_for_edit(&folds, &(22..23), "x"), vec![0..10]);
+        // Edit overlaps the fold removes it.
+        assert_eq!(
+            map_folds_for_edit(&folds, &(5..25), ""),
+            Vec::<std::ops::Range<usize>>::new()
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use std::rc::Rc;
+
+use gpui::{App, Context, KeyBinding, Window, actions};
+
+use crate::{
+    highlighter::{Diagnostic, DiagnosticSeverity},
+    input::{InputState, Position, RopeExt as _, state::CONTEXT},
+};
+
+actions!(input, [FormatDocument]);
+
+/// The source of the diagnostic of the formatter errors.
+const FORMAT_SOURCE: &str = "format";
+
+pub(super) type Formatter = Rc<dyn Fn(&str) -> Result<String, String>>;
+
+pub(super) fn init(cx: &mut App) {
+    cx.bind_keys(vec![KeyBinding::new(
+        "shift-alt-f",
+        FormatDocument,
+        Some(CONTEXT),
+    )]);
+}
+
+impl InputState {
+    /// Set the formatter of the [`InputState::format_document`], pass the whole text and
+    /// return the formatted text, or the error message.
+    ///
+    /// ```ignore
+    /// state.set_formatter(|text| {
+    ///     let value: serde_json::Value = serde_json::from_str(text).map_err(|err| err.to_string())?;
+    ///     serde_json::to_string_pretty(&value).map_err(|err| err.to_string())
+    /// });
+    /// ```
+    pub fn set_formatter(&mut self, formatter: impl Fn(&str) -> Result<String, String> + 'static) {
+        self.formatter = Some(Rc::new(formatter));
+    }
+
+    /// Format the whole text with the formatter set by [`InputState::set_formatter`],
+    /// this is also bound to `shift-alt-f` with the [`FormatDocument`] action.
+    ///
+    /// The text is updated as a single undo step, and the cursor and selection are kept
+    /// where possible, see [`InputState::set_value_diffed`]. If the formatter fails, the
+    /// text is not changed and the error is shown as a diagnostic in the code editor,
+    /// at the `line {n} column {n}` of the message if any, otherwise at the start of the text.
+    pub fn format_document(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.disabled || self.read_only {
+            return;
+        }
+        let Some(formatter) = self.formatter.clone() else {
+            return;
+        };
+
+        match formatter(&self.text.to_string()) {
+            Ok(formatted) => {
+                self.set_format_diagnostic(None, cx);
+                self.set_value_diffed(formatted, window, cx);
+            }
+            Err(message) => {
+                let diagnostic = self.format_error_to_diagnostic(message);
+                self.set_format_diagnostic(Some(diagnostic), cx);
+            }
+        }
+    }
+
+    pub(super) fn on_action_format_document(
+        &mut self,
+        _: &FormatDocument,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.formatter.is_none() {
+            cx.propagate();
+            return;
+        }
+
+        self.format_document(window, cx);
+    }
+
+    fn format_error_to_diagnostic(&self, message: String) -> Diagnostic {
+        let start = match error_location(&message) {
+            Some((line, column)) => {
+                let row = line
+                    .saturating_sub(1)
+                    .min(self.text.lines_len().saturating_sub(1));
+                let line_len = self
+                    .text
+                    .offset_to_position(self.text.line_end_offset(row))
+                    .character;
+                Position::new(row as u32, (column.saturating_sub(1) as u32).min(line_len))
+            }
+            None => Position::new(0, 0),
+        };
+        let line_len = self
+            .text
+            .offset_to_position(self.text.line_end_offset(start.line as usize))
+            .character;
+        let end = Position::new(start.line, (start.character + 1).min(line_len));
+
+        Diagnostic::new(start..end, message)
+            .with_severity(DiagnosticSeverity::Error)
+            .with_source(FORMAT_SOURCE)
+    }
+
+    /// Replace the diagnostic of the formatter error, the other diagnostics are kept.
+    fn set_format_diagnostic(&mut self, diagnostic: Option<Diagnostic>, cx: &mut Context<Self>) {
+        let Some(set) = self.diagnostics() else {
+            return;
+        };
+
+        let has_format_diagnostic = set
+            .iter()
+            .any(|entry| entry.diagnostic.source.as_deref() == Some(FORMAT_SOURCE));
+        if diagnostic.is_none() && !has_format_diagnostic {
+            return;
+        }
+
+        let diagnostics = set
+            .iter()
+            .map(|entry| entry.diagnostic.clone())
+            .filter(|diagnostic| diagnostic.source.as_deref() != Some(FORMAT_SOURCE))
+            .chain(diagnostic)
+            .collect::<Vec<_>>();
+        self.set_diagnostics(diagnostics, cx);
+    }
+}
+
+/// Parse the 1-based `line {n} column {n}` in the error message, e.g. the errors of `serde_json`.
+fn error_location(message: &str) -> Option<(usize, usize)> {
+    let number_after = |s: &str, word: &str| -> Option<(usize, usize)> {
+        let start = s.find(word)? + word.len();
+        let digits = s[start..]
+            .trim_start()
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect::<String>();
+        let end = s.len() - s[start..].trim_start().len() + digits.len();
+        Some((digits.parse().ok()?, end))
+    };
+
+    let (line, end) = number_after(message, "line ")?;
+    let (column, _) = number_after(&message[end..], "column ")?;
+    Some((line, column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::error_location;
+
+    #[test]
+    fn test_error_location() {
+        assert_eq!(
+            error_location("trailing comma at line 3 column 5"),
+            Some((3, 5))
+        );
+        assert_eq!(
+            error_location("EOF while parsing an object at line 10 column 0"),
+            Some((10, 0))
+        );
+        assert_eq!(error_location("expected value"), None);
+        assert_eq!(error_location("at line 3"), None);
+        assert_eq!(error_location("at line x column 1"), None);
+    }
+}
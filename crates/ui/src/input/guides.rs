@@ -0,0 +1,218 @@
+use gpui::{Bounds, Context, Path, PathBuilder, Pixels, Point, TextStyle, Window, point, px};
+
+use crate::input::{InputState, LastLayout, element::TextElement};
+
+/// The size of the dot to mark a space.
+const SPACE_DOT_SIZE: Pixels = px(2.);
+/// The half height of the arrow head to mark a tab.
+const TAB_ARROW_SIZE: Pixels = px(2.5);
+
+impl InputState {
+    /// Set the columns to draw vertical rulers, e.g. `vec![80, 120]`, default is empty.
+    ///
+    /// Only for [`InputMode::CodeEditor`](super::InputMode::CodeEditor) mode.
+    pub fn rulers(mut self, rulers: Vec<usize>) -> Self {
+        debug_assert!(self.mode.is_code_editor());
+        self.rulers = rulers;
+        self
+    }
+
+    /// Set the columns to draw vertical rulers.
+    ///
+    /// See also: [`Self::rulers`].
+    pub fn set_rulers(&mut self, rulers: Vec<usize>, _: &mut Window, cx: &mut Context<Self>) {
+        debug_assert!(self.mode.is_code_editor());
+        self.rulers = rulers;
+        cx.notify();
+    }
+
+    /// Set true to render the whitespace characters, dots for spaces and arrows for tabs,
+    /// default is false.
+    ///
+    /// Only for [`InputMode::CodeEditor`](super::InputMode::CodeEditor) mode.
+    pub fn show_whitespace(mut self, show: bool) -> Self {
+        debug_assert!(self.mode.is_code_editor());
+        self.show_whitespace = show;
+        self
+    }
+
+    /// Update the show whitespace mode.
+    ///
+    /// See also: [`Self::show_whitespace`].
+    pub fn set_show_whitespace(&mut self, show: bool, _: &mut Window, cx: &mut Context<Self>) {
+        debug_assert!(self.mode.is_code_editor());
+        self.show_whitespace = show;
+        cx.notify();
+    }
+
+    /// Set whether to show indent guides, same as [`Self::indent_guides`].
+    pub fn show_indent_guides(self, show: bool) -> Self {
+        self.indent_guides(show)
+    }
+
+    /// Update the show indent guides mode, same as [`Self::set_indent_guides`].
+    pub fn set_show_indent_guides(
+        &mut self,
+        show: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_indent_guides(show, window, cx);
+    }
+}
+
+impl TextElement {
+    /// Layout the vertical rulers at the columns of [`InputState::rulers`].
+    ///
+    /// The rulers cover the full height of the `input_bounds`, and follow the horizontal scroll.
+    pub(super) fn layout_rulers(
+        &self,
+        state: &InputState,
+        bounds: &Bounds<Pixels>,
+        input_bounds: &Bounds<Pixels>,
+        last_layout: &LastLayout,
+        text_style: &TextStyle,
+        window: &mut Window,
+    ) -> Option<Path<Pixels>> {
+        if state.rulers.is_empty() || !state.mode.is_code_editor() {
+            return None;
+        }
+
+        let text_left = input_bounds.origin.x + last_layout.line_number_width;
+        let text_right = input_bounds.right();
+        let mut builder = PathBuilder::stroke(px(1.));
+        let mut has_ruler = false;
+        for column in &state.rulers {
+            let x = bounds.origin.x
+                + last_layout.line_number_width
+                + self.measure_indent_width(text_style, *column, window);
+            // Hide the rulers scrolled under the gutter or out of the viewport.
+            if x < text_left || x > text_right {
+                continue;
+            }
+
+            builder.move_to(point(x, input_bounds.top()));
+            builder.line_to(point(x, input_bounds.bottom()));
+            has_ruler = true;
+        }
+
+        if !has_ruler {
+            return None;
+        }
+
+        builder.build().ok()
+    }
+
+    /// Layout the markers of the spaces and tabs in the visible lines,
+    /// see [`InputState::show_whitespace`].
+    pub(super) fn layout_whitespace(
+        &self,
+        state: &InputState,
+        bounds: &Bounds<Pixels>,
+        last_layout: &LastLayout,
+        text_style: &TextStyle,
+        window: &mut Window,
+    ) -> Option<Path<Pixels>> {
+        if !state.show_whitespace || state.masked || !state.mode.is_code_editor() {
+            return None;
+        }
+
+        let space_width = self.measure_indent_width(text_style, 1, window);
+        let tab_width = space_width * state.mode.tab_size().tab_size as f32;
+        let line_height = last_layout.line_height;
+        let origin = bounds.origin + point(last_layout.line_number_width, last_layout.visible_top);
+
+        let mut builder = PathBuilder::fill();
+        let mut has_marker = false;
+        let mut offset_y = px(0.);
+        for ix in last_layout.visible_range.clone() {
+            let Some(line_layout) = last_layout.line(ix) else {
+                continue;
+            };
+            // Skip the lines hidden in the folds.
+            if line_layout.wrapped_lines.is_empty() {
+                continue;
+            }
+
+            let line = state.text.slice_line(ix).to_string();
+            for (offset, c) in whitespace_chars(&line) {
+                let Some(pos) = line_layout.position_for_index(offset, line_height) else {
+                    continue;
+                };
+                // The next char may be soft wrapped to the next row.
+                let width = match line_layout.position_for_index(offset + 1, line_height) {
+                    Some(next) if next.y == pos.y && next.x > pos.x => next.x - pos.x,
+                    _ if c == '\t' => tab_width,
+                    _ => space_width,
+                };
+
+                let left = origin + point(pos.x, offset_y + pos.y + line_height / 2.);
+                if c == '\t' {
+                    Self::push_tab_arrow(&mut builder, left, width);
+                } else {
+                    Self::push_space_dot(&mut builder, left, width);
+                }
+                has_marker = true;
+            }
+
+            offset_y += line_layout.wrapped_lines.len() * line_height;
+        }
+
+        if !has_marker {
+            return None;
+        }
+
+        builder.build().ok()
+    }
+
+    /// Push a dot at the center of a space, the `left` is the left middle point of the space.
+    fn push_space_dot(builder: &mut PathBuilder, left: Point<Pixels>, width: Pixels) {
+        let half = SPACE_DOT_SIZE / 2.;
+        let center = left + point(width / 2., px(0.));
+        builder.move_to(center + point(-half, -half));
+        builder.line_to(center + point(half, -half));
+        builder.line_to(center + point(half, half));
+        builder.line_to(center + point(-half, half));
+        builder.close();
+    }
+
+    /// Push a right arrow across a tab, the `left` is the left middle point of the tab.
+    fn push_tab_arrow(builder: &mut PathBuilder, left: Point<Pixels>, width: Pixels) {
+        let start = left.x + width * 0.15;
+        let end = (left.x + width * 0.85).max(start + TAB_ARROW_SIZE * 2.);
+        let head = end - TAB_ARROW_SIZE;
+        let y = left.y;
+        builder.move_to(point(start, y - px(0.5)));
+        builder.line_to(point(head, y - px(0.5)));
+        builder.line_to(point(head, y - TAB_ARROW_SIZE));
+        builder.line_to(point(end, y));
+        builder.line_to(point(head, y + TAB_ARROW_SIZE));
+        builder.line_to(point(head, y + px(0.5)));
+        builder.line_to(point(start, y + px(0.5)));
+        builder.close();
+    }
+}
+
+/// Returns the byte offsets of the spaces and tabs in the line.
+fn whitespace_chars(line: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    line.char_indices().filter(|(_, c)| matches!(c, ' ' | '\t'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::whitespace_chars;
+
+    #[test]
+    fn test_whitespace_chars() {
+        assert_eq!(whitespace_chars("").count(), 0);
+        assert_eq!(whitespace_chars("foo").count(), 0);
+        assert_eq!(
+            whitespace_chars("\t\"名字\": 1,  ").collect::<Vec<_>>(),
+            vec![(0, '\t'), (10, ' '), (13, ' '), (14, ' ')]
+        );
+        assert_eq!(
+            whitespace_chars("  \u{3000}a\r").collect::<Vec<_>>(),
+            vec![(0, ' '), (1, ' ')]
+        );
+    }
+}
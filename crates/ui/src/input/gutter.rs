@@ -0,0 +1,301 @@
+use std::rc::Rc;
+
+use gpui::{AnyElement, App, Context, Hsla, Modifiers, Pixels, Point, SharedString, Window, px};
+
+use crate::{
+    Icon,
+    input::{
+        InputEvent, InputState, RopeExt as _,
+        element::{FOLD_INDICATOR_WIDTH, LINE_NUMBER_RIGHT_MARGIN},
+    },
+};
+
+/// The line info to render the gutter, see [`super::InputState::gutter_renderer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GutterLine {
+    /// The line number (zero-based).
+    pub line: usize,
+    /// The line number (zero-based) of the cursor.
+    pub current_line: Option<usize>,
+    /// The total number of lines.
+    pub total_lines: usize,
+}
+
+impl GutterLine {
+    /// Return true if the cursor is on this line.
+    #[inline]
+    pub fn is_current(&self) -> bool {
+        self.current_line == Some(self.line)
+    }
+
+    /// Returns the line number relative to the cursor line, like the `relativenumber` in Vim.
+    ///
+    /// Returns `None` if there is no cursor line.
+    #[inline]
+    pub fn relative_line(&self) -> Option<usize> {
+        self.current_line
+            .map(|current_line| current_line.abs_diff(self.line))
+    }
+}
+
+pub(super) type GutterRenderer = Rc<dyn Fn(&GutterLine, &mut Window, &mut App) -> AnyElement>;
+
+/// The event of clicking on the gutter, see [`InputState::on_gutter_click`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GutterClickEvent {
+    /// The line number (zero-based) of the clicked line.
+    pub line: usize,
+    /// The modifier keys pressed when clicking.
+    pub modifiers: Modifiers,
+}
+
+pub(super) type GutterClickHandler =
+    Rc<dyn Fn(&mut InputState, &GutterClickEvent, &mut Window, &mut Context<InputState>)>;
+
+/// A small decoration rendered in the gutter of a line, e.g. a breakpoint.
+///
+/// See [`InputState::set_gutter_markers`].
+#[derive(Clone)]
+pub struct GutterMarker {
+    pub(super) icon: Icon,
+    pub(super) color: Option<Hsla>,
+}
+
+impl GutterMarker {
+    /// Create a new marker with the icon.
+    pub fn new(icon: impl Into<Icon>) -> Self {
+        Self {
+            icon: icon.into(),
+            color: None,
+        }
+    }
+
+    /// Set the color of the marker icon, default is the `muted_foreground` of the theme.
+    pub fn color(mut self, color: impl Into<Hsla>) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+}
+
+/// A compact text badge rendered in the gutter after the line numbers, e.g. the author of
+/// the git blame, or the coverage of the line.
+///
+/// See [`InputState::set_line_annotations`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineAnnotation {
+    /// The line number (zero-based).
+    pub line: usize,
+    /// The text of the badge.
+    pub text: SharedString,
+    /// The color of the badge, default is the `muted_foreground` of the theme.
+    pub color: Option<Hsla>,
+    /// The tooltip to show when hovering the badge.
+    pub tooltip: Option<SharedString>,
+}
+
+impl LineAnnotation {
+    /// Create a new annotation of the line (zero-based) with the text.
+    pub fn new(line: usize, text: impl Into<SharedString>) -> Self {
+        Self {
+            line,
+            text: text.into(),
+            color: None,
+            tooltip: None,
+        }
+    }
+
+    /// Set the color of the badge.
+    pub fn color(mut self, color: impl Into<Hsla>) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    /// Set the tooltip to show when hovering the badge.
+    pub fn tooltip(mut self, tooltip: impl Into<SharedString>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+}
+
+impl InputState {
+    /// Set the handler to be called when clicking on the gutter,
+    /// only for [`super::InputMode::CodeEditor`] mode with line number enabled.
+    ///
+    /// When set, the click on the gutter will not move the cursor, call
+    /// [`InputState::select_line`] in the handler to select the clicked line.
+    ///
+    /// ```ignore
+    /// InputState::new(window, cx)
+    ///     .code_editor("rust")
+    ///     .on_gutter_click(|state, event, window, cx| {
+    ///         state.select_line(event.line, window, cx);
+    ///     })
+    /// ```
+    pub fn on_gutter_click<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut InputState, &GutterClickEvent, &mut Window, &mut Context<InputState>) + 'static,
+    {
+        self.gutter_click_handler = Some(Rc::new(f));
+        self
+    }
+
+    /// Set the markers to render in the gutter, the `usize` is the line number (zero-based).
+    ///
+    /// A line only has one marker, the latter one wins. The markers are not moved by editing,
+    /// update them when the text changed.
+    pub fn set_gutter_markers(
+        &mut self,
+        markers: impl IntoIterator<Item = (usize, GutterMarker)>,
+        cx: &mut Context<Self>,
+    ) {
+        self.gutter_markers = markers.into_iter().collect();
+        cx.notify();
+    }
+
+    /// Returns the marker of the line, see [`InputState::set_gutter_markers`].
+    pub fn gutter_marker(&self, line: usize) -> Option<&GutterMarker> {
+        self.gutter_markers.get(&line)
+    }
+
+    /// Set the annotations to render in the gutter after the line numbers,
+    /// only for [`super::InputMode::CodeEditor`] mode with line number enabled.
+    ///
+    /// A line only has one annotation, the latter one wins. The annotations of the folded lines
+    /// are hidden. Click on an annotation emits [`InputEvent::LineAnnotationClick`].
+    ///
+    /// Like the [`InputState::set_gutter_markers`], the annotations are not moved by editing,
+    /// update them when the text changed.
+    ///
+    /// ```ignore
+    /// state.set_line_annotations(
+    ///     vec![
+    ///         LineAnnotation::new(0, "alice 3d").tooltip("Fix the typo"),
+    ///         LineAnnotation::new(1, "bob 1w").color(cx.theme().blue),
+    ///     ],
+    ///     cx,
+    /// );
+    /// ```
+    pub fn set_line_annotations(
+        &mut self,
+        annotations: impl IntoIterator<Item = LineAnnotation>,
+        cx: &mut Context<Self>,
+    ) {
+        self.line_annotations = annotations
+            .into_iter()
+            .map(|annotation| (annotation.line, annotation))
+            .collect();
+        cx.notify();
+    }
+
+    /// Returns the annotation of the line, see [`InputState::set_line_annotations`].
+    pub fn line_annotation(&self, line: usize) -> Option<&LineAnnotation> {
+        self.line_annotations.get(&line)
+    }
+
+    /// Returns the line number (zero-based) if the position is on an annotation in the gutter.
+    fn line_annotation_for_position(&self, position: Point<Pixels>) -> Option<usize> {
+        if !self.mode.line_number() || self.line_annotations.is_empty() {
+            return None;
+        }
+        let last_layout = self.last_layout.as_ref()?;
+        let fold_indicator_width = if self.is_folding_enabled() {
+            FOLD_INDICATOR_WIDTH
+        } else {
+            px(0.)
+        };
+        let end = last_layout.line_number_width - LINE_NUMBER_RIGHT_MARGIN - fold_indicator_width;
+        let x = position.x - self.input_bounds.origin.x;
+        if x < end - self.line_annotation_width || x >= end {
+            return None;
+        }
+        if !self.input_bounds.contains(&position) {
+            return None;
+        }
+
+        let offset = self.index_for_mouse_position(position);
+        let line = self.text.offset_to_position(offset).line as usize;
+        self.line_annotations.contains_key(&line).then_some(line)
+    }
+
+    /// Handle the click on the line annotations, returns true if it's handled.
+    pub(super) fn handle_line_annotation_click(
+        &mut self,
+        position: Point<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(line) = self.line_annotation_for_position(position) else {
+            return false;
+        };
+
+        cx.emit(InputEvent::LineAnnotationClick { line });
+        true
+    }
+
+    /// Returns the line number (zero-based) if the position is in the gutter.
+    fn gutter_line_for_position(&self, position: Point<Pixels>) -> Option<usize> {
+        if !self.mode.line_number() {
+            return None;
+        }
+        let last_layout = self.last_layout.as_ref()?;
+        let x = position.x - self.input_bounds.origin.x;
+        if x < px(0.) || x >= last_layout.line_number_width - LINE_NUMBER_RIGHT_MARGIN {
+            return None;
+        }
+        if !self.input_bounds.contains(&position) {
+            return None;
+        }
+
+        let offset = self.index_for_mouse_position(position);
+        Some(self.text.offset_to_position(offset).line as usize)
+    }
+
+    /// Handle the click on the gutter, returns true if it's handled.
+    pub(super) fn handle_gutter_click(
+        &mut self,
+        position: Point<Pixels>,
+        modifiers: Modifiers,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(handler) = self.gutter_click_handler.clone() else {
+            return false;
+        };
+        let Some(line) = self.gutter_line_for_position(position) else {
+            return false;
+        };
+
+        handler(self, &GutterClickEvent { line, modifiers }, window, cx);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GutterLine;
+
+    #[test]
+    fn test_relative_line() {
+        let line = GutterLine {
+            line: 3,
+            current_line: Some(10),
+            total_lines: 20,
+        };
+        assert!(!line.is_current());
+        assert_eq!(line.relative_line(), Some(7));
+
+        let line = GutterLine {
+            line: 10,
+            current_line: Some(10),
+            total_lines: 20,
+        };
+        assert!(line.is_current());
+        assert_eq!(line.relative_line(), Some(0));
+
+        let line = GutterLine {
+            line: 10,
+            current_line: None,
+            total_lines: 20,
+        };
+        assert_eq!(line.relative_line(), None);
+    }
+}
@@ -2,10 +2,12 @@ use gpui::{
     Bounds, Context, EntityInputHandler as _, Hsla, Path, PathBuilder, Pixels, SharedString,
     TextRun, TextStyle, Window, point, px,
 };
-use ropey::RopeSlice;
+use ropey::{Rope, RopeSlice};
+use std::ops::Range;
 
 use crate::{
     RopeExt,
+    highlighter::BracketPair,
     input::{
         Indent, IndentInline, InputState, LastLayout, Outdent, OutdentInline, element::TextElement,
         mode::InputMode,
@@ -29,6 +31,16 @@ impl Default for TabSize {
     }
 }
 
+impl From<usize> for TabSize {
+    /// Create a soft tabs [`TabSize`] with the given size.
+    fn from(tab_size: usize) -> Self {
+        Self {
+            tab_size,
+            hard_tabs: false,
+        }
+    }
+}
+
 impl TabSize {
     pub(super) fn to_string(&self) -> SharedString {
         if self.hard_tabs {
@@ -51,6 +63,16 @@ impl TabSize {
 
         count
     }
+
+    /// Returns the number of spaces to delete by backspace at the `column` of the indentation,
+    /// to move back to the previous tab stop.
+    pub(super) fn outdent_len(&self, column: usize) -> usize {
+        let tab_size = self.tab_size.max(1);
+        match column % tab_size {
+            0 => column.min(tab_size),
+            rem => rem,
+        }
+    }
 }
 
 impl InputMode {
@@ -88,7 +110,12 @@ impl InputMode {
 
 impl TextElement {
     /// Measure the indent width in pixels for given column count.
-    fn measure_indent_width(&self, style: &TextStyle, column: usize, window: &Window) -> Pixels {
+    pub(super) fn measure_indent_width(
+        &self,
+        style: &TextStyle,
+        column: usize,
+        window: &Window,
+    ) -> Pixels {
         let font_size = style.font_size.to_pixels(window.rem_size());
         let layout = window.text_system().shape_line(
             SharedString::from(" ".repeat(column)),
@@ -133,6 +160,10 @@ impl TextElement {
             let Some(line_layout) = last_layout.line(ix) else {
                 continue;
             };
+            // Skip the lines hidden in the folds.
+            if line_layout.wrapped_lines.is_empty() {
+                continue;
+            }
 
             let mut current_indents = vec![];
             if line.len() > 0 {
@@ -203,11 +234,12 @@ impl InputState {
         cx.notify();
     }
 
-    /// Set the tab size for the input.
+    /// Set the tab size for the input, a `usize` is the size of soft tabs.
     ///
     /// Only for [`InputMode::PlainText`] and [`InputMode::CodeEditor`] mode with multi_line.
-    pub fn tab_size(mut self, tab: TabSize) -> Self {
+    pub fn tab_size(mut self, tab: impl Into<TabSize>) -> Self {
         debug_assert!(self.mode.is_multi_line() || self.mode.is_code_editor());
+        let tab = tab.into();
         match &mut self.mode {
             InputMode::PlainText { tab: t, .. } => *t = tab,
             InputMode::CodeEditor { tab: t, .. } => *t = tab,
@@ -216,12 +248,111 @@ impl InputState {
         self
     }
 
+    /// Set true to insert spaces (soft tabs) for indent, false to insert `\t`, default is true.
+    ///
+    /// With soft tabs, the Backspace in the indentation deletes the spaces back to the previous tab stop.
+    ///
+    /// Only for [`InputMode::PlainText`] and [`InputMode::CodeEditor`] mode with multi_line,
+    /// call this after [`InputState::tab_size`].
+    pub fn insert_spaces(mut self, insert_spaces: bool) -> Self {
+        debug_assert!(self.mode.is_multi_line() || self.mode.is_code_editor());
+        match &mut self.mode {
+            InputMode::PlainText { tab, .. } | InputMode::CodeEditor { tab, .. } => {
+                tab.hard_tabs = !insert_spaces
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Set true to auto indent the new line on Enter, default is false.
+    ///
+    /// - The new line inherits the leading whitespace of the current line.
+    /// - In the code editor, the new line after the start of a bracket pair (e.g. `{`) is
+    ///   indented one more level, the pairs are the [`LanguageConfig::bracket_pairs`].
+    /// - Pressing Enter between a pair, e.g. `{|}`, opens an indented blank line and pushes the
+    ///   end of the pair down to the next line.
+    /// - A single undo reverts the whole insertion.
+    ///
+    /// Only for the multi-line mode.
+    ///
+    /// [`LanguageConfig::bracket_pairs`]: crate::highlighter::LanguageConfig::bracket_pairs
+    pub fn auto_indent(mut self, auto_indent: bool) -> Self {
+        debug_assert!(self.mode.is_multi_line());
+        self.auto_indent = auto_indent;
+        self
+    }
+
+    /// Insert a new line with the auto indent at the selection, see [`InputState::auto_indent`].
+    pub(super) fn insert_auto_indent_newline(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let range = self.selected_range.start..self.selected_range.end;
+        let pairs = self
+            .language_config()
+            .map(|config| config.bracket_pairs.clone())
+            .unwrap_or_default();
+        let (text, cursor) = auto_indent_newline(
+            &self.text,
+            range.clone(),
+            &pairs,
+            &self.mode.tab_size().to_string(),
+        );
+
+        let text = self.filter_input(&[range.clone()], &text, cx).to_string();
+
+        // Insert as a single change, so a single undo reverts the whole insertion.
+        let new_offset = range.start + cursor.min(text.len());
+        self.replace_text_in_range_silent(None, &text, window, cx);
+        self.selected_range = (new_offset..new_offset).into();
+        self.update_preferred_column();
+        cx.notify();
+    }
+
+    /// Returns the start offset of the previous tab stop if the cursor is in the soft tabs indentation.
+    ///
+    /// Used by the Backspace to delete a full tab stop.
+    pub(super) fn previous_tab_stop(&self) -> Option<usize> {
+        let tab = self.mode.tab_size();
+        if !self.mode.is_indentable() || tab.hard_tabs || !self.selected_range.is_empty() {
+            return None;
+        }
+
+        let offset = self.cursor();
+        let line_start = self
+            .text
+            .line_start_offset(self.text.offset_to_point(offset).row);
+        let column = offset - line_start;
+        if column == 0
+            || self
+                .text
+                .slice(line_start..offset)
+                .chars()
+                .any(|c| c != ' ')
+        {
+            return None;
+        }
+
+        Some(offset - tab.outdent_len(column))
+    }
+
     pub(super) fn indent_inline(
         &mut self,
-        _: &IndentInline,
+        action: &IndentInline,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        // The `tab` is not an edit in the Vim normal and visual mode.
+        if self.is_vim_command_mode() {
+            cx.propagate();
+            return;
+        }
+        // Accept the completion with `tab` if the completion menu is open.
+        if self.handle_action_for_context_menu(Box::new(action.clone()), window, cx) {
+            return;
+        }
         // First, try to accept inline completion if present
         if self.accept_inline_completion(window, cx) {
             return;
@@ -252,7 +383,7 @@ impl InputState {
     }
 
     pub(super) fn indent(&mut self, block: bool, window: &mut Window, cx: &mut Context<Self>) {
-        if !self.mode.is_indentable() {
+        if !self.mode.is_indentable() || self.is_vim_command_mode() {
             cx.propagate();
             return;
         };
@@ -310,7 +441,7 @@ impl InputState {
     }
 
     pub(super) fn outdent(&mut self, block: bool, window: &mut Window, cx: &mut Context<Self>) {
-        if !self.mode.is_indentable() {
+        if !self.mode.is_indentable() || self.is_vim_command_mode() {
             cx.propagate();
             return;
         };
@@ -384,11 +515,87 @@ impl InputState {
     }
 }
 
+/// Returns the text to replace the `range` by a new line with the auto indent,
+/// and the cursor offset in the text.
+///
+/// The `indent` is the text of one indent level, and the `pairs` are the brackets
+/// to indent the content between.
+fn auto_indent_newline(
+    text: &Rope,
+    range: Range<usize>,
+    pairs: &[BracketPair],
+    indent: &str,
+) -> (String, usize) {
+    let line_start = text.line_start_offset(text.offset_to_point(range.start).row);
+    let line_end = text.line_end_offset(text.offset_to_point(range.end).row);
+    let before = text.slice(line_start..range.start).to_string();
+    let after = text.slice(range.end..line_end).to_string();
+
+    let current_indent = &before[..before.len() - before.trim_start_matches([' ', '\t']).len()];
+    let mut new_text = format!("\n{}", current_indent);
+
+    let is_char = |s: &str, c: char| s.len() == c.len_utf8() && s.starts_with(c);
+    let pair = before.trim_end().chars().last().and_then(|prev| {
+        pairs
+            .iter()
+            .find(|pair| pair.start != pair.end && is_char(&pair.start, prev))
+    });
+    let Some(pair) = pair else {
+        let cursor = new_text.len();
+        return (new_text, cursor);
+    };
+
+    new_text.push_str(indent);
+    let cursor = new_text.len();
+    if after
+        .trim_start()
+        .chars()
+        .next()
+        .is_some_and(|next| is_char(&pair.end, next))
+    {
+        new_text.push('\n');
+        new_text.push_str(current_indent);
+    }
+
+    (new_text, cursor)
+}
+
 #[cfg(test)]
 mod tests {
-    use ropey::RopeSlice;
+    use ropey::{Rope, RopeSlice};
+
+    use super::{TabSize, auto_indent_newline};
+    use crate::highlighter::BracketPair;
 
-    use super::TabSize;
+    #[test]
+    fn test_auto_indent_newline() {
+        let pairs: Vec<BracketPair> = vec![("[", "]").into(), ("{", "}").into()];
+        let newline = |text: &str, range: std::ops::Range<usize>| {
+            auto_indent_newline(&Rope::from(text), range, &pairs, "  ")
+        };
+
+        // Inherit the indent.
+        assert_eq!(newline("  foo", 5..5), ("\n  ".into(), 3));
+        assert_eq!(newline("\t\tfoo", 5..5), ("\n\t\t".into(), 3));
+        assert_eq!(newline("  foo\nbar", 9..9), ("\n".into(), 1));
+        // Cursor in the indent.
+        assert_eq!(newline("    foo", 2..2), ("\n  ".into(), 3));
+        // After the start of a pair.
+        assert_eq!(newline("  {", 3..3), ("\n    ".into(), 5));
+        assert_eq!(newline("  \"a\": [ ", 9..9), ("\n    ".into(), 5));
+        // Between a pair.
+        assert_eq!(newline("  {}", 3..3), ("\n    \n  ".into(), 5));
+        assert_eq!(newline("{ }", 1..1), ("\n  \n".into(), 3));
+        assert_eq!(newline("[foo]", 1..4), ("\n  \n".into(), 3));
+        // Not a matched pair.
+        assert_eq!(newline("{]", 1..1), ("\n  ".into(), 3));
+        assert_eq!(newline("}", 1..1), ("\n".into(), 1));
+        // No pairs, e.g. the plain text.
+        assert_eq!(
+            auto_indent_newline(&Rope::from("  {}"), 3..3, &[], "  "),
+            ("\n  ".into(), 3)
+        );
+    }
 
     #[test]
     fn test_tab_size() {
@@ -415,6 +622,21 @@ mod tests {
         assert_eq!(tab.to_string(), "\t");
     }
 
+    #[test]
+    fn test_outdent_len() {
+        let tab = TabSize::from(4);
+        assert!(!tab.hard_tabs);
+        assert_eq!(tab.outdent_len(0), 0);
+        assert_eq!(tab.outdent_len(1), 1);
+        assert_eq!(tab.outdent_len(3), 3);
+        assert_eq!(tab.outdent_len(4), 4);
+        assert_eq!(tab.outdent_len(6), 2);
+        assert_eq!(tab.outdent_len(8), 4);
+
+        let tab = TabSize::from(0);
+        assert_eq!(tab.outdent_len(3), 1);
+    }
+
     #[test]
     fn test_tab_size_indent_count() {
         let tab = TabSize {
@@ -1,13 +1,14 @@
 use gpui::prelude::FluentBuilder as _;
 use gpui::{
-    AnyElement, App, DefiniteLength, Edges, EdgesRefinement, Entity, InteractiveElement as _,
-    IntoElement, IsZero, MouseButton, ParentElement as _, Rems, RenderOnce, StyleRefinement,
-    Styled, Window, div, px, relative,
+    AnyElement, App, DefiniteLength, Edges, EdgesRefinement, Entity, ExternalPaths,
+    InteractiveElement as _, IntoElement, IsZero, KeyContext, MouseButton, ParentElement as _,
+    Rems, RenderOnce, StyleRefinement, Styled, Window, div, px, relative,
 };
 
 use crate::button::{Button, ButtonVariants as _};
 use crate::input::clear_button;
 use crate::input::element::{LINE_NUMBER_RIGHT_MARGIN, RIGHT_MARGIN};
+use crate::input::minimap::Minimap;
 use crate::scroll::Scrollbar;
 use crate::spinner::Spinner;
 use crate::{ActiveTheme, v_flex};
@@ -17,6 +18,8 @@ use crate::{Sizable, StyleSized};
 
 use super::InputState;
 
+const DROP_GROUP: &str = "input-file-drop";
+
 /// A text input element bind to an [`InputState`].
 #[derive(IntoElement)]
 pub struct Input {
@@ -196,39 +199,50 @@ impl Input {
         v_flex()
             .size_full()
             .children(state.search_panel.clone())
-            .child(div().flex_1().child(input_state.clone()).map(|this| {
-                if let Some(last_layout) = state.last_layout.as_ref() {
-                    let left = if last_layout.line_number_width.is_zero() {
-                        px(0.)
-                    } else {
-                        // Align left edge to the Line number.
-                        paddings.left + last_layout.line_number_width - LINE_NUMBER_RIGHT_MARGIN
-                    };
-
-                    let scroll_size = gpui::Size {
-                        width: state.scroll_size.width - left + paddings.right + RIGHT_MARGIN,
-                        height: state.scroll_size.height,
-                    };
-
-                    let scrollbar = if !state.soft_wrap {
-                        Scrollbar::new(&state.scroll_handle)
-                    } else {
-                        Scrollbar::vertical(&state.scroll_handle)
-                    };
-
-                    this.relative().child(
-                        div()
-                            .absolute()
-                            .top(-paddings.top)
-                            .left(left)
-                            .right(-paddings.right)
-                            .bottom(-paddings.bottom)
-                            .child(scrollbar.scroll_size(scroll_size)),
-                    )
-                } else {
-                    this
-                }
-            }))
+            .child(
+                div()
+                    .flex_1()
+                    .child(input_state.clone())
+                    .map(|this| {
+                        if let Some(last_layout) = state.last_layout.as_ref() {
+                            let left = if last_layout.line_number_width.is_zero() {
+                                px(0.)
+                            } else {
+                                // Align left edge to the Line number.
+                                paddings.left + last_layout.line_number_width
+                                    - LINE_NUMBER_RIGHT_MARGIN
+                            };
+
+                            let scroll_size = gpui::Size {
+                                width: state.scroll_size.width - left
+                                    + paddings.right
+                                    + RIGHT_MARGIN,
+                                height: state.scroll_size.height,
+                            };
+
+                            let scrollbar = if !state.soft_wrap {
+                                Scrollbar::new(&state.scroll_handle)
+                            } else {
+                                Scrollbar::vertical(&state.scroll_handle)
+                            };
+
+                            this.relative().child(
+                                div()
+                                    .absolute()
+                                    .top(-paddings.top)
+                                    .left(left)
+                                    .right(-paddings.right)
+                                    .bottom(-paddings.bottom)
+                                    .child(scrollbar.scroll_size(scroll_size)),
+                            )
+                        } else {
+                            this
+                        }
+                    })
+                    .when(state.minimap, |this| {
+                        this.flex().child(Minimap::new(input_state.clone()))
+                    }),
+            )
     }
 }
 
@@ -270,11 +284,18 @@ impl RenderOnce for Input {
         let show_clear_button =
             self.cleanable && !state.loading && state.text.len() > 0 && state.mode.is_single_line();
         let has_suffix = suffix.is_some() || state.loading || self.mask_toggle || show_clear_button;
+        let accept_file_drop = state.accept_file_drop && !state.disabled;
+
+        let mut key_context = KeyContext::default();
+        key_context.add(crate::input::CONTEXT);
+        if let Some(context) = state.key_context.clone() {
+            key_context.add(context);
+        }
 
         div()
             .id(("input", self.state.entity_id()))
             .flex()
-            .key_context(crate::input::CONTEXT)
+            .key_context(key_context)
             .track_focus(&state.focus_handle.clone())
             .tab_index(self.tab_index)
             .when(!state.disabled, |this| {
@@ -290,13 +311,20 @@ impl RenderOnce for Input {
                     .on_action(window.listener_for(&self.state, InputState::escape))
                     .on_action(window.listener_for(&self.state, InputState::paste))
                     .on_action(window.listener_for(&self.state, InputState::cut))
-                    .on_action(window.listener_for(&self.state, InputState::undo))
-                    .on_action(window.listener_for(&self.state, InputState::redo))
+                    .on_action(window.listener_for(&self.state, InputState::on_action_undo))
+                    .on_action(window.listener_for(&self.state, InputState::on_action_redo))
+                    .on_action(
+                        window.listener_for(&self.state, InputState::on_action_format_document),
+                    )
                     .when(state.mode.is_multi_line(), |this| {
                         this.on_action(window.listener_for(&self.state, InputState::indent_inline))
                             .on_action(window.listener_for(&self.state, InputState::outdent_inline))
                             .on_action(window.listener_for(&self.state, InputState::indent_block))
                             .on_action(window.listener_for(&self.state, InputState::outdent_block))
+                            .on_action(window.listener_for(
+                                &self.state,
+                                InputState::on_action_add_selection_to_next_match,
+                            ))
                     })
                     .on_action(
                         window.listener_for(&self.state, InputState::on_action_toggle_code_actions),
@@ -317,6 +345,17 @@ impl RenderOnce for Input {
                         window.listener_for(&self.state, InputState::on_action_go_to_definition),
                     )
             })
+            .when(
+                state.mode.is_code_editor() && state.mode.is_multi_line(),
+                |this| {
+                    this.on_action(window.listener_for(&self.state, InputState::on_action_fold))
+                        .on_action(window.listener_for(&self.state, InputState::on_action_unfold))
+                        .on_action(window.listener_for(&self.state, InputState::on_action_fold_all))
+                        .on_action(
+                            window.listener_for(&self.state, InputState::on_action_unfold_all),
+                        )
+                },
+            )
             .on_action(window.listener_for(&self.state, InputState::select_all))
             .on_action(window.listener_for(&self.state, InputState::select_to_start_of_line))
             .on_action(window.listener_for(&self.state, InputState::select_to_end_of_line))
@@ -352,6 +391,11 @@ impl RenderOnce for Input {
             )
             .on_mouse_move(window.listener_for(&self.state, InputState::on_mouse_move))
             .on_scroll_wheel(window.listener_for(&self.state, InputState::on_scroll_wheel))
+            .when(accept_file_drop, |this| {
+                this.group(DROP_GROUP)
+                    .relative()
+                    .on_drop(window.listener_for(&self.state, InputState::on_drop_files))
+            })
             .size_full()
             .line_height(LINE_HEIGHT)
             .input_px(self.size)
@@ -420,5 +464,19 @@ impl RenderOnce for Input {
                         .children(suffix),
                 )
             })
+            .when(accept_file_drop, |this| {
+                // The drop overlay, only visible when the files are dragged over.
+                this.child(
+                    div()
+                        .absolute()
+                        .inset_0()
+                        .rounded(cx.theme().radius)
+                        .border_2()
+                        .border_color(cx.theme().drag_border)
+                        .bg(cx.theme().drop_target)
+                        .invisible()
+                        .group_drag_over::<ExternalPaths>(DROP_GROUP, |this| this.visible()),
+                )
+            })
     }
 }
@@ -0,0 +1,194 @@
+//! Helpers for the JSON code editor, to show the [`serde_json`] parse errors as the diagnostics,
+//! and to get the JSON path at the cursor.
+
+use std::fmt;
+
+use gpui::{App, Entity, SharedString};
+use ropey::Rope;
+use sum_tree::Bias;
+
+use crate::{
+    highlighter::{Diagnostic, DiagnosticSeverity},
+    input::{InputState, Position, RopeExt as _},
+};
+
+/// Convert a [`serde_json::Error`] of parsing the `text` to a [`Diagnostic`].
+///
+/// The serde_json reports the 1-based line and byte column, this converts them to the
+/// 0-based line and character column, and the range covers the char at the error position.
+pub fn json_error_to_diagnostic(err: &serde_json::Error, text: &Rope) -> Diagnostic {
+    let row = err
+        .line()
+        .saturating_sub(1)
+        .min(text.lines_len().saturating_sub(1));
+    let line_start = text.line_start_offset(row);
+    let line_end = text.line_end_offset(row);
+
+    let offset = (line_start + err.column().saturating_sub(1)).min(line_end);
+    let mut start = text.offset_to_position(text.clip_offset(offset, Bias::Left));
+    let line_len = text.offset_to_position(line_end).character;
+    // The EOF errors are reported after the last char, point to the last char instead.
+    if start.character == line_len && line_len > 0 {
+        start.character -= 1;
+    }
+    let end = Position::new(start.line, (start.character + 1).min(line_len));
+
+    Diagnostic::new(start..end, err.to_string())
+        .with_severity(DiagnosticSeverity::Error)
+        .with_source("json")
+}
+
+/// Parse the `text` as JSON, and replace the diagnostics of the code editor with the parse error.
+///
+/// The diagnostics are cleared if the `text` is valid, and the parsed value is returned.
+///
+/// ```ignore
+/// let text = state.read(cx).value();
+/// if let Ok(value) = attach_json_diagnostics(&state, &text, cx) {
+///     let formatted = serde_json::to_string_pretty(&value).unwrap();
+/// }
+/// ```
+pub fn attach_json_diagnostics(
+    state: &Entity<InputState>,
+    text: &str,
+    cx: &mut App,
+) -> serde_json::Result<serde_json::Value> {
+    let result = serde_json::from_str::<serde_json::Value>(text);
+    let diagnostic = result
+        .as_ref()
+        .err()
+        .map(|err| json_error_to_diagnostic(err, &Rope::from(text)));
+
+    state.update(cx, |state, cx| {
+        state.set_diagnostics(diagnostic, cx);
+    });
+
+    result
+}
+
+/// A segment of the JSON path, see [`InputState::json_path_at`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonPathSegment {
+    /// The key of an object member.
+    Key(SharedString),
+    /// The index of an array item.
+    Index(usize),
+}
+
+impl fmt::Display for JsonPathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonPathSegment::Key(key) => write!(f, "{}", key),
+            JsonPathSegment::Index(index) => write!(f, "{}", index),
+        }
+    }
+}
+
+impl InputState {
+    /// Returns the JSON path of the value at the offset, e.g. `items` → `3` → `name`,
+    /// the empty path is the root value.
+    ///
+    /// The offset is the UTF-8 byte offset. This uses the syntax tree of the `json` code editor,
+    /// see also [`InputState::syntax_node_at`].
+    pub fn json_path_at(&self, offset: usize) -> Option<Vec<JsonPathSegment>> {
+        self.with_syntax_node_at(offset, |node| json_path(node, &self.text))
+    }
+}
+
+/// Returns the JSON path of the node in the tree of `tree-sitter-json`.
+fn json_path(node: tree_sitter::Node, text: &Rope) -> Vec<JsonPathSegment> {
+    let mut path = vec![];
+    let mut node = Some(node);
+    while let Some(n) = node {
+        let parent = n.parent();
+        if n.kind() == "pair" {
+            if let Some(key) = n.child_by_field_name("key") {
+                let key = text.slice(key.byte_range()).to_string();
+                let key = serde_json::from_str::<String>(&key)
+                    .unwrap_or_else(|_| key.trim_matches('"').to_string());
+                path.push(JsonPathSegment::Key(key.into()));
+            }
+        } else if let Some(array) = parent.filter(|p| p.kind() == "array") {
+            let mut cursor = array.walk();
+            let index = array
+                .named_children(&mut cursor)
+                .filter(|child| child.kind() != "comment")
+                .position(|child| child.id() == n.id());
+            if let Some(index) = index {
+                path.push(JsonPathSegment::Index(index));
+            }
+        }
+        node = parent;
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use ropey::Rope;
+
+    use super::{JsonPathSegment, json_error_to_diagnostic, json_path};
+    use crate::input::Position;
+
+    fn path_at(text: &str, offset: usize) -> Vec<String> {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_json::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(text, None).unwrap();
+        let node = tree
+            .root_node()
+            .named_descendant_for_byte_range(offset, offset)
+            .unwrap();
+        json_path(node, &Rope::from(text))
+            .iter()
+            .map(JsonPathSegment::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_json_path() {
+        let text = r#"{"items": [1, {"name": "foo", "a\"b": [true]}], "id": 2}"#;
+        assert_eq!(path_at(text, 0), Vec::<String>::new());
+        assert_eq!(path_at(text, text.find("items").unwrap()), vec!["items"]);
+        assert_eq!(path_at(text, text.find("1").unwrap()), vec!["items", "0"]);
+        assert_eq!(
+            path_at(text, text.find("foo").unwrap()),
+            vec!["items", "1", "name"]
+        );
+        assert_eq!(
+            path_at(text, text.find("true").unwrap()),
+            vec!["items", "1", "a\"b", "0"]
+        );
+        assert_eq!(path_at(text, text.find("2").unwrap()), vec!["id"]);
+    }
+
+    fn diagnostic_range(text: &str) -> std::ops::Range<Position> {
+        let err = serde_json::from_str::<serde_json::Value>(text).unwrap_err();
+        json_error_to_diagnostic(&err, &Rope::from(text)).range
+    }
+
+    #[test]
+    fn test_json_error_to_diagnostic() {
+        assert_eq!(
+            diagnostic_range("{\"a\": 1,}"),
+            Position::new(0, 8)..Position::new(0, 9)
+        );
+        // The column is in characters.
+        assert_eq!(
+            diagnostic_range("{\"中文\": 1,}"),
+            Position::new(0, 9)..Position::new(0, 10)
+        );
+        assert_eq!(
+            diagnostic_range("{\n  \"a\": 1\n  \"b\": 2\n}"),
+            Position::new(2, 2)..Position::new(2, 3)
+        );
+        // EOF
+        assert_eq!(
+            diagnostic_range("{\"a\": 1"),
+            Position::new(0, 6)..Position::new(0, 7)
+        );
+    }
+}
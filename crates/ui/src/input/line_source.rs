@@ -0,0 +1,443 @@
+use std::{ops::Range, rc::Rc};
+
+use gpui::{Context, Window};
+use ropey::Rope;
+use sum_tree::Bias;
+use tree_sitter::Point;
+
+use crate::input::{InputEvent, InputState, RopeExt as _, Selection};
+
+/// The number of lines to load at a time when scanning the line source for the search.
+const SCAN_CHUNK_LINES: usize = 4096;
+/// The max number of the loaded lines, the lines far from the viewport are unloaded above it.
+const MAX_LOADED_LINES: usize = 10_000;
+/// The max number of the matched lines loaded by a search, the nearest ones to the cursor.
+const MAX_MATCHED_LINES: usize = MAX_LOADED_LINES / 2;
+
+/// The lazily loaded lines of the input, see [`InputState::set_line_source`].
+pub(super) struct LineSource {
+    load: Rc<dyn Fn(Range<usize>) -> Vec<String>>,
+    /// Whether each line is loaded into the text.
+    loaded: Vec<bool>,
+    /// The number of the loaded lines.
+    loaded_len: usize,
+    /// The read-only state before the line source is set, restored by [`InputState::set_value`].
+    pub(super) was_read_only: bool,
+}
+
+impl InputState {
+    /// Back the input with the lines provided lazily by the `source`, instead of the whole text,
+    /// e.g. to view a large generated document without building one giant `String`.
+    ///
+    /// The `source` is called with the rows to load, and returns the lines without the line
+    /// endings. Only the lines rendered in the viewport, matched by the search, or copied are
+    /// loaded, the other lines are empty in the text until then, so the scrolling and the line
+    /// numbers work with the `total_lines` from the start.
+    /// The lines far from the viewport are unloaded again to keep the memory bounded.
+    ///
+    /// The input is read-only while the line source is set, call [`InputState::set_value`]
+    /// to go back to a normal text. The search matches a line at a time, so a regular expression
+    /// that matches across lines only finds the matches in the loaded lines. And the search only
+    /// loads the matched lines nearest to the cursor, the matches in the unloaded lines are removed,
+    /// search again to find them around the cursor.
+    ///
+    /// ```ignore
+    /// let lines = Rc::new(pretty_json_lines);
+    /// state.set_line_source(
+    ///     {
+    ///         let lines = lines.clone();
+    ///         move |rows| lines[rows].to_vec()
+    ///     },
+    ///     lines.len(),
+    ///     window,
+    ///     cx,
+    /// );
+    /// ```
+    pub fn set_line_source(
+        &mut self,
+        source: impl Fn(Range<usize>) -> Vec<String> + 'static,
+        total_lines: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let total_lines = total_lines.max(1);
+        // Use empty lines as the placeholders, to keep the rows of the lines to load.
+        self.set_value_without_history("\n".repeat(total_lines - 1), window, cx);
+        self.line_source = Some(LineSource {
+            load: Rc::new(source),
+            loaded: vec![false; total_lines],
+            loaded_len: 0,
+            was_read_only: self.read_only,
+        });
+        self.read_only = true;
+        cx.notify();
+    }
+
+    /// Returns true if the input is backed by a line source, see [`InputState::set_line_source`].
+    pub fn has_line_source(&self) -> bool {
+        self.line_source.is_some()
+    }
+
+    /// Returns true if any of the rows is not loaded yet from the line source.
+    pub(super) fn has_unloaded_lines(&self, rows: &Range<usize>) -> bool {
+        self.line_source.as_ref().is_some_and(|source| {
+            !unloaded_ranges(&source.loaded, rows.clone(), usize::MAX).is_empty()
+        })
+    }
+
+    /// Load the rows that are not loaded yet from the line source.
+    ///
+    /// The lines far from the `rows` are unloaded when there are too many loaded lines.
+    pub(super) fn load_lines(&mut self, rows: Range<usize>, cx: &mut Context<Self>) {
+        let Some(source) = self.line_source.as_mut() else {
+            return;
+        };
+
+        let mut lines = vec![];
+        for range in unloaded_ranges(&source.loaded, rows.clone(), usize::MAX) {
+            let mut loaded = (source.load)(range.clone()).into_iter();
+            lines.extend(
+                range
+                    .clone()
+                    .map(|row| (row, loaded.next().unwrap_or_default())),
+            );
+            source.loaded_len += range.len();
+            source.loaded[range].fill(true);
+        }
+        self.fill_lines(lines, cx);
+        self.unload_lines(rows, cx);
+    }
+
+    /// Unload the lines outside the window around the `rows` back to the empty placeholders,
+    /// if there are more than [`MAX_LOADED_LINES`] loaded lines.
+    ///
+    /// The lines of the selections and the active search match are kept, the search matches
+    /// in the unloaded lines are removed.
+    fn unload_lines(&mut self, rows: Range<usize>, cx: &mut Context<Self>) {
+        let Some(source) = self.line_source.as_ref() else {
+            return;
+        };
+        if source.loaded_len <= MAX_LOADED_LINES {
+            return;
+        }
+
+        let rows_of = |range: Range<usize>| {
+            self.text.offset_to_point(range.start).row..self.text.offset_to_point(range.end).row + 1
+        };
+        let mut keep_rows =
+            vec![rows.start.saturating_sub(MAX_LOADED_LINES / 4)..rows.end + MAX_LOADED_LINES / 4];
+        keep_rows.extend(
+            std::iter::once(&self.selected_range)
+                .chain(self.secondary_selections.iter())
+                .map(|selection| rows_of(selection.start..selection.end)),
+        );
+        let matcher = &self.search_matcher;
+        keep_rows.extend(
+            matcher
+                .matched_ranges
+                .get(matcher.current_match_ix)
+                .map(|matched| rows_of(matched.clone())),
+        );
+
+        let rows = source
+            .loaded
+            .iter()
+            .enumerate()
+            .filter(|(row, loaded)| **loaded && !keep_rows.iter().any(|rows| rows.contains(row)))
+            .map(|(row, _)| row)
+            .collect::<Vec<_>>();
+
+        if let Some(source) = self.line_source.as_mut() {
+            for row in rows.iter() {
+                source.loaded[*row] = false;
+            }
+            source.loaded_len -= rows.len();
+        }
+        let prev_matches = self.search_matcher.matched_ranges.clone();
+        self.fill_lines(
+            rows.into_iter().map(|row| (row, String::new())).collect(),
+            cx,
+        );
+        if self.search_matcher.matched_ranges != prev_matches {
+            cx.emit(InputEvent::SearchMatchesChanged {
+                count: self.search_matcher.matched_ranges.len(),
+                active: self.active_search_match(),
+            });
+        }
+    }
+
+    /// Load the rows covered by the byte range, e.g. to copy the selected text.
+    pub(super) fn load_lines_in_range(&mut self, range: &Range<usize>, cx: &mut Context<Self>) {
+        if self.line_source.is_none() {
+            return;
+        }
+
+        let start_row = self.text.offset_to_point(range.start).row;
+        let end_row = self.text.offset_to_point(range.end).row;
+        self.load_lines(start_row..end_row + 1, cx);
+    }
+
+    /// Scan the lines not loaded yet in chunks, and only load the lines matched by `is_match`,
+    /// at most [`MAX_MATCHED_LINES`] lines nearest to the `around_row`.
+    pub(super) fn load_matched_lines(
+        &mut self,
+        is_match: impl Fn(&str) -> bool,
+        around_row: usize,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(source) = self.line_source.as_mut() else {
+            return;
+        };
+
+        let mut lines = vec![];
+        let total_lines = source.loaded.len();
+        for range in unloaded_ranges(&source.loaded, 0..total_lines, SCAN_CHUNK_LINES) {
+            let loaded = (source.load)(range.clone());
+            lines.extend(
+                range
+                    .zip(loaded)
+                    .filter(|(_, line)| is_match(line.as_str())),
+            );
+        }
+        if lines.len() > MAX_MATCHED_LINES {
+            lines.sort_by_key(|(row, _)| row.abs_diff(around_row));
+            lines.truncate(MAX_MATCHED_LINES);
+            lines.sort_by_key(|(row, _)| *row);
+        }
+        for (row, _) in lines.iter() {
+            source.loaded[*row] = true;
+        }
+        source.loaded_len += lines.len();
+        self.fill_lines(lines, cx);
+    }
+
+    /// Replace the lines with the loaded lines or the empty placeholders, the rows must be sorted.
+    ///
+    /// This is not an edit, so no history, change event or notify, the selections and search
+    /// matches are kept at the same place.
+    fn fill_lines(&mut self, lines: Vec<(usize, String)>, cx: &mut Context<Self>) {
+        if lines.is_empty() {
+            return;
+        }
+
+        let to_points = |text: &Rope, selection: Selection| -> (Point, Point) {
+            (
+                text.offset_to_point(selection.start),
+                text.offset_to_point(selection.end),
+            )
+        };
+        let selected_range = to_points(&self.text, self.selected_range);
+        let secondary_selections = self
+            .secondary_selections
+            .iter()
+            .map(|selection| to_points(&self.text, *selection))
+            .collect::<Vec<_>>();
+        let last_selected_range = self
+            .last_selected_range
+            .map(|selection| to_points(&self.text, selection));
+
+        // Apply from the last one to keep the offsets of the previous rows.
+        for (rows, new_text) in group_rows(lines).into_iter().rev() {
+            let range =
+                self.text.line_start_offset(rows.start)..self.text.line_end_offset(rows.end - 1);
+            self.text.replace(range.clone(), &new_text);
+            self.text_wrapper
+                .update(&self.text, &range, &Rope::from(new_text.as_str()), cx);
+            self.update_folds_for_edit(&range, &new_text);
+            self.mode
+                .update_highlighter(&range, &self.text, &new_text, true, cx);
+            self.search_matcher
+                .shift_matches(&self.text, &range, new_text.len());
+        }
+        if let Some(diagnostics) = self.mode.diagnostics_mut() {
+            diagnostics.reset(&self.text)
+        }
+
+        // The column may be out of the replaced line, clamp it to the line end on a char boundary.
+        let from_point = |text: &Rope, point: Point| -> usize {
+            let offset = text.line_start_offset(point.row) + point.column;
+            text.clip_offset(offset.min(text.line_end_offset(point.row)), Bias::Left)
+        };
+        let from_points = |text: &Rope, (start, end): (Point, Point)| -> Selection {
+            Selection::new(from_point(text, start), from_point(text, end))
+        };
+        self.selected_range = from_points(&self.text, selected_range);
+        self.secondary_selections = secondary_selections
+            .into_iter()
+            .map(|points| from_points(&self.text, points))
+            .collect();
+        self.last_selected_range =
+            last_selected_range.map(|points| from_points(&self.text, points));
+        self.selected_word_range = None;
+        self.mode.update_auto_grow(&self.text_wrapper);
+    }
+}
+
+/// Returns the ranges of the rows not loaded yet, each range has `max_len` rows at most.
+fn unloaded_ranges(loaded: &[bool], rows: Range<usize>, max_len: usize) -> Vec<Range<usize>> {
+    let rows = rows.start.min(loaded.len())..rows.end.min(loaded.len());
+    let mut ranges: Vec<Range<usize>> = vec![];
+    for row in rows.filter(|row| !loaded[*row]) {
+        match ranges.last_mut() {
+            Some(last) if last.end == row && last.len() < max_len => last.end += 1,
+            _ => ranges.push(row..row + 1),
+        }
+    }
+    ranges
+}
+
+/// Group the sorted lines by the continuous rows, and join the lines of each group.
+///
+/// The trailing line endings are removed, and the others are replaced with spaces to keep the rows.
+fn group_rows(lines: Vec<(usize, String)>) -> Vec<(Range<usize>, String)> {
+    let mut groups: Vec<(Range<usize>, String)> = vec![];
+    for (row, mut line) in lines {
+        line.truncate(line.trim_end_matches(['\r', '\n']).len());
+        if line.contains(['\r', '\n']) {
+            line = line.replace(['\r', '\n'], " ");
+        }
+
+        match groups.last_mut() {
+            Some((rows, text)) if rows.end == row => {
+                rows.end += 1;
+                text.push('\n');
+                text.push_str(&line);
+            }
+            _ => groups.push((row..row + 1, line)),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{group_rows, unloaded_ranges};
+
+    #[test]
+    fn test_unloaded_ranges() {
+        let loaded = [true, false, false, true, false, false, false, true];
+        assert_eq!(unloaded_ranges(&loaded, 0..8, usize::MAX), vec![1..3, 4..7]);
+        assert_eq!(unloaded_ranges(&loaded, 2..5, usize::MAX), vec![2..3, 4..5]);
+        assert_eq!(unloaded_ranges(&loaded, 0..8, 2), vec![1..3, 4..6, 6..7]);
+        // Out of the bounds.
+        assert_eq!(unloaded_ranges(&loaded, 6..100, usize::MAX), vec![6..7]);
+        assert!(unloaded_ranges(&loaded, 8..10, usize::MAX).is_empty());
+    }
+
+    #[gpui::test]
+    fn test_unload_lines(cx: &mut gpui::TestAppContext) {
+        use gpui::AppContext as _;
+
+        use super::MAX_LOADED_LINES;
+        use crate::input::{InputState, RopeExt as _};
+
+        cx.update(|cx| crate::init(cx));
+        let window = cx.add_empty_window();
+        window.update(|window, cx| {
+            let state = cx.new(|cx| InputState::new(window, cx).multi_line(true));
+            state.update(cx, |state, cx| {
+                let total_lines = MAX_LOADED_LINES * 3;
+                state.set_line_source(
+                    |rows| rows.map(|row| format!("line {}", row)).collect(),
+                    total_lines,
+                    window,
+                    cx,
+                );
+                let line = |state: &InputState, row: usize| {
+                    let range = state.text.line_start_offset(row)..state.text.line_end_offset(row);
+                    state.text.slice(range).to_string()
+                };
+
+                state.load_lines(0..MAX_LOADED_LINES, cx);
+                assert_eq!(line(state, 0), "line 0");
+
+                // The lines far from the loading rows are unloaded.
+                let rows = MAX_LOADED_LINES * 2..MAX_LOADED_LINES * 2 + 100;
+                state.load_lines(rows.clone(), cx);
+                assert_eq!(line(state, 1), "");
+                assert_eq!(line(state, rows.start), format!("line {}", rows.start));
+                assert_eq!(state.text.lines_len(), total_lines);
+                assert!(state.has_unloaded_lines(&(1..2)));
+                // The line of the cursor is kept.
+                assert_eq!(line(state, 0), "line 0");
+                assert!(!state.has_unloaded_lines(&rows));
+            });
+        });
+    }
+
+    #[gpui::test]
+    fn test_unload_lines_with_selections(cx: &mut gpui::TestAppContext) {
+        use gpui::AppContext as _;
+
+        use super::MAX_LOADED_LINES;
+        use crate::input::{InputState, RopeExt as _, Selection};
+
+        cx.update(|cx| crate::init(cx));
+        let window = cx.add_empty_window();
+        window.update(|window, cx| {
+            let state = cx.new(|cx| InputState::new(window, cx).multi_line(true));
+            state.update(cx, |state, cx| {
+                state.set_line_source(
+                    |rows| rows.map(|row| format!("line {}", row)).collect(),
+                    MAX_LOADED_LINES * 3,
+                    window,
+                    cx,
+                );
+                let point = |state: &InputState, offset: usize| {
+                    let point = state.text.offset_to_point(offset);
+                    (point.row, point.column)
+                };
+
+                state.load_lines(0..MAX_LOADED_LINES, cx);
+                let offset = |state: &InputState, row: usize, column: usize| {
+                    state.text.line_start_offset(row) + column
+                };
+                state.selected_range = Selection::new(offset(state, 10, 2), offset(state, 10, 2));
+                state.secondary_selections = vec![
+                    Selection::new(offset(state, 100, 5), offset(state, 101, 3)),
+                    Selection::new(offset(state, 5000, 4), offset(state, 5000, 6)),
+                ];
+
+                // The rows of all the selections are kept after unloading the lines before them.
+                state.load_lines(MAX_LOADED_LINES * 2..MAX_LOADED_LINES * 2 + 100, cx);
+                assert!(state.has_unloaded_lines(&(0..10)));
+                assert!(!state.has_unloaded_lines(&(100..102)));
+                assert!(!state.has_unloaded_lines(&(5000..5001)));
+                assert_eq!(point(state, state.selected_range.start), (10, 2));
+                let selections = state.secondary_selections.clone();
+                assert_eq!(point(state, selections[0].start), (100, 5));
+                assert_eq!(point(state, selections[0].end), (101, 3));
+                assert_eq!(point(state, selections[1].start), (5000, 4));
+                assert_eq!(point(state, selections[1].end), (5000, 6));
+
+                // Reload the lines before them, the selections are still at the same place.
+                state.load_lines(0..MAX_LOADED_LINES, cx);
+                assert!(!state.has_unloaded_lines(&(0..10)));
+                assert_eq!(point(state, state.selected_range.start), (10, 2));
+                let selections = state.secondary_selections.clone();
+                assert_eq!(point(state, selections[0].start), (100, 5));
+                assert_eq!(point(state, selections[0].end), (101, 3));
+                assert_eq!(point(state, selections[1].start), (5000, 4));
+            });
+        });
+    }
+
+    #[test]
+    fn test_group_rows() {
+        let lines = vec![
+            (0, "{".to_string()),
+            (1, "  \"a\": 1,".to_string()),
+            (3, "  \"b\": 2".to_string()),
+            (4, "}\r\n".to_string()),
+            (6, "a\nb".to_string()),
+        ];
+        assert_eq!(
+            group_rows(lines),
+            vec![
+                (0..2, "{\n  \"a\": 1,".to_string()),
+                (3..5, "  \"b\": 2\n}".to_string()),
+                (6..7, "a b".to_string()),
+            ]
+        );
+    }
+}
@@ -10,12 +10,14 @@ mod completions;
 mod definitions;
 mod document_colors;
 mod hover;
+mod simple_completions;
 
 pub use code_actions::*;
 pub use completions::*;
 pub use definitions::*;
 pub use document_colors::*;
 pub use hover::*;
+pub use simple_completions::*;
 
 /// LSP ServerCapabilities
 ///
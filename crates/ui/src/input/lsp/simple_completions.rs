@@ -0,0 +1,224 @@
+use anyhow::Result;
+use gpui::{App, Context, SharedString, Task, Window};
+use lsp_types::{
+    CompletionContext, CompletionItem, CompletionItemKind, CompletionResponse, CompletionTextEdit,
+    TextEdit,
+};
+use ropey::Rope;
+use std::{ops::Range, rc::Rc};
+
+use crate::{
+    input::{CompletionProvider, InputState, RopeExt as _},
+    tree::fuzzy_match,
+};
+
+/// A completion item of [`InputState::set_completion_provider`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    /// The label to show in the completion menu.
+    pub label: SharedString,
+    /// The text to insert when accepted, default is the `label`.
+    pub insert_text: Option<SharedString>,
+    /// The detail to show after the label, e.g.: the type of the value.
+    pub detail: Option<SharedString>,
+    /// The kind of the completion.
+    pub kind: Option<CompletionItemKind>,
+}
+
+impl Completion {
+    /// Create a new completion with the label.
+    pub fn new(label: impl Into<SharedString>) -> Self {
+        Self {
+            label: label.into(),
+            insert_text: None,
+            detail: None,
+            kind: None,
+        }
+    }
+
+    /// Set the text to insert when accepted, default is the `label`.
+    pub fn insert_text(mut self, insert_text: impl Into<SharedString>) -> Self {
+        self.insert_text = Some(insert_text.into());
+        self
+    }
+
+    /// Set the detail to show after the label.
+    pub fn detail(mut self, detail: impl Into<SharedString>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Set the kind of the completion.
+    pub fn kind(mut self, kind: CompletionItemKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    fn into_item(self, query: &str, range: lsp_types::Range) -> CompletionItem {
+        let new_text = self.insert_text.unwrap_or_else(|| self.label.clone());
+        CompletionItem {
+            label: self.label.to_string(),
+            kind: self.kind,
+            detail: self.detail.map(|detail| detail.to_string()),
+            filter_text: Some(query.to_string()),
+            text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+                range,
+                new_text: new_text.to_string(),
+            })),
+            ..Default::default()
+        }
+    }
+}
+
+type CompletionFn = Rc<dyn Fn(&str, usize, &mut App) -> Task<Vec<Completion>>>;
+
+/// The [`CompletionProvider`] to filter the completions of a function by the word at the cursor.
+struct FnCompletionProvider {
+    completions: CompletionFn,
+}
+
+impl CompletionProvider for FnCompletionProvider {
+    fn completions(
+        &self,
+        text: &Rope,
+        offset: usize,
+        _: CompletionContext,
+        _: &mut Window,
+        cx: &mut Context<InputState>,
+    ) -> Task<Result<CompletionResponse>> {
+        let word_range = word_range(text, offset);
+        let query = text.slice(word_range.clone()).to_string();
+        let range = lsp_types::Range {
+            start: text.offset_to_position(word_range.start),
+            end: text.offset_to_position(word_range.end),
+        };
+
+        let task = (self.completions)(&text.to_string(), offset, cx);
+        cx.spawn(async move |_, _| {
+            let items = filter_completions(task.await, &query)
+                .into_iter()
+                .map(|completion| completion.into_item(&query, range))
+                .collect();
+
+            Ok(CompletionResponse::Array(items))
+        })
+    }
+
+    fn is_completion_trigger(&self, _: usize, new_text: &str, _: &mut Context<InputState>) -> bool {
+        !new_text.is_empty() && new_text.chars().all(is_word_char)
+    }
+}
+
+impl InputState {
+    /// Set a function to provide the completions when typing a word.
+    ///
+    /// The function receives the text and the cursor offset in bytes. The completions are
+    /// fuzzy filtered by the word at the cursor, and the accepted one replaces the word.
+    ///
+    /// This replaces the [`super::Lsp::completion_provider`].
+    ///
+    /// ```ignore
+    /// state.set_completion_provider(|text, offset, _| {
+    ///     Task::ready(vec![
+    ///         Completion::new("name").detail("string"),
+    ///         Completion::new("version").insert_text("version\": \"\"").detail("string"),
+    ///     ])
+    /// });
+    /// ```
+    pub fn set_completion_provider<F>(&mut self, completions: F)
+    where
+        F: Fn(&str, usize, &mut App) -> Task<Vec<Completion>> + 'static,
+    {
+        self.lsp.completion_provider = Some(Rc::new(FnCompletionProvider {
+            completions: Rc::new(completions),
+        }));
+    }
+}
+
+#[inline]
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Returns the byte range of the word around the offset, it's empty if there is no word.
+fn word_range(text: &Rope, offset: usize) -> Range<usize> {
+    let start = offset
+        - text
+            .chars_at(offset)
+            .reversed()
+            .take_while(|c| is_word_char(*c))
+            .map(|c| c.len_utf8())
+            .sum::<usize>();
+    let end = offset
+        + text
+            .chars_at(offset)
+            .take_while(|c| is_word_char(*c))
+            .map(|c| c.len_utf8())
+            .sum::<usize>();
+
+    start..end
+}
+
+/// Fuzzy filter the completions by the label, the best matches first.
+///
+/// Returns empty if the query is empty.
+fn filter_completions(completions: Vec<Completion>, query: &str) -> Vec<Completion> {
+    let mut matches = completions
+        .into_iter()
+        .filter_map(|completion| {
+            fuzzy_match(query, &completion.label).map(|m| (m.score, completion))
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by(|a, b| b.0.cmp(&a.0));
+    matches
+        .into_iter()
+        .map(|(_, completion)| completion)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ropey::Rope;
+
+    use super::{Completion, filter_completions, word_range};
+
+    #[test]
+    fn test_word_range() {
+        let text = Rope::from("{\"max_width\": 10, \"名字\"}");
+        let offset = text.to_string().find("_width").unwrap();
+        assert_eq!(word_range(&text, offset), 2..11);
+        assert_eq!(word_range(&text, 2), 2..11);
+        assert_eq!(word_range(&text, 11), 2..11);
+        assert_eq!(word_range(&text, 1), 1..1);
+
+        let offset = text.to_string().find("字").unwrap();
+        assert_eq!(word_range(&text, offset), offset - 3..offset + 3);
+    }
+
+    #[test]
+    fn test_filter_completions() {
+        let completions = vec![
+            Completion::new("version"),
+            Completion::new("name"),
+            Completion::new("description"),
+        ];
+
+        let labels = |completions: Vec<Completion>| {
+            completions
+                .into_iter()
+                .map(|c| c.label.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            labels(filter_completions(completions.clone(), "n")),
+            vec!["name", "version", "description"]
+        );
+        assert_eq!(
+            labels(filter_completions(completions.clone(), "ver")),
+            vec!["version"]
+        );
+        assert!(filter_completions(completions.clone(), "xyz").is_empty());
+        assert!(filter_completions(completions, "").is_empty());
+    }
+}
@@ -0,0 +1,490 @@
+use std::ops::Range;
+
+use gpui::{
+    App, Bounds, ContentMask, Context, CursorStyle, Element, ElementId, Entity, GlobalElementId,
+    Hitbox, HitboxBehavior, Hsla, InspectorElementId, IntoElement, LayoutId, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, Style, Window, fill, point, px, relative,
+    size,
+};
+
+use crate::{ActiveTheme as _, input::InputState};
+
+/// The width of the minimap, see [`InputState::minimap`].
+pub(super) const MINIMAP_WIDTH: Pixels = px(80.);
+/// The height of a row in the minimap.
+const MINIMAP_ROW_HEIGHT: Pixels = px(2.);
+/// The width of a column in the minimap.
+const MINIMAP_COLUMN_WIDTH: Pixels = px(1.);
+
+impl InputState {
+    /// Set true to show a minimap on the right side of the editor, default is false.
+    ///
+    /// The minimap is a condensed overview of the whole text, the visible rows of the editor
+    /// are highlighted as a slider. Click on the minimap to scroll to the position, or drag
+    /// the slider to scroll. The diagnostics and search matches are marked on the rows.
+    ///
+    /// Only for multi-line mode.
+    pub fn minimap(mut self, minimap: bool) -> Self {
+        debug_assert!(self.mode.is_multi_line());
+        self.minimap = minimap;
+        self
+    }
+
+    /// Set whether to show the minimap, see [`InputState::minimap`].
+    pub fn set_minimap(&mut self, minimap: bool, _: &mut Window, cx: &mut Context<Self>) {
+        debug_assert!(self.mode.is_multi_line());
+        self.minimap = minimap;
+        self.minimap_drag = None;
+        cx.notify();
+    }
+
+    /// Returns the layout of the minimap with the `height`.
+    ///
+    /// Returns `None` if the editor has not been laid out yet.
+    fn minimap_layout(&self, height: Pixels) -> Option<MinimapLayout> {
+        let line_height = self.last_layout.as_ref()?.line_height;
+        let viewport_height = self.input_bounds.size.height;
+        let max_scroll = self.scroll_size.height - viewport_height;
+        let scroll_ratio = if max_scroll > px(0.) {
+            (-self.scroll_handle.offset().y / max_scroll).clamp(0., 1.)
+        } else {
+            0.
+        };
+
+        Some(MinimapLayout::new(
+            self.text_wrapper.len(),
+            viewport_height / line_height,
+            scroll_ratio,
+            height,
+        ))
+    }
+
+    /// Start to drag the slider, if the `y` is not on the slider, scroll to center the row at `y` first.
+    fn on_minimap_mouse_down(&mut self, y: Pixels, height: Pixels, cx: &mut Context<Self>) {
+        let Some(layout) = self.minimap_layout(height) else {
+            return;
+        };
+        let Some(line_height) = self.last_layout.as_ref().map(|layout| layout.line_height) else {
+            return;
+        };
+
+        if !layout.slider_contains(y) {
+            let top = line_height * layout.row_for_y(y) - self.input_bounds.size.height / 2.;
+            let offset = self.scroll_handle.offset();
+            self.update_scroll_offset(Some(point(offset.x, -top)), cx);
+        }
+
+        if let Some(layout) = self.minimap_layout(height) {
+            self.minimap_drag = Some((y - layout.slider_top).clamp(px(0.), layout.slider_height));
+        }
+    }
+
+    /// Move the slider top to `y - drag offset` while dragging.
+    fn on_minimap_drag(&mut self, y: Pixels, height: Pixels, cx: &mut Context<Self>) {
+        let Some(drag_offset) = self.minimap_drag else {
+            return;
+        };
+        let Some(layout) = self.minimap_layout(height) else {
+            return;
+        };
+
+        let ratio = layout.ratio_for_slider_top(y - drag_offset);
+        let max_scroll = (self.scroll_size.height - self.input_bounds.size.height).max(px(0.));
+        let offset = self.scroll_handle.offset();
+        self.update_scroll_offset(Some(point(offset.x, -max_scroll * ratio)), cx);
+    }
+}
+
+/// The vertical layout of the minimap.
+///
+/// When the rows do not fit in the minimap, the minimap scrolls with the editor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MinimapLayout {
+    /// The first row (including the soft wrapped rows) shown in the minimap.
+    first_row: usize,
+    /// The top of the slider (the visible rows of the editor), relative to the minimap.
+    slider_top: Pixels,
+    slider_height: Pixels,
+    /// The height of the rows in the minimap, the slider moves within it.
+    track_height: Pixels,
+}
+
+impl MinimapLayout {
+    fn new(total_rows: usize, visible_rows: f32, scroll_ratio: f32, height: Pixels) -> Self {
+        let rows = (height / MINIMAP_ROW_HEIGHT).floor() as usize;
+        let track_height = (MINIMAP_ROW_HEIGHT * total_rows as f32).min(height);
+        let slider_height = (MINIMAP_ROW_HEIGHT * visible_rows).min(track_height);
+        let first_row = (total_rows.saturating_sub(rows) as f32 * scroll_ratio).round() as usize;
+
+        Self {
+            first_row,
+            slider_top: (track_height - slider_height) * scroll_ratio,
+            slider_height,
+            track_height,
+        }
+    }
+
+    #[inline]
+    fn slider_contains(&self, y: Pixels) -> bool {
+        y >= self.slider_top && y < self.slider_top + self.slider_height
+    }
+
+    /// Returns the scroll ratio (0..=1) to move the slider top to the `y`.
+    fn ratio_for_slider_top(&self, y: Pixels) -> f32 {
+        let range = self.track_height - self.slider_height;
+        if range <= px(0.) {
+            return 0.;
+        }
+
+        (y / range).clamp(0., 1.)
+    }
+
+    /// Returns the row (fractional, including the soft wrapped rows) at the `y`.
+    fn row_for_y(&self, y: Pixels) -> f32 {
+        self.first_row as f32 + y.max(px(0.)) / MINIMAP_ROW_HEIGHT
+    }
+}
+
+/// Returns the columns of the non-whitespace text in a row, `None` if the row is blank.
+///
+/// The tab is expanded to `tab_size` columns, and the columns are limited by `max_columns`.
+fn row_extent(
+    chars: impl Iterator<Item = char>,
+    tab_size: usize,
+    max_columns: usize,
+) -> Option<Range<usize>> {
+    let mut start = None;
+    let mut end = 0;
+    let mut column = 0;
+    for c in chars {
+        if column >= max_columns {
+            break;
+        }
+
+        let width = if c == '\t' { tab_size } else { 1 };
+        if !c.is_whitespace() {
+            start.get_or_insert(column);
+            end = column + width;
+        }
+        column += width;
+    }
+
+    start.map(|start| start..end.min(max_columns))
+}
+
+/// The minimap element of the [`InputState`], see [`InputState::minimap`].
+pub(super) struct Minimap {
+    state: Entity<InputState>,
+}
+
+impl Minimap {
+    pub(super) fn new(state: Entity<InputState>) -> Self {
+        Self { state }
+    }
+}
+
+pub(super) struct MinimapPrepaintState {
+    hitbox: Hitbox,
+    /// The quads of the markers and the text, in paint order.
+    quads: Vec<(Bounds<Pixels>, Hsla)>,
+    slider_bounds: Option<Bounds<Pixels>>,
+}
+
+impl IntoElement for Minimap {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+impl Element for Minimap {
+    type RequestLayoutState = ();
+    type PrepaintState = MinimapPrepaintState;
+
+    fn id(&self) -> Option<ElementId> {
+        None
+    }
+
+    fn source_location(&self) -> Option<&'static std::panic::Location<'static>> {
+        None
+    }
+
+    fn request_layout(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        _: Option<&InspectorElementId>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let mut style = Style::default();
+        style.flex_shrink = 0.;
+        style.size.width = MINIMAP_WIDTH.into();
+        style.size.height = relative(1.).into();
+
+        (window.request_layout(style, [], cx), ())
+    }
+
+    fn prepaint(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        _: Option<&InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        _: &mut Self::RequestLayoutState,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self::PrepaintState {
+        let hitbox = window.insert_hitbox(bounds, HitboxBehavior::Normal);
+        let state = self.state.read(cx);
+        let Some(layout) = state.minimap_layout(bounds.size.height) else {
+            return MinimapPrepaintState {
+                hitbox,
+                quads: vec![],
+                slider_bounds: None,
+            };
+        };
+
+        let tab_size = state.mode.tab_size().tab_size;
+        let max_columns = (MINIMAP_WIDTH / MINIMAP_COLUMN_WIDTH) as usize;
+        let rows = (bounds.size.height / MINIMAP_ROW_HEIGHT).ceil() as usize;
+        let visible_rows = layout.first_row..layout.first_row + rows;
+        let row_bounds = |row: usize, columns: Range<usize>| {
+            Bounds::new(
+                point(
+                    bounds.origin.x + MINIMAP_COLUMN_WIDTH * columns.start as f32,
+                    bounds.origin.y + MINIMAP_ROW_HEIGHT * (row - visible_rows.start) as f32,
+                ),
+                size(
+                    MINIMAP_COLUMN_WIDTH * columns.len() as f32,
+                    MINIMAP_ROW_HEIGHT,
+                ),
+            )
+        };
+
+        // The (start offset, first row, line) of the lines in the minimap.
+        let mut lines = vec![];
+        let mut row = 0;
+        let mut offset = 0;
+        for line in state.text_wrapper.lines.iter() {
+            if row >= visible_rows.end {
+                break;
+            }
+
+            let lines_len = line.lines_len();
+            if lines_len > 0 && row + lines_len > visible_rows.start {
+                lines.push((offset, row, line));
+            }
+            row += lines_len;
+            offset += line.len() + 1;
+        }
+
+        // Returns the row of the offset if it's visible in the minimap.
+        let row_for_offset = |offset: usize| -> Option<usize> {
+            let ix = lines
+                .partition_point(|(start, _, _)| *start <= offset)
+                .checked_sub(1)?;
+            let (start, first_row, line) = lines[ix];
+            if offset > start + line.len() {
+                return None;
+            }
+
+            let wrapped_ix = line
+                .wrapped_lines
+                .iter()
+                .position(|range| offset - start < range.end)
+                .unwrap_or(line.wrapped_lines.len().saturating_sub(1));
+            let row = first_row + wrapped_ix;
+            visible_rows.contains(&row).then_some(row)
+        };
+        let byte_range = match (lines.first(), lines.last()) {
+            (Some((start, _, _)), Some((end, _, line))) => *start..end + line.len(),
+            _ => 0..0,
+        };
+
+        let mut quads = vec![];
+        if let Some(diagnostics) = state.mode.diagnostics() {
+            for entry in diagnostics.range(byte_range.clone()) {
+                if let Some(row) = row_for_offset(entry.range.start) {
+                    quads.push((
+                        row_bounds(row, 0..max_columns),
+                        entry.severity.fg(cx).opacity(0.5),
+                    ));
+                }
+            }
+        }
+
+        let matched_ranges = &state.search_matcher.matched_ranges;
+        let start_ix = matched_ranges.partition_point(|range| range.end < byte_range.start);
+        for range in matched_ranges[start_ix..].iter() {
+            if range.start > byte_range.end {
+                break;
+            }
+
+            if let Some(row) = row_for_offset(range.start) {
+                quads.push((row_bounds(row, 0..max_columns), cx.theme().selection));
+            }
+        }
+
+        let text_color = cx.theme().foreground.opacity(0.35);
+        for (_, first_row, line) in lines.iter() {
+            for (ix, range) in line.wrapped_lines.iter().enumerate() {
+                let row = first_row + ix;
+                if !visible_rows.contains(&row) {
+                    continue;
+                }
+
+                let chars = line.text().slice(range.clone()).chars();
+                if let Some(columns) = row_extent(chars, tab_size, max_columns) {
+                    quads.push((row_bounds(row, columns), text_color));
+                }
+            }
+        }
+
+        let slider_bounds = Bounds::new(
+            point(bounds.origin.x, bounds.origin.y + layout.slider_top),
+            size(bounds.size.width, layout.slider_height),
+        );
+
+        MinimapPrepaintState {
+            hitbox,
+            quads,
+            slider_bounds: Some(slider_bounds),
+        }
+    }
+
+    fn paint(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        _: Option<&InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        _: &mut Self::RequestLayoutState,
+        prepaint: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let dragging = self.state.read(cx).minimap_drag.is_some();
+        window.set_cursor_style(CursorStyle::Arrow, &prepaint.hitbox);
+
+        window.with_content_mask(Some(ContentMask { bounds }), |window| {
+            for (quad_bounds, color) in prepaint.quads.iter() {
+                window.paint_quad(fill(*quad_bounds, *color));
+            }
+
+            if let Some(slider_bounds) = prepaint.slider_bounds {
+                let color = if dragging {
+                    cx.theme().scrollbar_thumb_hover
+                } else {
+                    cx.theme().scrollbar_thumb
+                };
+                window.paint_quad(fill(slider_bounds, color.opacity(0.5)));
+            }
+        });
+
+        window.on_mouse_event({
+            let state = self.state.clone();
+            let hitbox = prepaint.hitbox.clone();
+            move |event: &MouseDownEvent, phase, window, cx| {
+                if !phase.bubble() || !hitbox.is_hovered(window) {
+                    return;
+                }
+
+                // Avoid moving the cursor or opening the context menu of the editor.
+                cx.stop_propagation();
+                if event.button == MouseButton::Left {
+                    state.update(cx, |state, cx| {
+                        state.on_minimap_mouse_down(
+                            event.position.y - bounds.origin.y,
+                            bounds.size.height,
+                            cx,
+                        );
+                    });
+                }
+            }
+        });
+
+        window.on_mouse_event({
+            let state = self.state.clone();
+            move |event: &MouseMoveEvent, phase, _, cx| {
+                if !phase.bubble() || state.read(cx).minimap_drag.is_none() {
+                    return;
+                }
+
+                cx.stop_propagation();
+                state.update(cx, |state, cx| {
+                    if event.dragging() {
+                        state.on_minimap_drag(
+                            event.position.y - bounds.origin.y,
+                            bounds.size.height,
+                            cx,
+                        );
+                    } else {
+                        state.minimap_drag = None;
+                        cx.notify();
+                    }
+                });
+            }
+        });
+
+        window.on_mouse_event({
+            let state = self.state.clone();
+            move |_: &MouseUpEvent, phase, _, cx| {
+                if !phase.bubble() || state.read(cx).minimap_drag.is_none() {
+                    return;
+                }
+
+                state.update(cx, |state, cx| {
+                    state.minimap_drag = None;
+                    cx.notify();
+                });
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::px;
+
+    use super::{MinimapLayout, row_extent};
+
+    #[test]
+    fn test_row_extent() {
+        assert_eq!(row_extent("".chars(), 4, 80), None);
+        assert_eq!(row_extent("    ".chars(), 4, 80), None);
+        assert_eq!(row_extent("  \"name\": 1,  ".chars(), 4, 80), Some(2..12));
+        assert_eq!(row_extent("\tfoo".chars(), 4, 80), Some(4..7));
+        assert_eq!(row_extent("foo bar".chars(), 4, 5), Some(0..5));
+        assert_eq!(row_extent("      foo".chars(), 4, 5), None);
+    }
+
+    #[test]
+    fn test_minimap_layout() {
+        // All rows fit in the minimap.
+        let layout = MinimapLayout::new(100, 20., 0., px(400.));
+        assert_eq!(layout.first_row, 0);
+        assert_eq!(layout.track_height, px(200.));
+        assert_eq!(layout.slider_height, px(40.));
+        assert_eq!(layout.slider_top, px(0.));
+        let layout = MinimapLayout::new(100, 20., 1., px(400.));
+        assert_eq!(layout.first_row, 0);
+        assert_eq!(layout.slider_top, px(160.));
+        assert_eq!(layout.ratio_for_slider_top(px(80.)), 0.5);
+        assert_eq!(layout.ratio_for_slider_top(px(200.)), 1.);
+        assert_eq!(layout.ratio_for_slider_top(px(-10.)), 0.);
+
+        // The minimap scrolls with the editor.
+        let layout = MinimapLayout::new(1000, 20., 0.5, px(400.));
+        assert_eq!(layout.first_row, 400);
+        assert_eq!(layout.track_height, px(400.));
+        assert_eq!(layout.slider_top, px(180.));
+        assert!(layout.slider_contains(px(200.)));
+        assert!(!layout.slider_contains(px(100.)));
+        assert_eq!(layout.ratio_for_slider_top(px(180.)), 0.5);
+        assert_eq!(layout.row_for_y(px(10.)), 405.);
+
+        // The slider can not move if all rows are visible.
+        let layout = MinimapLayout::new(10, 20., 0., px(400.));
+        assert_eq!(layout.slider_height, px(20.));
+        assert_eq!(layout.ratio_for_slider_top(px(10.)), 0.);
+    }
+}
@@ -1,32 +1,54 @@
 mod blink_cursor;
+mod bracket;
 mod change;
 mod clear_button;
 mod cursor;
 mod element;
+mod fold;
+mod format;
+mod gutter;
+mod guides;
 mod indent;
 mod input;
+pub mod json;
+mod line_source;
 mod lsp;
 mod mask_pattern;
+mod minimap;
 mod mode;
 mod movement;
+mod multi_cursor;
 mod number_input;
 mod otp_input;
 pub(crate) mod popovers;
 mod rope_ext;
 mod search;
 mod state;
+mod stats;
+mod syntax;
 mod text_wrapper;
+mod view_state;
+mod vim;
 mod selection;
 
 pub(crate) use clear_button::*;
 pub use cursor::*;
+pub use fold::{Fold, FoldAll, Unfold, UnfoldAll};
+pub use format::FormatDocument;
+pub use gutter::{GutterClickEvent, GutterLine, GutterMarker, LineAnnotation};
 pub use indent::TabSize;
 pub use input::*;
 pub use lsp::*;
 pub use mask_pattern::MaskPattern;
+pub use multi_cursor::AddSelectionToNextMatch;
 pub use number_input::{NumberInput, NumberInputEvent, StepAction};
 pub use otp_input::*;
+pub use search::SearchOptions;
 pub use state::*;
+pub use stats::{DocumentStats, SelectionStats};
+pub use syntax::SyntaxNode;
+pub use view_state::EditorViewState;
+pub use vim::VimMode;
 
 pub use lsp_types::Position;
 pub use rope_ext::*;
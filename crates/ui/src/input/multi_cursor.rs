@@ -0,0 +1,324 @@
+use std::ops::Range;
+
+use gpui::{App, Context, KeyBinding, Window, actions};
+use sum_tree::Bias;
+
+use crate::input::{InputState, RopeExt as _, Selection, selection::TextSelector, state::CONTEXT};
+
+actions!(input, [AddSelectionToNextMatch]);
+
+pub(super) fn init(cx: &mut App) {
+    cx.bind_keys(vec![
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-d", AddSelectionToNextMatch, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-d", AddSelectionToNextMatch, Some(CONTEXT)),
+    ]);
+}
+
+impl InputState {
+    /// Returns the UTF-8 byte ranges of all the cursors in the text order, include the primary one,
+    /// an empty range is a cursor without selection.
+    ///
+    /// The multiple cursors are only supported in the multi-line mode, see [`InputState::add_cursor`].
+    pub fn cursors(&self) -> Vec<Range<usize>> {
+        let mut cursors = self
+            .secondary_selections
+            .iter()
+            .map(|selection| (*selection).into())
+            .chain(std::iter::once(self.selected_range.into()))
+            .collect::<Vec<Range<usize>>>();
+        cursors.sort_by_key(|range| range.start);
+        cursors
+    }
+
+    /// Returns true if there are multiple cursors.
+    pub fn has_multiple_cursors(&self) -> bool {
+        !self.secondary_selections.is_empty()
+    }
+
+    /// Add a cursor at the offset and make it the primary cursor, the current cursors are kept.
+    ///
+    /// If there is already a cursor at the offset, it is removed instead.
+    /// This is also triggered by clicking with `cmd` on macOS or `ctrl` on others.
+    pub fn add_cursor(&mut self, offset: usize, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.mode.is_multi_line() {
+            return;
+        }
+
+        let offset = self.text.clip_offset(offset, Bias::Left);
+        let cursor = offset..offset;
+        let old_len = self.secondary_selections.len();
+        self.secondary_selections
+            .retain(|selection| !overlaps(&(*selection).into(), &cursor));
+        if self.secondary_selections.len() < old_len {
+            cx.notify();
+            return;
+        }
+        if overlaps(&self.selected_range.into(), &cursor) {
+            return;
+        }
+
+        self.secondary_selections.push(self.selected_range);
+        self.set_primary_selection(cursor, cx);
+    }
+
+    /// Collapse the multiple cursors to the primary cursor.
+    pub fn clear_cursors(&mut self, cx: &mut Context<Self>) {
+        if self.secondary_selections.is_empty() {
+            return;
+        }
+
+        self.secondary_selections.clear();
+        cx.notify();
+    }
+
+    /// Select the word at the cursor if the selection is empty, otherwise add a selection
+    /// at the next occurrence of the selected text, and make it the primary selection.
+    pub fn add_selection_to_next_match(&mut self, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.mode.is_multi_line() {
+            return;
+        }
+
+        if self.selected_range.is_empty() {
+            if let Some(range) = TextSelector::word_range(&self.text, self.cursor()) {
+                self.set_primary_selection(range, cx);
+            }
+            return;
+        }
+
+        let selected_range: Range<usize> = self.selected_range.into();
+        let needle = self.text.slice(selected_range).to_string();
+        let Some(range) = next_match(
+            &self.text.to_string(),
+            &needle,
+            self.selected_range.end,
+            &self.cursors(),
+        ) else {
+            return;
+        };
+
+        self.secondary_selections.push(self.selected_range);
+        self.set_primary_selection(range, cx);
+    }
+
+    pub(super) fn on_action_add_selection_to_next_match(
+        &mut self,
+        _: &AddSelectionToNextMatch,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.add_selection_to_next_match(window, cx);
+    }
+
+    fn set_primary_selection(&mut self, range: Range<usize>, cx: &mut Context<Self>) {
+        self.selected_range = range.into();
+        self.selection_reversed = false;
+        self.selected_word_range = None;
+        self.secondary_selections = merge_selections(
+            self.selected_range.into(),
+            self.secondary_selections
+                .iter()
+                .map(|selection| (*selection).into())
+                .collect(),
+        )
+        .into_iter()
+        .map(Selection::from)
+        .collect();
+        self.update_preferred_column();
+        self.scroll_to(self.cursor(), None, cx);
+        self.pause_blink_cursor(cx);
+        cx.notify();
+    }
+
+    /// Replace each cursor with the range and text returned by `f` as a single undo step,
+    /// then collapse the cursors to the end of the inserted text.
+    ///
+    /// The `f` is called with the cursors in the text order, before any change is applied.
+    pub(super) fn edit_cursors(
+        &mut self,
+        f: impl Fn(&Self, Range<usize>) -> (Range<usize>, String),
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let primary: Range<usize> = self.selected_range.into();
+        let cursors = self.cursors();
+        let primary_ix = cursors
+            .iter()
+            .position(|range| *range == primary)
+            .unwrap_or_default();
+
+        let mut prev_end = 0;
+        let edits = cursors
+            .into_iter()
+            .map(|range| {
+                let (mut range, text) = f(self, range);
+                // Avoid the overlapping edits.
+                range.start = range.start.max(prev_end);
+                range.end = range.end.max(range.start);
+                prev_end = range.end;
+                (range, text)
+            })
+            .collect::<Vec<_>>();
+
+        self.secondary_selections.clear();
+        self.history.break_group();
+        let mut inserted = vec![0; edits.len()];
+        let mut is_first = true;
+        // Apply from the last one to keep the offsets of the previous edits.
+        for (ix, (range, text)) in edits.iter().enumerate().rev() {
+            if range.is_empty() && text.is_empty() {
+                continue;
+            }
+            if !is_first {
+                self.history.start_grouping();
+            }
+            is_first = false;
+
+            let old_len = self.text.len();
+            let range_utf16 = self.range_to_utf16(range);
+            self.replace_text_in_range_silent(Some(range_utf16), text, window, cx);
+            inserted[ix] = (self.text.len() + range.len()).saturating_sub(old_len);
+        }
+        self.history.end_grouping();
+        self.history.break_group();
+
+        let offsets = cursors_after_edits(
+            &edits
+                .iter()
+                .zip(inserted)
+                .map(|((range, _), inserted)| (range.clone(), inserted))
+                .collect::<Vec<_>>(),
+        );
+        let mut secondary_selections = offsets
+            .iter()
+            .map(|offset| Selection::new(*offset, *offset))
+            .collect::<Vec<_>>();
+        let primary = secondary_selections.remove(primary_ix);
+        self.secondary_selections = secondary_selections;
+        self.set_primary_selection(primary.into(), cx);
+    }
+}
+
+/// Returns true if the two ranges are overlapped, the empty range overlaps the range it touches.
+fn overlaps(a: &Range<usize>, b: &Range<usize>) -> bool {
+    if a.is_empty() || b.is_empty() {
+        a.start <= b.end && b.start <= a.end
+    } else {
+        a.start < b.end && b.start < a.end
+    }
+}
+
+/// Merge the overlapped selections, and remove the selections overlapped with the primary.
+fn merge_selections(primary: Range<usize>, selections: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    let mut selections = selections;
+    selections.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<usize>> = vec![];
+    for range in selections {
+        if let Some(last) = merged.last_mut()
+            && overlaps(last, &range)
+        {
+            last.end = last.end.max(range.end);
+        } else {
+            merged.push(range);
+        }
+    }
+    merged.retain(|range| !overlaps(range, &primary));
+    merged
+}
+
+/// Returns the cursor offsets after the edits, each cursor is placed at the end of its inserted text.
+///
+/// The edits are the sorted replaced ranges before the change, and the length of the inserted text.
+fn cursors_after_edits(edits: &[(Range<usize>, usize)]) -> Vec<usize> {
+    let mut delta = 0isize;
+    edits
+        .iter()
+        .map(|(range, inserted)| {
+            let offset = range.start.saturating_add_signed(delta) + inserted;
+            delta += *inserted as isize - range.len() as isize;
+            offset
+        })
+        .collect()
+}
+
+/// Find the next occurrence of the `needle` from the `offset`, wrap around to the start of the text,
+/// and skip the occurrences overlapped with the existing `selections`.
+fn next_match(
+    haystack: &str,
+    needle: &str,
+    offset: usize,
+    selections: &[Range<usize>],
+) -> Option<Range<usize>> {
+    if needle.is_empty() {
+        return None;
+    }
+
+    let offset = offset.min(haystack.len());
+    let after = haystack[offset..]
+        .match_indices(needle)
+        .map(|(ix, _)| offset + ix);
+    let before = haystack[..offset]
+        .match_indices(needle)
+        .map(|(ix, _)| ix)
+        .filter(|ix| ix + needle.len() <= offset);
+
+    after
+        .chain(before)
+        .map(|ix| ix..ix + needle.len())
+        .find(|range| {
+            !selections
+                .iter()
+                .any(|selection| overlaps(selection, range))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cursors_after_edits, merge_selections, next_match};
+
+    #[test]
+    fn test_merge_selections() {
+        assert_eq!(
+            merge_selections(10..10, vec![5..5, 0..0, 5..5, 2..4, 3..6]),
+            vec![0..0, 2..6]
+        );
+        // Remove the selections overlapped with the primary.
+        assert_eq!(
+            merge_selections(3..8, vec![0..2, 3..3, 6..9, 8..10, 12..12]),
+            vec![0..2, 12..12]
+        );
+        // The adjacent non-empty selections are kept.
+        assert_eq!(merge_selections(0..3, vec![3..6]), vec![3..6]);
+    }
+
+    #[test]
+    fn test_cursors_after_edits() {
+        // Typing "ab" at 3 cursors.
+        assert_eq!(
+            cursors_after_edits(&[(0..0, 2), (5..5, 2), (10..10, 2)]),
+            vec![2, 9, 16]
+        );
+        // Backspace at 3 cursors.
+        assert_eq!(
+            cursors_after_edits(&[(0..0, 0), (4..5, 0), (9..10, 0)]),
+            vec![0, 4, 8]
+        );
+        // Replace the selections with "x".
+        assert_eq!(cursors_after_edits(&[(0..3, 1), (6..9, 1)]), vec![1, 5]);
+    }
+
+    #[test]
+    fn test_next_match() {
+        let text = "foo bar foo baz foo";
+        assert_eq!(next_match(text, "foo", 3, &[0..3]), Some(8..11));
+        assert_eq!(next_match(text, "foo", 11, &[0..3, 8..11]), Some(16..19));
+        // Wrap around to the start.
+        assert_eq!(next_match(text, "foo", 19, &[8..11, 16..19]), Some(0..3));
+        // All matched.
+        assert_eq!(next_match(text, "foo", 19, &[0..3, 8..11, 16..19]), None);
+        assert_eq!(next_match(text, "", 0, &[]), None);
+        assert_eq!(next_match(text, "qux", 0, &[]), None);
+    }
+}
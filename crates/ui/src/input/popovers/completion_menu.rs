@@ -281,7 +281,9 @@ impl CompletionMenu {
         }
 
         cx.propagate();
-        if action.partial_eq(&input::Enter { secondary: false }) {
+        if action.partial_eq(&input::Enter { secondary: false })
+            || action.partial_eq(&input::IndentInline)
+        {
             self.on_action_enter(window, cx);
         } else if action.partial_eq(&input::Escape) {
             self.on_action_escape(window, cx);
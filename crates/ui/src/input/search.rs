@@ -4,8 +4,8 @@ use std::{ops::Range, rc::Rc};
 
 use gpui::{
     App, AppContext as _, Context, Empty, Entity, FocusHandle, Focusable, Half,
-    InteractiveElement as _, IntoElement, KeyBinding, ParentElement as _, Pixels, Render, Styled,
-    Subscription, Window, actions, div, prelude::FluentBuilder as _,
+    InteractiveElement as _, IntoElement, KeyBinding, ParentElement as _, Pixels, Render,
+    SharedString, Styled, Subscription, Window, actions, div, prelude::FluentBuilder as _,
 };
 use ropey::Rope;
 
@@ -34,10 +34,78 @@ pub(super) fn init(cx: &mut App) {
     )]);
 }
 
+/// The options to search text in the [`InputState`], see [`InputState::find`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Match case, default is false.
+    pub case_sensitive: bool,
+    /// Only match the whole word, default is false.
+    pub whole_word: bool,
+    /// Treat the query as a regular expression, default is false.
+    pub regex: bool,
+}
+
+#[derive(Debug, Clone)]
+enum SearchQuery {
+    Plain(AhoCorasick),
+    Regex(regex::Regex),
+}
+
+impl SearchQuery {
+    /// Build the query, returns `None` if the query is empty or an invalid regular expression.
+    fn new(query: &str, options: SearchOptions) -> Option<Self> {
+        if query.is_empty() {
+            return None;
+        }
+
+        if !options.regex && !options.whole_word {
+            return AhoCorasick::builder()
+                .ascii_case_insensitive(!options.case_sensitive)
+                .build([query])
+                .ok()
+                .map(Self::Plain);
+        }
+
+        let mut pattern = if options.regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        if options.whole_word {
+            pattern = format!(r"\b(?:{})\b", pattern);
+        }
+
+        regex::RegexBuilder::new(&pattern)
+            .case_insensitive(!options.case_sensitive)
+            .build()
+            .ok()
+            .map(Self::Regex)
+    }
+
+    fn find_iter(&self, text: &str) -> Vec<Range<usize>> {
+        match self {
+            Self::Plain(query) => query.find_iter(text).map(|m| m.range()).collect(),
+            Self::Regex(regex) => regex
+                .find_iter(text)
+                .map(|m| m.range())
+                // Skip the empty matches, e.g.: `^`, `a*`.
+                .filter(|range| !range.is_empty())
+                .collect(),
+        }
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            Self::Plain(query) => query.is_match(text),
+            Self::Regex(regex) => regex.find_iter(text).any(|m| !m.is_empty()),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SearchMatcher {
     text: Rope,
-    pub query: Option<AhoCorasick>,
+    query: Option<SearchQuery>,
 
     pub(super) matched_ranges: Rc<Vec<Range<usize>>>,
     pub(super) current_match_ix: usize,
@@ -66,47 +134,86 @@ impl SearchMatcher {
         self.update_matches();
     }
 
-    fn update_matches(&mut self) {
-        let mut new_ranges = Vec::new();
-        if let Some(query) = &self.query {
-            let text = self.text.to_string();
-            // FIXME: Use stream find
-            let matches = query.stream_find_iter(text.as_bytes());
-
-            for query_match in matches.into_iter() {
-                let query_match = query_match.expect("query match for select all action");
-                new_ranges.push(query_match.range());
+    /// Shift the matches after the replaced range and remove the matches in it,
+    /// instead of re-matching the whole text.
+    ///
+    /// This is used to load and unload the lines of [`InputState::set_line_source`], that only
+    /// replaces the lines between the loaded text and the empty placeholders, so the other matches
+    /// are not changed, and the active match is kept if it's not removed.
+    pub(super) fn shift_matches(&mut self, text: &Rope, range: &Range<usize>, new_len: usize) {
+        self.text = text.clone();
+        if self.matched_ranges.is_empty() {
+            return;
+        }
+
+        let delta = new_len as isize - range.len() as isize;
+        let mut removed_before_active = 0;
+        let mut matched_ranges = Vec::with_capacity(self.matched_ranges.len());
+        for (ix, matched) in self.matched_ranges.iter().enumerate() {
+            if matched.start >= range.end {
+                matched_ranges.push(
+                    matched.start.saturating_add_signed(delta)
+                        ..matched.end.saturating_add_signed(delta),
+                );
+            } else if matched.end <= range.start {
+                matched_ranges.push(matched.clone());
+            } else if ix < self.current_match_ix {
+                removed_before_active += 1;
             }
         }
+        self.current_match_ix = (self.current_match_ix - removed_before_active)
+            .min(matched_ranges.len().saturating_sub(1));
+        self.matched_ranges = Rc::new(matched_ranges);
+    }
+
+    fn update_matches(&mut self) {
+        let new_ranges = match &self.query {
+            Some(query) => query.find_iter(&self.text.to_string()),
+            None => vec![],
+        };
         self.matched_ranges = Rc::new(new_ranges);
         if !self.replacing {
             self.current_match_ix = 0;
-            self.replacing = false;
         }
+        self.current_match_ix = self
+            .current_match_ix
+            .min(self.matched_ranges.len().saturating_sub(1));
     }
 
     /// Update the search query and reset the current match index.
     pub fn update_query(&mut self, query: &str, case_insensitive: bool) {
-        if query.len() > 0 {
-            self.query = Some(
-                AhoCorasick::builder()
-                    .ascii_case_insensitive(case_insensitive)
-                    .build(&[query.to_string()])
-                    .expect("failed to build AhoCorasick query in SearchMatcher"),
-            );
-        } else {
-            self.query = None;
-        }
+        self.set_query(
+            query,
+            SearchOptions {
+                case_sensitive: !case_insensitive,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Update the search query with options and reset the current match index.
+    pub fn set_query(&mut self, query: &str, options: SearchOptions) {
+        self.query = SearchQuery::new(query, options);
         self.update_matches();
     }
 
     /// Returns the number of matches found.
-    #[allow(unused)]
     #[inline]
     fn len(&self) -> usize {
         self.matched_ranges.len()
     }
 
+    /// Returns the index of the current match, `None` if there is no match.
+    #[inline]
+    fn active(&self) -> Option<usize> {
+        (self.len() > 0).then_some(self.current_match_ix)
+    }
+
+    /// Returns the range of the current match.
+    fn current(&self) -> Option<Range<usize>> {
+        self.matched_ranges.get(self.current_match_ix).cloned()
+    }
+
     fn peek(&self) -> Option<Range<usize>> {
         self.matched_ranges.get(self.current_match_ix + 1).cloned()
     }
@@ -170,7 +277,6 @@ pub(super) struct SearchPanel {
     replace_input: Entity<InputState>,
     case_insensitive: bool,
     replace_mode: bool,
-    matcher: SearchMatcher,
     input_width: Pixels,
 
     open: bool,
@@ -179,15 +285,150 @@ pub(super) struct SearchPanel {
 
 impl InputState {
     /// Update the search matcher when text changes.
-    pub(super) fn update_search(&mut self, cx: &mut App) {
-        let Some(search_panel) = self.search_panel.as_ref() else {
+    pub(super) fn update_search(&mut self, cx: &mut Context<Self>) {
+        if self.search_matcher.query.is_none() {
             return;
-        };
+        }
 
         let text = self.text.clone();
-        search_panel.update(cx, |this, _| {
-            this.matcher.update(&text);
+        self.update_search_matcher(cx, |matcher| matcher.update(&text));
+    }
+
+    /// Update the search matcher, and emit [`InputEvent::SearchMatchesChanged`] if the matches changed.
+    fn update_search_matcher<R>(
+        &mut self,
+        cx: &mut Context<Self>,
+        f: impl FnOnce(&mut SearchMatcher) -> R,
+    ) -> R {
+        let matcher = &mut self.search_matcher;
+        let prev_matches = (matcher.matched_ranges.clone(), matcher.active());
+        let result = f(matcher);
+        if prev_matches != (matcher.matched_ranges.clone(), matcher.active()) {
+            cx.emit(InputEvent::SearchMatchesChanged {
+                count: matcher.len(),
+                active: matcher.active(),
+            });
+            cx.notify();
+        }
+
+        result
+    }
+
+    /// Search the query in the text and highlight the matches, returns the byte ranges of the matches.
+    ///
+    /// The active match is the first match at or after the cursor, an empty query to clear the search.
+    ///
+    /// The [`InputEvent::SearchMatchesChanged`] will be emitted if the matches changed.
+    pub fn find(
+        &mut self,
+        query: &str,
+        options: SearchOptions,
+        cx: &mut Context<Self>,
+    ) -> Vec<Range<usize>> {
+        self.find_from(query, options, self.cursor(), cx)
+    }
+
+    fn find_from(
+        &mut self,
+        query: &str,
+        options: SearchOptions,
+        offset: usize,
+        cx: &mut Context<Self>,
+    ) -> Vec<Range<usize>> {
+        let mut offset = offset;
+        if self.line_source.is_some()
+            && let Some(search_query) = SearchQuery::new(query, options)
+        {
+            // Only load the matched lines, and keep the offset at the same place.
+            let point = self.text.offset_to_point(offset);
+            self.load_matched_lines(|line| search_query.is_match(line), point.row, cx);
+            offset = self.text.point_to_offset(point);
+        }
+
+        let text = self.text.clone();
+        self.update_search_matcher(cx, |matcher| {
+            matcher.update(&text);
+            matcher.set_query(query, options);
+            matcher.update_cursor_by_offset(offset);
+            matcher.matched_ranges.to_vec()
+        })
+    }
+
+    /// Clear the search matches.
+    pub fn clear_search(&mut self, cx: &mut Context<Self>) {
+        self.find("", SearchOptions::default(), cx);
+    }
+
+    /// Returns the byte ranges of the search matches.
+    pub fn search_matches(&self) -> &[Range<usize>] {
+        &self.search_matcher.matched_ranges
+    }
+
+    /// Returns the index of the active search match, `None` if there is no match.
+    pub fn active_search_match(&self) -> Option<usize> {
+        self.search_matcher.active()
+    }
+
+    /// Move to the next search match and scroll it into view, returns the range of the match.
+    pub fn find_next(&mut self, _: &mut Window, cx: &mut Context<Self>) -> Option<Range<usize>> {
+        let range = self.update_search_matcher(cx, |matcher| matcher.next())?;
+        self.scroll_to(range.end, Some(MoveDirection::Down), cx);
+        Some(range)
+    }
+
+    /// Move to the previous search match and scroll it into view, returns the range of the match.
+    pub fn find_prev(&mut self, _: &mut Window, cx: &mut Context<Self>) -> Option<Range<usize>> {
+        let range = self.update_search_matcher(cx, |matcher| matcher.next_back())?;
+        self.scroll_to(range.start, Some(MoveDirection::Up), cx);
+        Some(range)
+    }
+
+    /// Replace the active search match with the replacement, then the next match will be active.
+    pub fn replace_current(
+        &mut self,
+        replacement: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(range) = self.search_matcher.current() else {
+            return;
+        };
+
+        let range_utf16 = self.range_to_utf16(&range);
+        self.search_matcher.replacing = true;
+        self.without_read_only(|this| {
+            this.replace_text_in_range_silent(Some(range_utf16), replacement, window, cx)
+        });
+        self.search_matcher.replacing = false;
+
+        if let Some(range) = self.search_matcher.current() {
+            self.scroll_to(range.end, Some(MoveDirection::Down), cx);
+        }
+    }
+
+    /// Replace all the search matches with the replacement, as a single undo step.
+    pub fn replace_all(&mut self, replacement: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let ranges = self.search_matcher.matched_ranges.clone();
+        let (Some(first), Some(last)) = (ranges.first(), ranges.last()) else {
+            return;
+        };
+
+        // Only replace the text between the first and last match.
+        let range = first.start..last.end;
+        let mut new_text = String::new();
+        let mut offset = range.start;
+        for match_range in ranges.iter() {
+            new_text.push_str(&self.text.slice(offset..match_range.start).to_string());
+            new_text.push_str(replacement);
+            offset = match_range.end;
+        }
+
+        let range_utf16 = self.range_to_utf16(&range);
+        self.history.break_group();
+        self.without_read_only(|this| {
+            this.replace_text_in_range_silent(Some(range_utf16), &new_text, window, cx)
         });
+        self.history.break_group();
     }
 
     pub(super) fn on_action_search(
@@ -205,17 +446,28 @@ impl InputState {
             None => SearchPanel::new(cx.entity(), window, cx),
         };
 
-        let text = self.text.clone();
         let editor = cx.entity();
         let selected_text = Rope::from(self.selected_text());
-        search_panel.update(cx, |this, cx| {
+        let (query, options) = search_panel.update(cx, |this, cx| {
             this.editor = editor;
-            this.matcher.update(&text);
             this.show(&selected_text, window, cx);
+            this.query(cx)
         });
+        // Restore the matches of the last query, if no text is selected.
+        if selected_text.len() == 0 {
+            let offset = self.visible_range_offset().start;
+            self.find_from(&query, options, offset, cx);
+        }
         self.search_panel = Some(search_panel);
         cx.notify();
     }
+
+    fn visible_range_offset(&self) -> Range<usize> {
+        self.last_layout
+            .as_ref()
+            .map(|l| l.visible_range_offset.clone())
+            .unwrap_or_default()
+    }
 }
 
 impl SearchPanel {
@@ -243,7 +495,6 @@ impl SearchPanel {
                 replace_input,
                 case_insensitive: true,
                 replace_mode: false,
-                matcher: SearchMatcher::new(),
                 open: true,
                 input_width: Pixels::ZERO,
                 _subscriptions,
@@ -269,27 +520,30 @@ impl SearchPanel {
         });
     }
 
-    fn update_search_query(&mut self, cx: &mut Context<Self>) {
-        let query = self.search_input.read(cx).value();
-        let visible_range_offset = self
-            .editor
-            .read(cx)
-            .last_layout
-            .as_ref()
-            .map(|l| l.visible_range_offset.clone());
-
-        self.matcher
-            .update_query(query.as_str(), self.case_insensitive);
+    /// Returns the search query and options of the panel.
+    fn query(&self, cx: &App) -> (SharedString, SearchOptions) {
+        (
+            self.search_input.read(cx).value(),
+            SearchOptions {
+                case_sensitive: !self.case_insensitive,
+                ..Default::default()
+            },
+        )
+    }
 
-        if let Some(visible_range_offset) = visible_range_offset {
-            self.matcher
-                .update_cursor_by_offset(visible_range_offset.start);
-        }
+    fn update_search_query(&mut self, cx: &mut Context<Self>) {
+        let (query, options) = self.query(cx);
+        self.editor.update(cx, |state, cx| {
+            // Keep the active match in the visible area.
+            let offset = state.visible_range_offset().start;
+            state.find_from(&query, options, offset, cx);
+        });
         cx.notify();
     }
 
     pub(super) fn hide(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.open = false;
+        self.editor.update(cx, |state, cx| state.clear_search(cx));
         self.editor.read(cx).focus_handle.focus(window);
         cx.notify();
     }
@@ -310,88 +564,34 @@ impl SearchPanel {
         self.editor.focus_handle(cx).focus(window);
     }
 
-    fn prev(&mut self, _: &mut Window, cx: &mut Context<Self>) {
-        if let Some(range) = self.matcher.next_back() {
-            self.editor.update(cx, |state, cx| {
-                state.scroll_to(range.start, Some(MoveDirection::Up), cx);
-            });
-        }
-    }
-
-    fn next(&mut self, _: &mut Window, cx: &mut Context<Self>) {
-        if let Some(range) = self.matcher.next() {
-            self.editor.update(cx, |state, cx| {
-                state.scroll_to(range.end, Some(MoveDirection::Down), cx);
-            });
-        }
+    fn prev(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |state, cx| state.find_prev(window, cx));
     }
 
-    pub(super) fn matcher(&self) -> Option<&SearchMatcher> {
-        if !self.open {
-            return None;
-        }
-
-        Some(&self.matcher)
+    fn next(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |state, cx| state.find_next(window, cx));
     }
 
     fn replace_next(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let new_text = self.replace_input.read(cx).value();
-        self.matcher.replacing = true;
-        if let Some(range) = self
-            .matcher
-            .matched_ranges
-            .get(self.matcher.current_match_ix)
-            .cloned()
-        {
-            let text_state = self.editor.clone();
-
-            let next_range = self.matcher.peek().unwrap_or(range.clone());
-            cx.spawn_in(window, async move |_, cx| {
-                cx.update(|window, cx| {
-                    text_state.update(cx, |state, cx| {
-                        let range_utf16 = state.range_to_utf16(&range);
-                        state.scroll_to(next_range.end, Some(MoveDirection::Down), cx);
-                        state.replace_text_in_range_silent(
-                            Some(range_utf16),
-                            new_text.as_str(),
-                            window,
-                            cx,
-                        );
-                    });
-                })
-            })
-            .detach();
+        if self.editor.read(cx).read_only {
+            return;
         }
+        let new_text = self.replace_input.read(cx).value();
+        self.editor.update(cx, |state, cx| {
+            state.replace_current(&new_text, window, cx);
+        });
     }
 
     fn replace_all(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let new_text = self.replace_input.read(cx).value();
-        self.matcher.replacing = true;
-        let ranges = self.matcher.matched_ranges.clone();
-        if ranges.is_empty() {
+        if self.editor.read(cx).read_only {
             return;
         }
-
-        let editor = self.editor.clone();
-        cx.spawn_in(window, async move |_, cx| {
-            cx.update(|window, cx| {
-                editor.update(cx, |state, cx| {
-                    // Replace from the end to avoid messing up the ranges.
-                    let mut rope = state.text.clone();
-                    for range in ranges.iter().rev() {
-                        rope.replace(range.clone(), new_text.as_str());
-                    }
-                    state.replace_text_in_range_silent(
-                        Some(0..state.text.len()),
-                        &rope.to_string(),
-                        window,
-                        cx,
-                    );
-                    state.scroll_to(0, Some(MoveDirection::Down), cx);
-                });
-            })
-        })
-        .detach();
+        let new_text = self.replace_input.read(cx).value();
+        self.editor.update(cx, |state, cx| {
+            state.replace_all(&new_text, window, cx);
+        });
     }
 }
 
@@ -407,7 +607,9 @@ impl Render for SearchPanel {
             return Empty.into_any_element();
         }
 
-        let has_matches = self.matcher.len() > 0;
+        let matcher = &self.editor.read(cx).search_matcher;
+        let has_matches = matcher.len() > 0;
+        let label = matcher.label();
 
         v_flex()
             .id("search-panel")
@@ -500,7 +702,7 @@ impl Render for SearchPanel {
                             })),
                     )
                     .child(
-                        Label::new(self.matcher.label())
+                        Label::new(label)
                             .when(!has_matches, |this| {
                                 this.text_color(cx.theme().muted_foreground)
                             })
@@ -586,6 +788,49 @@ mod tests {
         assert_eq!(matcher.next_back(), None);
     }
 
+    #[test]
+    fn test_search_options() {
+        let mut matcher = SearchMatcher::new();
+        matcher.update(&Rope::from("foo Foo food foo_bar f.o"));
+
+        matcher.set_query("foo", SearchOptions::default());
+        assert_eq!(
+            matcher.matched_ranges.as_slice(),
+            &[0..3, 4..7, 8..11, 13..16]
+        );
+
+        let options = SearchOptions {
+            case_sensitive: true,
+            ..Default::default()
+        };
+        matcher.set_query("foo", options);
+        assert_eq!(matcher.matched_ranges.as_slice(), &[0..3, 8..11, 13..16]);
+
+        let options = SearchOptions {
+            whole_word: true,
+            ..Default::default()
+        };
+        matcher.set_query("foo", options);
+        assert_eq!(matcher.matched_ranges.as_slice(), &[0..3, 4..7]);
+
+        matcher.set_query("f.o", SearchOptions::default());
+        assert_eq!(matcher.matched_ranges.as_slice(), &[21..24]);
+
+        let options = SearchOptions {
+            regex: true,
+            ..Default::default()
+        };
+        matcher.set_query("f.o", options);
+        assert_eq!(matcher.len(), 5);
+        matcher.set_query(r"f\.o", options);
+        assert_eq!(matcher.matched_ranges.as_slice(), &[21..24]);
+
+        // Invalid regex
+        matcher.set_query("(", options);
+        assert_eq!(matcher.len(), 0);
+        assert_eq!(matcher.active(), None);
+    }
+
     #[test]
     fn test_search_label() {
         let mut matcher = SearchMatcher::new();
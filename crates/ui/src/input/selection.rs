@@ -57,7 +57,7 @@ impl InputState {
     }
 }
 
-struct TextSelector;
+pub(super) struct TextSelector;
 impl TextSelector {
     /// Select a word in the given text at the specified offset.
     ///
@@ -5,21 +5,35 @@
 use anyhow::Result;
 use gpui::{
     Action, App, AppContext, Bounds, ClipboardItem, Context, Entity, EntityInputHandler,
-    EventEmitter, FocusHandle, Focusable, InteractiveElement as _, IntoElement, KeyBinding,
-    KeyDownEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement as _,
-    Pixels, Point, Render, ScrollHandle, ScrollWheelEvent, SharedString, Styled as _, Subscription,
-    Task, UTF16Selection, Window, actions, div, point, prelude::FluentBuilder as _, px,
+    EventEmitter, ExternalPaths, FocusHandle, Focusable, InteractiveElement as _, IntoElement,
+    KeyBinding, KeyDownEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
+    ParentElement as _, Pixels, Point, Render, ScrollHandle, ScrollWheelEvent, SharedString,
+    Styled as _, Subscription, Task, TextRun, Timer, UTF16Selection, Window, actions, div, point,
+    prelude::FluentBuilder as _, px,
 };
 use ropey::{Rope, RopeSlice};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::ops::Range;
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::time::Duration;
 use sum_tree::Bias;
 use unicode_segmentation::*;
 
 use super::{
-    blink_cursor::BlinkCursor, change::Change, element::TextElement, mask_pattern::MaskPattern,
-    mode::InputMode, number_input, text_wrapper::TextWrapper,
+    blink_cursor::BlinkCursor,
+    change::Change,
+    element::TextElement,
+    fold,
+    format::{self, Formatter},
+    gutter::{GutterClickHandler, GutterLine, GutterMarker, GutterRenderer, LineAnnotation},
+    line_source::LineSource,
+    mask_pattern::MaskPattern,
+    mode::InputMode,
+    multi_cursor, number_input,
+    text_wrapper::TextWrapper,
+    vim::{VimMode, VimState},
 };
 use crate::Size;
 use crate::actions::{SelectDown, SelectLeft, SelectRight, SelectUp};
@@ -28,12 +42,16 @@ use crate::input::{
     HoverDefinition, Lsp, Position,
     element::RIGHT_MARGIN,
     popovers::{ContextMenu, DiagnosticPopover, HoverPopover, MouseContextMenu},
-    search::{self, SearchPanel},
+    search::{self, SearchMatcher, SearchPanel},
     text_wrapper::LineLayout,
 };
 use crate::input::{InlineCompletion, RopeExt as _, Selection};
 use crate::{Root, history::History};
-use crate::{highlighter::DiagnosticSet, input::text_wrapper::LineItem};
+use crate::{
+    diff::diff_text,
+    highlighter::{Diagnostic, DiagnosticSet, LanguageConfig},
+    input::text_wrapper::LineItem,
+};
 
 #[derive(Action, Clone, PartialEq, Eq, Deserialize)]
 #[action(namespace = input, no_json)]
@@ -92,12 +110,85 @@ actions!(
 #[derive(Clone)]
 pub enum InputEvent {
     Change,
-    PressEnter { secondary: bool },
+    /// Emitted once the text is not changed for the [`InputState::change_debounce`] duration
+    /// after the last [`InputEvent::Change`], or immediately on blur if a change is pending.
+    ChangeDebounced,
+    PressEnter {
+        secondary: bool,
+    },
     Focus,
     Blur,
+    /// Emitted after an undo step is applied.
+    Undo,
+    /// Emitted after a redo step is applied.
+    Redo,
+    /// Emitted when the search matches or the active match changed.
+    ///
+    /// The `active` is the index of the active match, `None` if there is no match.
+    SearchMatchesChanged {
+        count: usize,
+        active: Option<usize>,
+    },
+    /// Emitted when the cursor or selection changed, and once when the input is focused.
+    ///
+    /// The `cursor` is the (0-based) [`Position`] of the cursor, the column is in characters,
+    /// and the `selection_len` is the number of the selected characters.
+    SelectionChanged {
+        cursor: Position,
+        selection_len: usize,
+    },
+    /// Emitted when the files are dropped from the OS onto the input,
+    /// only if [`InputState::accept_file_drop`] is enabled.
+    FilesDropped {
+        paths: Vec<PathBuf>,
+    },
+    /// Emitted after the text is copied to the clipboard.
+    Copy {
+        text: SharedString,
+    },
+    /// Emitted after the text is cut to the clipboard.
+    Cut {
+        text: SharedString,
+    },
+    /// Emitted after the text is pasted, the `text` is the inserted text
+    /// transformed by [`InputState::on_paste`].
+    Paste {
+        text: SharedString,
+    },
+    /// Emitted when the inserted text is rejected by [`InputState::max_length`] or
+    /// [`InputState::char_filter`], the `text` is the rejected part.
+    ///
+    /// Only the allowed prefix of the text is inserted.
+    Rejected {
+        text: SharedString,
+    },
+    /// Emitted when the mode of the Vim keybindings changed, see [`InputState::vim_mode`].
+    VimModeChanged {
+        mode: VimMode,
+    },
+    /// Emitted when an annotation in the gutter is clicked, the `line` is zero-based,
+    /// see [`InputState::set_line_annotations`].
+    LineAnnotationClick {
+        line: usize,
+    },
+}
+
+type PasteHandler = Rc<dyn Fn(String) -> Option<String>>;
+type CharFilter = Rc<dyn Fn(char) -> bool>;
+
+/// The options for [`InputState::set_value_with_options`].
+///
+/// The default is to reset the cursor and scroll to the top.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SetValueOptions {
+    /// Keep the scroll offset, clamped to the last line of the new text.
+    pub preserve_scroll: bool,
+    /// Keep the cursor and selection, clamped to the length of the new text.
+    pub preserve_cursor: bool,
 }
 
 pub(super) const CONTEXT: &str = "Input";
+const DEFAULT_MAX_UNDOS: usize = 200;
 
 pub(crate) fn init(cx: &mut App) {
     cx.bind_keys([
@@ -220,6 +311,9 @@ pub(crate) fn init(cx: &mut App) {
 
     search::init(cx);
     number_input::init(cx);
+    fold::init(cx);
+    format::init(cx);
+    multi_cursor::init(cx);
 }
 
 #[derive(Clone)]
@@ -271,7 +365,11 @@ pub struct InputState {
     /// - "Hello 世界💝" = 16
     /// - "💝" = 4
     pub(super) selected_range: Selection,
+    /// The selections of the other cursors, the `selected_range` is the primary one,
+    /// see [`InputState::cursors`].
+    pub(super) secondary_selections: Vec<Selection>,
     pub(super) search_panel: Option<Entity<SearchPanel>>,
+    pub(super) search_matcher: SearchMatcher,
     pub(super) searchable: bool,
     /// Range for save the selected word, use to keep word range when drag move.
     pub(super) selected_word_range: Option<Selection>,
@@ -285,12 +383,75 @@ pub struct InputState {
     /// The text bounds
     pub(super) last_bounds: Option<Bounds<Pixels>>,
     pub(super) last_selected_range: Option<Selection>,
+    /// The selection of the last emitted [`InputEvent::SelectionChanged`].
+    last_emitted_selection: Option<Selection>,
     pub(super) selecting: bool,
     pub(super) size: Size,
     pub(super) disabled: bool,
+    /// Read-only mode, the text can be selected and copied, but not edited by user.
+    pub(super) read_only: bool,
     pub(super) masked: bool,
     pub(super) clean_on_escape: bool,
+    /// Auto close the brackets and highlight the matching bracket, only for the code editor.
+    pub(super) auto_close_brackets: bool,
+    /// Auto indent the new line on Enter, see [`InputState::auto_indent`].
+    pub(super) auto_indent: bool,
+    /// Accept the files dropped from the OS, see [`InputState::accept_file_drop`].
+    pub(super) accept_file_drop: bool,
+    /// Copy or cut the current line if the selection is empty.
+    copy_line_on_empty_selection: bool,
+    /// Transform the pasted text, see [`InputState::on_paste`].
+    paste_handler: Option<PasteHandler>,
+    /// The maximum number of characters, see [`InputState::max_length`].
+    max_length: Option<usize>,
+    /// The filter of the characters to insert, see [`InputState::char_filter`].
+    char_filter: Option<CharFilter>,
+    /// The formatter of the [`InputState::format_document`], see [`InputState::set_formatter`].
+    pub(super) formatter: Option<Formatter>,
+    /// The lazily loaded lines, see [`InputState::set_line_source`].
+    pub(super) line_source: Option<LineSource>,
+    /// The debounce duration of [`InputEvent::ChangeDebounced`], see [`InputState::change_debounce`].
+    change_debounce: Option<Duration>,
+    /// A flag to indicate if there is a change not emitted by [`InputEvent::ChangeDebounced`] yet.
+    change_debounce_pending: bool,
+    _change_debounce_task: Task<()>,
+    /// The additional key context of this input, see [`InputState::key_context`].
+    pub(super) key_context: Option<SharedString>,
     pub(super) soft_wrap: bool,
+    /// Render control characters (except tab and newline) as visible replacement symbols.
+    pub(super) render_control_chars: bool,
+    /// Custom renderer to replace the default line numbers.
+    pub(super) gutter_renderer: Option<GutterRenderer>,
+    /// The measured width of the custom gutter in last layout.
+    pub(super) gutter_width: Option<Pixels>,
+    /// The handler of clicking on the gutter, see [`InputState::on_gutter_click`].
+    pub(super) gutter_click_handler: Option<GutterClickHandler>,
+    /// The markers of the lines in the gutter, see [`InputState::set_gutter_markers`].
+    pub(super) gutter_markers: HashMap<usize, GutterMarker>,
+    /// The annotations of the lines in the gutter, see [`InputState::set_line_annotations`].
+    pub(super) line_annotations: HashMap<usize, LineAnnotation>,
+    /// The width of the annotations column in the last layout.
+    pub(super) line_annotation_width: Pixels,
+    /// The cached [`LanguageConfig`] of the code editor language, see [`InputState::language_config`].
+    pub(super) language_config: Option<Rc<LanguageConfig>>,
+    /// Whether to enable the code folding, see [`InputState::folding`].
+    pub(super) folding: bool,
+    /// The foldable ranges set by [`InputState::set_fold_ranges`], `None` to use the syntax tree.
+    pub(super) fold_ranges: Option<Vec<Range<usize>>>,
+    /// The folded ranges, sorted by the start.
+    pub(super) folds: Vec<Range<usize>>,
+    /// Whether to show the minimap, see [`InputState::minimap`].
+    pub(super) minimap: bool,
+    /// The offset of the mouse to the top of the minimap slider while dragging.
+    pub(super) minimap_drag: Option<Pixels>,
+    /// The columns to draw vertical rulers, see [`InputState::rulers`].
+    pub(super) rulers: Vec<usize>,
+    /// Whether to render the spaces and tabs, see [`InputState::show_whitespace`].
+    pub(super) show_whitespace: bool,
+    /// The state of the Vim keybindings, `None` if disabled, see [`InputState::vim_mode`].
+    pub(super) vim: Option<VimState>,
+    /// The hint text of the blank line at the cursor, see [`InputState::blank_line_hint`].
+    pub(super) blank_line_hint: Option<Rc<dyn Fn(usize) -> Option<SharedString>>>,
     pub(super) pattern: Option<regex::Regex>,
     pub(super) validate: Option<Box<dyn Fn(&str, &mut Context<Self>) -> bool + 'static>>,
     pub(crate) scroll_handle: ScrollHandle,
@@ -343,7 +504,9 @@ impl InputState {
     pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
         let focus_handle = cx.focus_handle().tab_stop(true);
         let blink_cursor = cx.new(|_| BlinkCursor::new());
-        let history = History::new().group_interval(std::time::Duration::from_secs(1));
+        let history = History::new()
+            .max_undos(DEFAULT_MAX_UNDOS)
+            .group_interval(std::time::Duration::from_secs(1));
 
         let _subscriptions = vec![
             // Observe the blink cursor to repaint the view when it changes.
@@ -361,6 +524,10 @@ impl InputState {
             }),
             cx.on_focus(&focus_handle, window, Self::on_focus),
             cx.on_blur(&focus_handle, window, Self::on_blur),
+            // The selection changes are always notified, check them after the update.
+            cx.observe(&cx.entity(), |input, _, cx| {
+                input.check_selection_changed(cx)
+            }),
         ];
 
         let text_style = window.text_style();
@@ -374,16 +541,49 @@ impl InputState {
             history,
             selected_range: Selection::default(),
             search_panel: None,
+            search_matcher: SearchMatcher::new(),
             searchable: false,
             selected_word_range: None,
+            secondary_selections: vec![],
             selection_reversed: false,
             ime_marked_range: None,
             input_bounds: Bounds::default(),
             selecting: false,
             disabled: false,
+            read_only: false,
             masked: false,
             clean_on_escape: false,
+            auto_close_brackets: false,
+            auto_indent: false,
+            accept_file_drop: false,
+            copy_line_on_empty_selection: false,
+            paste_handler: None,
+            max_length: None,
+            char_filter: None,
+            formatter: None,
+            line_source: None,
+            change_debounce: None,
+            change_debounce_pending: false,
+            _change_debounce_task: Task::ready(()),
+            key_context: None,
             soft_wrap: true,
+            render_control_chars: false,
+            gutter_renderer: None,
+            gutter_width: None,
+            gutter_click_handler: None,
+            gutter_markers: HashMap::default(),
+            line_annotations: HashMap::default(),
+            line_annotation_width: px(0.),
+            folding: true,
+            fold_ranges: None,
+            language_config: None,
+            folds: vec![],
+            minimap: false,
+            minimap_drag: None,
+            rulers: vec![],
+            show_whitespace: false,
+            vim: None,
+            blank_line_hint: None,
             loading: false,
             pattern: None,
             validate: None,
@@ -391,6 +591,7 @@ impl InputState {
             last_layout: None,
             last_bounds: None,
             last_selected_range: None,
+            last_emitted_selection: None,
             last_cursor: None,
             scroll_handle: ScrollHandle::new(),
             scroll_size: gpui::size(px(0.), px(0.)),
@@ -438,6 +639,7 @@ impl InputState {
     /// - height: 100%
     /// - multi_line: true
     /// - indent_guides: true
+    /// - render_control_chars: true
     ///
     /// If `highlighter` is None, will use the default highlighter.
     ///
@@ -452,7 +654,9 @@ impl InputState {
     pub fn code_editor(mut self, language: impl Into<SharedString>) -> Self {
         let language: SharedString = language.into();
         self.mode = InputMode::code_editor(language);
+        self.update_language_config();
         self.searchable = true;
+        self.render_control_chars = true;
         self
     }
 
@@ -526,6 +730,7 @@ impl InputState {
             }
             _ => {}
         }
+        self.update_language_config();
         cx.notify();
     }
 
@@ -536,6 +741,8 @@ impl InputState {
             }
             _ => {}
         }
+        // The language may be registered after the editor is created.
+        self.update_language_config();
         cx.notify();
     }
 
@@ -549,6 +756,48 @@ impl InputState {
         self.mode.diagnostics_mut()
     }
 
+    /// Replace the diagnostics of the code editor, pass an empty list to clear them.
+    ///
+    /// The diagnostic range is rendered as squiggly underline with the color of the severity,
+    /// and the message is shown on hover. Use [`RopeExt::offset_to_position`] on the
+    /// [`InputState::text`] to convert a byte offset to the [`Position`].
+    ///
+    /// Only for [`InputMode::CodeEditor`] mode.
+    ///
+    /// [`RopeExt::offset_to_position`]: crate::input::RopeExt::offset_to_position
+    pub fn set_diagnostics<D, I>(&mut self, diagnostics: D, cx: &mut Context<Self>)
+    where
+        D: IntoIterator<Item = I>,
+        I: Into<Diagnostic>,
+    {
+        let Some(set) = self.mode.diagnostics_mut() else {
+            return;
+        };
+
+        let mut diagnostics: Vec<Diagnostic> = diagnostics.into_iter().map(Into::into).collect();
+        diagnostics.sort_by_key(|diagnostic| diagnostic.range.start);
+        set.clear();
+        set.extend(diagnostics);
+        cx.notify();
+    }
+
+    /// Clear all the overlays on the text at once, e.g. before re-running the analysis.
+    ///
+    /// This clears the diagnostics, the gutter markers and the line annotations, and closes the hover and diagnostic
+    /// popovers. The text, selection and search matches are kept, use
+    /// [`InputState::clear_search`] to clear the search matches.
+    pub fn clear_overlays(&mut self, cx: &mut Context<Self>) {
+        if let Some(set) = self.mode.diagnostics_mut() {
+            set.clear();
+        }
+        self.gutter_markers.clear();
+        self.line_annotations.clear();
+        self.hover_popover = None;
+        self.diagnostic_popover = None;
+        self.hover_definition.clear();
+        cx.notify();
+    }
+
     /// Set placeholder
     pub fn set_placeholder(
         &mut self,
@@ -596,21 +845,82 @@ impl InputState {
     /// Set the text of the input field.
     ///
     /// And the selection_range will be reset to 0..0.
+    ///
+    /// This is recorded as a single undo step, so the user can undo to the previous text,
+    /// use [`Self::set_value_without_history`] to avoid that.
     pub fn set_value(
         &mut self,
         value: impl Into<SharedString>,
         window: &mut Window,
         cx: &mut Context<Self>,
+    ) {
+        self.set_value_with_options(value, SetValueOptions::default(), window, cx);
+    }
+
+    /// Set the text of the input field with [`SetValueOptions`] to keep the scroll offset or cursor.
+    ///
+    /// This is useful to update an output editor on every keystroke without jumping to the top.
+    pub fn set_value_with_options(
+        &mut self,
+        value: impl Into<SharedString>,
+        options: SetValueOptions,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.history.break_group();
+        self.set_value_inner(value, options, window, cx);
+        self.history.break_group();
+    }
+
+    /// Set the text of the input field without recording the undo history.
+    ///
+    /// This is useful for the read-only output editors.
+    pub fn set_value_without_history(
+        &mut self,
+        value: impl Into<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) {
         self.history.ignore = true;
+        self.set_value_inner(value, SetValueOptions::default(), window, cx);
+        self.history.ignore = false;
+    }
+
+    fn set_value_inner(
+        &mut self,
+        value: impl Into<SharedString>,
+        options: SetValueOptions,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let value: SharedString = value.into();
+        if let Some(line_source) = self.line_source.take() {
+            self.read_only = line_source.was_read_only;
+        }
+        let selected_range = self.selected_range;
+        let scroll_offset = self.scroll_handle.offset();
+        // Keep the folds if the value is not changed.
+        let folds = (!self.folds.is_empty()
+            && self.text.len() == value.len()
+            && self.text.slice(..) == value.as_str())
+        .then(|| self.folds.clone());
         let was_disabled = self.disabled;
+        let was_read_only = self.read_only;
         self.disabled = false;
+        self.read_only = false;
         self.replace_text(value, window, cx);
         self.disabled = was_disabled;
-        self.history.ignore = false;
+        self.read_only = was_read_only;
+        if let Some(folds) = folds {
+            self.restore_folds(folds, cx);
+        }
 
-        // Ensure cursor to start when set text
-        if self.mode.is_single_line() {
+        if options.preserve_cursor {
+            self.selected_range = (self.text.clip_offset(selected_range.start, Bias::Left)
+                ..self.text.clip_offset(selected_range.end, Bias::Left))
+                .into();
+        } else if self.mode.is_single_line() {
+            // Ensure cursor to start when set text
             self.selected_range = (self.text.len()..self.text.len()).into();
         } else {
             self.selected_range.clear();
@@ -621,12 +931,85 @@ impl InputState {
             self.lsp.reset();
         }
 
-        // Move scroll to top
-        self.scroll_handle.set_offset(point(px(0.), px(0.)));
+        if options.preserve_scroll {
+            self.restore_scroll_offset(scroll_offset);
+        } else {
+            // Move scroll to top
+            self.scroll_handle.set_offset(point(px(0.), px(0.)));
+        }
 
         cx.notify();
     }
 
+    /// Restore the scroll offset after the text changed, without scrolling to the cursor.
+    pub(super) fn restore_scroll_offset(&mut self, mut scroll_offset: Point<Pixels>) {
+        // Clamp to the last line, if the new text is shorter.
+        if let Some(line_height) = self.last_layout.as_ref().map(|l| l.line_height) {
+            let max_scroll_y = (self.text_wrapper.len() as f32 * line_height
+                - self.input_bounds.size.height)
+                .max(px(0.));
+            scroll_offset.y = scroll_offset.y.max(-max_scroll_y);
+        }
+
+        // Avoid to scroll to the cursor on next layout.
+        self.last_selected_range = Some(self.selected_range);
+        self.scroll_handle.set_offset(scroll_offset);
+    }
+
+    /// Set the text by applying the diff to the current text as the incremental edits,
+    /// instead of replacing the whole text.
+    ///
+    /// The cursor, selection and scroll offset are kept where possible,
+    /// and all the edits are a single undo step.
+    ///
+    /// This is useful to update the text by the result of the formatter.
+    pub fn set_value_diffed(
+        &mut self,
+        value: impl Into<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let value: SharedString = value.into();
+        let old_text = self.text.to_string();
+        let hunks = diff_text(&old_text, &value);
+        if hunks.is_empty() {
+            return;
+        }
+
+        let mut selected_range = self.selected_range;
+        let scroll_offset = self.scroll_handle.offset();
+        let was_disabled = self.disabled;
+        let was_read_only = self.read_only;
+        self.disabled = false;
+        self.read_only = false;
+
+        self.history.break_group();
+        // Apply from the end, so the ranges of the previous hunks are not changed.
+        for (ix, hunk) in hunks.into_iter().rev().enumerate() {
+            let new_text = &value[hunk.new.clone()];
+            selected_range.start = map_offset_for_edit(selected_range.start, &hunk.old, new_text);
+            selected_range.end = map_offset_for_edit(selected_range.end, &hunk.old, new_text);
+
+            if ix > 0 {
+                self.history.start_grouping();
+            }
+            let range_utf16 = self.range_to_utf16(&hunk.old);
+            self.replace_text_in_range_silent(Some(range_utf16), new_text, window, cx);
+        }
+        self.history.end_grouping();
+        self.history.break_group();
+
+        self.disabled = was_disabled;
+        self.read_only = was_read_only;
+        // The offset inside a replaced range may be not at a char boundary of the new text.
+        self.selected_range = (self.text.clip_offset(selected_range.start, Bias::Left)
+            ..self.text.clip_offset(selected_range.end, Bias::Left))
+            .into();
+        self.update_preferred_column();
+        self.restore_scroll_offset(scroll_offset);
+        cx.notify();
+    }
+
     /// Insert text at the current cursor position.
     ///
     /// And the cursor will be moved to the end of inserted text.
@@ -638,7 +1021,9 @@ impl InputState {
     ) {
         let text: SharedString = text.into();
         let range_utf16 = self.range_to_utf16(&(self.cursor()..self.cursor()));
-        self.replace_text_in_range_silent(Some(range_utf16), &text, window, cx);
+        self.without_read_only(|this| {
+            this.replace_text_in_range_silent(Some(range_utf16), &text, window, cx)
+        });
         self.selected_range = (self.selected_range.end..self.selected_range.end).into();
     }
 
@@ -652,10 +1037,39 @@ impl InputState {
         cx: &mut Context<Self>,
     ) {
         let text: SharedString = text.into();
-        self.replace_text_in_range_silent(None, &text, window, cx);
+        self.without_read_only(|this| this.replace_text_in_range_silent(None, &text, window, cx));
         self.selected_range = (self.selected_range.end..self.selected_range.end).into();
     }
 
+    /// Insert text at the cursor position, replacing the selected text if any.
+    ///
+    /// This is a single undo step and emits [`InputEvent::Change`],
+    /// the cursor will be moved to the end of inserted text and scrolled into view.
+    pub fn insert_at_cursor(
+        &mut self,
+        text: impl Into<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let text: SharedString = text.into();
+        let range_utf16 = self.range_to_utf16(&self.selected_range.into());
+        self.history.break_group();
+        self.without_read_only(|this| {
+            this.replace_text_in_range_silent(Some(range_utf16), &text, window, cx)
+        });
+        self.history.break_group();
+        self.scroll_to(self.cursor(), None, cx);
+    }
+
+    /// Apply the edits by the code, the read-only mode only blocks the user input.
+    pub(super) fn without_read_only<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let was_read_only = self.read_only;
+        self.read_only = false;
+        let result = f(self);
+        self.read_only = was_read_only;
+        result
+    }
+
     fn replace_text(
         &mut self,
         text: impl Into<SharedString>,
@@ -677,6 +1091,30 @@ impl InputState {
         self
     }
 
+    /// Set with read-only mode, default is false.
+    ///
+    /// Unlike the disabled mode, the text can still be selected, scrolled and copied,
+    /// but the user can't insert, delete or paste text, so [`InputEvent::Change`] is never
+    /// emitted by the user edits. The edits by the code, e.g. [`Self::set_value`],
+    /// [`Self::insert_at_cursor`] and [`Self::replace_all`], still work.
+    ///
+    /// See also: [`Self::set_read_only`], [`Self::is_read_only`].
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Set the read-only mode of the input field.
+    pub fn set_read_only(&mut self, read_only: bool, cx: &mut Context<Self>) {
+        self.read_only = read_only;
+        cx.notify();
+    }
+
+    /// Return true if the input field is read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
     /// Set with password masked state.
     ///
     /// Only for [`InputMode::SingleLine`] mode.
@@ -695,6 +1133,68 @@ impl InputState {
         cx.notify();
     }
 
+    /// Set true to auto close the brackets and quotes, and highlight the matching bracket, default is false.
+    ///
+    /// Only works for the code editor, the pairs are defined by the [`LanguageConfig`] of the language:
+    ///
+    /// - Typing the start of a pair, e.g. `{`, inserts the end `}` after the cursor, except inside a string literal.
+    /// - Typing the end of a pair skips over it if the next char is the same.
+    /// - A single undo removes both the start and the auto inserted end.
+    pub fn auto_close_brackets(mut self, auto_close: bool) -> Self {
+        self.auto_close_brackets = auto_close;
+        self
+    }
+
+    /// Set true to accept the files dragged from the OS (e.g. Finder or Explorer), default is false.
+    ///
+    /// A drop overlay is shown while the files are dragged over the input, and the
+    /// [`InputEvent::FilesDropped`] is emitted with the paths when dropped,
+    /// the text is not changed, read the files in the event handler.
+    pub fn accept_file_drop(mut self, accept: bool) -> Self {
+        self.accept_file_drop = accept;
+        self
+    }
+
+    /// Set true to copy or cut the whole current line when the selection is empty,
+    /// like most code editors, default is false.
+    pub fn copy_line_on_empty_selection(mut self, enabled: bool) -> Self {
+        self.copy_line_on_empty_selection = enabled;
+        self
+    }
+
+    /// Set the handler to transform the pasted text before it is inserted.
+    ///
+    /// Return `None` to reject the paste, or `Some` with the text to insert.
+    ///
+    /// ```ignore
+    /// InputState::new(window, cx)
+    ///     .code_editor("json")
+    ///     .on_paste(|text| {
+    ///         if text.contains('\0') {
+    ///             return None;
+    ///         }
+    ///         Some(text.trim().to_string())
+    ///     })
+    /// ```
+    pub fn on_paste(mut self, handler: impl Fn(String) -> Option<String> + 'static) -> Self {
+        self.paste_handler = Some(Rc::new(handler));
+        self
+    }
+
+    /// Set an additional key context for this input, the `Input` key context is always present.
+    ///
+    /// This allows to bind the keys only for this input, they will only be dispatched when it is focused:
+    ///
+    /// ```ignore
+    /// cx.bind_keys([KeyBinding::new("cmd-k", ClearLine, Some("JsonEditor"))]);
+    ///
+    /// let state = cx.new(|cx| InputState::new(window, cx).code_editor("json").key_context("JsonEditor"));
+    /// ```
+    pub fn key_context(mut self, context: impl Into<SharedString>) -> Self {
+        self.key_context = Some(context.into());
+        self
+    }
+
     /// Set true to clear the input by pressing Escape key.
     pub fn clean_on_escape(mut self) -> Self {
         self.clean_on_escape = true;
@@ -709,14 +1209,23 @@ impl InputState {
     }
 
     /// Update the soft wrap mode for multi-line input, default is true.
+    ///
+    /// When enabled, the long lines are wrapped at the viewport width, the wrapped rows
+    /// are not numbered in the gutter, and the Up/Down keys move the cursor by the visual rows.
     pub fn set_soft_wrap(&mut self, wrap: bool, _: &mut Window, cx: &mut Context<Self>) {
         debug_assert!(self.mode.is_multi_line());
         self.soft_wrap = wrap;
         if wrap {
+            // The last layout has no wrap width if soft wrap was disabled,
+            // so calculate it from the input bounds, excluding the gutter.
             let wrap_width = self
                 .last_layout
                 .as_ref()
-                .and_then(|b| b.wrap_width)
+                .map(|layout| {
+                    layout.wrap_width.unwrap_or(
+                        self.input_bounds.size.width - layout.line_number_width - RIGHT_MARGIN,
+                    )
+                })
                 .unwrap_or(self.input_bounds.size.width);
 
             self.text_wrapper.set_wrap_width(Some(wrap_width), cx);
@@ -728,9 +1237,82 @@ impl InputState {
         } else {
             self.text_wrapper.set_wrap_width(None, cx);
         }
+        self.mode.update_auto_grow(&self.text_wrapper);
         cx.notify();
     }
 
+    /// Set true to render control characters (other than tab and newline) as visible
+    /// replacement symbols, default is false (Default true for Code Editor).
+    ///
+    /// This avoids layout glitches caused by invisible bytes, and helps to spot them.
+    pub fn render_control_chars(mut self, render: bool) -> Self {
+        self.render_control_chars = render;
+        self
+    }
+
+    /// Update the render control characters mode.
+    ///
+    /// See also: [`Self::render_control_chars`].
+    pub fn set_render_control_chars(
+        &mut self,
+        render: bool,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.render_control_chars = render;
+        cx.notify();
+    }
+
+    /// Set a custom renderer to replace the default line numbers in the gutter,
+    /// only for [`InputMode::CodeEditor`] mode with line number enabled.
+    ///
+    /// The gutter width adapts to the widest rendered element of the visible lines.
+    ///
+    /// ```ignore
+    /// // Relative line numbers, like `relativenumber` in Vim.
+    /// InputState::new(window, cx)
+    ///     .code_editor("rust")
+    ///     .gutter_renderer(|line, _, cx| {
+    ///         let number = match line.relative_line() {
+    ///             Some(0) | None => line.line + 1,
+    ///             Some(n) => n,
+    ///         };
+    ///         div().text_color(cx.theme().muted_foreground).child(number.to_string())
+    ///     })
+    /// ```
+    pub fn gutter_renderer<F, E>(mut self, f: F) -> Self
+    where
+        F: Fn(&GutterLine, &mut Window, &mut App) -> E + 'static,
+        E: IntoElement,
+    {
+        self.gutter_renderer = Some(Rc::new(move |line, window, cx| {
+            f(line, window, cx).into_any_element()
+        }));
+        self
+    }
+
+    /// Set a hint to show on the blank line at the cursor, only for the multi-line modes.
+    ///
+    /// The function receives the line number (zero-based) and returns the hint text, the hint is
+    /// rendered as ghosted non-editable text after the whitespaces of the line.
+    ///
+    /// Unlike the placeholder that only shows when the whole text is empty, this hint is shown
+    /// on any blank line while the input is focused and nothing is selected.
+    ///
+    /// ```ignore
+    /// InputState::new(window, cx)
+    ///     .code_editor("json")
+    ///     .blank_line_hint(|_| Some("Add property".into()))
+    /// ```
+    pub fn blank_line_hint<F>(mut self, f: F) -> Self
+    where
+        F: Fn(usize) -> Option<SharedString> + 'static,
+    {
+        debug_assert!(self.mode.is_multi_line());
+        self.blank_line_hint = Some(Rc::new(f));
+        self
+    }
+
     /// Set the regular expression pattern of the input field.
     ///
     /// Only for [`InputMode::SingleLine`] mode.
@@ -762,6 +1344,130 @@ impl InputState {
         self
     }
 
+    /// Set the maximum number of characters of the input.
+    ///
+    /// The insertions (including typing and pasting) beyond the limit are truncated,
+    /// and [`InputEvent::Rejected`] is emitted. This doesn't limit the text set by
+    /// [`InputState::set_value`].
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Set the maximum number of characters of the input, `None` to remove the limit.
+    ///
+    /// See [`InputState::max_length`].
+    pub fn set_max_length(
+        &mut self,
+        max_length: Option<usize>,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+        self.max_length = max_length;
+    }
+
+    /// Set a filter of the characters that can be inserted, e.g. only digits.
+    ///
+    /// The inserted text is cut before the first rejected character, and
+    /// [`InputEvent::Rejected`] is emitted. Like [`InputState::max_length`], this only filters
+    /// the typing and pasting, not the text set by [`InputState::set_value`].
+    ///
+    /// ```ignore
+    /// InputState::new(window, cx)
+    ///     .max_length(6)
+    ///     .char_filter(|c| c.is_ascii_digit())
+    /// ```
+    pub fn char_filter(mut self, f: impl Fn(char) -> bool + 'static) -> Self {
+        self.char_filter = Some(Rc::new(f));
+        self
+    }
+
+    /// Set the duration to coalesce the rapid edits, default is `None`.
+    ///
+    /// When set, [`InputEvent::ChangeDebounced`] is emitted after the user pauses typing for
+    /// the duration, in addition to the immediate [`InputEvent::Change`] of each edit.
+    /// The timer is reset on each edit, and the pending event is emitted on blur.
+    ///
+    /// This is useful to avoid the expensive work (e.g. formatting, linting) on every keystroke.
+    pub fn change_debounce(mut self, debounce: Duration) -> Self {
+        self.change_debounce = Some(debounce);
+        self
+    }
+
+    /// Set the debounce duration of [`InputEvent::ChangeDebounced`], `None` to disable.
+    ///
+    /// The pending event is emitted immediately when disabled.
+    pub fn set_change_debounce(&mut self, debounce: Option<Duration>, cx: &mut Context<Self>) {
+        self.change_debounce = debounce;
+        if debounce.is_none() {
+            self.flush_change_debounced(cx);
+        }
+    }
+
+    /// Restart the timer of [`InputEvent::ChangeDebounced`] if [`InputState::change_debounce`] is set.
+    fn schedule_change_debounced(&mut self, cx: &mut Context<Self>) {
+        let Some(debounce) = self.change_debounce else {
+            return;
+        };
+
+        self.change_debounce_pending = true;
+        self._change_debounce_task = cx.spawn(async move |this, cx| {
+            Timer::after(debounce).await;
+            _ = this.update(cx, |this, cx| {
+                if this.change_debounce_pending {
+                    this.change_debounce_pending = false;
+                    cx.emit(InputEvent::ChangeDebounced);
+                }
+            });
+        });
+    }
+
+    /// Emit the pending [`InputEvent::ChangeDebounced`] immediately, if any.
+    fn flush_change_debounced(&mut self, cx: &mut Context<Self>) {
+        self._change_debounce_task = Task::ready(());
+        if self.change_debounce_pending {
+            self.change_debounce_pending = false;
+            cx.emit(InputEvent::ChangeDebounced);
+        }
+    }
+
+    /// Returns the prefix of the `new_text` allowed by the [`InputState::max_length`] and
+    /// [`InputState::char_filter`] to replace each of the `ranges`.
+    fn allowed_input<'a>(&self, ranges: &[Range<usize>], new_text: &'a str) -> &'a str {
+        if self.max_length.is_none() && self.char_filter.is_none() {
+            return new_text;
+        }
+
+        let available = self.max_length.map(|max_length| {
+            let replaced = ranges
+                .iter()
+                .map(|range| self.text.slice(range.clone()).len_chars())
+                .sum::<usize>();
+            let len = self.text.len_chars() - replaced;
+            max_length.saturating_sub(len) / ranges.len().max(1)
+        });
+        allowed_prefix(new_text, available, self.char_filter.as_deref())
+    }
+
+    /// Filter the text typed or pasted by the user to replace each of the `ranges`,
+    /// and emit [`InputEvent::Rejected`] with the rejected part, see [`Self::allowed_input`].
+    ///
+    /// The text set by the code, e.g. [`InputState::set_value`] or undo, is not filtered.
+    pub(super) fn filter_input<'a>(
+        &self,
+        ranges: &[Range<usize>],
+        new_text: &'a str,
+        cx: &mut Context<Self>,
+    ) -> &'a str {
+        let allowed_text = self.allowed_input(ranges, new_text);
+        if allowed_text.len() < new_text.len() {
+            cx.emit(InputEvent::Rejected {
+                text: new_text[allowed_text.len()..].to_string().into(),
+            });
+        }
+        allowed_text
+    }
+
     /// Set true to show spinner at the input right.
     ///
     /// Only for [`InputMode::SingleLine`] mode.
@@ -821,6 +1527,63 @@ impl InputState {
         self.focus(window, cx);
     }
 
+    /// Move the cursor to the given offset and scroll it into view.
+    ///
+    /// The offset is the UTF-8 byte offset of the value, it will be clamped to the text length
+    /// and the nearest previous char boundary.
+    pub fn set_cursor(&mut self, offset: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.text.clip_offset(offset, Bias::Left);
+        self.selection_reversed = false;
+        self.selected_word_range = None;
+        self.move_to(offset, None, cx);
+        self.focus(window, cx);
+    }
+
+    /// Select the given range and scroll the cursor into view.
+    ///
+    /// The range is the UTF-8 byte range of the value, it will be clamped to the text length
+    /// and the char boundaries. If the `range.start` is greater than `range.end`,
+    /// the selection is reversed and the cursor will be placed at the `range.end`.
+    pub fn set_selection(
+        &mut self,
+        range: Range<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let start = self.text.clip_offset(range.start, Bias::Left);
+        let end = self.text.clip_offset(range.end, Bias::Left);
+
+        self.selection_reversed = start > end;
+        self.selected_range = (start.min(end)..start.max(end)).into();
+        self.selected_word_range = None;
+        self.secondary_selections.clear();
+        self.scroll_to(self.cursor(), None, cx);
+        self.pause_blink_cursor(cx);
+        self.update_preferred_column();
+        self.hide_context_menu(cx);
+        self.clear_inline_completion(cx);
+        self.focus(window, cx);
+        cx.notify();
+    }
+
+    /// Returns the UTF-8 byte ranges of all the selections in the text order,
+    /// an empty range is a cursor without selection.
+    ///
+    /// This is the same as [`InputState::cursors`].
+    pub fn selections(&self) -> Vec<Range<usize>> {
+        self.cursors()
+    }
+
+    /// Select the (0-based) line, excluding the line ending, and scroll it into view.
+    ///
+    /// The line will be clamped to the last line.
+    pub fn select_line(&mut self, line: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let line = line.min(self.text.lines_len().saturating_sub(1));
+        let start = self.text.line_start_offset(line);
+        let end = self.text.line_end_offset(line);
+        self.set_selection(start..end, window, cx);
+    }
+
     /// Focus the input field.
     pub fn focus(&self, window: &mut Window, cx: &mut Context<Self>) {
         self.focus_handle.focus(window);
@@ -855,6 +1618,7 @@ impl InputState {
 
     pub(super) fn select_all(&mut self, _: &SelectAll, _: &mut Window, cx: &mut Context<Self>) {
         self.selected_range = (0..self.text.len()).into();
+        self.secondary_selections.clear();
         cx.notify();
     }
 
@@ -1029,7 +1793,31 @@ impl InputState {
     }
 
     pub(super) fn backspace(&mut self, _: &Backspace, window: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
+        if self.handle_vim_key("h", window, cx) {
+            return;
+        }
+        if self.read_only {
+            return;
+        }
+        if self.has_multiple_cursors() {
+            self.edit_cursors(
+                |this, range| {
+                    let start = if range.is_empty() {
+                        this.previous_boundary(range.start)
+                    } else {
+                        range.start
+                    };
+                    (start..range.end, String::new())
+                },
+                window,
+                cx,
+            );
+            return;
+        }
+
+        if let Some(offset) = self.previous_tab_stop() {
+            self.select_to(offset, cx)
+        } else if self.selected_range.is_empty() {
             self.select_to(self.previous_boundary(self.cursor()), cx)
         }
         self.replace_text_in_range(None, "", window, cx);
@@ -1037,6 +1825,28 @@ impl InputState {
     }
 
     pub(super) fn delete(&mut self, _: &Delete, window: &mut Window, cx: &mut Context<Self>) {
+        if self.handle_vim_key("x", window, cx) {
+            return;
+        }
+        if self.read_only {
+            return;
+        }
+        if self.has_multiple_cursors() {
+            self.edit_cursors(
+                |this, range| {
+                    let end = if range.is_empty() {
+                        this.next_boundary(range.end)
+                    } else {
+                        range.end
+                    };
+                    (range.start..end, String::new())
+                },
+                window,
+                cx,
+            );
+            return;
+        }
+
         if self.selected_range.is_empty() {
             self.select_to(self.next_boundary(self.cursor()), cx)
         }
@@ -1142,13 +1952,44 @@ impl InputState {
         if self.handle_action_for_context_menu(Box::new(action.clone()), window, cx) {
             return;
         }
+        if self.handle_vim_key("j", window, cx) {
+            return;
+        }
 
         // Clear inline completion on enter (user chose not to accept it)
         if self.has_inline_completion() {
             self.clear_inline_completion(cx);
         }
 
-        if self.mode.is_multi_line() {
+        if self.mode.is_multi_line() && self.filter_input(&self.cursors(), "\n", cx).is_empty() {
+            // The newline is rejected by the max length or the char filter.
+        } else if self.has_multiple_cursors() {
+            let is_code_editor = self.mode.is_code_editor();
+            let cursors = self.cursors();
+            self.edit_cursors(
+                |this, range| {
+                    // Keep the indent of the current line.
+                    let indent = if is_code_editor {
+                        let row = this.text.offset_to_point(range.start).row;
+                        this.text
+                            .slice(this.text.line_start_offset(row)..range.start)
+                            .chars()
+                            .take_while(|c| *c == ' ' || *c == '\t')
+                            .collect::<String>()
+                    } else {
+                        String::new()
+                    };
+                    let new_line_text = format!("\n{}", indent);
+                    let new_line_text = this.allowed_input(&cursors, &new_line_text).to_string();
+                    (range, new_line_text)
+                },
+                window,
+                cx,
+            );
+        } else if self.mode.is_multi_line() && self.auto_indent {
+            self.insert_auto_indent_newline(window, cx);
+            self.pause_blink_cursor(cx);
+        } else if self.mode.is_multi_line() {
             // Get current line indent
             let indent = if self.mode.is_code_editor() {
                 self.indent_of_next_line()
@@ -1158,6 +1999,8 @@ impl InputState {
 
             // Add newline and indent
             let new_line_text = format!("\n{}", indent);
+            let range = self.selected_range.start..self.selected_range.end;
+            let new_line_text = self.filter_input(&[range], &new_line_text, cx).to_string();
             self.replace_text_in_range_silent(None, &new_line_text, window, cx);
             self.pause_blink_cursor(cx);
         } else {
@@ -1187,10 +2030,19 @@ impl InputState {
             return; // Consume the escape, don't propagate
         }
 
+        if self.has_multiple_cursors() {
+            self.clear_cursors(cx);
+            return;
+        }
+
         if self.ime_marked_range.is_some() {
             self.unmark_text(window, cx);
         }
 
+        if self.vim_escape(cx) {
+            return;
+        }
+
         if self.clean_on_escape {
             return self.clean(window, cx);
         }
@@ -1215,6 +2067,20 @@ impl InputState {
             }
         }
 
+        if event.button == MouseButton::Left && self.handle_fold_click(event.position, cx) {
+            return;
+        }
+        if event.button == MouseButton::Left
+            && self.handle_line_annotation_click(event.position, cx)
+        {
+            return;
+        }
+        if event.button == MouseButton::Left
+            && self.handle_gutter_click(event.position, event.modifiers, window, cx)
+        {
+            return;
+        }
+
         self.selecting = true;
         let offset = self.index_for_mouse_position(event.position);
 
@@ -1222,6 +2088,19 @@ impl InputState {
             return;
         }
 
+        // Secondary click to add a cursor
+        if event.button == MouseButton::Left
+            && event.click_count == 1
+            && event.modifiers.secondary()
+            && !event.modifiers.shift
+            && self.mode.is_multi_line()
+        {
+            self.selecting = false;
+            self.add_cursor(offset, window, cx);
+            return;
+        }
+        self.secondary_selections.clear();
+
         // Double click to select word
         if event.button == MouseButton::Left && event.click_count == 2 {
             self.select_word(offset, window, cx);
@@ -1316,6 +2195,22 @@ impl InputState {
         self.diagnostic_popover = None;
     }
 
+    pub(super) fn on_drop_files(
+        &mut self,
+        paths: &ExternalPaths,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.accept_file_drop || self.disabled || paths.paths().is_empty() {
+            return;
+        }
+
+        cx.stop_propagation();
+        cx.emit(InputEvent::FilesDropped {
+            paths: paths.paths().to_vec(),
+        });
+    }
+
     pub(super) fn update_scroll_offset(
         &mut self,
         offset: Option<Point<Pixels>>,
@@ -1426,35 +2321,95 @@ impl InputState {
         window.show_character_palette();
     }
 
+    /// Returns the range to copy or cut, the current line if the selection is empty
+    /// and [`InputState::copy_line_on_empty_selection`] is enabled.
+    fn clipboard_range(&self) -> Option<Range<usize>> {
+        let range: Range<usize> = self.selected_range.into();
+        if !range.is_empty() {
+            return Some(range);
+        }
+        if !self.copy_line_on_empty_selection || self.text.len() == 0 {
+            return None;
+        }
+
+        Some(line_range_with_newline(&self.text, self.cursor()))
+    }
+
     pub(super) fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
+        let Some(range) = self.clipboard_range() else {
             return;
-        }
+        };
+        // Load the lines to copy from the line source, the range is changed after loading.
+        let range = if self.line_source.is_some() {
+            self.load_lines_in_range(&range, cx);
+            let Some(range) = self.clipboard_range() else {
+                return;
+            };
+            range
+        } else {
+            range
+        };
 
-        let selected_text = self.text.slice(self.selected_range).to_string();
-        cx.write_to_clipboard(ClipboardItem::new_string(selected_text));
+        let selected_text = self.text.slice(range).to_string();
+        cx.write_to_clipboard(ClipboardItem::new_string(selected_text.clone()));
+        cx.emit(InputEvent::Copy {
+            text: selected_text.into(),
+        });
     }
 
     pub(super) fn cut(&mut self, _: &Cut, window: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
+        if self.read_only {
             return;
         }
+        let Some(range) = self.clipboard_range() else {
+            return;
+        };
 
-        let selected_text = self.text.slice(self.selected_range).to_string();
-        cx.write_to_clipboard(ClipboardItem::new_string(selected_text));
+        let selected_text = self.text.slice(range.clone()).to_string();
+        cx.write_to_clipboard(ClipboardItem::new_string(selected_text.clone()));
 
+        let old_text = self.text.clone();
+        self.selected_range = range.into();
         self.replace_text_in_range_silent(None, "", window, cx);
+        if self.text != old_text {
+            cx.emit(InputEvent::Cut {
+                text: selected_text.into(),
+            });
+        }
     }
 
     pub(super) fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
         if let Some(clipboard) = cx.read_from_clipboard() {
             let mut new_text = clipboard.text().unwrap_or_default();
+            if let Some(handler) = self.paste_handler.clone() {
+                let Some(text) = handler(new_text) else {
+                    return;
+                };
+                new_text = text;
+            }
             if !self.mode.is_multi_line() {
                 new_text = new_text.replace('\n', "");
             }
 
-            self.replace_text_in_range_silent(None, &new_text, window, cx);
+            let inserted_text = self
+                .filter_input(&self.cursors(), &new_text, cx)
+                .to_string();
+            let old_text = self.text.clone();
+            if self.has_multiple_cursors() {
+                self.edit_cursors(|_, range| (range, inserted_text.clone()), window, cx);
+            } else {
+                self.replace_text_in_range_silent(None, &inserted_text, window, cx);
+            }
             self.scroll_to(self.cursor(), None, cx);
+            // Only emit when pasted, e.g. not when disabled or the text is all rejected.
+            if self.text != old_text {
+                cx.emit(InputEvent::Paste {
+                    text: inserted_text.into(),
+                });
+            }
         }
     }
 
@@ -1465,33 +2420,80 @@ impl InputState {
 
         let old_text = text.slice(range.clone()).to_string();
         let new_range = range.start..range.start + new_text.len();
+        let change = Change::new(range.clone(), &old_text, new_range, new_text);
+
+        // Coalesce the consecutive typed chars into one undo step,
+        // and start a new step when switch between typing and other edits.
+        if let Some(last) = self.history.undos().last() {
+            let is_typing = change.is_typing();
+            if is_typing != last.is_typing()
+                || (is_typing && last.new_range.end != change.old_range.start)
+            {
+                self.history.break_group();
+            }
+        }
 
-        self.history
-            .push(Change::new(range.clone(), &old_text, new_range, new_text));
+        self.history.push(change);
     }
 
-    pub(super) fn undo(&mut self, _: &Undo, window: &mut Window, cx: &mut Context<Self>) {
+    /// Set the maximum number of undo steps to keep, default is 200.
+    pub fn max_undos(mut self, max_undos: usize) -> Self {
+        self.history = self.history.max_undos(max_undos);
+        self
+    }
+
+    /// Return true if there is any change to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.history.undos().is_empty()
+    }
+
+    /// Return true if there is any change to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.history.redos().is_empty()
+    }
+
+    /// Undo the last change, and emit [`InputEvent::Undo`].
+    pub fn undo(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+
         self.history.ignore = true;
         if let Some(changes) = self.history.undo() {
             for change in changes {
                 let range_utf16 = self.range_to_utf16(&change.new_range.into());
                 self.replace_text_in_range_silent(Some(range_utf16), &change.old_text, window, cx);
             }
+            cx.emit(InputEvent::Undo);
         }
         self.history.ignore = false;
     }
 
-    pub(super) fn redo(&mut self, _: &Redo, window: &mut Window, cx: &mut Context<Self>) {
+    /// Redo the last undone change, and emit [`InputEvent::Redo`].
+    pub fn redo(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+
         self.history.ignore = true;
         if let Some(changes) = self.history.redo() {
             for change in changes {
                 let range_utf16 = self.range_to_utf16(&change.old_range.into());
                 self.replace_text_in_range_silent(Some(range_utf16), &change.new_text, window, cx);
             }
+            cx.emit(InputEvent::Redo);
         }
         self.history.ignore = false;
     }
 
+    pub(super) fn on_action_undo(&mut self, _: &Undo, window: &mut Window, cx: &mut Context<Self>) {
+        self.undo(window, cx);
+    }
+
+    pub(super) fn on_action_redo(&mut self, _: &Redo, window: &mut Window, cx: &mut Context<Self>) {
+        self.redo(window, cx);
+    }
+
     /// Get byte offset of the cursor.
     ///
     /// The offset is the UTF-8 offset.
@@ -1507,6 +2509,36 @@ impl InputState {
         }
     }
 
+    /// Measure the rendered width of the text in the current font of the editor.
+    ///
+    /// For the multi-line text, the width of the widest line is returned.
+    ///
+    /// The font is updated on layout, so this follows the font and zoom changes after next render.
+    pub fn measure_text(&self, text: &str, window: &mut Window) -> Pixels {
+        let (font, font_size) = self.text_wrapper.font();
+        text.lines()
+            .map(|line| {
+                let run = TextRun {
+                    len: line.len(),
+                    font: font.clone(),
+                    color: gpui::black(),
+                    background_color: None,
+                    underline: None,
+                    strikethrough: None,
+                };
+                window
+                    .text_system()
+                    .shape_line(
+                        SharedString::from(line.to_string()),
+                        font_size,
+                        &[run],
+                        None,
+                    )
+                    .width
+            })
+            .fold(px(0.), |a, b| a.max(b))
+    }
+
     pub(crate) fn index_for_mouse_position(&self, position: Point<Pixels>) -> usize {
         // If the text is empty, always return 0
         if self.text.len() == 0 {
@@ -1612,6 +2644,7 @@ impl InputState {
     /// Ensure the offset use self.next_boundary or self.previous_boundary to get the correct offset.
     pub(crate) fn select_to(&mut self, offset: usize, cx: &mut Context<Self>) {
         self.clear_inline_completion(cx);
+        self.secondary_selections.clear();
 
         let offset = offset.clamp(0, self.text.len());
         if self.selection_reversed {
@@ -1691,7 +2724,8 @@ impl InputState {
 
     /// Returns the true to let InputElement to render cursor, when Input is focused and current BlinkCursor is visible.
     pub(crate) fn show_cursor(&self, window: &Window, cx: &App) -> bool {
-        (self.focus_handle.is_focused(window) || self.is_context_menu_open(cx))
+        !self.read_only
+            && (self.focus_handle.is_focused(window) || self.is_context_menu_open(cx))
             && self.blink_cursor.read(cx).visible()
             && window.is_window_active()
     }
@@ -1701,6 +2735,23 @@ impl InputState {
             cursor.start(cx);
         });
         cx.emit(InputEvent::Focus);
+        self.emit_selection_changed(cx);
+    }
+
+    /// Emit [`InputEvent::SelectionChanged`] with the current cursor and selection.
+    fn emit_selection_changed(&mut self, cx: &mut Context<Self>) {
+        self.last_emitted_selection = Some(self.selected_range);
+        cx.emit(InputEvent::SelectionChanged {
+            cursor: self.cursor_position(),
+            selection_len: self.selected_text().chars().count(),
+        });
+    }
+
+    /// Emit [`InputEvent::SelectionChanged`] if the selection has changed since the last emit.
+    fn check_selection_changed(&mut self, cx: &mut Context<Self>) {
+        if self.last_emitted_selection != Some(self.selected_range) {
+            self.emit_selection_changed(cx);
+        }
     }
 
     fn on_blur(&mut self, window: &mut Window, cx: &mut Context<Self>) {
@@ -1721,6 +2772,7 @@ impl InputState {
         Root::update(window, cx, |root, _, _| {
             root.focused_input = None;
         });
+        self.flush_change_debounced(cx);
         cx.emit(InputEvent::Blur);
         cx.notify();
     }
@@ -1731,8 +2783,16 @@ impl InputState {
         });
     }
 
-    pub(super) fn on_key_down(&mut self, _: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+    pub(super) fn on_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         self.pause_blink_cursor(cx);
+        if self.on_vim_key_down(event, window, cx) {
+            cx.stop_propagation();
+        }
     }
 
     pub(super) fn on_drag_move(
@@ -1951,12 +3011,24 @@ impl EntityInputHandler for InputState {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if self.disabled {
+        if self.disabled || self.read_only || self.line_source.is_some() {
             return;
         }
 
         self.pause_blink_cursor(cx);
 
+        if range_utf16.is_none()
+            && self.ime_marked_range.is_none()
+            && !self.silent_replace_text
+            && self.has_multiple_cursors()
+        {
+            let new_text = self.filter_input(&self.cursors(), new_text, cx).to_string();
+            self.edit_cursors(|_, range| (range, new_text.clone()), window, cx);
+            return;
+        }
+        // The other edits are only applied to the primary cursor.
+        self.secondary_selections.clear();
+
         let range = range_utf16
             .as_ref()
             .map(|range_utf16| self.range_from_utf16(range_utf16))
@@ -1966,6 +3038,25 @@ impl EntityInputHandler for InputState {
             }))
             .unwrap_or(self.selected_range.into());
 
+        // Only filter the user input, a rejected text still replaces the selection.
+        let new_text = if self.silent_replace_text {
+            new_text
+        } else {
+            let allowed_text = self.filter_input(&[range.clone()], new_text, cx);
+            if allowed_text.is_empty() && !new_text.is_empty() && range.is_empty() {
+                return;
+            }
+            allowed_text
+        };
+
+        if range_utf16.is_none()
+            && self.ime_marked_range.is_none()
+            && !self.silent_replace_text
+            && self.handle_auto_close(new_text, window, cx)
+        {
+            return;
+        }
+
         let old_text = self.text.clone();
         self.text.replace(range.clone(), new_text);
 
@@ -1995,6 +3086,7 @@ impl EntityInputHandler for InputState {
         }
         self.text_wrapper
             .update(&self.text, &range, &Rope::from(new_text), cx);
+        self.update_folds_for_edit(&range, new_text);
         self.mode
             .update_highlighter(&range, &self.text, &new_text, true, cx);
         self.lsp.update(&self.text, window, cx);
@@ -2007,6 +3099,7 @@ impl EntityInputHandler for InputState {
             self.handle_completion_trigger(&range, &new_text, window, cx);
         }
         cx.emit(InputEvent::Change);
+        self.schedule_change_debounced(cx);
         cx.notify();
     }
 
@@ -2019,11 +3112,12 @@ impl EntityInputHandler for InputState {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if self.disabled {
+        if self.disabled || self.read_only || self.line_source.is_some() {
             return;
         }
 
         self.lsp.reset();
+        self.secondary_selections.clear();
 
         let range = range_utf16
             .as_ref()
@@ -2034,6 +3128,8 @@ impl EntityInputHandler for InputState {
             }))
             .unwrap_or(self.selected_range.into());
 
+        let new_text = self.filter_input(&[range.clone()], new_text, cx);
+
         let old_text = self.text.clone();
         self.text.replace(range.clone(), new_text);
 
@@ -2050,6 +3146,7 @@ impl EntityInputHandler for InputState {
         }
         self.text_wrapper
             .update(&self.text, &range, &Rope::from(new_text), cx);
+        self.update_folds_for_edit(&range, new_text);
         self.mode
             .update_highlighter(&range, &self.text, &new_text, true, cx);
         self.lsp.update(&self.text, window, cx);
@@ -2176,3 +3273,318 @@ impl Render for InputState {
             .children(self.hover_popover.clone())
     }
 }
+
+/// Returns the longest prefix of the `new_text` that fits in the `available` number of
+/// characters, and only contains the characters allowed by the `char_filter`.
+fn allowed_prefix<'a>(
+    new_text: &'a str,
+    available: Option<usize>,
+    char_filter: Option<&dyn Fn(char) -> bool>,
+) -> &'a str {
+    let mut end = 0;
+    for (count, (ix, c)) in new_text.char_indices().enumerate() {
+        if available.is_some_and(|available| count >= available)
+            || char_filter.is_some_and(|char_filter| !char_filter(c))
+        {
+            break;
+        }
+        end = ix + c.len_utf8();
+    }
+
+    &new_text[..end]
+}
+
+/// Returns the byte range of the line at the offset, including the trailing newline.
+fn line_range_with_newline(text: &Rope, offset: usize) -> Range<usize> {
+    let row = text.offset_to_point(offset).row;
+    let start = text.line_start_offset(row);
+    let end = text.line_end_offset(row);
+    let end = if end < text.len() { end + 1 } else { end };
+    start..end
+}
+
+/// Map the offset before the edit that replaces the `range` with the `new_text`,
+/// to the offset after the edit.
+///
+/// The offset inside the replaced range is kept if it's still inside the new text.
+pub(super) fn map_offset_for_edit(offset: usize, range: &Range<usize>, new_text: &str) -> usize {
+    if offset >= range.end {
+        offset - range.len() + new_text.len()
+    } else if offset > range.start {
+        offset.min(range.start + new_text.len())
+    } else {
+        offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ropey::Rope;
+
+    use super::{allowed_prefix, line_range_with_newline, map_offset_for_edit};
+
+    #[test]
+    fn test_allowed_prefix() {
+        let digits = |c: char| c.is_ascii_digit();
+
+        assert_eq!(allowed_prefix("hello", None, None), "hello");
+        assert_eq!(allowed_prefix("hello", Some(3), None), "hel");
+        assert_eq!(allowed_prefix("你好世界", Some(2), None), "你好");
+        assert_eq!(allowed_prefix("hello", Some(0), None), "");
+        assert_eq!(allowed_prefix("123a45", None, Some(&digits)), "123");
+        assert_eq!(allowed_prefix("123a45", Some(2), Some(&digits)), "12");
+        assert_eq!(allowed_prefix("a123", None, Some(&digits)), "");
+    }
+
+    #[gpui::test]
+    fn test_input_filter(cx: &mut gpui::TestAppContext) {
+        use gpui::{AppContext as _, EntityInputHandler as _};
+
+        use super::InputState;
+
+        cx.update(|cx| crate::init(cx));
+        let window = cx.add_empty_window();
+        window.update(|window, cx| {
+            let state = cx.new(|cx| {
+                InputState::new(window, cx)
+                    .max_length(4)
+                    .char_filter(|c| c.is_ascii_digit())
+            });
+            state.update(cx, |state, cx| {
+                // The text set by the code and the undo are not filtered.
+                state.set_value("abc123", window, cx);
+                assert_eq!(state.value(), "abc123");
+                state.set_value("12", window, cx);
+                state.undo(window, cx);
+                assert_eq!(state.value(), "abc123");
+
+                state.set_value("12", window, cx);
+                state.set_selection(2..2, window, cx);
+                state.replace_text_in_range(None, "3a4", window, cx);
+                assert_eq!(state.value(), "123");
+                state.replace_text_in_range(None, "4567", window, cx);
+                assert_eq!(state.value(), "1234");
+
+                // A rejected char still replaces the selection.
+                state.set_selection(0..3, window, cx);
+                state.replace_text_in_range(None, "x", window, cx);
+                assert_eq!(state.value(), "4");
+            });
+        });
+    }
+
+    #[gpui::test]
+    fn test_input_filter_newline(cx: &mut gpui::TestAppContext) {
+        use gpui::AppContext as _;
+
+        use super::{Enter, InputState};
+
+        cx.update(|cx| crate::init(cx));
+        let window = cx.add_empty_window();
+        window.update(|window, cx| {
+            let enter = Enter { secondary: false };
+            let state = cx.new(|cx| InputState::new(window, cx).multi_line(true).max_length(4));
+            state.update(cx, |state, cx| {
+                state.set_value("abc", window, cx);
+                state.set_selection(3..3, window, cx);
+                state.enter(&enter, window, cx);
+                assert_eq!(state.value(), "abc\n");
+                // The newline beyond the max length is rejected.
+                state.enter(&enter, window, cx);
+                assert_eq!(state.value(), "abc\n");
+            });
+
+            // The indent of the new line is truncated.
+            let state = cx.new(|cx| {
+                InputState::new(window, cx)
+                    .multi_line(true)
+                    .auto_indent(true)
+                    .max_length(6)
+            });
+            state.update(cx, |state, cx| {
+                state.set_value("  ab", window, cx);
+                state.set_selection(4..4, window, cx);
+                state.enter(&enter, window, cx);
+                assert_eq!(state.value(), "  ab\n ");
+                state.enter(&enter, window, cx);
+                assert_eq!(state.value(), "  ab\n ");
+            });
+        });
+    }
+
+    #[gpui::test]
+    fn test_set_value_diffed(cx: &mut gpui::TestAppContext) {
+        use gpui::AppContext as _;
+
+        use super::InputState;
+
+        cx.update(|cx| crate::init(cx));
+        let window = cx.add_empty_window();
+        window.update(|window, cx| {
+            let state = cx.new(|cx| InputState::new(window, cx).multi_line(true));
+            state.update(cx, |state, cx| {
+                // The cursor inside the replaced chars is clipped to the char boundary.
+                state.set_value("aéééb", window, cx);
+                state.set_selection(3..3, window, cx);
+                state.set_value_diffed("a你b", window, cx);
+                assert_eq!(state.value(), "a你b");
+                assert_eq!(state.cursor(), 1);
+
+                // All the hunks are a single undo step.
+                state.set_value("foo\nbar\nbaz\n", window, cx);
+                state.set_value_diffed("FOO\nbar\nBAZ\n", window, cx);
+                assert_eq!(state.value(), "FOO\nbar\nBAZ\n");
+                state.undo(window, cx);
+                assert_eq!(state.value(), "foo\nbar\nbaz\n");
+                state.redo(window, cx);
+                assert_eq!(state.value(), "FOO\nbar\nBAZ\n");
+            });
+        });
+    }
+
+    #[gpui::test]
+    fn test_cut_paste_events(cx: &mut gpui::TestAppContext) {
+        use std::{cell::RefCell, rc::Rc};
+
+        use gpui::{AppContext as _, ClipboardItem};
+
+        use super::{Cut, InputEvent, InputState, Paste};
+
+        cx.update(|cx| crate::init(cx));
+        let window = cx.add_empty_window();
+        let events = Rc::new(RefCell::new(vec![]));
+        let state = window.update(|window, cx| {
+            let state = cx.new(|cx| InputState::new(window, cx).max_length(3));
+            cx.subscribe(&state, {
+                let events = events.clone();
+                move |_, event: &InputEvent, _| match event {
+                    InputEvent::Cut { text } => events.borrow_mut().push(format!("cut {text}")),
+                    InputEvent::Paste { text } => events.borrow_mut().push(format!("paste {text}")),
+                    _ => {}
+                }
+            })
+            .detach();
+            state
+        });
+
+        window.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                state.set_value("abc", window, cx);
+                state.set_selection(0..1, window, cx);
+                state.cut(&Cut, window, cx);
+                assert_eq!(state.value(), "bc");
+
+                // Blocked by the read-only.
+                state.set_read_only(true, cx);
+                state.set_selection(0..1, window, cx);
+                state.cut(&Cut, window, cx);
+                cx.write_to_clipboard(ClipboardItem::new_string("x".into()));
+                state.paste(&Paste, window, cx);
+                assert_eq!(state.value(), "bc");
+                state.set_read_only(false, cx);
+
+                // All rejected by the max length.
+                state.set_value("abc", window, cx);
+                state.set_selection(3..3, window, cx);
+                state.paste(&Paste, window, cx);
+                assert_eq!(state.value(), "abc");
+
+                state.set_selection(0..1, window, cx);
+                state.paste(&Paste, window, cx);
+                assert_eq!(state.value(), "xbc");
+            });
+        });
+        assert_eq!(*events.borrow(), vec!["cut a", "paste x"]);
+    }
+
+    #[gpui::test]
+    fn test_selection_changed(cx: &mut gpui::TestAppContext) {
+        use std::{cell::RefCell, rc::Rc};
+
+        use gpui::AppContext as _;
+
+        use super::{InputEvent, InputState};
+
+        cx.update(|cx| crate::init(cx));
+        let window = cx.add_empty_window();
+        let events = Rc::new(RefCell::new(vec![]));
+        let state = window.update(|window, cx| {
+            let state = cx.new(|cx| InputState::new(window, cx));
+            cx.subscribe(&state, {
+                let events = events.clone();
+                move |_, event: &InputEvent, _| {
+                    if let InputEvent::SelectionChanged { selection_len, .. } = event {
+                        events.borrow_mut().push(*selection_len);
+                    }
+                }
+            })
+            .detach();
+            state
+        });
+
+        // Emitted once by the state change without painting, not when the selection is the same.
+        window.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                state.set_value("hello", window, cx);
+                state.set_selection(0..2, window, cx);
+            });
+        });
+        window.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                state.set_selection(0..2, window, cx);
+                cx.notify();
+            });
+        });
+        assert_eq!(*events.borrow(), vec![2]);
+    }
+
+    #[gpui::test]
+    fn test_read_only_edits_by_code(cx: &mut gpui::TestAppContext) {
+        use gpui::{AppContext as _, EntityInputHandler as _};
+
+        use super::InputState;
+
+        cx.update(|cx| crate::init(cx));
+        let window = cx.add_empty_window();
+        window.update(|window, cx| {
+            let state = cx.new(|cx| InputState::new(window, cx).read_only(true));
+            state.update(cx, |state, cx| {
+                state.set_value("foo", window, cx);
+                state.set_selection(3..3, window, cx);
+                // The user input is blocked.
+                state.replace_text_in_range(None, "x", window, cx);
+                assert_eq!(state.value(), "foo");
+
+                state.insert_at_cursor(" bar", window, cx);
+                assert_eq!(state.value(), "foo bar");
+                assert!(state.is_read_only());
+            });
+        });
+    }
+
+    #[test]
+    fn test_map_offset_for_edit() {
+        // Replace "bc" with "XYZ" in "abcdef"
+        let range = 1..3;
+        assert_eq!(map_offset_for_edit(0, &range, "XYZ"), 0);
+        assert_eq!(map_offset_for_edit(1, &range, "XYZ"), 1);
+        assert_eq!(map_offset_for_edit(2, &range, "XYZ"), 2);
+        assert_eq!(map_offset_for_edit(3, &range, "XYZ"), 4);
+        assert_eq!(map_offset_for_edit(6, &range, "XYZ"), 7);
+        // Delete "bc"
+        assert_eq!(map_offset_for_edit(2, &range, ""), 1);
+        assert_eq!(map_offset_for_edit(4, &range, ""), 2);
+    }
+
+    #[test]
+    fn test_line_range_with_newline() {
+        let text = Rope::from("foo\n中文\n\nbar");
+        assert_eq!(line_range_with_newline(&text, 0), 0..4);
+        assert_eq!(line_range_with_newline(&text, 3), 0..4);
+        assert_eq!(line_range_with_newline(&text, 4), 4..11);
+        assert_eq!(line_range_with_newline(&text, 11), 11..12);
+        // The last line has no trailing newline.
+        assert_eq!(line_range_with_newline(&text, 12), 12..15);
+        assert_eq!(line_range_with_newline(&text, 15), 12..15);
+    }
+}
@@ -0,0 +1,156 @@
+use std::ops::Range;
+
+use ropey::Rope;
+
+use crate::input::{InputState, RopeExt as _};
+
+/// The statistics of the text of the [`InputState`], see [`InputState::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DocumentStats {
+    /// The number of characters.
+    pub chars: usize,
+    /// The number of words, separated by the whitespace.
+    pub words: usize,
+    /// The number of lines, an empty text has 1 line.
+    pub lines: usize,
+    /// The size in UTF-8 bytes.
+    pub bytes: usize,
+    /// The number of the selected characters.
+    pub selection_len: usize,
+}
+
+/// The statistics of the selected text, see [`InputState::selection_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SelectionStats {
+    /// The number of the selected characters.
+    pub chars: usize,
+    /// The number of the selected words, separated by the whitespace.
+    pub words: usize,
+    /// The number of lines covered by the selection, 0 if nothing is selected.
+    ///
+    /// The last line is not counted if the selection ends at the start of it.
+    pub lines: usize,
+    /// The size of the selected text in UTF-8 bytes.
+    pub bytes: usize,
+}
+
+impl InputState {
+    /// Returns the [`DocumentStats`] of the current text.
+    ///
+    /// This counts the whole text, avoid calling it on every render for the large text.
+    pub fn stats(&self) -> DocumentStats {
+        DocumentStats {
+            chars: self.text.len_chars(),
+            words: count_words(self.text.chars()),
+            lines: self.text.lines_len(),
+            bytes: self.text.len(),
+            selection_len: self.selected_chars_len(),
+        }
+    }
+
+    /// Returns the number of the selected characters of all the selections.
+    ///
+    /// Unlike [`Self::selection_stats`], this doesn't scan the selected text,
+    /// so it's cheap to call on every render.
+    pub fn selected_chars_len(&self) -> usize {
+        self.selections()
+            .into_iter()
+            .map(|range| self.text.slice(range).len_chars())
+            .sum()
+    }
+
+    /// Returns the [`SelectionStats`] of the selected text, e.g. to show "120 chars selected".
+    pub fn selection_stats(&self) -> SelectionStats {
+        self.selections()
+            .into_iter()
+            .map(|range| range_stats(&self.text, range))
+            .fold(SelectionStats::default(), |acc, stats| SelectionStats {
+                chars: acc.chars + stats.chars,
+                words: acc.words + stats.words,
+                lines: acc.lines + stats.lines,
+                bytes: acc.bytes + stats.bytes,
+            })
+    }
+}
+
+/// Returns the [`SelectionStats`] of the text in the byte range.
+fn range_stats(text: &Rope, range: Range<usize>) -> SelectionStats {
+    if range.is_empty() {
+        return SelectionStats::default();
+    }
+
+    let slice = text.slice(range.clone());
+    let start = text.offset_to_point(range.start);
+    let end = text.offset_to_point(range.end);
+    let end_row = if end.column == 0 && end.row > start.row {
+        end.row - 1
+    } else {
+        end.row
+    };
+
+    SelectionStats {
+        chars: slice.len_chars(),
+        words: count_words(slice.chars()),
+        lines: end_row - start.row + 1,
+        bytes: range.len(),
+    }
+}
+
+/// Count the words separated by the whitespace.
+fn count_words(chars: impl Iterator<Item = char>) -> usize {
+    let mut words = 0;
+    let mut in_word = false;
+    for c in chars {
+        if c.is_whitespace() {
+            in_word = false;
+        } else if !in_word {
+            in_word = true;
+            words += 1;
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use ropey::Rope;
+
+    use super::{SelectionStats, count_words, range_stats};
+
+    #[test]
+    fn test_count_words() {
+        assert_eq!(count_words("".chars()), 0);
+        assert_eq!(count_words("  \n ".chars()), 0);
+        assert_eq!(count_words("hello".chars()), 1);
+        assert_eq!(count_words(" Hello,  world!\n\tfoo-bar 中文 ".chars()), 4);
+    }
+
+    #[test]
+    fn test_range_stats() {
+        let text = Rope::from("hello world\n你好\nfoo");
+
+        assert_eq!(range_stats(&text, 3..3), SelectionStats::default());
+        assert_eq!(
+            range_stats(&text, 0..5),
+            SelectionStats {
+                chars: 5,
+                words: 1,
+                lines: 1,
+                bytes: 5,
+            }
+        );
+        // "world\n你好"
+        assert_eq!(
+            range_stats(&text, 6..18),
+            SelectionStats {
+                chars: 8,
+                words: 2,
+                lines: 2,
+                bytes: 12,
+            }
+        );
+        // The whole first line with the line ending.
+        assert_eq!(range_stats(&text, 0..12).lines, 1);
+        assert_eq!(range_stats(&text, 0..text.len()).lines, 3);
+    }
+}
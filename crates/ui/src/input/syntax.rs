@@ -0,0 +1,164 @@
+use std::{ops::Range, rc::Rc};
+
+use gpui::SharedString;
+
+use crate::input::{InputState, mode::InputMode};
+
+/// A read-only node of the syntax tree of the code editor, see [`InputState::syntax_node_at`].
+///
+/// The node is a snapshot of the last parse, it's not updated with the text changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxNode {
+    /// The kind of the node in the grammar, e.g.: `pair`, `array`, `string` in JSON.
+    pub kind: SharedString,
+    /// The field name of the node in the parent, e.g.: `key`, `value` of the `pair` in JSON.
+    pub field_name: Option<SharedString>,
+    /// The byte range of the node in the text.
+    pub range: Range<usize>,
+    /// The index of the node in the named children of the parent, 0 for the root.
+    pub index: usize,
+    /// The parent node, `None` for the root.
+    pub parent: Option<Rc<SyntaxNode>>,
+}
+
+impl SyntaxNode {
+    /// Returns an iterator of the node and its ancestors, from the node to the root.
+    pub fn ancestors(&self) -> impl Iterator<Item = &SyntaxNode> {
+        std::iter::successors(Some(self), |node| node.parent.as_deref())
+    }
+
+    /// Returns the kinds of the ancestors from the root to the node, e.g.:
+    /// `["document", "object", "pair", "string"]`.
+    pub fn scopes(&self) -> Vec<SharedString> {
+        let mut scopes = self
+            .ancestors()
+            .map(|node| node.kind.clone())
+            .collect::<Vec<_>>();
+        scopes.reverse();
+        scopes
+    }
+
+    fn new(node: tree_sitter::Node, parent: Option<Rc<SyntaxNode>>) -> Self {
+        let (index, field_name) = child_position(node);
+        Self {
+            kind: SharedString::new_static(node.kind()),
+            field_name: field_name.map(SharedString::new_static),
+            range: node.byte_range(),
+            index,
+            parent,
+        }
+    }
+}
+
+impl From<tree_sitter::Node<'_>> for SyntaxNode {
+    fn from(node: tree_sitter::Node) -> Self {
+        let mut nodes = std::iter::successors(Some(node), |node| node.parent()).collect::<Vec<_>>();
+        let mut parent = None;
+        while nodes.len() > 1 {
+            let node = nodes.pop().unwrap();
+            parent = Some(Rc::new(SyntaxNode::new(node, parent)));
+        }
+
+        SyntaxNode::new(node, parent)
+    }
+}
+
+impl InputState {
+    /// Returns the smallest named [`SyntaxNode`] at the offset, with its ancestors.
+    ///
+    /// The offset is the UTF-8 byte offset. This uses the syntax tree of the highlighting,
+    /// returns `None` if not in [`InputMode::CodeEditor`] mode or the text is not parsed yet.
+    pub fn syntax_node_at(&self, offset: usize) -> Option<SyntaxNode> {
+        self.with_syntax_node_at(offset, SyntaxNode::from)
+    }
+
+    /// Call `f` with the smallest named node of the syntax tree at the offset.
+    pub(super) fn with_syntax_node_at<R>(
+        &self,
+        offset: usize,
+        f: impl FnOnce(tree_sitter::Node) -> R,
+    ) -> Option<R> {
+        let InputMode::CodeEditor { highlighter, .. } = &self.mode else {
+            return None;
+        };
+        let highlighter = highlighter.borrow();
+        let tree = highlighter.as_ref().and_then(|h| h.tree())?;
+
+        let offset = offset.min(self.text.len());
+        let node = tree
+            .root_node()
+            .named_descendant_for_byte_range(offset, offset)?;
+        Some(f(node))
+    }
+}
+
+/// Returns the index in the named children of the parent and the field name of the node.
+fn child_position(node: tree_sitter::Node) -> (usize, Option<&'static str>) {
+    let Some(parent) = node.parent() else {
+        return (0, None);
+    };
+
+    let mut index = 0;
+    for i in 0..parent.child_count() {
+        let Some(child) = parent.child(i) else {
+            break;
+        };
+        if child.id() == node.id() {
+            return (index, parent.field_name_for_child(i as u32));
+        }
+        if child.is_named() {
+            index += 1;
+        }
+    }
+
+    (index, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SyntaxNode;
+
+    #[test]
+    fn test_syntax_node() {
+        let text = r#"{"items": [1, {"name": "foo"}]}"#;
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_json::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(text, None).unwrap();
+
+        let offset = text.find("foo").unwrap();
+        let leaf = tree
+            .root_node()
+            .named_descendant_for_byte_range(offset, offset)
+            .unwrap();
+        let node = SyntaxNode::from(leaf);
+        assert_eq!(node.kind.as_ref(), "string_content");
+        assert_eq!(node.range, offset..offset + 3);
+        assert_eq!(
+            node.scopes().iter().map(|s| s.as_ref()).collect::<Vec<_>>(),
+            vec![
+                "document",
+                "object",
+                "pair",
+                "array",
+                "object",
+                "pair",
+                "string",
+                "string_content"
+            ]
+        );
+
+        let string = node.parent.as_ref().unwrap();
+        assert_eq!(string.field_name.as_deref(), Some("value"));
+        assert_eq!(string.index, 1);
+
+        let object = node
+            .ancestors()
+            .find(|n| n.kind.as_ref() == "object")
+            .unwrap();
+        assert_eq!(object.index, 1);
+        assert_eq!(object.field_name, None);
+        assert_eq!(node.ancestors().last().unwrap().kind.as_ref(), "document");
+    }
+}
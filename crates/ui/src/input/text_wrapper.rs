@@ -15,6 +15,8 @@ pub(super) struct LineItem {
     ///
     /// Not contains the line end `\n`.
     pub(super) wrapped_lines: Vec<Range<usize>>,
+    /// Whether this line is hidden in a fold, see [`TextWrapper::set_hidden_rows`].
+    hidden: bool,
 }
 
 impl LineItem {
@@ -24,10 +26,28 @@ impl LineItem {
         self.line.len()
     }
 
+    /// Get the original line text, without end `\n`.
+    #[inline]
+    pub(super) fn text(&self) -> &Rope {
+        &self.line
+    }
+
     /// Get number of soft wrapped lines of this line (include the first line).
+    ///
+    /// Returns 0 if the line is hidden in a fold.
     #[inline]
     pub(super) fn lines_len(&self) -> usize {
-        self.wrapped_lines.len()
+        if self.hidden {
+            0
+        } else {
+            self.wrapped_lines.len()
+        }
+    }
+
+    /// Return true if this line is hidden in a fold.
+    #[inline]
+    pub(super) fn is_hidden(&self) -> bool {
+        self.hidden
     }
 
     /// Get the height of this line item with given line height.
@@ -59,6 +79,8 @@ pub(super) struct TextWrapper {
     pub(super) longest_row: LongestRow,
     /// The lines by split \n
     pub(super) lines: Vec<LineItem>,
+    /// The rows (0-based) hidden in the folds.
+    hidden_rows: Vec<Range<usize>>,
 
     _initialized: bool,
 }
@@ -74,6 +96,7 @@ impl TextWrapper {
             soft_lines: 0,
             longest_row: LongestRow::default(),
             lines: Vec::new(),
+            hidden_rows: Vec::new(),
             _initialized: false,
         }
     }
@@ -114,6 +137,12 @@ impl TextWrapper {
         self.update_all(&self.text.clone(), cx);
     }
 
+    /// Get the font and font size used to layout the text.
+    #[inline]
+    pub(super) fn font(&self) -> (&Font, Pixels) {
+        (&self.font, self.font_size)
+    }
+
     pub(super) fn prepare_if_need(&mut self, text: &Rope, cx: &mut App) {
         if self._initialized {
             return;
@@ -219,6 +248,7 @@ impl TextWrapper {
             new_lines.push(LineItem {
                 line: Rope::from(line),
                 wrapped_lines,
+                hidden: false,
             });
         }
 
@@ -229,11 +259,32 @@ impl TextWrapper {
         }
 
         self.text = changed_text.clone();
-        self.soft_lines = self.lines.iter().map(|l| l.lines_len()).sum();
         self.longest_row = LongestRow {
             row: longest_row_ix,
             len: longest_row_len,
+        };
+        self.apply_hidden_rows();
+    }
+
+    /// Set the rows (0-based) to hide, they are not displayed and take no height.
+    ///
+    /// The rows are kept after the text changed, update them if the rows are moved by the edit.
+    pub(super) fn set_hidden_rows(&mut self, rows: Vec<Range<usize>>) {
+        self.hidden_rows = rows;
+        self.apply_hidden_rows();
+    }
+
+    fn apply_hidden_rows(&mut self) {
+        for line in self.lines.iter_mut() {
+            line.hidden = false;
+        }
+        for rows in &self.hidden_rows {
+            let end = rows.end.min(self.lines.len());
+            for line in self.lines[rows.start.min(end)..end].iter_mut() {
+                line.hidden = true;
+            }
         }
+        self.soft_lines = self.lines.iter().map(|l| l.lines_len()).sum();
     }
 
     /// Update the text wrapper and recalculate the wrapped lines.
@@ -353,6 +404,13 @@ impl LineLayout {
         }
     }
 
+    /// Create an empty layout for the line hidden in a fold.
+    ///
+    /// It has no wrapped lines to paint, only keeps the bytes length of the line to map the offsets.
+    pub(crate) fn hidden(len: usize) -> Self {
+        Self { len, ..Self::new() }
+    }
+
     pub(crate) fn lines(mut self, wrapped_lines: SmallVec<[ShapedLine; 1]>) -> Self {
         self.set_wrapped_lines(wrapped_lines);
         self
@@ -699,6 +757,54 @@ mod tests {
         assert_eq!(wrapper.lines.len(), 2);
     }
 
+    #[test]
+    fn test_hidden_rows() {
+        let font = gpui::Font {
+            family: "Arial".into(),
+            weight: FontWeight::default(),
+            style: FontStyle::Normal,
+            features: FontFeatures::default(),
+            fallbacks: None,
+        };
+
+        fn fake_wrap_line(_line: &str, _wrap_width: Pixels) -> Vec<Boundary> {
+            vec![]
+        }
+
+        let mut wrapper = TextWrapper::new(font, px(14.), None);
+        let mut text = Rope::from("{\n  \"a\": 1,\n  \"b\": 2\n}\nend");
+        wrapper._update(&text, &(0..text.len()), &text, &mut fake_wrap_line);
+        assert_eq!(wrapper.len(), 5);
+
+        wrapper.set_hidden_rows(vec![1..4]);
+        assert_eq!(wrapper.len(), 2);
+        assert!(!wrapper.lines[0].is_hidden());
+        assert!(wrapper.lines[1].is_hidden());
+        assert!(wrapper.lines[3].is_hidden());
+        assert_eq!(wrapper.lines[3].lines_len(), 0);
+
+        // The row after the hidden rows is the next display row.
+        let end_offset = text.line_start_offset(4);
+        assert_eq!(
+            wrapper.offset_to_display_point(end_offset),
+            DisplayPoint::new(1, 0, 0)
+        );
+        assert_eq!(
+            wrapper.display_point_to_offset(DisplayPoint::new(1, 0, 0)),
+            end_offset
+        );
+
+        // The hidden rows are kept after the text changed.
+        let range = text.len()..text.len();
+        text.replace(range.clone(), "\nmore");
+        wrapper._update(&text, &range, &Rope::from("\nmore"), &mut fake_wrap_line);
+        assert_eq!(wrapper.len(), 3);
+        assert!(wrapper.lines[2].is_hidden());
+
+        wrapper.set_hidden_rows(vec![]);
+        assert_eq!(wrapper.len(), 6);
+    }
+
     #[test]
     fn test_line_layout() {
         let mut line_layout = LineLayout::new();
@@ -730,21 +836,25 @@ mod tests {
             LineItem {
                 line: Rope::from("Hello, 世界!\r"),
                 wrapped_lines: vec![0..15],
+                hidden: false,
             },
             // range: 16..36
             LineItem {
                 line: Rope::from("This is second line."),
                 wrapped_lines: vec![0..10, 10..20],
+                hidden: false,
             },
             // range: 37..56
             LineItem {
                 line: Rope::from("This is third line."),
                 wrapped_lines: vec![0..9, 9..15, 15..20],
+                hidden: false,
             },
             // range: 57..79
             LineItem {
                 line: Rope::from("这里是第 4 行。"),
                 wrapped_lines: vec![0..22],
+                hidden: false,
             },
         ];
 
@@ -0,0 +1,126 @@
+use std::ops::Range;
+
+use gpui::{Context, Pixels, Point, Window, px};
+use serde::{Deserialize, Serialize};
+use sum_tree::Bias;
+
+use crate::input::{InputState, Rope, RopeExt as _};
+
+/// A serializable view state of the [`InputState`], include the selection, scroll offset and folds.
+///
+/// Use [`InputState::save_view_state`] to save and [`InputState::restore_view_state`] to restore,
+/// for example to keep the cursor and scroll of each document when switching tabs,
+/// or persist it to disk across sessions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EditorViewState {
+    /// The UTF-8 byte range of the selection, from the anchor to the cursor.
+    ///
+    /// The `start` is greater than the `end` if the selection is reversed,
+    /// an empty range is a cursor without selection.
+    pub selection: Range<usize>,
+    /// The scroll offset, the `y` is negative when scrolled down.
+    #[serde(default)]
+    pub scroll_offset: Point<Pixels>,
+    /// The folded UTF-8 byte ranges, see [`InputState::folded_ranges`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub folds: Vec<Range<usize>>,
+}
+
+impl EditorViewState {
+    /// Returns the UTF-8 byte offset of the cursor.
+    pub fn cursor(&self) -> usize {
+        self.selection.end
+    }
+}
+
+impl InputState {
+    /// Save the [`EditorViewState`] of the cursor, selection, scroll offset and folds.
+    pub fn save_view_state(&self) -> EditorViewState {
+        let range: Range<usize> = self.selected_range.into();
+        let selection = if self.selection_reversed {
+            range.end..range.start
+        } else {
+            range
+        };
+
+        EditorViewState {
+            selection,
+            scroll_offset: self.scroll_handle.offset(),
+            folds: self.folded_ranges().to_vec(),
+        }
+    }
+
+    /// Restore the [`EditorViewState`] saved by [`InputState::save_view_state`].
+    ///
+    /// If the text is changed after saving, the offsets are clamped to the text, and the
+    /// invalid folds are ignored. The folds that hide the cursor are unfolded.
+    pub fn restore_view_state(
+        &mut self,
+        state: EditorViewState,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.is_folding_enabled() {
+            self.restore_fold_ranges(state.folds, cx);
+        }
+
+        let (range, reversed) = clamp_selection(&self.text, state.selection);
+        self.selected_range = range.into();
+        self.selection_reversed = reversed;
+        self.selected_word_range = None;
+        self.update_preferred_column();
+        self.reveal_cursor_in_folds();
+
+        let mut scroll_offset = state.scroll_offset;
+        scroll_offset.x = scroll_offset.x.min(px(0.));
+        scroll_offset.y = scroll_offset.y.min(px(0.));
+        self.restore_scroll_offset(scroll_offset);
+        cx.notify();
+    }
+}
+
+/// Clamp the selection from the anchor to the cursor to the text,
+/// returns the sorted range and whether it's reversed.
+fn clamp_selection(text: &Rope, selection: Range<usize>) -> (Range<usize>, bool) {
+    let anchor = text.clip_offset(selection.start, Bias::Left);
+    let cursor = text.clip_offset(selection.end, Bias::Left);
+    (anchor.min(cursor)..anchor.max(cursor), anchor > cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{point, px};
+
+    use super::{EditorViewState, clamp_selection};
+    use crate::input::Rope;
+
+    #[test]
+    fn test_clamp_selection() {
+        let text = Rope::from("hello 中文");
+        assert_eq!(clamp_selection(&text, 1..3), (1..3, false));
+        assert_eq!(clamp_selection(&text, 3..1), (1..3, true));
+        assert_eq!(clamp_selection(&text, 2..2), (2..2, false));
+        // Clamp to the char boundary and the text length.
+        assert_eq!(clamp_selection(&text, 7..100), (6..12, false));
+        assert_eq!(clamp_selection(&text, 100..200), (12..12, false));
+    }
+
+    #[test]
+    fn test_view_state_serde() {
+        let state = EditorViewState {
+            selection: 10..2,
+            scroll_offset: point(px(0.), px(-120.)),
+            folds: vec![4..20],
+        };
+        assert_eq!(state.cursor(), 2);
+
+        let json = serde_json::to_string(&state).unwrap();
+        let restored: EditorViewState = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, state);
+
+        let restored: EditorViewState =
+            serde_json::from_str(r#"{"selection":{"start":1,"end":1}}"#).unwrap();
+        assert_eq!(restored.selection, 1..1);
+        assert!(restored.folds.is_empty());
+    }
+}
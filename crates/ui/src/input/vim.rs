@@ -0,0 +1,922 @@
+use std::ops::Range;
+
+use gpui::{Context, KeyDownEvent, Keystroke, Window};
+use ropey::Rope;
+use sum_tree::Bias;
+
+use crate::input::{InputEvent, InputState, RopeExt as _, Search};
+
+/// The mode of the Vim keybindings, see [`InputState::vim_mode`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VimMode {
+    /// The keys are the motions and commands, nothing is inserted.
+    #[default]
+    Normal,
+    /// The keys are inserted like a normal text field, press `Escape` to back to normal.
+    Insert,
+    /// The motions extend the selection, and the operators apply to the selection.
+    Visual,
+}
+
+impl VimMode {
+    /// Returns the label of the mode to show in the status bar, e.g. `NORMAL`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            VimMode::Normal => "NORMAL",
+            VimMode::Insert => "INSERT",
+            VimMode::Visual => "VISUAL",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(super) struct VimState {
+    mode: VimMode,
+    /// The keys of the incomplete command, e.g. `d`, `g`.
+    pending: String,
+    /// The last deleted or yanked text, and whether it is whole lines.
+    register: Option<(String, bool)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Motion {
+    /// `h`
+    Left,
+    /// `l`
+    Right,
+    /// `k`
+    Up,
+    /// `j`
+    Down,
+    /// `w`
+    NextWordStart,
+    /// `b`
+    PreviousWordStart,
+    /// `e`
+    WordEnd,
+    /// `0`
+    LineStart,
+    /// `$`
+    LineEnd,
+    /// `gg`
+    FirstLine,
+    /// `G`
+    LastLine,
+}
+
+impl Motion {
+    fn parse(keys: &str) -> Parsed<Self> {
+        Parsed::Done(match keys {
+            "h" => Motion::Left,
+            "l" => Motion::Right,
+            "k" => Motion::Up,
+            "j" => Motion::Down,
+            "w" => Motion::NextWordStart,
+            "b" => Motion::PreviousWordStart,
+            "e" => Motion::WordEnd,
+            "0" => Motion::LineStart,
+            "$" => Motion::LineEnd,
+            "gg" => Motion::FirstLine,
+            "G" => Motion::LastLine,
+            "g" => return Parsed::Pending,
+            _ => return Parsed::Invalid,
+        })
+    }
+
+    /// The operators with the linewise motions apply to the whole lines.
+    fn is_linewise(&self) -> bool {
+        matches!(
+            self,
+            Motion::Up | Motion::Down | Motion::FirstLine | Motion::LastLine
+        )
+    }
+
+    /// The operators with the inclusive motions include the char at the target.
+    fn is_inclusive(&self) -> bool {
+        matches!(self, Motion::WordEnd)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    /// `d`
+    Delete,
+    /// `c`
+    Change,
+    /// `y`
+    Yank,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InsertPosition {
+    /// `i`
+    Before,
+    /// `a`
+    After,
+    /// `I`
+    LineStart,
+    /// `A`
+    LineEnd,
+    /// `o`
+    LineBelow,
+    /// `O`
+    LineAbove,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Move(Motion),
+    /// The operator with a motion, e.g. `dw`.
+    Operate(Operator, Motion),
+    /// The operator on the current line, e.g. `dd`.
+    OperateLine(Operator),
+    /// The operator on the visual selection.
+    OperateSelection(Operator),
+    Insert(InsertPosition),
+    /// `x`
+    DeleteChar,
+    /// `p` or `P`
+    Paste {
+        before: bool,
+    },
+    /// `u`
+    Undo,
+    /// `ctrl-r`
+    Redo,
+    /// `v`
+    ToggleVisual,
+    /// `/`
+    Search,
+    /// `n`
+    NextMatch,
+    /// `N`
+    PreviousMatch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Parsed<T> {
+    /// Wait for more keys.
+    Pending,
+    Invalid,
+    Done(T),
+}
+
+impl<T> Parsed<T> {
+    fn map<U>(self, f: impl FnOnce(T) -> U) -> Parsed<U> {
+        match self {
+            Parsed::Pending => Parsed::Pending,
+            Parsed::Invalid => Parsed::Invalid,
+            Parsed::Done(value) => Parsed::Done(f(value)),
+        }
+    }
+}
+
+impl Command {
+    /// Parse the command from the keys typed in the normal or visual mode.
+    fn parse(keys: &str, visual: bool) -> Parsed<Self> {
+        if let Some(key) = keys.strip_prefix("ctrl-") {
+            return match key {
+                "r" if !visual => Parsed::Done(Command::Redo),
+                _ => Parsed::Invalid,
+            };
+        }
+
+        let operator = match keys.chars().next() {
+            Some('d') => Some(Operator::Delete),
+            Some('c') => Some(Operator::Change),
+            Some('y') => Some(Operator::Yank),
+            _ => None,
+        };
+
+        if let Some(operator) = operator {
+            if visual {
+                return Parsed::Done(Command::OperateSelection(operator));
+            }
+
+            let motion = &keys[1..];
+            return match motion {
+                "" => Parsed::Pending,
+                _ if motion == &keys[..1] => Parsed::Done(Command::OperateLine(operator)),
+                // Like Vim, `cw` changes to the end of the word, same as `ce`.
+                "w" if operator == Operator::Change => {
+                    Parsed::Done(Command::Operate(operator, Motion::WordEnd))
+                }
+                _ => Motion::parse(motion).map(|motion| Command::Operate(operator, motion)),
+            };
+        }
+
+        let command = match keys {
+            "x" if visual => Command::OperateSelection(Operator::Delete),
+            "x" => Command::DeleteChar,
+            "v" => Command::ToggleVisual,
+            "/" => Command::Search,
+            "n" => Command::NextMatch,
+            "N" => Command::PreviousMatch,
+            _ if visual => return Motion::parse(keys).map(Command::Move),
+            "i" => Command::Insert(InsertPosition::Before),
+            "a" => Command::Insert(InsertPosition::After),
+            "I" => Command::Insert(InsertPosition::LineStart),
+            "A" => Command::Insert(InsertPosition::LineEnd),
+            "o" => Command::Insert(InsertPosition::LineBelow),
+            "O" => Command::Insert(InsertPosition::LineAbove),
+            "p" => Command::Paste { before: false },
+            "P" => Command::Paste { before: true },
+            "u" => Command::Undo,
+            _ => return Motion::parse(keys).map(Command::Move),
+        };
+
+        Parsed::Done(command)
+    }
+}
+
+impl InputState {
+    /// Set true to enable the Vim keybindings with the normal, insert and visual modes,
+    /// default is false.
+    ///
+    /// The input starts in the normal mode, use [`InputState::vim_mode_state`] to get the current mode.
+    ///
+    /// Only for the multi-line input.
+    pub fn vim_mode(mut self, enabled: bool) -> Self {
+        debug_assert!(self.mode.is_multi_line());
+        self.vim = enabled.then(VimState::default);
+        self
+    }
+
+    /// Update the Vim keybindings enabled or not.
+    ///
+    /// See also: [`InputState::vim_mode`].
+    pub fn set_vim_mode(&mut self, enabled: bool, _: &mut Window, cx: &mut Context<Self>) {
+        if self.vim.is_some() == enabled {
+            return;
+        }
+
+        self.vim = enabled.then(VimState::default);
+        if enabled {
+            cx.emit(InputEvent::VimModeChanged {
+                mode: VimMode::Normal,
+            });
+        }
+        cx.notify();
+    }
+
+    /// Returns the current Vim mode, `None` if the Vim keybindings is not enabled.
+    pub fn vim_mode_state(&self) -> Option<VimMode> {
+        self.vim.as_ref().map(|vim| vim.mode)
+    }
+
+    /// Returns true if the Vim keybindings is in the normal or visual mode, the keys are not inserted.
+    pub(super) fn is_vim_command_mode(&self) -> bool {
+        self.vim
+            .as_ref()
+            .is_some_and(|vim| vim.mode != VimMode::Insert)
+    }
+
+    /// Handle the key down in the normal and visual mode, returns true if the key is consumed.
+    pub(super) fn on_vim_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(key) = vim_key(&event.keystroke) else {
+            return false;
+        };
+
+        self.handle_vim_key(&key, window, cx)
+    }
+
+    /// Handle the key in the normal and visual mode, returns false in the insert mode.
+    ///
+    /// This also used by the actions bound to the keys, e.g. `enter` is `j` in the normal mode.
+    pub(super) fn handle_vim_key(
+        &mut self,
+        key: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(vim) = self.vim.as_mut() else {
+            return false;
+        };
+        if vim.mode == VimMode::Insert {
+            return false;
+        }
+
+        let keys = std::mem::take(&mut vim.pending) + key;
+        match Command::parse(&keys, vim.mode == VimMode::Visual) {
+            Parsed::Pending => vim.pending = keys,
+            Parsed::Invalid => {}
+            Parsed::Done(command) => self.run_vim_command(command, window, cx),
+        }
+        true
+    }
+
+    /// Leave the insert or visual mode, returns false if there is nothing to leave.
+    pub(super) fn vim_escape(&mut self, cx: &mut Context<Self>) -> bool {
+        let Some(vim) = self.vim.as_ref() else {
+            return false;
+        };
+        if vim.mode == VimMode::Normal && vim.pending.is_empty() {
+            return false;
+        }
+
+        self.switch_vim_mode(VimMode::Normal, cx);
+        true
+    }
+
+    fn switch_vim_mode(&mut self, mode: VimMode, cx: &mut Context<Self>) {
+        let Some(vim) = self.vim.as_mut() else {
+            return;
+        };
+        vim.pending.clear();
+        if vim.mode == mode {
+            return;
+        }
+
+        let prev_mode = std::mem::replace(&mut vim.mode, mode);
+        match prev_mode {
+            VimMode::Visual => {
+                let (_, head) = self.vim_visual_range();
+                self.move_to(head, None, cx);
+            }
+            // Like Vim, move the cursor back onto the last inserted char.
+            VimMode::Insert => {
+                let offset = self.cursor();
+                if offset > self.start_of_line() {
+                    self.move_to(self.previous_boundary(offset), None, cx);
+                }
+            }
+            VimMode::Normal => {}
+        }
+
+        cx.emit(InputEvent::VimModeChanged { mode });
+        cx.notify();
+    }
+
+    /// Returns the (anchor, head) of the visual selection, the head is the char under the cursor.
+    fn vim_visual_range(&self) -> (usize, usize) {
+        let range = self.selected_range;
+        if range.is_empty() {
+            return (range.start, range.start);
+        }
+
+        let last = self.previous_boundary(range.end);
+        if self.selection_reversed {
+            (last, range.start)
+        } else {
+            (range.start, last)
+        }
+    }
+
+    /// Select from the `anchor` to the `head`, both chars are included.
+    fn vim_select(&mut self, anchor: usize, head: usize, cx: &mut Context<Self>) {
+        let start = anchor.min(head);
+        let end = self.next_boundary(anchor.max(head));
+        self.selected_range = (start..end).into();
+        self.selection_reversed = head < anchor;
+        self.scroll_to(self.cursor(), None, cx);
+        cx.notify();
+    }
+
+    fn vim_motion_target(&self, motion: Motion, head: usize) -> usize {
+        let text = &self.text;
+        let point = text.offset_to_point(head);
+        match motion {
+            Motion::Left if point.column > 0 => self.previous_boundary(head),
+            Motion::Right if head < text.line_end_offset(point.row) => self.next_boundary(head),
+            Motion::Left | Motion::Right => head,
+            Motion::Up | Motion::Down => {
+                let row = if motion == Motion::Up {
+                    point.row.saturating_sub(1)
+                } else {
+                    (point.row + 1).min(text.lines_len().saturating_sub(1))
+                };
+                let column = self
+                    .preferred_column
+                    .map_or(point.column, |(_, column)| column);
+                let offset = text.line_start_offset(row) + column.min(text.line_len(row));
+                text.clip_offset(offset, Bias::Left)
+            }
+            Motion::NextWordStart => next_word_start(text, head),
+            Motion::PreviousWordStart => previous_word_start(text, head),
+            Motion::WordEnd => word_end(text, head),
+            Motion::LineStart => text.line_start_offset(point.row),
+            Motion::LineEnd => text.line_end_offset(point.row),
+            Motion::FirstLine => 0,
+            Motion::LastLine => text.line_start_offset(text.lines_len().saturating_sub(1)),
+        }
+    }
+
+    /// Replace the text in the range, the cursor is moved to the end of the new text.
+    fn vim_replace(
+        &mut self,
+        range: Range<usize>,
+        new_text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let range_utf16 = self.range_to_utf16(&range);
+        self.selection_reversed = false;
+        self.replace_text_in_range_silent(Some(range_utf16), new_text, window, cx);
+    }
+
+    /// Move the cursor in the normal mode, it stays on a char if the line is not empty.
+    fn vim_move_to(&mut self, offset: usize, cx: &mut Context<Self>) {
+        self.selection_reversed = false;
+        self.move_to(clamp_to_char(&self.text, offset), None, cx);
+    }
+
+    fn run_vim_command(&mut self, command: Command, window: &mut Window, cx: &mut Context<Self>) {
+        let visual = self.vim_mode_state() == Some(VimMode::Visual);
+        let editable = !self.read_only && !self.disabled;
+        let (anchor, head) = if visual {
+            self.vim_visual_range()
+        } else {
+            (self.cursor(), self.cursor())
+        };
+        let row = self.text.offset_to_point(head).row;
+
+        match command {
+            Command::Move(motion) => {
+                let target = self.vim_motion_target(motion, head);
+                if visual {
+                    self.vim_select(anchor, target, cx);
+                } else {
+                    let preferred_column = self.preferred_column;
+                    self.vim_move_to(target, cx);
+                    if matches!(motion, Motion::Up | Motion::Down) && preferred_column.is_some() {
+                        self.preferred_column = preferred_column;
+                    }
+                }
+            }
+            Command::Operate(operator, motion) => {
+                let target = self.vim_motion_target(motion, head);
+                if motion.is_linewise() {
+                    let target_row = self.text.offset_to_point(target).row;
+                    self.vim_operate_lines(operator, row, target_row, window, cx);
+                } else {
+                    let mut range = head.min(target)..head.max(target);
+                    if motion.is_inclusive() {
+                        range.end = self.next_boundary(range.end);
+                    }
+                    // Like Vim, `dw` on the last word doesn't join the next line.
+                    if motion == Motion::NextWordStart {
+                        range.end = range
+                            .end
+                            .min(self.text.line_end_offset(row))
+                            .max(range.start);
+                    }
+                    self.vim_operate(operator, range, window, cx);
+                }
+            }
+            Command::OperateLine(operator) => {
+                self.vim_operate_lines(operator, row, row, window, cx);
+            }
+            Command::OperateSelection(operator) => {
+                let range = self.selected_range.into();
+                self.switch_vim_mode(VimMode::Normal, cx);
+                self.vim_operate(operator, range, window, cx);
+            }
+            Command::DeleteChar => {
+                if head < self.text.line_end_offset(row) {
+                    let range = head..self.next_boundary(head);
+                    self.vim_operate(Operator::Delete, range, window, cx);
+                }
+            }
+            Command::Insert(position) => {
+                let line_start = self.text.line_start_offset(row);
+                let line_end = self.text.line_end_offset(row);
+                let indent = line_indent(&self.text, row);
+                match position {
+                    InsertPosition::Before => {}
+                    InsertPosition::After => {
+                        if head < line_end {
+                            self.move_to(self.next_boundary(head), None, cx);
+                        }
+                    }
+                    InsertPosition::LineStart => {
+                        self.move_to(line_start + indent.len(), None, cx);
+                    }
+                    InsertPosition::LineEnd => self.move_to(line_end, None, cx),
+                    InsertPosition::LineBelow if editable => {
+                        self.vim_replace(line_end..line_end, &format!("\n{}", indent), window, cx);
+                    }
+                    InsertPosition::LineAbove if editable => {
+                        let new_text = format!("{}\n", indent);
+                        self.vim_replace(line_start..line_start, &new_text, window, cx);
+                        self.move_to(line_start + indent.len(), None, cx);
+                    }
+                    InsertPosition::LineBelow | InsertPosition::LineAbove => return,
+                }
+                self.switch_vim_mode(VimMode::Insert, cx);
+            }
+            Command::Paste { before } => {
+                let Some((text, linewise)) = self.vim.as_ref().and_then(|vim| vim.register.clone())
+                else {
+                    return;
+                };
+                if !editable {
+                    return;
+                }
+
+                if linewise {
+                    let lines_len = self.text.lines_len();
+                    let offset = if before {
+                        self.text.line_start_offset(row)
+                    } else if row + 1 < lines_len {
+                        self.text.line_start_offset(row + 1)
+                    } else {
+                        self.text.len()
+                    };
+                    // Paste after the last line without the trailing newline.
+                    let new_text = if offset == self.text.len() && !before {
+                        format!("\n{}", text.trim_end_matches('\n'))
+                    } else {
+                        text
+                    };
+                    self.vim_replace(offset..offset, &new_text, window, cx);
+                    let pasted_row = if before { row } else { row + 1 };
+                    let offset = self.text.line_start_offset(pasted_row);
+                    self.vim_move_to(offset, cx);
+                } else {
+                    let offset = if before {
+                        head
+                    } else {
+                        self.next_boundary(head).min(self.text.line_end_offset(row))
+                    };
+                    self.vim_replace(offset..offset, &text, window, cx);
+                    // Stay on the last pasted char.
+                    let offset = self.previous_boundary(offset + text.len());
+                    self.vim_move_to(offset, cx);
+                }
+            }
+            Command::Undo => {
+                self.undo(window, cx);
+                self.vim_move_to(self.cursor(), cx);
+            }
+            Command::Redo => {
+                self.redo(window, cx);
+                self.vim_move_to(self.cursor(), cx);
+            }
+            Command::ToggleVisual => {
+                if visual {
+                    self.switch_vim_mode(VimMode::Normal, cx);
+                } else {
+                    self.switch_vim_mode(VimMode::Visual, cx);
+                    self.vim_select(head, head, cx);
+                }
+            }
+            Command::Search => {
+                self.on_action_search(&Search, window, cx);
+            }
+            Command::NextMatch | Command::PreviousMatch => {
+                let range = if command == Command::NextMatch {
+                    self.find_next(window, cx)
+                } else {
+                    self.find_prev(window, cx)
+                };
+                let Some(range) = range else {
+                    return;
+                };
+
+                if visual {
+                    self.vim_select(anchor, range.start, cx);
+                } else {
+                    self.vim_move_to(range.start, cx);
+                }
+            }
+        }
+    }
+
+    fn vim_operate(
+        &mut self,
+        operator: Operator,
+        range: Range<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if range.is_empty() {
+            return;
+        }
+
+        let text = self.text.slice(range.clone()).to_string();
+        if let Some(vim) = self.vim.as_mut() {
+            vim.register = Some((text, false));
+        }
+
+        match operator {
+            Operator::Yank => self.vim_move_to(range.start, cx),
+            Operator::Delete => {
+                self.vim_replace(range, "", window, cx);
+                self.vim_move_to(self.cursor(), cx);
+            }
+            Operator::Change => {
+                self.vim_replace(range, "", window, cx);
+                self.switch_vim_mode(VimMode::Insert, cx);
+            }
+        }
+    }
+
+    /// Apply the operator to the whole lines between the rows (inclusive).
+    fn vim_operate_lines(
+        &mut self,
+        operator: Operator,
+        row: usize,
+        other_row: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (first_row, last_row) = (row.min(other_row), row.max(other_row));
+        let content_range =
+            self.text.line_start_offset(first_row)..self.text.line_end_offset(last_row);
+        let text = self.text.slice(content_range.clone()).to_string() + "\n";
+        if let Some(vim) = self.vim.as_mut() {
+            vim.register = Some((text, true));
+        }
+
+        match operator {
+            Operator::Yank => {}
+            Operator::Delete => {
+                let range = lines_range(&self.text, first_row, last_row);
+                self.vim_replace(range, "", window, cx);
+                let row = first_row.min(self.text.lines_len().saturating_sub(1));
+                let offset = self.text.line_start_offset(row) + line_indent(&self.text, row).len();
+                self.vim_move_to(offset, cx);
+            }
+            Operator::Change => {
+                let indent = line_indent(&self.text, first_row);
+                self.vim_replace(content_range, &indent, window, cx);
+                self.switch_vim_mode(VimMode::Insert, cx);
+            }
+        }
+    }
+}
+
+/// Returns the key for the Vim command, `None` for the keys with the `cmd`, `alt` modifiers
+/// or without a char, e.g. `escape`.
+fn vim_key(keystroke: &Keystroke) -> Option<String> {
+    let modifiers = &keystroke.modifiers;
+    if modifiers.platform || modifiers.alt || modifiers.function {
+        return None;
+    }
+    if modifiers.control {
+        return Some(format!("ctrl-{}", keystroke.key));
+    }
+
+    keystroke.key_char.clone()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharKind {
+    Whitespace,
+    Word,
+    Punctuation,
+}
+
+impl From<char> for CharKind {
+    fn from(c: char) -> Self {
+        if c.is_whitespace() {
+            CharKind::Whitespace
+        } else if c.is_alphanumeric() || c == '_' {
+            CharKind::Word
+        } else {
+            CharKind::Punctuation
+        }
+    }
+}
+
+/// The `w` motion, returns the start of the next word.
+fn next_word_start(text: &Rope, offset: usize) -> usize {
+    let mut offset = offset;
+    let mut chars = text.chars_at(offset).peekable();
+    if let Some(kind) = chars.peek().map(|c| CharKind::from(*c)) {
+        if kind != CharKind::Whitespace {
+            while let Some(c) = chars.next_if(|c| CharKind::from(*c) == kind) {
+                offset += c.len_utf8();
+            }
+        }
+    }
+    while let Some(c) = chars.next_if(|c| CharKind::from(*c) == CharKind::Whitespace) {
+        offset += c.len_utf8();
+    }
+
+    offset
+}
+
+/// The `b` motion, returns the start of the current or previous word.
+fn previous_word_start(text: &Rope, offset: usize) -> usize {
+    let mut offset = offset;
+    let mut chars = text.chars_at(offset).reversed().peekable();
+    while let Some(c) = chars.next_if(|c| CharKind::from(*c) == CharKind::Whitespace) {
+        offset -= c.len_utf8();
+    }
+    if let Some(kind) = chars.peek().map(|c| CharKind::from(*c)) {
+        while let Some(c) = chars.next_if(|c| CharKind::from(*c) == kind) {
+            offset -= c.len_utf8();
+        }
+    }
+
+    offset
+}
+
+/// The `e` motion, returns the start of the last char of the current or next word.
+fn word_end(text: &Rope, offset: usize) -> usize {
+    let mut chars = text.chars_at(offset).peekable();
+    let Some(first) = chars.next() else {
+        return offset;
+    };
+
+    let mut end = offset + first.len_utf8();
+    while let Some(c) = chars.next_if(|c| CharKind::from(*c) == CharKind::Whitespace) {
+        end += c.len_utf8();
+    }
+
+    let mut last = offset;
+    if let Some(kind) = chars.peek().map(|c| CharKind::from(*c)) {
+        while let Some(c) = chars.next_if(|c| CharKind::from(*c) == kind) {
+            last = end;
+            end += c.len_utf8();
+        }
+    }
+
+    last
+}
+
+/// Clamp the offset onto the last char, if it's at the end of a non-empty line.
+fn clamp_to_char(text: &Rope, offset: usize) -> usize {
+    let row = text.offset_to_point(offset).row;
+    let line_start = text.line_start_offset(row);
+    let line_end = text.line_end_offset(row);
+    if offset >= line_end && line_end > line_start {
+        text.clip_offset(line_end - 1, Bias::Left)
+    } else {
+        offset
+    }
+}
+
+/// Returns the range to delete the lines between the rows (inclusive),
+/// the newline before is included if the last line is included.
+fn lines_range(text: &Rope, first_row: usize, last_row: usize) -> Range<usize> {
+    let start = text.line_start_offset(first_row);
+    let end = text.line_end_offset(last_row);
+    if end < text.len() {
+        start..end + 1
+    } else if start > 0 {
+        start - 1..end
+    } else {
+        start..end
+    }
+}
+
+/// Returns the leading whitespace of the line.
+fn line_indent(text: &Rope, row: usize) -> String {
+    text.slice_line(row)
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ropey::Rope;
+
+    use super::{
+        Command, InsertPosition, Motion, Operator, Parsed, clamp_to_char, lines_range,
+        next_word_start, previous_word_start, word_end,
+    };
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            Command::parse("w", false),
+            Parsed::Done(Command::Move(Motion::NextWordStart))
+        );
+        assert_eq!(Command::parse("g", false), Parsed::Pending);
+        assert_eq!(
+            Command::parse("gg", false),
+            Parsed::Done(Command::Move(Motion::FirstLine))
+        );
+        assert_eq!(Command::parse("d", false), Parsed::Pending);
+        assert_eq!(Command::parse("dg", false), Parsed::Pending);
+        assert_eq!(
+            Command::parse("dgg", false),
+            Parsed::Done(Command::Operate(Operator::Delete, Motion::FirstLine))
+        );
+        assert_eq!(
+            Command::parse("ce", false),
+            Parsed::Done(Command::Operate(Operator::Change, Motion::WordEnd))
+        );
+        assert_eq!(
+            Command::parse("cw", false),
+            Parsed::Done(Command::Operate(Operator::Change, Motion::WordEnd))
+        );
+        assert_eq!(
+            Command::parse("dw", false),
+            Parsed::Done(Command::Operate(Operator::Delete, Motion::NextWordStart))
+        );
+        assert_eq!(
+            Command::parse("yy", false),
+            Parsed::Done(Command::OperateLine(Operator::Yank))
+        );
+        assert_eq!(Command::parse("dy", false), Parsed::Invalid);
+        assert_eq!(
+            Command::parse("O", false),
+            Parsed::Done(Command::Insert(InsertPosition::LineAbove))
+        );
+        assert_eq!(Command::parse("ctrl-r", false), Parsed::Done(Command::Redo));
+        assert_eq!(Command::parse("ctrl-x", true), Parsed::Invalid);
+        assert_eq!(Command::parse("q", false), Parsed::Invalid);
+
+        // Visual mode
+        assert_eq!(
+            Command::parse("d", true),
+            Parsed::Done(Command::OperateSelection(Operator::Delete))
+        );
+        assert_eq!(
+            Command::parse("x", true),
+            Parsed::Done(Command::OperateSelection(Operator::Delete))
+        );
+        assert_eq!(
+            Command::parse("$", true),
+            Parsed::Done(Command::Move(Motion::LineEnd))
+        );
+        assert_eq!(Command::parse("i", true), Parsed::Invalid);
+        assert_eq!(
+            Command::parse("v", true),
+            Parsed::Done(Command::ToggleVisual)
+        );
+    }
+
+    #[test]
+    fn test_word_motions() {
+        let text = Rope::from("let foo_bar = baz(1);\n  中文 end");
+
+        assert_eq!(next_word_start(&text, 0), 4);
+        assert_eq!(next_word_start(&text, 5), 12);
+        assert_eq!(next_word_start(&text, 14), 17);
+        assert_eq!(next_word_start(&text, 20), 24);
+        assert_eq!(next_word_start(&text, 24), 31);
+        assert_eq!(next_word_start(&text, text.len()), text.len());
+
+        assert_eq!(previous_word_start(&text, 12), 4);
+        assert_eq!(previous_word_start(&text, 6), 4);
+        assert_eq!(previous_word_start(&text, 24), 19);
+        assert_eq!(previous_word_start(&text, 31), 24);
+        assert_eq!(previous_word_start(&text, 0), 0);
+
+        assert_eq!(word_end(&text, 0), 2);
+        assert_eq!(word_end(&text, 2), 10);
+        assert_eq!(word_end(&text, 4), 10);
+        assert_eq!(word_end(&text, 17), 18);
+        assert_eq!(word_end(&text, 24), 27);
+        assert_eq!(word_end(&text, 33), 33);
+    }
+
+    #[test]
+    fn test_clamp_to_char() {
+        let text = Rope::from("foo\n\n中文");
+        assert_eq!(clamp_to_char(&text, 1), 1);
+        assert_eq!(clamp_to_char(&text, 3), 2);
+        assert_eq!(clamp_to_char(&text, 4), 4);
+        assert_eq!(clamp_to_char(&text, 11), 8);
+    }
+
+    #[test]
+    fn test_lines_range() {
+        let text = Rope::from("foo\nbar\nbaz");
+        assert_eq!(lines_range(&text, 0, 0), 0..4);
+        assert_eq!(lines_range(&text, 1, 1), 4..8);
+        assert_eq!(lines_range(&text, 1, 2), 3..11);
+        assert_eq!(lines_range(&text, 2, 2), 7..11);
+        assert_eq!(lines_range(&text, 0, 2), 0..11);
+    }
+
+    #[gpui::test]
+    fn test_tab_in_normal_mode(cx: &mut gpui::TestAppContext) {
+        use gpui::AppContext as _;
+
+        use crate::input::{IndentInline, InputState, OutdentInline, VimMode};
+
+        cx.update(|cx| crate::init(cx));
+        let window = cx.add_empty_window();
+        window.update(|window, cx| {
+            let state = cx.new(|cx| {
+                InputState::new(window, cx)
+                    .code_editor("rust")
+                    .vim_mode(true)
+            });
+            state.update(cx, |state, cx| {
+                state.set_value("    foo", window, cx);
+                state.set_selection(4..4, window, cx);
+                state.indent_inline(&IndentInline, window, cx);
+                assert_eq!(state.value(), "    foo");
+                state.outdent_inline(&OutdentInline, window, cx);
+                assert_eq!(state.value(), "    foo");
+
+                state.handle_vim_key("i", window, cx);
+                assert_eq!(state.vim_mode_state(), Some(VimMode::Insert));
+                state.outdent_inline(&OutdentInline, window, cx);
+                assert_eq!(state.value(), "  foo");
+            });
+        });
+    }
+}
@@ -205,11 +205,7 @@ impl DivInspector {
 
         let (new_style, diagnostics) = rust_to_style(self.unconvertible_style.clone(), code);
         self.rust_state.state.update(cx, |state, cx| {
-            if let Some(set) = state.diagnostics_mut() {
-                set.clear();
-                set.extend(diagnostics);
-            }
-            cx.notify();
+            state.set_diagnostics(diagnostics, cx);
         });
         self.json_state.error = None;
         self.json_state.editing = false;
@@ -435,7 +431,7 @@ impl Render for DivInspector {
                                 .flex_1()
                                 .gap_y_1()
                                 .font_family(cx.theme().mono_font_family.clone())
-                                .text_size(cx.theme().mono_font_size)
+                                .text_size(cx.theme().scaled_mono_font_size())
                                 .child(Input::new(&self.rust_state.state).h_full())
                                 .when_some(self.rust_state.error.clone(), |this, err| {
                                     this.child(Alert::error("rust-error", err).text_xs())
@@ -463,7 +459,7 @@ impl Render for DivInspector {
                                 .flex_1()
                                 .gap_y_1()
                                 .font_family(cx.theme().mono_font_family.clone())
-                                .text_size(cx.theme().mono_font_size)
+                                .text_size(cx.theme().scaled_mono_font_size())
                                 .child(Input::new(&self.json_state.state).h_full())
                                 .when_some(self.json_state.error.clone(), |this, err| {
                                     this.child(Alert::error("json-error", err).text_xs())
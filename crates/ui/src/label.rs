@@ -1,13 +1,87 @@
-use std::ops::Range;
+use std::{cell::Cell, ops::Range, rc::Rc, time::Duration};
 
 use gpui::{
-    div, prelude::FluentBuilder, rems, App, HighlightStyle, IntoElement, ParentElement, RenderOnce,
-    SharedString, StyleRefinement, Styled, StyledText, Window,
+    div, prelude::FluentBuilder, px, relative, rems, AnyElement, App, AppContext as _, Bounds,
+    ClipboardItem, Element, ElementId, Empty, FocusHandle, GlobalElementId, HighlightStyle,
+    InspectorElementId, InteractiveElement as _, IntoElement, KeyDownEvent, LayoutId,
+    ParentElement, Pixels, RenderOnce, ShapedLine, SharedString, StatefulInteractiveElement as _,
+    Style, StyleRefinement, Styled, StyledText, TextRun, TextStyle, Window,
 };
+use rust_i18n::t;
 
-use crate::{ActiveTheme, StyledExt};
+use crate::{h_flex, tooltip::Tooltip, ActiveTheme, Icon, IconName, Sizable as _, StyledExt};
 
 const MASKED: &'static str = "•";
+const ELLIPSIS: &'static str = "…";
+
+/// The position to clip the overflowed text of the [`Label`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Truncate {
+    /// Keep the start of the text, e.g.: `src/ui/comp…`.
+    End,
+    /// Keep the start and the end of the text, e.g.: `src/u…nent.rs`.
+    Middle,
+}
+
+/// The text direction of the [`Label`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextDirection {
+    /// Detect the direction by the first strong directional character of the text.
+    #[default]
+    Auto,
+    /// Left-to-right.
+    Ltr,
+    /// Right-to-left, e.g.: Arabic, Hebrew.
+    Rtl,
+}
+
+impl TextDirection {
+    /// Detect the direction of the text by the first strong directional character,
+    /// like the `dir="auto"` in HTML.
+    ///
+    /// Returns [`TextDirection::Ltr`] if there is no strong directional character.
+    pub fn detect(text: &str) -> Self {
+        for c in text.chars() {
+            if is_rtl_char(c) {
+                return Self::Rtl;
+            }
+            if c.is_alphabetic() {
+                return Self::Ltr;
+            }
+        }
+
+        Self::Ltr
+    }
+
+    /// Resolve the [`TextDirection::Auto`] by the text.
+    pub fn resolve(self, text: &str) -> Self {
+        match self {
+            Self::Auto => Self::detect(text),
+            _ => self,
+        }
+    }
+
+    #[inline]
+    pub fn is_rtl(&self) -> bool {
+        matches!(self, Self::Rtl)
+    }
+}
+
+/// Return true if the char is a strong right-to-left character.
+fn is_rtl_char(c: char) -> bool {
+    matches!(c,
+        // Hebrew, Arabic, Syriac, Arabic Supplement, Thaana, NKo, Samaritan, Mandaic
+        '\u{0590}'..='\u{085F}'
+        // Arabic Extended
+        | '\u{0870}'..='\u{08FF}'
+        // Hebrew and Arabic Presentation Forms
+        | '\u{FB1D}'..='\u{FDFF}'
+        | '\u{FE70}'..='\u{FEFF}'
+        // Other historic RTL scripts and Arabic Mathematical Symbols
+        | '\u{10800}'..='\u{10FFF}'
+        | '\u{1E800}'..='\u{1EFFF}')
+        && c.is_alphabetic()
+}
 
 /// Represents the type of match for highlighting text in a label.
 #[derive(Clone)]
@@ -56,6 +130,12 @@ pub struct Label {
     secondary: Option<SharedString>,
     masked: bool,
     highlights_text: Option<HighlightsMatch>,
+    highlighted_ranges: Vec<Range<usize>>,
+    direction: TextDirection,
+    truncate: Option<Truncate>,
+    /// The id to keep the copy state, set by [`Label::copyable`].
+    copy_id: Option<ElementId>,
+    on_copied: Option<Rc<dyn Fn(SharedString, &mut Window, &mut App)>>,
 }
 
 impl Label {
@@ -68,6 +148,11 @@ impl Label {
             secondary: None,
             masked: false,
             highlights_text: None,
+            highlighted_ranges: vec![],
+            direction: TextDirection::default(),
+            truncate: None,
+            copy_id: None,
+            on_copied: None,
         }
     }
 
@@ -90,6 +175,60 @@ impl Label {
         self
     }
 
+    /// Set the byte ranges of the text to highlight, e.g. the matched chars of a fuzzy search.
+    ///
+    /// The ranges are of the full text including the secondary text, they are expanded to the
+    /// char boundaries, and the overlapping or adjacent ranges are merged.
+    pub fn highlight(mut self, ranges: Vec<Range<usize>>) -> Self {
+        self.highlighted_ranges = ranges;
+        self
+    }
+
+    /// Set the text direction of the label, default is [`TextDirection::Auto`].
+    ///
+    /// The right-to-left label is aligned to the right, and the overflowed text is clipped on
+    /// the left side, which is the end of the text.
+    pub fn direction(mut self, direction: TextDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Clip the overflowed text with a trailing ellipsis, the full text is shown in a tooltip
+    /// on hover when the text is truncated.
+    ///
+    /// The text is measured by the laid out width, the label is kept in a single line.
+    pub fn truncate(mut self) -> Self {
+        self.truncate = Some(Truncate::End);
+        self
+    }
+
+    /// Like [`Label::truncate`], but clip the middle of the text to keep the start and the end,
+    /// this is useful for the file paths.
+    pub fn truncate_middle(mut self) -> Self {
+        self.truncate = Some(Truncate::Middle);
+        self
+    }
+
+    /// Make the label copyable, a copy icon is shown on hover, click it or press `enter` when
+    /// focused to copy the full text (including the secondary text) to the clipboard.
+    ///
+    /// The full text is copied even if the label is masked or truncated.
+    ///
+    /// The `id` keeps the focus and copied state, it must be unique in the window.
+    pub fn copyable(mut self, id: impl Into<ElementId>) -> Self {
+        self.copy_id = Some(id.into());
+        self
+    }
+
+    /// Set a callback to be invoked when the text is copied by [`Label::copyable`].
+    pub fn on_copied<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(SharedString, &mut Window, &mut App) + 'static,
+    {
+        self.on_copied = Some(Rc::new(handler));
+        self
+    }
+
     fn full_text(&self) -> SharedString {
         match &self.secondary {
             Some(secondary) => format!("{} {}", self.label, secondary).into(),
@@ -143,6 +282,8 @@ impl Label {
             }
         }
 
+        ranges.extend(merge_ranges(&full_text, &self.highlighted_ranges));
+
         ranges
     }
 
@@ -191,8 +332,14 @@ impl Styled for Label {
     }
 }
 
-impl RenderOnce for Label {
-    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+/// The state of the copyable [`Label`].
+struct CopyState {
+    focus_handle: FocusHandle,
+    copied: bool,
+}
+
+impl Label {
+    fn render_text(self, id: ElementId, cx: &mut App) -> AnyElement {
         let mut text = self.full_text();
         let chars_count = text.chars().count();
 
@@ -201,14 +348,375 @@ impl RenderOnce for Label {
         };
 
         let highlights = self.measure_highlights(text.len(), cx);
+        let is_rtl = self.direction.resolve(&self.label).is_rtl();
 
-        div()
+        let base = div()
             .line_height(rems(1.25))
             .text_color(cx.theme().foreground)
+            .when(is_rtl, |this| this.flex().justify_end().text_right());
+
+        let Some(truncate) = self.truncate else {
+            return base
+                .refine_style(&self.style)
+                .child(
+                    StyledText::new(&text)
+                        .when_some(highlights, |this, hl| this.with_highlights(hl)),
+                )
+                .into_any_element();
+        };
+
+        let truncated = Rc::new(Cell::new(false));
+        base.id(id)
+            .min_w_0()
+            .overflow_hidden()
             .refine_style(&self.style)
+            .child(TruncatedText {
+                text: text.clone(),
+                highlights: highlights.unwrap_or_default(),
+                truncate,
+                is_rtl,
+                truncated: truncated.clone(),
+            })
+            .tooltip(move |window, cx| {
+                if truncated.get() {
+                    Tooltip::new(text.clone()).build(window, cx)
+                } else {
+                    cx.new(|_| Empty).into()
+                }
+            })
+            .into_any_element()
+    }
+}
+
+impl RenderOnce for Label {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let Some(id) = self.copy_id.clone() else {
+            let id = ElementId::Name(format!("label:{}", self.label).into());
+            return self.render_text(id, cx);
+        };
+
+        let state = window.use_keyed_state(id.clone(), cx, |_, cx| CopyState {
+            focus_handle: cx.focus_handle().tab_stop(true),
+            copied: false,
+        });
+        let (focus_handle, copied) = {
+            let state = state.read(cx);
+            (state.focus_handle.clone(), state.copied)
+        };
+        let is_focused = focus_handle.is_focused(window);
+        let group_name = SharedString::from(format!("{}-copy", id));
+
+        let copy = Rc::new({
+            let value = self.full_text();
+            let on_copied = self.on_copied.clone();
+            move |window: &mut Window, cx: &mut App| {
+                cx.write_to_clipboard(ClipboardItem::new_string(value.to_string()));
+                state.update(cx, |state, cx| {
+                    state.copied = true;
+                    cx.notify();
+                });
+
+                let state = state.clone();
+                cx.spawn(async move |cx| {
+                    cx.background_executor().timer(Duration::from_secs(2)).await;
+                    _ = state.update(cx, |state, cx| {
+                        state.copied = false;
+                        cx.notify();
+                    });
+                })
+                .detach();
+
+                if let Some(on_copied) = &on_copied {
+                    on_copied(value.clone(), window, cx);
+                }
+            }
+        });
+
+        let muted_foreground = cx.theme().muted_foreground;
+        h_flex()
+            .id(id.clone())
+            .group(group_name.clone())
+            .track_focus(&focus_handle)
+            .min_w_0()
+            .max_w_full()
+            .gap_1()
+            .cursor_pointer()
+            .rounded(cx.theme().radius * 0.5)
+            .focus_ring(is_focused, px(2.), window, cx)
+            .child(self.render_text(id, cx))
             .child(
-                StyledText::new(&text).when_some(highlights, |this, hl| this.with_highlights(hl)),
+                div()
+                    .flex_shrink_0()
+                    .when(!copied && !is_focused, |this| {
+                        this.invisible()
+                            .group_hover(group_name, |this| this.visible())
+                    })
+                    .child(
+                        Icon::new(if copied {
+                            IconName::Check
+                        } else {
+                            IconName::Copy
+                        })
+                        .xsmall()
+                        .text_color(muted_foreground),
+                    ),
             )
+            .when(copied, |this| {
+                this.child(
+                    div()
+                        .flex_shrink_0()
+                        .text_xs()
+                        .text_color(muted_foreground)
+                        .child(t!("Label.Copied").to_string()),
+                )
+            })
+            .on_click({
+                let copy = copy.clone();
+                move |_, window, cx| {
+                    cx.stop_propagation();
+                    copy(window, cx);
+                }
+            })
+            .on_key_down(move |event: &KeyDownEvent, window, cx| {
+                if event.keystroke.key == "enter" {
+                    cx.stop_propagation();
+                    copy(window, cx);
+                }
+            })
+            .into_any_element()
+    }
+}
+
+/// Clamp the ranges to the char boundaries of the text, and merge the overlapping or adjacent ranges.
+fn merge_ranges(text: &str, ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut ranges = ranges
+        .iter()
+        .filter(|range| range.start < range.end)
+        .filter_map(|range| {
+            let mut start = range.start.min(text.len());
+            while !text.is_char_boundary(start) {
+                start -= 1;
+            }
+            let mut end = range.end.min(text.len());
+            while !text.is_char_boundary(end) {
+                end += 1;
+            }
+            (start < end).then_some(start..end)
+        })
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Returns the byte offsets `(head_end, tail_start)` to keep the `text[..head_end]` and
+/// `text[tail_start..]` around the ellipsis to fit in the `max_width`.
+///
+/// Returns `None` if the text is not overflowed.
+fn truncate_text(
+    text: &str,
+    truncate: Truncate,
+    max_width: Pixels,
+    measure: impl Fn(&str) -> Pixels,
+) -> Option<(usize, usize)> {
+    if measure(text) <= max_width {
+        return None;
+    }
+
+    let offsets = text
+        .char_indices()
+        .map(|(ix, _)| ix)
+        .chain([text.len()])
+        .collect::<Vec<_>>();
+    let chars_count = offsets.len() - 1;
+    // The offsets to keep `n` chars.
+    let keep = |n: usize| match truncate {
+        Truncate::End => (offsets[n], text.len()),
+        Truncate::Middle => (offsets[n.div_ceil(2)], offsets[chars_count - n / 2]),
+    };
+    let fits = |n: usize| {
+        let (head_end, tail_start) = keep(n);
+        let candidate = format!("{}{}{}", &text[..head_end], ELLIPSIS, &text[tail_start..]);
+        measure(&candidate) <= max_width
+    };
+
+    // Binary search the max chars to keep, at least the ellipsis is kept.
+    let (mut low, mut high) = (0, chars_count.saturating_sub(1));
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+        if fits(mid) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    Some(keep(low))
+}
+
+/// Map the highlights of the text to the truncated text, see [`truncate_text`].
+fn truncate_highlights(
+    highlights: &[(Range<usize>, HighlightStyle)],
+    (head_end, tail_start): (usize, usize),
+) -> Vec<(Range<usize>, HighlightStyle)> {
+    let shift = head_end + ELLIPSIS.len();
+    let mut result = vec![];
+    for (range, style) in highlights {
+        if range.start < head_end {
+            result.push((range.start..range.end.min(head_end), *style));
+        }
+        if range.end > tail_start {
+            let start = range.start.max(tail_start) - tail_start + shift;
+            result.push((start..range.end - tail_start + shift, *style));
+        }
+    }
+    result
+}
+
+fn text_runs(
+    text_style: &TextStyle,
+    len: usize,
+    highlights: &[(Range<usize>, HighlightStyle)],
+) -> Vec<TextRun> {
+    let mut runs = vec![];
+    let mut ix = 0;
+    for (range, highlight) in highlights {
+        if ix < range.start {
+            runs.push(text_style.clone().to_run(range.start - ix));
+        }
+        runs.push(text_style.clone().highlight(*highlight).to_run(range.len()));
+        ix = range.end;
+    }
+    if ix < len {
+        runs.push(text_style.clone().to_run(len - ix));
+    }
+    runs
+}
+
+/// A single line text that clipped by the [`Truncate`] to fit in the laid out width.
+struct TruncatedText {
+    text: SharedString,
+    highlights: Vec<(Range<usize>, HighlightStyle)>,
+    truncate: Truncate,
+    is_rtl: bool,
+    /// Whether the text is truncated in the last prepaint.
+    truncated: Rc<Cell<bool>>,
+}
+
+impl IntoElement for TruncatedText {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+impl Element for TruncatedText {
+    type RequestLayoutState = TextStyle;
+    type PrepaintState = Option<ShapedLine>;
+
+    fn id(&self) -> Option<ElementId> {
+        None
+    }
+
+    fn source_location(&self) -> Option<&'static std::panic::Location<'static>> {
+        None
+    }
+
+    fn request_layout(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        _: Option<&InspectorElementId>,
+        window: &mut Window,
+        _: &mut App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let text_style = window.text_style();
+        let font_size = text_style.font_size.to_pixels(window.rem_size());
+        let line_height = window.line_height();
+        let text = self.text.clone();
+        let runs = text_runs(&text_style, text.len(), &self.highlights);
+
+        // The intrinsic width is the full text width, but it can be shrunk to zero.
+        let mut style = Style::default();
+        style.min_size.width = px(0.).into();
+        style.max_size.width = relative(1.).into();
+        let layout_id = window.request_measured_layout(style, move |known, _, window, _| {
+            let width = known.width.unwrap_or_else(|| {
+                window
+                    .text_system()
+                    .shape_line(text.clone(), font_size, &runs, None)
+                    .width
+            });
+            gpui::size(width, line_height)
+        });
+
+        (layout_id, text_style)
+    }
+
+    fn prepaint(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        _: Option<&InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        text_style: &mut Self::RequestLayoutState,
+        window: &mut Window,
+        _: &mut App,
+    ) -> Self::PrepaintState {
+        let font_size = text_style.font_size.to_pixels(window.rem_size());
+        let shape = |text: &str, highlights: &[(Range<usize>, HighlightStyle)]| {
+            let runs = text_runs(text_style, text.len(), highlights);
+            window.text_system().shape_line(
+                SharedString::from(text.to_string()),
+                font_size,
+                &runs,
+                None,
+            )
+        };
+
+        let measure = |text: &str| shape(text, &[]).width;
+        let Some(keep) = truncate_text(&self.text, self.truncate, bounds.size.width, measure)
+        else {
+            self.truncated.set(false);
+            return Some(shape(&self.text, &self.highlights));
+        };
+
+        self.truncated.set(true);
+        let (head_end, tail_start) = keep;
+        let text = format!(
+            "{}{}{}",
+            &self.text[..head_end],
+            ELLIPSIS,
+            &self.text[tail_start..]
+        );
+        Some(shape(&text, &truncate_highlights(&self.highlights, keep)))
+    }
+
+    fn paint(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        _: Option<&InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        _: &mut Self::RequestLayoutState,
+        line: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some(line) = line.take() else {
+            return;
+        };
+
+        let mut origin = bounds.origin;
+        if self.is_rtl {
+            origin.x += (bounds.size.width - line.width).max(px(0.));
+        }
+        _ = line.paint(origin, window.line_height(), window, cx);
     }
 }
 
@@ -306,6 +814,95 @@ mod tests {
         assert_eq!(result[0], start..end);
     }
 
+    #[test]
+    fn test_highlight() {
+        let label = Label::new("Hello World").highlight(vec![6..8, 0..2, 1..3, 3..4, 8..9]);
+        let result = label.highlight_ranges("Hello World".len());
+        assert_eq!(result, vec![0..4, 6..9]);
+
+        // Secondary text ranges are kept first.
+        let label = Label::new("Hello").secondary("World").highlight(vec![6..8]);
+        let result = label.highlight_ranges("Hello World".len());
+        assert_eq!(result, vec![0..5, 5..11, 6..8]);
+
+        // Expand to the char boundaries, and clamp to the text length.
+        let text = "你好世界";
+        let label = Label::new(text).highlight(vec![1..4, 7..100, 20..30, 5..5]);
+        let result = label.highlight_ranges(text.len());
+        assert_eq!(result, vec![0..12]);
+        assert_eq!(merge_ranges(text, &[1..2, 7..8, 5..5]), vec![0..3, 6..9]);
+    }
+
+    #[test]
+    fn test_truncate_text() {
+        // Each char is 10px wide.
+        let measure = |text: &str| px(text.chars().count() as f32 * 10.);
+        let text = "src/ui/label.rs";
+
+        assert_eq!(truncate_text(text, Truncate::End, px(150.), measure), None);
+        assert_eq!(
+            truncate_text(text, Truncate::End, px(80.), measure),
+            Some((7, text.len()))
+        );
+        assert_eq!(
+            truncate_text(text, Truncate::Middle, px(80.), measure),
+            Some((4, 12))
+        );
+        // The ellipsis is kept at least.
+        assert_eq!(
+            truncate_text(text, Truncate::End, px(5.), measure),
+            Some((0, text.len()))
+        );
+        assert_eq!(
+            truncate_text(text, Truncate::Middle, px(5.), measure),
+            Some((0, text.len()))
+        );
+
+        // Unicode text
+        let text = "你好世界你好";
+        assert_eq!(
+            truncate_text(text, Truncate::Middle, px(50.), measure),
+            Some((6, 12))
+        );
+    }
+
+    #[test]
+    fn test_truncate_highlights() {
+        let style = HighlightStyle::default();
+        // "Hello World" -> "Hel…rld"
+        let highlights = vec![(0..5, style), (5..11, style), (2..9, style)];
+        let result = truncate_highlights(&highlights, (3, 8));
+        let ranges = result.into_iter().map(|(r, _)| r).collect::<Vec<_>>();
+        let ellipsis = ELLIPSIS.len();
+        assert_eq!(
+            ranges,
+            vec![
+                0..3,
+                3 + ellipsis..6 + ellipsis,
+                2..3,
+                3 + ellipsis..4 + ellipsis
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_direction() {
+        assert_eq!(TextDirection::detect("Hello World"), TextDirection::Ltr);
+        assert_eq!(TextDirection::detect("你好世界"), TextDirection::Ltr);
+        assert_eq!(TextDirection::detect("שלום עולם"), TextDirection::Rtl);
+        assert_eq!(TextDirection::detect("مرحبا بالعالم"), TextDirection::Rtl);
+        // The first strong character decides the direction.
+        assert_eq!(TextDirection::detect("123. שלום"), TextDirection::Rtl);
+        assert_eq!(TextDirection::detect("Error: שלום"), TextDirection::Ltr);
+        assert_eq!(TextDirection::detect("~/مرحبا.txt"), TextDirection::Rtl);
+        // No strong characters
+        assert_eq!(TextDirection::detect(""), TextDirection::Ltr);
+        assert_eq!(TextDirection::detect("123 ,.!"), TextDirection::Ltr);
+
+        assert_eq!(TextDirection::Ltr.resolve("שלום"), TextDirection::Ltr);
+        assert_eq!(TextDirection::Auto.resolve("שלום"), TextDirection::Rtl);
+    }
+
     #[test]
     fn test_highlight_ranges_prefix() {
         // Test prefix match - should only match the first occurrence
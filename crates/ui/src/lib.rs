@@ -31,8 +31,10 @@ pub mod checkbox;
 pub mod clipboard;
 pub mod collapsible;
 pub mod color_picker;
+pub mod command_palette;
 pub mod description_list;
 pub mod dialog;
+pub mod diff;
 pub mod divider;
 pub mod dock;
 pub mod form;
@@ -59,12 +61,14 @@ pub mod sidebar;
 pub mod skeleton;
 pub mod slider;
 pub mod spinner;
+pub mod status_bar;
 pub mod switch;
 pub mod tab;
 pub mod table;
 pub mod tag;
 pub mod text;
 pub mod theme;
+pub mod toolbar;
 pub mod tooltip;
 pub mod tree;
 pub use time::{calendar, date_picker};
@@ -78,7 +82,7 @@ pub use index_path::IndexPath;
 pub use input::{Rope, RopeExt, RopeLines};
 #[cfg(any(feature = "inspector", debug_assertions))]
 pub use inspector::*;
-pub use root::Root;
+pub use root::{ErrorBoundary, ResetZoom, Root, ZoomIn, ZoomOut, error_boundary};
 pub use styled::*;
 pub use theme::*;
 pub use title_bar::*;
@@ -122,6 +126,39 @@ pub fn set_locale(locale: &str) {
     rust_i18n::set_locale(locale)
 }
 
+/// Returns the scale factor of the UI, default is 1.0.
+#[inline]
+pub fn ui_scale(cx: &App) -> f32 {
+    Theme::global(cx).ui_scale
+}
+
+/// Set the scale factor of the UI, see [`Theme::set_ui_scale`].
+#[inline]
+pub fn set_ui_scale(scale: f32, cx: &mut App) {
+    Theme::set_ui_scale(scale, cx)
+}
+
+/// Set true to reduce the motion of the component animations, see [`Theme::set_reduced_motion`].
+#[inline]
+pub fn set_reduced_motion(reduced: bool, cx: &mut App) {
+    Theme::set_reduced_motion(reduced, cx)
+}
+
+/// Set the theme mode of the application, see [`Theme::change`].
+///
+/// All windows are refreshed to redraw with the new theme colors, use
+/// [`ThemeModePreference::System`] to follow the system appearance.
+#[inline]
+pub fn set_theme(mode: impl Into<ThemeModePreference>, cx: &mut App) {
+    Theme::change(mode, None, cx)
+}
+
+/// Toggle the theme mode between light and dark, see [`Theme::toggle`].
+#[inline]
+pub fn toggle_theme(cx: &mut App) {
+    Theme::toggle(None, cx)
+}
+
 #[inline]
 pub(crate) fn measure_enable() -> bool {
     std::env::var("ZED_MEASUREMENTS").is_ok() || std::env::var("GPUI_MEASUREMENTS").is_ok()
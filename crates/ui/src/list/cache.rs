@@ -1,6 +1,6 @@
 use std::rc::Rc;
 
-use gpui::{App, Pixels, Size};
+use gpui::{App, Pixels, Size, px};
 
 use crate::IndexPath;
 
@@ -69,6 +69,8 @@ pub(crate) struct RowsCache {
     /// The sections, the item is number of rows in each section.
     pub(crate) sections: Rc<Vec<usize>>,
     pub(crate) entries_sizes: Rc<Vec<Size<Pixels>>>,
+    /// The top position of each section header in the flattened rows.
+    section_tops: Rc<Vec<Pixels>>,
     measured_size: MeasuredEntrySize,
 }
 
@@ -94,6 +96,29 @@ impl RowsCache {
             .position(|p| p.is_entry() && p.eq_index_path(path))
     }
 
+    /// Returns the section of the header to pin at the top of the viewport, with the y offset of
+    /// the header, the offset is negative when the header is pushed up by the next header.
+    ///
+    /// The `scroll_top` is the scrolled distance from the top of the rows, returns `None` if
+    /// the sections have no header, or the first header is not scrolled to the top yet.
+    pub(crate) fn sticky_header(&self, scroll_top: Pixels) -> Option<(usize, Pixels)> {
+        let header_height = self.measured_size.section_header_size.height;
+        if header_height <= px(0.) {
+            return None;
+        }
+
+        let section = self
+            .section_tops
+            .partition_point(|top| *top <= scroll_top)
+            .checked_sub(1)?;
+        let offset = match self.section_tops.get(section + 1) {
+            Some(next_top) => (*next_top - scroll_top - header_height).min(px(0.)),
+            None => px(0.),
+        };
+
+        Some((section, offset))
+    }
+
     /// Returns the sections count in the cache.
     pub(crate) fn sections_count(&self) -> usize {
         self.sections.len()
@@ -168,6 +193,8 @@ impl RowsCache {
         }
 
         let mut entries_sizes = vec![];
+        let mut section_tops = vec![];
+        let mut top = px(0.);
         let mut total_items_count = 0;
         self.measured_size = measured_size;
         self.sections = Rc::new(new_sections);
@@ -179,6 +206,10 @@ impl RowsCache {
                     total_items_count += items_count;
                     let mut children = vec![];
                     children.push(RowEntry::SectionHeader(section));
+                    section_tops.push(top);
+                    top += measured_size.section_header_size.height
+                        + measured_size.item_size.height * *items_count as f32
+                        + measured_size.section_footer_size.height;
                     entries_sizes.push(measured_size.section_header_size);
                     for row in 0..*items_count {
                         children.push(RowEntry::Entry(IndexPath {
@@ -195,6 +226,7 @@ impl RowsCache {
                 .collect(),
         );
         self.entries_sizes = Rc::new(entries_sizes);
+        self.section_tops = Rc::new(section_tops);
         self.items_count = total_items_count;
     }
 }
@@ -203,7 +235,39 @@ impl RowsCache {
 mod tests {
     use std::rc::Rc;
 
-    use crate::{IndexPath, list::cache::RowsCache};
+    use gpui::{px, size};
+
+    use crate::{
+        IndexPath,
+        list::cache::{MeasuredEntrySize, RowsCache},
+    };
+
+    #[test]
+    fn test_sticky_header() {
+        let mut row_cache = RowsCache::default();
+        assert_eq!(row_cache.sticky_header(px(0.)), None);
+
+        // The header is 20px, the sections are at 0, 100 and 150.
+        row_cache.measured_size = MeasuredEntrySize {
+            section_header_size: size(px(100.), px(20.)),
+            ..Default::default()
+        };
+        row_cache.section_tops = Rc::new(vec![px(0.), px(100.), px(150.)]);
+
+        assert_eq!(row_cache.sticky_header(px(-10.)), None);
+        assert_eq!(row_cache.sticky_header(px(0.)), Some((0, px(0.))));
+        assert_eq!(row_cache.sticky_header(px(50.)), Some((0, px(0.))));
+        assert_eq!(row_cache.sticky_header(px(80.)), Some((0, px(0.))));
+        // Pushed up by the next header.
+        assert_eq!(row_cache.sticky_header(px(90.)), Some((0, px(-10.))));
+        assert_eq!(row_cache.sticky_header(px(100.)), Some((1, px(0.))));
+        assert_eq!(row_cache.sticky_header(px(145.)), Some((1, px(-15.))));
+        assert_eq!(row_cache.sticky_header(px(1000.)), Some((2, px(0.))));
+
+        // No headers to pin.
+        row_cache.measured_size = MeasuredEntrySize::default();
+        assert_eq!(row_cache.sticky_header(px(50.)), None);
+    }
 
     #[test]
     fn test_prev_next() {
@@ -51,6 +51,7 @@ struct ListOptions {
     search_placeholder: Option<SharedString>,
     max_height: Option<Length>,
     paddings: EdgesRefinement<DefiniteLength>,
+    sticky_section_headers: bool,
 }
 
 impl Default for ListOptions {
@@ -61,6 +62,7 @@ impl Default for ListOptions {
             max_height: None,
             search_placeholder: None,
             paddings: EdgesRefinement::default(),
+            sticky_section_headers: false,
         }
     }
 }
@@ -486,6 +488,18 @@ where
         let rows_cache = self.rows_cache.clone();
         let scrollbar_visible = self.options.scrollbar_visible;
         let scroll_handle = self.scroll_handle.clone();
+        let sticky_header = if self.options.sticky_section_headers && items_count > 0 {
+            let padding_top = self
+                .options
+                .paddings
+                .top
+                .map(|top| top.to_pixels(window.text_style().font_size, window.rem_size()))
+                .unwrap_or_default();
+            let scroll_top = -scroll_handle.base_handle().offset().y - padding_top;
+            rows_cache.sticky_header(scroll_top)
+        } else {
+            None
+        };
 
         v_flex()
             .flex_grow()
@@ -548,6 +562,21 @@ where
                     )
                 }
             })
+            .when_some(sticky_header, |this, (section_ix, offset_y)| {
+                // Pin the header of the current section over the rows.
+                this.children(
+                    self.delegate
+                        .render_section_header(section_ix, window, cx)
+                        .map(|header| {
+                            div()
+                                .absolute()
+                                .top(offset_y)
+                                .left_0()
+                                .right_0()
+                                .child(header)
+                        }),
+                )
+            })
             .when(scrollbar_visible, |this| {
                 this.child(Scrollbar::vertical(&scroll_handle))
             })
@@ -692,6 +721,15 @@ where
         self
     }
 
+    /// Set whether to pin the header of the current section at the top of the viewport,
+    /// until the next section header pushes it up, default is `false`.
+    ///
+    /// The header should have an opaque background, e.g. [`crate::list::ListSectionHeader`].
+    pub fn sticky_section_headers(mut self, sticky: bool) -> Self {
+        self.options.sticky_section_headers = sticky;
+        self
+    }
+
     /// Sets the placeholder text for the search input.
     pub fn search_placeholder(mut self, placeholder: impl Into<SharedString>) -> Self {
         self.options.search_placeholder = Some(placeholder.into());
@@ -96,6 +96,12 @@ impl ListItem {
         self
     }
 
+    /// Insert a child element before all the children.
+    pub(crate) fn prefix_child(mut self, child: impl IntoElement) -> Self {
+        self.children.insert(0, child.into_any_element());
+        self
+    }
+
     pub fn on_click(
         mut self,
         handler: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
@@ -216,7 +222,13 @@ impl RenderOnce for ListItem {
                             .left_0()
                             .right_0()
                             .bottom_0()
-                            .border_1()
+                            .map(|this| {
+                                if cx.theme().high_contrast {
+                                    this.border_2()
+                                } else {
+                                    this.border_1()
+                                }
+                            })
                             .border_color(cx.theme().list_active_border)
                             .refine_style(&selected_style),
                     )
@@ -3,9 +3,11 @@ mod delegate;
 mod list;
 mod list_item;
 mod loading;
+mod section;
 mod separator_item;
 
 pub use delegate::*;
 pub use list::*;
 pub use list_item::*;
+pub use section::*;
 pub use separator_item::*;
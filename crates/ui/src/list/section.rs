@@ -0,0 +1,191 @@
+use std::rc::Rc;
+
+use gpui::{
+    AnyElement, App, Context, IntoElement, ParentElement, RenderOnce, SharedString,
+    StyleRefinement, Styled, Window,
+};
+use smallvec::SmallVec;
+
+use crate::{
+    ActiveTheme as _, IndexPath, StyledExt as _, h_flex,
+    list::{ListDelegate, ListItem, ListState},
+};
+
+/// A section of the grouped list, with a header and the items.
+#[derive(Debug, Clone)]
+pub struct ListSection<T> {
+    pub header: SharedString,
+    pub items: Vec<T>,
+}
+
+impl<T> ListSection<T> {
+    /// Create a new ListSection with the given header.
+    pub fn new(header: impl Into<SharedString>) -> Self {
+        Self {
+            header: header.into(),
+            items: vec![],
+        }
+    }
+
+    /// Add an item to the section.
+    pub fn item(mut self, item: T) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    /// Add multiple items to the section.
+    pub fn items(mut self, items: impl IntoIterator<Item = T>) -> Self {
+        self.items.extend(items);
+        self
+    }
+}
+
+/// The header of a list section, rendered with a distinct style from the [`ListItem`].
+///
+/// The background is opaque, so it can be pinned at the top of the list,
+/// see [`crate::list::List::sticky_section_headers`].
+#[derive(IntoElement)]
+pub struct ListSectionHeader {
+    style: StyleRefinement,
+    children: SmallVec<[AnyElement; 2]>,
+}
+
+impl ListSectionHeader {
+    /// Create a new ListSectionHeader with the title.
+    pub fn new(title: impl Into<SharedString>) -> Self {
+        Self {
+            style: StyleRefinement::default(),
+            children: SmallVec::new(),
+        }
+        .child(title.into())
+    }
+}
+
+impl ParentElement for ListSectionHeader {
+    fn extend(&mut self, elements: impl IntoIterator<Item = AnyElement>) {
+        self.children.extend(elements);
+    }
+}
+
+impl Styled for ListSectionHeader {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for ListSectionHeader {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        h_flex()
+            .w_full()
+            .px_3()
+            .py_1()
+            .gap_2()
+            .bg(cx.theme().list_head)
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .text_xs()
+            .font_semibold()
+            .text_color(cx.theme().muted_foreground)
+            .refine_style(&self.style)
+            .children(self.children)
+    }
+}
+
+/// A [`ListDelegate`] of the grouped items, each [`ListSection`] is rendered with
+/// a [`ListSectionHeader`] and the items rendered by the `render_item` closure.
+///
+/// ```ignore
+/// let delegate = SectionListDelegate::new(
+///     vec![
+///         ListSection::new("Today").items(["a.rs", "b.rs"]),
+///         ListSection::new("Yesterday").items(["c.rs"]),
+///     ],
+///     |ix, item, _, _| ListItem::new(ix).child(*item),
+/// );
+/// let state = cx.new(|cx| ListState::new(delegate, window, cx));
+///
+/// List::new(&state).sticky_section_headers(true)
+/// ```
+pub struct SectionListDelegate<T: 'static> {
+    sections: Vec<ListSection<T>>,
+    selected_index: Option<IndexPath>,
+    render_item: Rc<dyn Fn(IndexPath, &T, &mut Window, &mut App) -> ListItem>,
+}
+
+impl<T: 'static> SectionListDelegate<T> {
+    /// Create a new SectionListDelegate with the sections and the closure to render the items.
+    pub fn new(
+        sections: Vec<ListSection<T>>,
+        render_item: impl Fn(IndexPath, &T, &mut Window, &mut App) -> ListItem + 'static,
+    ) -> Self {
+        Self {
+            sections,
+            selected_index: None,
+            render_item: Rc::new(render_item),
+        }
+    }
+
+    /// Returns the sections.
+    pub fn sections(&self) -> &[ListSection<T>] {
+        &self.sections
+    }
+
+    /// Replace the sections, the selection is cleared.
+    pub fn set_sections(&mut self, sections: Vec<ListSection<T>>) {
+        self.sections = sections;
+        self.selected_index = None;
+    }
+
+    /// Returns the item at the index.
+    pub fn item(&self, ix: IndexPath) -> Option<&T> {
+        self.sections.get(ix.section)?.items.get(ix.row)
+    }
+
+    /// Returns the selected item.
+    pub fn selected_item(&self) -> Option<&T> {
+        self.selected_index.and_then(|ix| self.item(ix))
+    }
+}
+
+impl<T: 'static> ListDelegate for SectionListDelegate<T> {
+    type Item = ListItem;
+
+    fn sections_count(&self, _: &App) -> usize {
+        self.sections.len()
+    }
+
+    fn items_count(&self, section: usize, _: &App) -> usize {
+        self.sections
+            .get(section)
+            .map_or(0, |section| section.items.len())
+    }
+
+    fn render_section_header(
+        &mut self,
+        section: usize,
+        _: &mut Window,
+        _: &mut Context<ListState<Self>>,
+    ) -> Option<impl IntoElement> {
+        let section = self.sections.get(section)?;
+        Some(ListSectionHeader::new(section.header.clone()))
+    }
+
+    fn render_item(
+        &mut self,
+        ix: IndexPath,
+        window: &mut Window,
+        cx: &mut Context<ListState<Self>>,
+    ) -> Option<Self::Item> {
+        let item = self.sections.get(ix.section)?.items.get(ix.row)?;
+        Some((self.render_item)(ix, item, window, cx))
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: Option<IndexPath>,
+        _: &mut Window,
+        _: &mut Context<ListState<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 };
 
 use gpui::{
-    div, prelude::FluentBuilder, px, Animation, AnimationExt, AnyElement, App, AppContext,
+    div, prelude::FluentBuilder, px, Animation, AnyElement, App, AppContext,
     ClickEvent, Context, DismissEvent, ElementId, Entity, EventEmitter, InteractiveElement as _,
     IntoElement, ParentElement as _, Render, SharedString, StatefulInteractiveElement,
     StyleRefinement, Styled, Subscription, Window,
@@ -14,7 +14,7 @@ use gpui::{
 use smol::Timer;
 
 use crate::{
-    animation::cubic_bezier,
+    animation::{cubic_bezier, MotionExt as _},
     button::{Button, ButtonVariants as _},
     h_flex, v_flex, ActiveTheme as _, Icon, IconName, Sizable as _, StyledExt,
 };
@@ -70,6 +70,7 @@ pub struct Notification {
     message: Option<SharedString>,
     icon: Option<Icon>,
     autohide: bool,
+    timeout: Duration,
     action_builder: Option<Rc<dyn Fn(&mut Self, &mut Window, &mut Context<Self>) -> Button>>,
     content_builder: Option<Rc<dyn Fn(&mut Self, &mut Window, &mut Context<Self>) -> AnyElement>>,
     on_click: Option<Rc<dyn Fn(&ClickEvent, &mut Window, &mut App)>>,
@@ -124,6 +125,7 @@ impl Notification {
             type_: None,
             icon: None,
             autohide: true,
+            timeout: Duration::from_secs(5),
             action_builder: None,
             content_builder: None,
             on_click: None,
@@ -210,6 +212,14 @@ impl Notification {
         self
     }
 
+    /// Set the duration to show before auto hide, default is 5 seconds.
+    ///
+    /// This only works when the [`Notification::autohide`] is true.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
     /// Set the click callback of the notification.
     pub fn on_click(
         mut self,
@@ -336,7 +346,7 @@ impl Render for Notification {
                             .on_click(cx.listener(|this, _, window, cx| this.dismiss(window, cx))),
                     ),
             )
-            .with_animation(
+            .with_motion(
                 ElementId::NamedInteger("slide-down".into(), closing as u64),
                 Animation::new(Duration::from_secs_f64(0.25))
                     .with_easing(cubic_bezier(0.4, 0., 0.2, 1.)),
@@ -356,6 +366,7 @@ impl Render for Notification {
                             .when(opacity < 0.85, |this| this.shadow_none())
                     }
                 },
+                cx,
             )
     }
 }
@@ -386,6 +397,7 @@ impl NotificationList {
         let notification = notification.into();
         let id = notification.id.clone();
         let autohide = notification.autohide;
+        let timeout = notification.timeout;
 
         // Remove the notification by id, for keep unique.
         self.notifications.retain(|note| note.read(cx).id != id);
@@ -402,9 +414,9 @@ impl NotificationList {
 
         self.notifications.push_back(notification.clone());
         if autohide {
-            // Sleep for 5 seconds to autohide the notification
+            // Sleep for the timeout to autohide the notification
             cx.spawn_in(window, async move |_, cx| {
-                Timer::after(Duration::from_secs(5)).await;
+                Timer::after(timeout).await;
 
                 if let Err(err) =
                     notification.update_in(cx, |note, window, cx| note.dismiss(window, cx))
@@ -57,6 +57,44 @@ impl ResizableState {
         &self.sizes
     }
 
+    /// Set the size of the panels, e.g. to restore the sizes saved from [`ResizableState::sizes`].
+    ///
+    /// The sizes are scaled to fit the container, so the proportions are kept
+    /// when the container size is different from the saved one.
+    pub fn set_sizes(&mut self, sizes: impl IntoIterator<Item = Pixels>, cx: &mut Context<Self>) {
+        self.sizes = sizes.into_iter().collect();
+        self.panels
+            .resize(self.sizes.len(), ResizablePanelState::default());
+        for (panel, size) in self.panels.iter_mut().zip(self.sizes.iter()) {
+            panel.size = Some(*size);
+        }
+        self.adjust_to_container_size(cx);
+        cx.notify();
+    }
+
+    /// Reset the panels to split the container size equally,
+    /// the size range of the panels is still respected.
+    ///
+    /// This is called when double clicking the resize handle.
+    pub fn reset_sizes(&mut self, cx: &mut Context<Self>) {
+        let container_size = self.container_size();
+        if self.panels.is_empty() || container_size.is_zero() {
+            return;
+        }
+
+        let size_ranges = self
+            .panels
+            .iter()
+            .map(|panel| panel.size_range.clone())
+            .collect::<Vec<_>>();
+        self.sizes = equal_sizes(container_size, &size_ranges);
+        for (panel, size) in self.panels.iter_mut().zip(self.sizes.iter()) {
+            panel.size = Some(*size);
+        }
+        cx.emit(ResizablePanelEvent::Resized);
+        cx.notify();
+    }
+
     pub(crate) fn insert_panel(
         &mut self,
         size: Option<Pixels>,
@@ -287,9 +325,96 @@ impl ResizableState {
 
 impl EventEmitter<ResizablePanelEvent> for ResizableState {}
 
+/// Split the container size equally to the panels, the size ranges are respected.
+///
+/// The size taken or left by the clamped panels is redistributed to the other panels,
+/// so the sizes sum up to the container size unless all the panels are clamped.
+fn equal_sizes(container_size: Pixels, size_ranges: &[Range<Pixels>]) -> Vec<Pixels> {
+    let mut sizes: Vec<Option<Pixels>> = vec![None; size_ranges.len()];
+    loop {
+        let free_count = sizes.iter().filter(|size| size.is_none()).count();
+        if free_count == 0 {
+            break;
+        }
+
+        let used_size = px(sizes.iter().flatten().map(|size| size.as_f32()).sum());
+        let size = (container_size - used_size) / free_count as f32;
+        let mut clamped = false;
+        for (ix, size_range) in size_ranges.iter().enumerate() {
+            if sizes[ix].is_some() || size_range.is_empty() {
+                continue;
+            }
+            if size < size_range.start || size > size_range.end {
+                sizes[ix] = Some(size.clamp(size_range.start, size_range.end));
+                clamped = true;
+            }
+        }
+
+        if !clamped {
+            for free_size in sizes.iter_mut().filter(|size| size.is_none()) {
+                *free_size = Some(size);
+            }
+        }
+    }
+
+    sizes.into_iter().flatten().collect()
+}
+
 #[derive(Debug, Clone, Default)]
 pub(crate) struct ResizablePanelState {
     pub size: Option<Pixels>,
     pub size_range: Range<Pixels>,
     bounds: Bounds<Pixels>,
 }
+
+#[cfg(test)]
+mod tests {
+    use gpui::{point, px, size, AppContext as _, Bounds};
+
+    use super::{equal_sizes, ResizableState};
+    use crate::PixelsExt as _;
+
+    #[gpui::test]
+    fn test_set_and_reset_sizes(cx: &mut gpui::TestAppContext) {
+        let state = cx.new(|_| ResizableState::default());
+        state.update(cx, |state, cx| {
+            state.bounds = Bounds::new(point(px(0.), px(0.)), size(px(600.), px(400.)));
+
+            // The saved sizes are scaled to the container size.
+            state.set_sizes([px(100.), px(300.)], cx);
+            assert_eq!(state.sizes(), &vec![px(150.), px(450.)]);
+
+            state.reset_sizes(cx);
+            assert_eq!(state.sizes(), &vec![px(300.), px(300.)]);
+
+            // The size left by the clamped panel is given to the other one.
+            state.panels[0].size_range = px(100.)..px(200.);
+            state.reset_sizes(cx);
+            assert_eq!(state.sizes(), &vec![px(200.), px(400.)]);
+        });
+    }
+
+    #[test]
+    fn test_equal_sizes() {
+        let sum = |sizes: &[gpui::Pixels]| sizes.iter().map(|size| size.as_f32()).sum::<f32>();
+
+        let sizes = equal_sizes(
+            px(600.),
+            &[px(100.)..px(150.), px(0.)..px(0.), px(250.)..px(300.)],
+        );
+        assert_eq!(sizes, vec![px(150.), px(200.), px(250.)]);
+        assert_eq!(sum(&sizes), 600.);
+
+        // Clamped to the min size, the others are reduced.
+        let sizes = equal_sizes(
+            px(300.),
+            &[px(200.)..px(400.), px(0.)..px(0.), px(0.)..px(0.)],
+        );
+        assert_eq!(sizes, vec![px(200.), px(50.), px(50.)]);
+        assert_eq!(sum(&sizes), 300.);
+
+        // All the panels are clamped.
+        let sizes = equal_sizes(px(600.), &[px(100.)..px(200.), px(100.)..px(200.)]);
+        assert_eq!(sizes, vec![px(200.), px(200.)]);
+    }
+}
@@ -13,7 +13,10 @@ use crate::{AxisExt, ElementExt, h_flex, resizable::PANEL_MIN_SIZE, v_flex};
 
 use super::{ResizableState, resizable_panel, resize_handle};
 
+/// Events emitted by the [`ResizableState`].
 pub enum ResizablePanelEvent {
+    /// The panels have been resized by dragging or resetting,
+    /// read the new sizes by [`ResizableState::sizes`].
     Resized,
 }
 
@@ -150,6 +153,7 @@ impl RenderOnce for ResizablePanelGroup {
                         panel.panel_ix = ix;
                         panel.axis = self.axis;
                         panel.state = Some(state.clone());
+                        panel.on_resize = Some(self.on_resize.clone());
                         panel
                     }),
             )
@@ -182,6 +186,7 @@ pub struct ResizablePanel {
     axis: Axis,
     panel_ix: usize,
     state: Option<Entity<ResizableState>>,
+    on_resize: Option<Rc<dyn Fn(&Entity<ResizableState>, &mut Window, &mut App)>>,
     /// Initial size is the size that the panel has when it is created.
     initial_size: Option<Pixels>,
     /// size range limit of this panel.
@@ -197,6 +202,7 @@ impl ResizablePanel {
             panel_ix: 0,
             initial_size: None,
             state: None,
+            on_resize: None,
             size_range: (PANEL_MIN_SIZE..Pixels::MAX),
             axis: Axis::Horizontal,
             children: vec![],
@@ -287,17 +293,27 @@ impl RenderOnce for ResizablePanel {
             .children(self.children)
             .when(self.panel_ix > 0, |this| {
                 let ix = self.panel_ix - 1;
-                this.child(resize_handle(("resizable-handle", ix), self.axis).on_drag(
-                    DragPanel,
-                    move |drag_panel, _, _, cx| {
-                        cx.stop_propagation();
-                        // Set current resizing panel ix
-                        state.update(cx, |state, _| {
-                            state.resizing_panel_ix = Some(ix);
-                        });
-                        cx.new(|_| drag_panel.deref().clone())
-                    },
-                ))
+                let on_resize = self.on_resize.clone();
+                this.child(
+                    resize_handle(("resizable-handle", ix), self.axis)
+                        .on_drag(DragPanel, {
+                            let state = state.clone();
+                            move |drag_panel, _, _, cx| {
+                                cx.stop_propagation();
+                                // Set current resizing panel ix
+                                state.update(cx, |state, _| {
+                                    state.resizing_panel_ix = Some(ix);
+                                });
+                                cx.new(|_| drag_panel.deref().clone())
+                            }
+                        })
+                        .on_double_click(move |window, cx| {
+                            state.update(cx, |state, cx| state.reset_sizes(cx));
+                            if let Some(on_resize) = &on_resize {
+                                on_resize(&state, window, cx);
+                            }
+                        }),
+                )
             })
     }
 }
@@ -1,5 +1,6 @@
 use crate::{
-    ActiveTheme, ElementExt, Placement,
+    ActiveTheme, ElementExt, Placement, Theme, ThemeModePreference, WindowExt as _,
+    button::{Mnemonic, MnemonicState},
     dialog::Dialog,
     input::InputState,
     notification::{Notification, NotificationList},
@@ -7,20 +8,39 @@ use crate::{
     window_border,
 };
 use gpui::{
-    AnyView, App, AppContext, Context, DefiniteLength, Entity, FocusHandle, InteractiveElement,
-    IntoElement, KeyBinding, ParentElement as _, Render, Styled, WeakFocusHandle, Window, actions,
-    div, prelude::FluentBuilder as _,
+    AnyElement, AnyView, App, AppContext, ClickEvent, Context, DefiniteLength, Entity, FocusHandle,
+    InteractiveElement, IntoElement, KeyBinding, ParentElement as _, Render, RenderOnce,
+    SharedString, Styled, Subscription, WeakEntity, WeakFocusHandle, Window, actions, div,
+    prelude::FluentBuilder as _,
+};
+use rust_i18n::t;
+use std::{
+    any::{Any, TypeId},
+    panic::{self, AssertUnwindSafe},
+    rc::Rc,
 };
-use std::{any::TypeId, rc::Rc};
 
-actions!(root, [Tab, TabPrev]);
+actions!(root, [Tab, TabPrev, ZoomIn, ZoomOut, ResetZoom]);
 
 const CONTEXT: &str = "Root";
+const ZOOM_STEP: f32 = 0.1;
+
 pub(crate) fn init(cx: &mut App) {
     cx.bind_keys([
         KeyBinding::new("tab", Tab, Some(CONTEXT)),
         KeyBinding::new("shift-tab", TabPrev, Some(CONTEXT)),
+        KeyBinding::new("secondary-=", ZoomIn, Some(CONTEXT)),
+        KeyBinding::new("secondary-+", ZoomIn, Some(CONTEXT)),
+        KeyBinding::new("secondary--", ZoomOut, Some(CONTEXT)),
+        KeyBinding::new("secondary-0", ResetZoom, Some(CONTEXT)),
     ]);
+
+    // The mnemonics of the Buttons, see `Button::mnemonic`.
+    cx.bind_keys(
+        ('a'..='z')
+            .chain('0'..='9')
+            .map(|key| KeyBinding::new(&format!("alt-{key}"), Mnemonic { key }, Some(CONTEXT))),
+    );
 }
 
 /// Root is a view for the App window for as the top level view (Must be the first view in the window).
@@ -30,9 +50,12 @@ pub struct Root {
     pub(crate) active_sheet: Option<ActiveSheet>,
     pub(crate) active_dialogs: Vec<ActiveDialog>,
     pub(super) focused_input: Option<Entity<InputState>>,
+    /// The rendered Buttons that have the mnemonic.
+    pub(crate) mnemonic_buttons: Vec<WeakEntity<MnemonicState>>,
     pub notification: Entity<NotificationList>,
     sheet_size: Option<DefiniteLength>,
     view: AnyView,
+    _subscriptions: Vec<Subscription>,
 }
 
 #[derive(Clone)]
@@ -69,13 +92,22 @@ impl ActiveDialog {
 impl Root {
     /// Create a new Root view.
     pub fn new(view: impl Into<AnyView>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        // Follow the system appearance changes, see `ThemeModePreference::System`.
+        let _subscriptions = vec![cx.observe_window_appearance(window, |_, window, cx| {
+            if Theme::global(cx).follow_system {
+                Theme::change(ThemeModePreference::System, Some(window), cx);
+            }
+        })];
+
         Self {
             active_sheet: None,
             active_dialogs: Vec::new(),
             focused_input: None,
+            mnemonic_buttons: Vec::new(),
             notification: cx.new(|cx| NotificationList::new(window, cx)),
             sheet_size: None,
             view: view.into(),
+            _subscriptions,
         }
     }
 
@@ -277,6 +309,10 @@ impl Root {
         cx.notify();
     }
 
+    /// Push a notification to the notification layer, the notifications are stacked at the
+    /// top right corner, and dismissed after the timeout or by the close button.
+    ///
+    /// The notification with the same id replaces the previous one, see [`Notification::id`].
     pub fn push_notification(
         &mut self,
         note: impl Into<Notification>,
@@ -288,6 +324,7 @@ impl Root {
         cx.notify();
     }
 
+    /// Dismiss the notification with the id of the type `T`.
     pub fn remove_notification<T: Sized + 'static>(
         &mut self,
         window: &mut Window,
@@ -300,6 +337,7 @@ impl Root {
         cx.notify();
     }
 
+    /// Remove all the notifications.
     pub fn clear_notifications(&mut self, window: &mut Window, cx: &mut Context<'_, Root>) {
         self.notification
             .update(cx, |view, cx| view.clear(window, cx));
@@ -311,6 +349,48 @@ impl Root {
         &self.view
     }
 
+    /// Run the closure and catch the panic inside it, returns the panic message if it panicked.
+    ///
+    /// The panic is logged and shown as an error notification instead of taking down the window,
+    /// use it to guard the pure computations that may panic by an unexpected input, e.g. a formatter.
+    ///
+    /// The closure takes no window or app context by design, continuing after a panic in the
+    /// middle of updating the entities is not safe, so only the captured data can be used.
+    ///
+    /// NOTE: The panic can't be caught if the `panic = "abort"` is set in the profile.
+    ///
+    /// ```ignore
+    /// if let Ok(formatted) = Root::catch_panic(window, cx, || format_code(&text)) {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn catch_panic<R>(
+        window: &mut Window,
+        cx: &mut App,
+        f: impl FnOnce() -> R,
+    ) -> Result<R, SharedString> {
+        let payload = match panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(result) => return Ok(result),
+            Err(payload) => payload,
+        };
+
+        let message = panic_message(payload.as_ref());
+        tracing::error!("caught panic: {}", message);
+        // Defer to avoid updating the Root during the rendering.
+        window.defer(cx, {
+            let message = message.clone();
+            move |window, cx| {
+                window.push_notification(
+                    Notification::error(message)
+                        .id::<PanicNotification>()
+                        .title(t!("Root.Unexpected Error")),
+                    cx,
+                );
+            }
+        });
+        Err(message)
+    }
+
     fn on_action_tab(&mut self, _: &Tab, window: &mut Window, _: &mut Context<Self>) {
         window.focus_next();
     }
@@ -318,11 +398,153 @@ impl Root {
     fn on_action_tab_prev(&mut self, _: &TabPrev, window: &mut Window, _: &mut Context<Self>) {
         window.focus_prev();
     }
+
+    /// Click the Button of the mnemonic in the focused container, that is the focused Dialog or Sheet,
+    /// or the window if they are not focused.
+    fn on_action_mnemonic(
+        &mut self,
+        action: &Mnemonic,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let containers = self
+            .active_dialogs
+            .iter()
+            .rev()
+            .map(|dialog| &dialog.focus_handle)
+            .chain(self.active_sheet.as_ref().map(|sheet| &sheet.focus_handle))
+            .collect::<Vec<_>>();
+        let focused_container = containers
+            .iter()
+            .find(|container| container.contains_focused(window, cx));
+
+        self.mnemonic_buttons
+            .retain(|state| state.upgrade().is_some());
+        let on_click = self
+            .mnemonic_buttons
+            .iter()
+            .filter_map(|state| state.upgrade())
+            .find_map(|state| {
+                let state = state.read(cx);
+                let in_scope = match focused_container {
+                    Some(container) => container.contains(&state.focus_handle, window),
+                    None => !containers
+                        .iter()
+                        .any(|container| container.contains(&state.focus_handle, window)),
+                };
+
+                (state.mnemonic == action.key && in_scope)
+                    .then(|| state.on_click.clone())
+                    .flatten()
+            });
+
+        let Some(on_click) = on_click else {
+            cx.propagate();
+            return;
+        };
+
+        // Defer the click, the handler may update the Root, e.g.: open a Dialog.
+        window.defer(cx, move |window, cx| {
+            on_click(&ClickEvent::default(), window, cx);
+        });
+    }
+
+    fn on_action_zoom_in(&mut self, _: &ZoomIn, _: &mut Window, cx: &mut Context<Self>) {
+        Theme::set_ui_scale(cx.theme().ui_scale + ZOOM_STEP, cx);
+    }
+
+    fn on_action_zoom_out(&mut self, _: &ZoomOut, _: &mut Window, cx: &mut Context<Self>) {
+        Theme::set_ui_scale(cx.theme().ui_scale - ZOOM_STEP, cx);
+    }
+
+    fn on_action_reset_zoom(&mut self, _: &ResetZoom, _: &mut Window, cx: &mut Context<Self>) {
+        Theme::set_ui_scale(1., cx);
+    }
+}
+
+/// The notification id of the caught panics, only the latest one is shown.
+struct PanicNotification;
+
+/// Returns the message of the panic payload.
+fn panic_message(payload: &(dyn Any + Send)) -> SharedString {
+    if let Some(message) = payload.downcast_ref::<&'static str>() {
+        SharedString::from(*message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        SharedString::from(message.clone())
+    } else {
+        SharedString::from("Unknown panic")
+    }
+}
+
+/// Create an [`ErrorBoundary`] to run the `compute` and render its result by the `render`.
+pub fn error_boundary<T, F, R, E>(compute: F, render: R) -> ErrorBoundary
+where
+    T: 'static,
+    F: FnOnce() -> T + 'static,
+    R: FnOnce(T, &mut Window, &mut App) -> E + 'static,
+    E: IntoElement,
+{
+    ErrorBoundary {
+        builder: Box::new(move |window, cx| {
+            let result = Root::catch_panic(window, cx, compute)?;
+            Ok(render(result, window, cx).into_any_element())
+        }),
+        fallback: None,
+    }
+}
+
+/// An element to catch the panic of a computation by [`Root::catch_panic`], and render its
+/// result, the fallback is rendered with the panic message instead if it panicked.
+///
+/// Only the computation is guarded, it takes no window or app context, so the panic can't leave
+/// the entities in a broken state. The panics in the rendering, layout and paint are not caught.
+///
+/// ```ignore
+/// let source = preview.read(cx).source.clone();
+/// error_boundary(move || parse_document(&source), |document, _, _| document.render())
+///     .fallback(|message, _, _| Label::new(format!("Failed to render the preview: {}", message)))
+/// ```
+#[derive(IntoElement)]
+pub struct ErrorBoundary {
+    builder: Box<dyn FnOnce(&mut Window, &mut App) -> Result<AnyElement, SharedString>>,
+    fallback: Option<Box<dyn FnOnce(SharedString, &mut Window, &mut App) -> AnyElement>>,
+}
+
+impl ErrorBoundary {
+    /// Set the fallback element to render with the panic message when the computation panicked,
+    /// default is an error message.
+    pub fn fallback<F, E>(mut self, fallback: F) -> Self
+    where
+        F: FnOnce(SharedString, &mut Window, &mut App) -> E + 'static,
+        E: IntoElement,
+    {
+        self.fallback = Some(Box::new(move |message, window, cx| {
+            fallback(message, window, cx).into_any_element()
+        }));
+        self
+    }
+}
+
+impl RenderOnce for ErrorBoundary {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let message = match (self.builder)(window, cx) {
+            Ok(element) => return element,
+            Err(message) => message,
+        };
+
+        match self.fallback {
+            Some(fallback) => fallback(message, window, cx),
+            None => div()
+                .text_color(cx.theme().danger)
+                .child(t!("Root.Unexpected Error").to_string())
+                .into_any_element(),
+        }
+    }
 }
 
 impl Render for Root {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        window.set_rem_size(cx.theme().font_size);
+        window.set_rem_size(cx.theme().scaled_font_size());
 
         window_border().child(
             div()
@@ -330,6 +552,10 @@ impl Render for Root {
                 .key_context(CONTEXT)
                 .on_action(cx.listener(Self::on_action_tab))
                 .on_action(cx.listener(Self::on_action_tab_prev))
+                .on_action(cx.listener(Self::on_action_mnemonic))
+                .on_action(cx.listener(Self::on_action_zoom_in))
+                .on_action(cx.listener(Self::on_action_zoom_out))
+                .on_action(cx.listener(Self::on_action_reset_zoom))
                 .relative()
                 .size_full()
                 .font_family(cx.theme().font_family.clone())
@@ -339,3 +565,22 @@ impl Render for Root {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::panic_message;
+
+    #[test]
+    fn test_panic_message() {
+        let static_str = std::panic::catch_unwind(|| panic!("boom")).unwrap_err();
+        let string = std::panic::catch_unwind(|| panic!("index {} out of range", 3)).unwrap_err();
+        let other = std::panic::catch_unwind(|| std::panic::panic_any(42)).unwrap_err();
+
+        assert_eq!(panic_message(static_str.as_ref()).as_ref(), "boom");
+        assert_eq!(
+            panic_message(string.as_ref()).as_ref(),
+            "index 3 out of range"
+        );
+        assert_eq!(panic_message(other.as_ref()).as_ref(), "Unknown panic");
+    }
+}
@@ -395,7 +395,7 @@ impl Scrollbar {
     fn style_for_active(cx: &App) -> (Hsla, Hsla, Hsla, Pixels, Pixels, Pixels) {
         (
             cx.theme().scrollbar_thumb_hover,
-            cx.theme().scrollbar,
+            cx.theme().scrollbar_track,
             cx.theme().border,
             THUMB_ACTIVE_WIDTH,
             THUMB_ACTIVE_INSET,
@@ -406,7 +406,7 @@ impl Scrollbar {
     fn style_for_hovered_thumb(cx: &App) -> (Hsla, Hsla, Hsla, Pixels, Pixels, Pixels) {
         (
             cx.theme().scrollbar_thumb_hover,
-            cx.theme().scrollbar,
+            cx.theme().scrollbar_track,
             cx.theme().border,
             THUMB_ACTIVE_WIDTH,
             THUMB_ACTIVE_INSET,
@@ -417,7 +417,7 @@ impl Scrollbar {
     fn style_for_hovered_bar(cx: &App) -> (Hsla, Hsla, Hsla, Pixels, Pixels, Pixels) {
         (
             cx.theme().scrollbar_thumb,
-            cx.theme().scrollbar,
+            cx.theme().scrollbar_track,
             gpui::transparent_black(),
             THUMB_ACTIVE_WIDTH,
             THUMB_ACTIVE_INSET,
@@ -434,7 +434,7 @@ impl Scrollbar {
 
         (
             cx.theme().scrollbar_thumb,
-            cx.theme().scrollbar,
+            cx.theme().scrollbar_track,
             gpui::transparent_black(),
             width,
             inset,
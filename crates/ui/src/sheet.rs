@@ -1,15 +1,16 @@
 use std::{rc::Rc, time::Duration};
 
 use gpui::{
-    Animation, AnimationExt as _, AnyElement, App, ClickEvent, DefiniteLength, DismissEvent, Edges,
-    EventEmitter, FocusHandle, InteractiveElement as _, IntoElement, KeyBinding, MouseButton,
-    ParentElement, Pixels, RenderOnce, StyleRefinement, Styled, Window, anchored, div, point,
+    Animation, AnyElement, App, ClickEvent, DefiniteLength, DismissEvent, Edges, EventEmitter,
+    FocusHandle, InteractiveElement as _, IntoElement, KeyBinding, MouseButton, ParentElement,
+    Pixels, RenderOnce, StyleRefinement, Styled, Window, anchored, div, point,
     prelude::FluentBuilder as _, px,
 };
 
 use crate::{
     ActiveTheme, IconName, Placement, Sizable, StyledExt as _, WindowExt as _,
     actions::Cancel,
+    animation::MotionExt as _,
     button::{Button, ButtonVariants as _},
     dialog::overlay_color,
     h_flex,
@@ -256,7 +257,7 @@ impl RenderOnce for Sheet {
                                         .child(footer),
                                 )
                             })
-                            .with_animation(
+                            .with_motion(
                                 "slide",
                                 Animation::new(Duration::from_secs_f64(0.15)),
                                 move |this, delta| {
@@ -268,6 +269,7 @@ impl RenderOnce for Sheet {
                                         Placement::Left => this.left(y),
                                     })
                                 },
+                                cx,
                             ),
                     ),
             )
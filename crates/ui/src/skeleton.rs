@@ -36,7 +36,7 @@ impl Styled for Skeleton {
 
 impl RenderOnce for Skeleton {
     fn render(self, _: &mut gpui::Window, cx: &mut gpui::App) -> impl IntoElement {
-        div()
+        let skeleton = div()
             .w_full()
             .h_4()
             .bg(if self.secondary {
@@ -44,7 +44,14 @@ impl RenderOnce for Skeleton {
             } else {
                 cx.theme().skeleton
             })
-            .refine_style(&self.style)
+            .refine_style(&self.style);
+
+        // Keep the skeleton static without the pulse when the reduced motion is enabled.
+        if cx.theme().reduced_motion {
+            return skeleton.into_any_element();
+        }
+
+        skeleton
             .with_animation(
                 "skeleton",
                 Animation::new(Duration::from_secs(2))
@@ -55,5 +62,6 @@ impl RenderOnce for Skeleton {
                     this.opacity(v)
                 },
             )
+            .into_any_element()
     }
 }
@@ -0,0 +1,197 @@
+use gpui::{
+    AnyElement, App, IntoElement, ParentElement, RenderOnce, SharedString, StyleRefinement, Styled,
+    Window, div, prelude::FluentBuilder as _, px,
+};
+use rust_i18n::t;
+use smallvec::SmallVec;
+
+use crate::{
+    ActiveTheme, StyledExt as _, h_flex,
+    input::{InputState, Position, RopeExt as _, VimMode},
+};
+
+/// A thin bar to show the status items, usually at the bottom of a window or an editor.
+///
+/// The items are placed in the left, center and right slots, and clipped when
+/// there is not enough space.
+///
+/// ```ignore
+/// StatusBar::new()
+///     .left("JSON valid ✓")
+///     .right(InputStatus::new(editor.read(cx)))
+/// ```
+#[derive(IntoElement)]
+pub struct StatusBar {
+    style: StyleRefinement,
+    left: SmallVec<[AnyElement; 2]>,
+    center: SmallVec<[AnyElement; 2]>,
+    right: SmallVec<[AnyElement; 2]>,
+}
+
+impl StatusBar {
+    /// Create a new status bar.
+    pub fn new() -> Self {
+        Self {
+            style: StyleRefinement::default(),
+            left: SmallVec::new(),
+            center: SmallVec::new(),
+            right: SmallVec::new(),
+        }
+    }
+
+    /// Add an item to the left slot.
+    pub fn left(mut self, item: impl IntoElement) -> Self {
+        self.left.push(item.into_any_element());
+        self
+    }
+
+    /// Add an item to the center slot.
+    pub fn center(mut self, item: impl IntoElement) -> Self {
+        self.center.push(item.into_any_element());
+        self
+    }
+
+    /// Add an item to the right slot.
+    pub fn right(mut self, item: impl IntoElement) -> Self {
+        self.right.push(item.into_any_element());
+        self
+    }
+}
+
+impl Styled for StatusBar {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for StatusBar {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let slot = || {
+            h_flex()
+                .min_w_0()
+                .gap_3()
+                .overflow_hidden()
+                .whitespace_nowrap()
+        };
+
+        h_flex()
+            .w_full()
+            .h(px(24.))
+            .px_2()
+            .gap_3()
+            .overflow_hidden()
+            .text_xs()
+            .text_color(cx.theme().muted_foreground)
+            .bg(cx.theme().status_bar)
+            .border_t_1()
+            .border_color(cx.theme().status_bar_border)
+            .refine_style(&self.style)
+            .child(slot().flex_1().children(self.left))
+            .when(!self.center.is_empty(), |this| {
+                this.child(slot().flex_shrink().children(self.center))
+            })
+            .child(slot().flex_1().justify_end().children(self.right))
+    }
+}
+
+/// The status of an [`InputState`] to show in the [`StatusBar`].
+///
+/// Includes the cursor position, the selection length, the total lines, the encoding
+/// and the byte size, with the Vim mode if [`InputState::vim_mode`] is enabled. Create it on
+/// render and notify the view on the [`crate::input::InputEvent::SelectionChanged`],
+/// [`crate::input::InputEvent::Change`] and [`crate::input::InputEvent::VimModeChanged`]
+/// to keep it updated.
+#[derive(IntoElement, Debug, Clone)]
+pub struct InputStatus {
+    cursor: Position,
+    selection_len: usize,
+    lines: usize,
+    bytes: usize,
+    encoding: SharedString,
+    vim_mode: Option<VimMode>,
+}
+
+impl InputStatus {
+    /// Create the status from the input state.
+    pub fn new(state: &InputState) -> Self {
+        let text = state.text();
+
+        Self {
+            cursor: state.cursor_position(),
+            selection_len: state.selected_chars_len(),
+            lines: text.lines_len(),
+            bytes: text.len(),
+            encoding: "UTF-8".into(),
+            vim_mode: state.vim_mode_state(),
+        }
+    }
+
+    /// Set the encoding to show, default is `UTF-8`.
+    pub fn encoding(mut self, encoding: impl Into<SharedString>) -> Self {
+        self.encoding = encoding.into();
+        self
+    }
+}
+
+impl RenderOnce for InputStatus {
+    fn render(self, _: &mut Window, _: &mut App) -> impl IntoElement {
+        h_flex()
+            .gap_3()
+            .when_some(self.vim_mode, |this, mode| {
+                this.child(div().font_semibold().child(mode.label()))
+            })
+            .child(div().child(SharedString::from(t!(
+                "StatusBar.line_column",
+                line = self.cursor.line + 1,
+                column = self.cursor.character + 1
+            ))))
+            .when(self.selection_len > 0, |this| {
+                this.child(div().child(SharedString::from(t!(
+                    "StatusBar.selected",
+                    count = self.selection_len
+                ))))
+            })
+            .child(div().child(SharedString::from(t!(
+                "StatusBar.lines",
+                count = self.lines
+            ))))
+            .child(div().child(self.encoding))
+            .child(div().child(format_bytes(self.bytes)))
+    }
+}
+
+/// Format the byte size to a human readable string, e.g.: `512 B`, `1.5 KB`.
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if size < 1024. {
+            break;
+        }
+        size /= 1024.;
+        unit = next;
+    }
+
+    format!("{:.1} {}", size, unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_bytes;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KB");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+}
@@ -156,10 +156,14 @@ pub trait StyledExt: Styled + Sized {
         }
     }
 
-    /// Render a border with a width of 1px, color ring color
+    /// Render a border with a width of 1px (2px in high contrast), color ring color
     #[inline]
     fn focused_border(self, cx: &App) -> Self {
-        self.border_1().border_color(cx.theme().ring)
+        if cx.theme().high_contrast {
+            self.border_2().border_color(cx.theme().ring)
+        } else {
+            self.border_1().border_color(cx.theme().ring)
+        }
     }
 
     font_weight!(font_thin, THIN);
@@ -547,7 +551,12 @@ impl<T: ParentElement + Styled + Sized> FocusableExt<T> for T {
             return self;
         }
 
-        const RING_BORDER_WIDTH: Pixels = px(1.5);
+        // Draw a thicker and opaque ring in high contrast to be clearly visible.
+        let (ring_width, ring_color) = if cx.theme().high_contrast {
+            (px(2.), cx.theme().ring)
+        } else {
+            (px(1.5), cx.theme().ring.alpha(0.2))
+        };
         let rem_size = window.rem_size();
         let style = self.style();
 
@@ -597,7 +606,7 @@ impl<T: ParentElement + Styled + Sized> FocusableExt<T> for T {
                 .map(|v| v.to_pixels(rem_size))
                 .unwrap_or_default(),
         }
-        .map(|v| *v + RING_BORDER_WIDTH);
+        .map(|v| *v + ring_width);
 
         let mut inner_style = StyleRefinement::default();
         inner_style.corner_radii.top_left = Some(radius.top_left.into());
@@ -605,7 +614,7 @@ impl<T: ParentElement + Styled + Sized> FocusableExt<T> for T {
         inner_style.corner_radii.bottom_left = Some(radius.bottom_left.into());
         inner_style.corner_radii.bottom_right = Some(radius.bottom_right.into());
 
-        let inset = RING_BORDER_WIDTH + margins;
+        let inset = ring_width + margins;
 
         self.child(
             div()
@@ -615,8 +624,8 @@ impl<T: ParentElement + Styled + Sized> FocusableExt<T> for T {
                 .left(-(inset + border_widths.left))
                 .right(-(inset + border_widths.right))
                 .bottom(-(inset + border_widths.bottom))
-                .border(RING_BORDER_WIDTH)
-                .border_color(cx.theme().ring.alpha(0.2))
+                .border(ring_width)
+                .border_color(ring_color)
                 .refine_style(&inner_style),
         )
     }
@@ -1,10 +1,11 @@
 use crate::{
-    h_flex, text::Text, tooltip::Tooltip, ActiveTheme, Disableable, Side, Sizable, Size, StyledExt,
+    animation::MotionExt as _, h_flex, text::Text, tooltip::Tooltip, ActiveTheme, Disableable,
+    Side, Sizable, Size, StyledExt,
 };
 use gpui::{
-    div, prelude::FluentBuilder as _, px, Animation, AnimationExt as _, App, ElementId,
-    InteractiveElement, IntoElement, ParentElement as _, RenderOnce, SharedString,
-    StatefulInteractiveElement, StyleRefinement, Styled, Window,
+    div, prelude::FluentBuilder as _, px, Animation, App, ElementId, InteractiveElement,
+    IntoElement, ParentElement as _, RenderOnce, SharedString, StatefulInteractiveElement,
+    StyleRefinement, Styled, Window,
 };
 use std::{rc::Rc, time::Duration};
 
@@ -166,7 +167,7 @@ impl RenderOnce for Switch {
                                         })
                                         .detach();
 
-                                        this.with_animation(
+                                        this.with_motion(
                                             ElementId::NamedInteger("move".into(), checked as u64),
                                             Animation::new(duration),
                                             move |this, delta| {
@@ -178,8 +179,8 @@ impl RenderOnce for Switch {
                                                 };
                                                 this.left(x)
                                             },
+                                            cx,
                                         )
-                                        .into_any_element()
                                     } else {
                                         let max_x = bg_width - bar_width - inset * 2;
                                         let x = if checked { max_x } else { px(0.) };
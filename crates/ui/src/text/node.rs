@@ -556,7 +556,7 @@ impl CodeBlock {
                     .rounded(cx.theme().radius)
                     .bg(cx.theme().muted)
                     .font_family(cx.theme().mono_font_family.clone())
-                    .text_size(cx.theme().mono_font_size)
+                    .text_size(cx.theme().scaled_mono_font_size())
                     .relative()
                     .refine_style(&style.code_block)
                     .child(Inline::new(
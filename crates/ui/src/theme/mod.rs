@@ -1,4 +1,7 @@
-use crate::{highlighter::HighlightTheme, scroll::ScrollbarShow};
+use crate::{
+    highlighter::{DiagnosticStyle, HighlightTheme, SyntaxColors, ThemeStyle},
+    scroll::ScrollbarShow,
+};
 use gpui::{px, App, Global, Hsla, Pixels, SharedString, Window, WindowAppearance};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -18,11 +21,17 @@ pub use registry::*;
 pub use schema::*;
 pub use theme_color::*;
 
+/// The minimum scale factor of the UI.
+pub const MIN_UI_SCALE: f32 = 0.5;
+/// The maximum scale factor of the UI.
+pub const MAX_UI_SCALE: f32 = 3.0;
+
 pub fn init(cx: &mut App) {
     registry::init(cx);
 
     Theme::sync_system_appearance(None, cx);
     Theme::sync_scrollbar_appearance(cx);
+    Theme::sync_reduced_motion(cx);
 }
 
 pub trait ActiveTheme {
@@ -73,6 +82,23 @@ pub struct Theme {
     pub tile_shadow: bool,
     /// The border radius of the tile panel, default is 0px.
     pub tile_radius: Pixels,
+    /// The style to render the diagnostics, default: [`DiagnosticStyle::Default`].
+    pub diagnostic_style: DiagnosticStyle,
+    /// The scale factor of the UI, default is 1.0.
+    ///
+    /// This scales the `font_size` (the rem size) and the `mono_font_size`,
+    /// so the text and the rem based spacing are scaled coherently.
+    pub ui_scale: f32,
+    /// Whether the theme is a high contrast theme, e.g.: [`Theme::high_contrast_light`].
+    ///
+    /// The focus rings and the selected list items are drawn with thicker borders in high contrast.
+    pub high_contrast: bool,
+    /// Whether to reduce the motion, the component animations are skipped if true,
+    /// default is detected from the OS setting, see [`Theme::set_reduced_motion`].
+    pub reduced_motion: bool,
+    /// Whether the theme mode follows the system appearance, see [`ThemeModePreference::System`].
+    #[serde(skip)]
+    pub follow_system: bool,
 }
 
 impl Default for Theme {
@@ -116,6 +142,16 @@ impl Theme {
         self.mode.is_dark()
     }
 
+    /// Returns the theme mode set by [`Theme::change`], this is [`ThemeModePreference::System`]
+    /// when following the system appearance, while the `mode` is the resolved light or dark mode.
+    pub fn preferred_mode(&self) -> ThemeModePreference {
+        if self.follow_system {
+            ThemeModePreference::System
+        } else {
+            self.mode.into()
+        }
+    }
+
     /// Returns the current theme name.
     pub fn theme_name(&self) -> &SharedString {
         if self.is_dark() {
@@ -127,14 +163,16 @@ impl Theme {
 
     /// Sync the theme with the system appearance
     pub fn sync_system_appearance(window: Option<&mut Window>, cx: &mut App) {
+        let appearance = Self::system_appearance(window.as_deref(), cx);
+        Self::change(appearance, window, cx);
+    }
+
+    fn system_appearance(window: Option<&Window>, cx: &App) -> WindowAppearance {
         // Better use window.appearance() for avoid error on Linux.
         // https://github.com/longbridge/gpui-component/issues/104
-        let appearance = window
-            .as_ref()
+        window
             .map(|window| window.appearance())
-            .unwrap_or_else(|| cx.window_appearance());
-
-        Self::change(appearance, window, cx);
+            .unwrap_or_else(|| cx.window_appearance())
     }
 
     /// Sync the Scrollbar showing behavior with the system
@@ -146,9 +184,97 @@ impl Theme {
         };
     }
 
-    /// Change the theme mode.
-    pub fn change(mode: impl Into<ThemeMode>, window: Option<&mut Window>, cx: &mut App) {
-        let mode = mode.into();
+    /// Set the style to render the diagnostics.
+    ///
+    /// Use [`DiagnosticStyle::ColorBlind`] for a color-blind friendly palette with different underline shapes.
+    pub fn set_diagnostic_style(style: DiagnosticStyle, cx: &mut App) {
+        Theme::global_mut(cx).diagnostic_style = style;
+        cx.refresh_windows();
+    }
+
+    /// Returns the syntax styles of the code editor highlighting, by the highlight names.
+    #[inline]
+    pub fn syntax(&self) -> &SyntaxColors {
+        &self.highlight_theme.style.syntax
+    }
+
+    /// Set the style of a highlight name (e.g. `string`, `property`) in the current theme,
+    /// pass `None` to fall back to the editor foreground color.
+    ///
+    /// Returns false if the name is not in [`crate::highlighter::HIGHLIGHT_NAMES`]. The style is
+    /// reset when the theme is changed, use the `highlight.syntax` of the [`ThemeConfig`] to keep it.
+    pub fn set_syntax_style(name: &str, style: Option<ThemeStyle>, cx: &mut App) -> bool {
+        let theme = Theme::global_mut(cx);
+        let changed = Arc::make_mut(&mut theme.highlight_theme)
+            .style
+            .syntax
+            .set_style(name, style);
+        cx.refresh_windows();
+        changed
+    }
+
+    /// Set the scale factor of the UI, clamped to [`MIN_UI_SCALE`]..=[`MAX_UI_SCALE`].
+    ///
+    /// The [`crate::Root`] also binds `secondary-=`, `secondary--` and `secondary-0`
+    /// to zoom in, zoom out and reset the scale.
+    pub fn set_ui_scale(scale: f32, cx: &mut App) {
+        Theme::global_mut(cx).ui_scale = scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        cx.refresh_windows();
+    }
+
+    /// Set true to reduce the motion, the component animations (e.g.: Dialog, Sheet, Notification,
+    /// Switch and the Tree chevron) are skipped and the elements are rendered at the end state.
+    pub fn set_reduced_motion(reduced: bool, cx: &mut App) {
+        Theme::global_mut(cx).reduced_motion = reduced;
+        cx.refresh_windows();
+    }
+
+    /// Sync the reduced motion with the OS setting.
+    ///
+    /// - macOS: `Reduce motion` of the Accessibility Display settings.
+    /// - Linux: `enable-animations` of the GNOME desktop interface settings.
+    ///
+    /// The other platforms are not detected, use [`Theme::set_reduced_motion`] instead.
+    ///
+    /// The setting is read by a command in the background, and applied when it's done.
+    pub fn sync_reduced_motion(cx: &mut App) {
+        let reduced = cx.background_spawn(async move { system_reduced_motion() });
+        cx.spawn(async move |cx| {
+            let reduced = reduced.await;
+            _ = cx.update(|cx| {
+                if cx.theme().reduced_motion != reduced {
+                    Theme::set_reduced_motion(reduced, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Returns the `font_size` multiplied by the `ui_scale`, used as the rem size of the window.
+    #[inline]
+    pub fn scaled_font_size(&self) -> Pixels {
+        self.font_size * self.ui_scale
+    }
+
+    /// Returns the `mono_font_size` multiplied by the `ui_scale`.
+    #[inline]
+    pub fn scaled_mono_font_size(&self) -> Pixels {
+        self.mono_font_size * self.ui_scale
+    }
+
+    /// Change the theme mode, and refresh all windows to redraw with the new colors.
+    ///
+    /// The [`ThemeModePreference::System`] follows the system appearance, the [`crate::Root`] of
+    /// each window updates the theme when the system appearance changes.
+    pub fn change(mode: impl Into<ThemeModePreference>, window: Option<&mut Window>, cx: &mut App) {
+        let preference = mode.into();
+        let follow_system = preference == ThemeModePreference::System;
+        let mode = match preference {
+            ThemeModePreference::Light => ThemeMode::Light,
+            ThemeModePreference::Dark => ThemeMode::Dark,
+            ThemeModePreference::System => Self::system_appearance(window.as_deref(), cx).into(),
+        };
+
         if !cx.has_global::<Theme>() {
             let mut theme = Theme::default();
             theme.light_theme = ThemeRegistry::global(cx).default_light_theme().clone();
@@ -158,6 +284,7 @@ impl Theme {
 
         let theme = cx.global_mut::<Theme>();
         theme.mode = mode;
+        theme.follow_system = follow_system;
         if mode.is_dark() {
             theme.apply_config(&theme.dark_theme.clone());
         } else {
@@ -167,6 +294,17 @@ impl Theme {
         if let Some(window) = window {
             window.refresh();
         }
+        cx.refresh_windows();
+    }
+
+    /// Toggle the theme mode between light and dark, this stops following the system appearance.
+    pub fn toggle(window: Option<&mut Window>, cx: &mut App) {
+        let mode = if Theme::global(cx).is_dark() {
+            ThemeMode::Light
+        } else {
+            ThemeMode::Dark
+        };
+        Self::change(mode, window, cx);
     }
 
     /// Get the editor background color, if not set, use the theme background color.
@@ -202,6 +340,11 @@ impl From<&ThemeColor> for Theme {
             tile_grid_size: px(8.),
             tile_shadow: true,
             tile_radius: px(0.),
+            diagnostic_style: DiagnosticStyle::default(),
+            ui_scale: 1.,
+            high_contrast: false,
+            reduced_motion: false,
+            follow_system: false,
             colors: *colors,
             light_theme: Rc::new(ThemeConfig::default()),
             dark_theme: Rc::new(ThemeConfig::default()),
@@ -210,6 +353,37 @@ impl From<&ThemeColor> for Theme {
     }
 }
 
+/// Read the reduced motion setting of the OS, returns false if not available.
+fn system_reduced_motion() -> bool {
+    let (program, args, reduced_value) = if cfg!(target_os = "macos") {
+        (
+            "defaults",
+            ["read", "com.apple.universalaccess", "reduceMotion"],
+            "1",
+        )
+    } else if cfg!(any(target_os = "linux", target_os = "freebsd")) {
+        (
+            "gsettings",
+            ["get", "org.gnome.desktop.interface", "enable-animations"],
+            "false",
+        )
+    } else {
+        return false;
+    };
+
+    match std::process::Command::new(program).args(args).output() {
+        Ok(output) if output.status.success() => {
+            is_reduced_motion(&String::from_utf8_lossy(&output.stdout), reduced_value)
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if the output of the OS setting is the value of the reduced motion.
+fn is_reduced_motion(output: &str, reduced_value: &str) -> bool {
+    output.trim() == reduced_value
+}
+
 #[derive(
     Debug, Clone, Copy, Default, PartialEq, PartialOrd, Eq, Hash, Serialize, Deserialize, JsonSchema,
 )]
@@ -243,3 +417,107 @@ impl From<WindowAppearance> for ThemeMode {
         }
     }
 }
+
+/// The theme mode to set by [`Theme::change`], include following the system appearance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ThemeModePreference {
+    #[default]
+    Light,
+    Dark,
+    /// Follow the system appearance, it's resolved to the [`ThemeMode::Light`] or
+    /// [`ThemeMode::Dark`] in the [`Theme::mode`].
+    System,
+}
+
+impl ThemeModePreference {
+    /// Return lower_case name: `light`, `dark`, `system`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThemeModePreference::Light => "light",
+            ThemeModePreference::Dark => "dark",
+            ThemeModePreference::System => "system",
+        }
+    }
+}
+
+impl From<ThemeMode> for ThemeModePreference {
+    fn from(mode: ThemeMode) -> Self {
+        match mode {
+            ThemeMode::Light => Self::Light,
+            ThemeMode::Dark => Self::Dark,
+        }
+    }
+}
+
+impl From<WindowAppearance> for ThemeModePreference {
+    fn from(appearance: WindowAppearance) -> Self {
+        ThemeMode::from(appearance).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use gpui::TestAppContext;
+
+    use super::*;
+
+    #[gpui::test]
+    fn test_change_theme(cx: &mut TestAppContext) {
+        cx.update(|cx| init(cx));
+
+        let notified = Rc::new(Cell::new(0));
+        cx.update(|cx| {
+            let notified = notified.clone();
+            cx.observe_global::<Theme>(move |_| notified.set(notified.get() + 1))
+                .detach();
+        });
+
+        cx.update(|cx| crate::set_theme(ThemeMode::Dark, cx));
+        cx.update(|cx| {
+            assert!(cx.theme().is_dark());
+            assert_eq!(cx.theme().preferred_mode(), ThemeModePreference::Dark);
+            assert_eq!(cx.theme().background, ThemeColor::dark().background);
+        });
+        // The observers of the theme are notified to re-read the colors.
+        assert!(notified.get() > 0);
+
+        cx.update(|cx| crate::toggle_theme(cx));
+        cx.update(|cx| {
+            assert!(!cx.theme().is_dark());
+            assert_eq!(cx.theme().preferred_mode(), ThemeModePreference::Light);
+        });
+
+        cx.update(|cx| crate::set_theme(ThemeModePreference::System, cx));
+        cx.update(|cx| {
+            assert_eq!(cx.theme().mode, ThemeMode::from(cx.window_appearance()));
+            assert_eq!(cx.theme().preferred_mode(), ThemeModePreference::System);
+        });
+
+        // Toggle stops following the system appearance.
+        cx.update(|cx| crate::toggle_theme(cx));
+        cx.update(|cx| assert!(!cx.theme().follow_system));
+    }
+
+    #[test]
+    fn test_is_reduced_motion() {
+        // macOS `defaults read com.apple.universalaccess reduceMotion`
+        assert!(is_reduced_motion("1\n", "1"));
+        assert!(!is_reduced_motion("0\n", "1"));
+        // GNOME `gsettings get org.gnome.desktop.interface enable-animations`
+        assert!(is_reduced_motion("false\n", "false"));
+        assert!(!is_reduced_motion("true\n", "false"));
+        assert!(!is_reduced_motion("", "false"));
+    }
+
+    #[gpui::test]
+    fn test_reduced_motion(cx: &mut TestAppContext) {
+        cx.update(|cx| init(cx));
+
+        cx.update(|cx| Theme::set_reduced_motion(true, cx));
+        cx.update(|cx| assert!(cx.theme().reduced_motion));
+        cx.update(|cx| Theme::set_reduced_motion(false, cx));
+        cx.update(|cx| assert!(!cx.theme().reduced_motion));
+    }
+}
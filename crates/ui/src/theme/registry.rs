@@ -11,6 +11,7 @@ use std::{
 };
 
 const DEFAULT_THEME: &str = include_str!("./default-theme.json");
+const HIGH_CONTRAST_THEME: &str = include_str!("./high-contrast-theme.json");
 pub(crate) static DEFAULT_THEME_COLORS: LazyLock<
     HashMap<ThemeMode, (Arc<ThemeColor>, Arc<HighlightTheme>)>,
 > = LazyLock::new(|| {
@@ -39,6 +40,17 @@ pub(crate) static DEFAULT_THEME_COLORS: LazyLock<
     colors
 });
 
+/// Returns the built-in high contrast theme of the mode.
+pub(super) fn high_contrast_theme(mode: ThemeMode) -> Rc<ThemeConfig> {
+    let theme = serde_json::from_str::<ThemeSet>(HIGH_CONTRAST_THEME)
+        .expect("failed to parse high contrast theme.")
+        .themes
+        .into_iter()
+        .find(|theme| theme.mode == mode)
+        .expect("missing high contrast theme.");
+    Rc::new(theme)
+}
+
 pub(super) fn init(cx: &mut App) {
     cx.set_global(ThemeRegistry::default());
     ThemeRegistry::global_mut(cx).init_default_themes();
@@ -46,6 +58,7 @@ pub(super) fn init(cx: &mut App) {
     // Observe changes to the theme registry to apply changes to the active theme
     cx.observe_global::<ThemeRegistry>(|cx| {
         let mode = Theme::global(cx).mode;
+        let preferred_mode = Theme::global(cx).preferred_mode();
         let light_theme = Theme::global(cx).light_theme.name.clone();
         let dark_theme = Theme::global(cx).dark_theme.name.clone();
 
@@ -67,7 +80,7 @@ pub(super) fn init(cx: &mut App) {
         };
 
         tracing::info!("Reload active theme: {:?}...", theme_name);
-        Theme::change(mode, None, cx);
+        Theme::change(preferred_mode, None, cx);
         cx.refresh_windows();
     })
     .detach();
@@ -78,6 +91,8 @@ pub struct ThemeRegistry {
     themes_dir: PathBuf,
     default_themes: HashMap<ThemeMode, Rc<ThemeConfig>>,
     themes: HashMap<SharedString, Rc<ThemeConfig>>,
+    /// The themes registered by [`ThemeRegistry::register`], kept when reloading.
+    registered_themes: Vec<Rc<ThemeConfig>>,
     has_custom_themes: bool,
 }
 
@@ -147,6 +162,14 @@ impl ThemeRegistry {
         &self.default_themes[&ThemeMode::Dark]
     }
 
+    /// Register a theme config, e.g. loaded by [`ThemeConfig::from_json`], it replaces the
+    /// theme with the same name.
+    pub fn register(&mut self, theme: Rc<ThemeConfig>) {
+        self.registered_themes.retain(|t| t.name != theme.name);
+        self.registered_themes.push(theme.clone());
+        self.themes.insert(theme.name.clone(), theme);
+    }
+
     fn init_default_themes(&mut self) {
         let default_themes: Vec<ThemeConfig> = serde_json::from_str::<ThemeSet>(DEFAULT_THEME)
             .expect("failed to parse default theme.")
@@ -166,6 +189,15 @@ impl ThemeRegistry {
                 (name, Rc::clone(theme))
             })
             .collect();
+        self.insert_high_contrast_themes();
+    }
+
+    /// Insert the built-in high contrast themes, they are not the default themes.
+    fn insert_high_contrast_themes(&mut self) {
+        for mode in [ThemeMode::Light, ThemeMode::Dark] {
+            let theme = high_contrast_theme(mode);
+            self.themes.insert(theme.name.clone(), theme);
+        }
     }
 
     fn _watch_themes_dir(themes_dir: PathBuf, cx: &mut App) -> anyhow::Result<()> {
@@ -247,6 +279,7 @@ impl ThemeRegistry {
             self.themes
                 .insert(theme.name.clone(), Rc::new((**theme).clone()));
         }
+        self.insert_high_contrast_themes();
 
         for theme in themes.iter() {
             if self.themes.contains_key(&theme.name) {
@@ -262,6 +295,9 @@ impl ThemeRegistry {
             self.themes
                 .insert(theme.name.clone(), Rc::new(theme.clone()));
         }
+        for theme in self.registered_themes.iter() {
+            self.themes.insert(theme.name.clone(), theme.clone());
+        }
 
         Ok(())
     }
@@ -1,15 +1,17 @@
-use std::{rc::Rc, sync::Arc};
+use std::{path::Path, rc::Rc, sync::Arc};
 
 use anyhow::Result;
-use gpui::{Hsla, SharedString, px};
+use gpui::{App, Hsla, SharedString, px};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    Colorize, Theme, ThemeColor, ThemeMode,
+    Colorize, Theme, ThemeColor, ThemeMode, ThemeRegistry,
     highlighter::{HighlightTheme, HighlightThemeStyle},
 };
 
+use super::registry::high_contrast_theme;
+
 /// Represents a theme configuration.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
@@ -34,6 +36,8 @@ pub struct ThemeConfig {
     pub name: SharedString,
     /// The mode of the theme, default is light.
     pub mode: ThemeMode,
+    /// Whether this theme is a high contrast theme, see [`Theme::high_contrast`].
+    pub high_contrast: bool,
 
     /// The base font size, default is 16.
     #[serde(rename = "font.size")]
@@ -69,6 +73,48 @@ pub struct ThemeConfig {
     pub highlight: Option<HighlightThemeStyle>,
 }
 
+impl ThemeConfig {
+    /// Parse a theme config from the JSON, the format is the same as a theme of the theme files.
+    ///
+    /// The unknown keys are ignored with a warning, and the missing keys fall back to
+    /// the default theme of the mode, so a partial config only overrides the given keys.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+
+        let known = serde_json::to_value(ThemeConfig {
+            highlight: Some(HighlightThemeStyle::default()),
+            ..Default::default()
+        })?;
+        for key in unknown_keys(&value, &known) {
+            tracing::warn!("ignored unknown theme key: {}", key);
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+/// Returns the dotted paths of the keys in `value` that are not in `known`.
+///
+/// Only the objects in `known` are checked, the `null` of the optional fields are not.
+fn unknown_keys(value: &serde_json::Value, known: &serde_json::Value) -> Vec<String> {
+    let mut keys = vec![];
+    let (Some(value), Some(known)) = (value.as_object(), known.as_object()) else {
+        return keys;
+    };
+
+    for (key, value) in value {
+        match known.get(key) {
+            Some(known) => keys.extend(
+                unknown_keys(value, known)
+                    .into_iter()
+                    .map(|sub_key| format!("{}.{}", key, sub_key)),
+            ),
+            None => keys.push(key.clone()),
+        }
+    }
+    keys
+}
+
 #[derive(Debug, Default, Clone, JsonSchema, Serialize, Deserialize)]
 pub struct ThemeConfigColors {
     /// Used for accents such as hover background on MenuItem, ListItem, etc.
@@ -224,6 +270,9 @@ pub struct ThemeConfigColors {
     /// Scrollbar thumb hover background color.
     #[serde(rename = "scrollbar.thumb.hover.background")]
     pub scrollbar_thumb_hover: Option<SharedString>,
+    /// Scrollbar track background color, shown behind the thumb when the scrollbar is hovered or active.
+    #[serde(rename = "scrollbar.track.background")]
+    pub scrollbar_track: Option<SharedString>,
     /// Secondary background color.
     #[serde(rename = "secondary.background")]
     pub secondary: Option<SharedString>,
@@ -239,6 +288,9 @@ pub struct ThemeConfigColors {
     /// Input selection background color.
     #[serde(rename = "selection.background")]
     pub selection: Option<SharedString>,
+    /// Input selection background color when the input is not focused or the window is inactive.
+    #[serde(rename = "selection.inactive.background")]
+    pub selection_inactive: Option<SharedString>,
     /// Sidebar background color.
     #[serde(rename = "sidebar.background")]
     pub sidebar: Option<SharedString>,
@@ -287,6 +339,12 @@ pub struct ThemeConfigColors {
     /// Bearish color for candlestick charts (downward price movement).
     #[serde(rename = "bearish.background")]
     pub bearish: Option<SharedString>,
+    /// StatusBar background color.
+    #[serde(rename = "status_bar.background")]
+    pub status_bar: Option<SharedString>,
+    /// StatusBar border color.
+    #[serde(rename = "status_bar.border")]
+    pub status_bar_border: Option<SharedString>,
     /// Switch background color.
     #[serde(rename = "switch.background")]
     pub switch: Option<SharedString>,
@@ -344,6 +402,12 @@ pub struct ThemeConfigColors {
     /// Background color for Tiles.
     #[serde(rename = "tiles.background")]
     pub tiles: Option<SharedString>,
+    /// Toolbar background color.
+    #[serde(rename = "toolbar.background")]
+    pub toolbar: Option<SharedString>,
+    /// Toolbar border color.
+    #[serde(rename = "toolbar.border")]
+    pub toolbar_border: Option<SharedString>,
     /// Warning background color.
     #[serde(rename = "warning.background")]
     pub warning: Option<SharedString>,
@@ -595,6 +659,7 @@ impl ThemeColor {
         apply_color!(scrollbar, fallback = self.background);
         apply_color!(scrollbar_thumb, fallback = self.accent);
         apply_color!(scrollbar_thumb_hover, fallback = self.scrollbar_thumb);
+        apply_color!(scrollbar_track, fallback = self.scrollbar);
         apply_color!(selection, fallback = self.primary);
         apply_color!(sidebar, fallback = self.background);
         apply_color!(sidebar_accent, fallback = self.accent);
@@ -627,7 +692,12 @@ impl ThemeColor {
         apply_color!(table_row_border, fallback = self.border);
         apply_color!(title_bar, fallback = self.background);
         apply_color!(title_bar_border, fallback = self.border);
+        // The status bar colors depend on the title bar colors, so they must be applied after them.
+        apply_color!(status_bar, fallback = self.title_bar);
+        apply_color!(status_bar_border, fallback = self.title_bar_border);
         apply_color!(tiles, fallback = self.background);
+        apply_color!(toolbar, fallback = self.secondary);
+        apply_color!(toolbar_border, fallback = self.border);
         apply_color!(overlay);
         apply_color!(window_border, fallback = self.border);
 
@@ -637,10 +707,73 @@ impl ThemeColor {
         self.list_active = self.list_active.alpha(self.list_active.a.min(0.2));
         self.table_active = self.table_active.alpha(self.table_active.a.min(0.2));
         self.selection = self.selection.alpha(self.selection.a.min(0.3));
+        apply_color!(
+            selection_inactive,
+            fallback = self.selection.saturation(0.1)
+        );
     }
 }
 
 impl Theme {
+    /// Create a theme from the JSON of a theme config, see [`ThemeConfig::from_json`].
+    ///
+    /// ```ignore
+    /// let theme = Theme::from_json(r##"{ "name": "Brand", "colors": { "primary.background": "#ff6600" } }"##)?;
+    /// theme.install(cx);
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(Self::from_config(Rc::new(ThemeConfig::from_json(json)?)))
+    }
+
+    fn from_config(config: Rc<ThemeConfig>) -> Self {
+        let mut theme = if config.mode.is_dark() {
+            let mut theme = Self::from(ThemeColor::dark().as_ref());
+            theme.highlight_theme = HighlightTheme::default_dark();
+            theme
+        } else {
+            Self::from(ThemeColor::light().as_ref())
+        };
+        theme.apply_config(&config);
+        theme
+    }
+
+    /// The built-in high contrast light theme, the text, borders and focus rings meet the
+    /// WCAG AA contrast ratio against the background.
+    ///
+    /// ```ignore
+    /// Theme::high_contrast_light().install(cx);
+    /// ```
+    pub fn high_contrast_light() -> Self {
+        Self::from_config(high_contrast_theme(ThemeMode::Light))
+    }
+
+    /// The built-in high contrast dark theme, see [`Theme::high_contrast_light`].
+    pub fn high_contrast_dark() -> Self {
+        Self::from_config(high_contrast_theme(ThemeMode::Dark))
+    }
+
+    /// Create a theme from a JSON file of a theme config, see [`Theme::from_json`].
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json)
+    }
+
+    /// Install the theme to the app, the theme config is registered to the [`ThemeRegistry`]
+    /// and applied to the global theme, then all windows are refreshed.
+    ///
+    /// The other settings of the global theme, e.g. the `ui_scale`, are kept.
+    pub fn install(&self, cx: &mut App) {
+        let config = if self.is_dark() {
+            self.dark_theme.clone()
+        } else {
+            self.light_theme.clone()
+        };
+
+        ThemeRegistry::global_mut(cx).register(config.clone());
+        Theme::global_mut(cx).apply_config(&config);
+        cx.refresh_windows();
+    }
+
     /// Apply the given theme configuration to the current theme.
     pub fn apply_config(&mut self, config: &Rc<ThemeConfig>) {
         if config.mode.is_dark() {
@@ -701,13 +834,15 @@ impl Theme {
 
         self.colors.apply_config(&config, &default_theme.colors);
         self.mode = config.mode;
+        self.high_contrast = config.high_contrast;
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::try_parse_color;
-    use gpui::hsla;
+    use super::{ThemeConfig, try_parse_color, unknown_keys};
+    use crate::{Theme, ThemeColor, ThemeMode};
+    use gpui::{hsla, px};
 
     #[test]
     fn test_try_parse_color() {
@@ -720,4 +855,84 @@ mod tests {
             Some(hsla(0.34986225, 1.0, 0.4745098, 0.53333336))
         );
     }
+
+    #[test]
+    fn test_theme_from_json() {
+        let json = r##"{
+            "name": "Brand",
+            "mode": "dark",
+            "radius": 2,
+            "unknown": true,
+            "colors": {
+                "primary.background": "#ff6600",
+                "primary.unknown": "#ff6600"
+            },
+            "highlight": {
+                "editor.background": "#000000",
+                "syntax": {
+                    "string": { "color": "#00aa00", "font_style": "italic" }
+                }
+            }
+        }"##;
+
+        let theme = Theme::from_json(json).unwrap();
+        assert_eq!(theme.mode, ThemeMode::Dark);
+        assert_eq!(theme.theme_name().as_ref(), "Brand");
+        assert_eq!(theme.radius, px(2.));
+        assert_eq!(theme.primary, try_parse_color("#ff6600").unwrap());
+        // Fallback to the default dark theme.
+        assert_eq!(theme.background, ThemeColor::dark().background);
+        assert_eq!(theme.radius_lg, px(8.));
+        assert_eq!(
+            theme.highlight_theme.style.editor_background,
+            Some(try_parse_color("#000000").unwrap())
+        );
+        let string = theme.syntax().style("string").unwrap();
+        assert_eq!(string.font_style, Some(gpui::FontStyle::Italic));
+        // The unspecified names are rendered with the editor foreground color.
+        assert_eq!(theme.syntax().style("keyword"), None);
+
+        let value = serde_json::from_str(json).unwrap();
+        let known = serde_json::to_value(ThemeConfig {
+            highlight: Some(Default::default()),
+            ..Default::default()
+        })
+        .unwrap();
+        let mut keys = unknown_keys(&value, &known);
+        keys.sort();
+        assert_eq!(keys, vec!["colors.primary.unknown", "unknown"]);
+
+        assert!(Theme::from_json("{ invalid }").is_err());
+    }
+
+    #[test]
+    fn test_high_contrast_theme() {
+        let theme = Theme::high_contrast_light();
+        assert!(theme.high_contrast);
+        assert_eq!(theme.mode, ThemeMode::Light);
+        assert_eq!(theme.theme_name().as_ref(), "High Contrast Light");
+        assert_eq!(theme.foreground, try_parse_color("#000000").unwrap());
+        assert_eq!(theme.background, try_parse_color("#ffffff").unwrap());
+        assert_eq!(theme.ring, try_parse_color("#0040c0").unwrap());
+
+        let theme = Theme::high_contrast_dark();
+        assert!(theme.high_contrast);
+        assert_eq!(theme.mode, ThemeMode::Dark);
+        assert_eq!(theme.foreground, try_parse_color("#ffffff").unwrap());
+        assert_eq!(theme.background, try_parse_color("#000000").unwrap());
+
+        assert!(
+            !Theme::from_json(r#"{ "name": "Brand" }"#)
+                .unwrap()
+                .high_contrast
+        );
+
+        // All the keys of the built-in themes are known.
+        let value: serde_json::Value =
+            serde_json::from_str(include_str!("./high-contrast-theme.json")).unwrap();
+        let known = serde_json::to_value(ThemeConfig::default()).unwrap();
+        for theme in value["themes"].as_array().unwrap() {
+            assert_eq!(unknown_keys(theme, &known), Vec::<String>::new());
+        }
+    }
 }
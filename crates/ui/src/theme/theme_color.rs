@@ -109,6 +109,8 @@ pub struct ThemeColor {
     pub scrollbar_thumb: Hsla,
     /// Scrollbar thumb hover background color.
     pub scrollbar_thumb_hover: Hsla,
+    /// Scrollbar track background color, shown behind the thumb when the scrollbar is hovered or active.
+    pub scrollbar_track: Hsla,
     /// Secondary background color.
     pub secondary: Hsla,
     /// Active secondary background color.
@@ -119,6 +121,8 @@ pub struct ThemeColor {
     pub secondary_hover: Hsla,
     /// Input selection background color.
     pub selection: Hsla,
+    /// Input selection background color when the input is not focused or the window is inactive.
+    pub selection_inactive: Hsla,
     /// Sidebar background color.
     pub sidebar: Hsla,
     /// Sidebar accent background color.
@@ -151,6 +155,10 @@ pub struct ThemeColor {
     pub bullish: Hsla,
     /// Bearish color for candlestick charts (downward price movement).
     pub bearish: Hsla,
+    /// StatusBar background color.
+    pub status_bar: Hsla,
+    /// StatusBar border color.
+    pub status_bar_border: Hsla,
     /// Switch background color.
     pub switch: Hsla,
     /// Switch thumb background color.
@@ -189,6 +197,10 @@ pub struct ThemeColor {
     pub title_bar_border: Hsla,
     /// Background color for Tiles.
     pub tiles: Hsla,
+    /// Toolbar background color.
+    pub toolbar: Hsla,
+    /// Toolbar border color.
+    pub toolbar_border: Hsla,
     /// Warning background color.
     pub warning: Hsla,
     /// Warning active background color.
@@ -0,0 +1,406 @@
+use std::rc::Rc;
+
+use gpui::{
+    AnyElement, App, ElementId, InteractiveElement as _, IntoElement, ParentElement, Pixels,
+    RenderOnce, StyleRefinement, Styled, Window, div, prelude::FluentBuilder as _, px,
+};
+use rust_i18n::t;
+
+use crate::{
+    ActiveTheme, ElementExt as _, IconName, Sizable as _, StyledExt as _,
+    button::{Button, ButtonVariants as _},
+    divider::Divider,
+    h_flex,
+    menu::{DropdownMenu as _, PopupMenuItem},
+};
+
+/// The gap between the toolbar items.
+const ITEM_GAP: Pixels = px(4.);
+/// The width of the "more" button before it's measured.
+const DEFAULT_MORE_WIDTH: Pixels = px(28.);
+
+/// The group of the [`Toolbar`] items.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToolbarGroup {
+    /// Aligned to the start of the toolbar.
+    #[default]
+    Left,
+    /// In the center of the toolbar.
+    Center,
+    /// Aligned to the end of the toolbar.
+    Right,
+}
+
+struct ToolbarItem {
+    group: ToolbarGroup,
+    /// The element of the item, `None` for the separator.
+    element: Option<AnyElement>,
+    /// The menu item to show in the "more" menu when the item is collapsed,
+    /// `None` if the item can't be collapsed.
+    menu_item: Option<Rc<dyn Fn(&mut Window, &mut App) -> PopupMenuItem>>,
+}
+
+/// The layout of the toolbar measured in the last frame.
+#[derive(Default)]
+struct ToolbarState {
+    width: Pixels,
+    more_width: Option<Pixels>,
+    /// The widths of the items when they were visible last time.
+    item_widths: Vec<Pixels>,
+}
+
+/// A bar of the actions, usually placed at the top of a window, a panel or an editor.
+///
+/// The items are placed in the left, center and right groups, use [`Toolbar::group`] to choose
+/// the group of the following items. When the toolbar is too narrow, the items added by
+/// [`Toolbar::collapsible_item`] are collapsed into a "more" menu from the last one.
+///
+/// ```ignore
+/// Toolbar::new("toolbar")
+///     .item(Button::new("open").ghost().small().icon(IconName::FolderOpen))
+///     .separator()
+///     .collapsible_item(
+///         Button::new("format").ghost().small().label("Format"),
+///         |_, _| PopupMenuItem::new("Format"),
+///     )
+///     .group(ToolbarGroup::Right)
+///     .item(Button::new("settings").ghost().small().icon(IconName::Settings))
+/// ```
+#[derive(IntoElement)]
+pub struct Toolbar {
+    id: ElementId,
+    style: StyleRefinement,
+    group: ToolbarGroup,
+    items: Vec<ToolbarItem>,
+}
+
+impl Toolbar {
+    /// Create a new toolbar with the id, the id is used to keep the layout for the overflow.
+    pub fn new(id: impl Into<ElementId>) -> Self {
+        Self {
+            id: id.into(),
+            style: StyleRefinement::default(),
+            group: ToolbarGroup::default(),
+            items: vec![],
+        }
+    }
+
+    /// Set the group of the following items, default is [`ToolbarGroup::Left`].
+    pub fn group(mut self, group: ToolbarGroup) -> Self {
+        self.group = group;
+        self
+    }
+
+    /// Add an item to the current group, the item is always visible.
+    pub fn item(mut self, item: impl IntoElement) -> Self {
+        self.items.push(ToolbarItem {
+            group: self.group,
+            element: Some(item.into_any_element()),
+            menu_item: None,
+        });
+        self
+    }
+
+    /// Add an item to the current group, that is collapsed into the "more" menu as the `menu_item`
+    /// when the toolbar is too narrow.
+    pub fn collapsible_item(
+        mut self,
+        item: impl IntoElement,
+        menu_item: impl Fn(&mut Window, &mut App) -> PopupMenuItem + 'static,
+    ) -> Self {
+        self.items.push(ToolbarItem {
+            group: self.group,
+            element: Some(item.into_any_element()),
+            menu_item: Some(Rc::new(menu_item)),
+        });
+        self
+    }
+
+    /// Add a vertical separator to the current group.
+    ///
+    /// The separator is hidden if the item after it is collapsed.
+    pub fn separator(mut self) -> Self {
+        self.items.push(ToolbarItem {
+            group: self.group,
+            element: None,
+            menu_item: None,
+        });
+        self
+    }
+}
+
+impl Styled for Toolbar {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for Toolbar {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let state = window.use_keyed_state(self.id.clone(), cx, |_, _| ToolbarState::default());
+        state.update(cx, |state, _| {
+            state.item_widths.resize(self.items.len(), px(0.));
+        });
+
+        let visible = {
+            let state = state.read(cx);
+            let layout = self
+                .items
+                .iter()
+                .zip(state.item_widths.iter())
+                .map(|(item, width)| ItemLayout {
+                    group: item.group,
+                    width: *width,
+                    separator: item.element.is_none(),
+                    collapsible: item.menu_item.is_some(),
+                })
+                .collect::<Vec<_>>();
+            visible_items(
+                &layout,
+                state.width,
+                state.more_width.unwrap_or(DEFAULT_MORE_WIDTH),
+            )
+        };
+
+        let collapsed_menu_items = self
+            .items
+            .iter()
+            .zip(visible.iter())
+            .filter(|(_, visible)| !**visible)
+            .filter_map(|(item, _)| item.menu_item.clone())
+            .collect::<Vec<_>>();
+
+        let border_color = cx.theme().toolbar_border;
+        let mut groups: [Vec<AnyElement>; 3] = [vec![], vec![], vec![]];
+        for (ix, (item, visible)) in self.items.into_iter().zip(visible).enumerate() {
+            if !visible {
+                continue;
+            }
+
+            let element = match item.element {
+                Some(element) => element,
+                None => div()
+                    .h_4()
+                    .px_1()
+                    .child(Divider::vertical().color(border_color))
+                    .into_any_element(),
+            };
+            let group = match item.group {
+                ToolbarGroup::Left => 0,
+                ToolbarGroup::Center => 1,
+                ToolbarGroup::Right => 2,
+            };
+            groups[group].push(
+                div()
+                    .relative()
+                    .flex_shrink_0()
+                    .child(element)
+                    .on_prepaint({
+                        let state = state.clone();
+                        move |bounds, _, cx| {
+                            state.update(cx, |state, cx| {
+                                if let Some(width) = state.item_widths.get_mut(ix)
+                                    && *width != bounds.size.width
+                                {
+                                    *width = bounds.size.width;
+                                    cx.notify();
+                                }
+                            })
+                        }
+                    })
+                    .into_any_element(),
+            );
+        }
+
+        if !collapsed_menu_items.is_empty() {
+            let more_button = Button::new("more")
+                .ghost()
+                .small()
+                .icon(IconName::Ellipsis)
+                .tooltip(t!("Toolbar.more"))
+                .dropdown_menu(move |mut menu, window, cx| {
+                    for menu_item in collapsed_menu_items.iter() {
+                        menu = menu.item(menu_item(window, cx));
+                    }
+                    menu
+                });
+
+            groups[2].push(
+                div()
+                    .relative()
+                    .flex_shrink_0()
+                    .child(more_button)
+                    .on_prepaint({
+                        let state = state.clone();
+                        move |bounds, _, cx| {
+                            state.update(cx, |state, cx| {
+                                if state.more_width != Some(bounds.size.width) {
+                                    state.more_width = Some(bounds.size.width);
+                                    cx.notify();
+                                }
+                            })
+                        }
+                    })
+                    .into_any_element(),
+            );
+        }
+
+        let [left, center, right] = groups;
+        let group = || h_flex().gap(ITEM_GAP);
+
+        h_flex()
+            .id(self.id)
+            .w_full()
+            .h_10()
+            .px_2()
+            .flex_shrink_0()
+            .bg(cx.theme().toolbar)
+            .border_b_1()
+            .border_color(border_color)
+            .refine_style(&self.style)
+            .child(
+                h_flex()
+                    .relative()
+                    .size_full()
+                    .min_w_0()
+                    .gap(ITEM_GAP)
+                    .overflow_hidden()
+                    .child(group().flex_1().children(left))
+                    .when(!center.is_empty(), |this| {
+                        this.child(group().children(center))
+                    })
+                    .child(group().flex_1().justify_end().children(right))
+                    .on_prepaint(move |bounds, _, cx| {
+                        state.update(cx, |state, cx| {
+                            if state.width != bounds.size.width {
+                                state.width = bounds.size.width;
+                                cx.notify();
+                            }
+                        })
+                    }),
+            )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ItemLayout {
+    group: ToolbarGroup,
+    width: Pixels,
+    separator: bool,
+    collapsible: bool,
+}
+
+/// Returns whether each item is visible in the `width`.
+///
+/// The collapsible items are collapsed from the last one until the rest of the items
+/// and the "more" button fit in the width, then the separators without a visible item
+/// before or after them in the same group are hidden.
+fn visible_items(items: &[ItemLayout], width: Pixels, more_width: Pixels) -> Vec<bool> {
+    let mut visible = vec![true; items.len()];
+    let total = items
+        .iter()
+        .map(|item| item.width + ITEM_GAP)
+        .fold(px(0.), |a, b| a + b);
+    if width <= px(0.) || total <= width {
+        return visible;
+    }
+
+    let mut overflow = total + more_width - width;
+    for (ix, item) in items.iter().enumerate().rev() {
+        if overflow <= px(0.) {
+            break;
+        }
+        if item.collapsible {
+            visible[ix] = false;
+            overflow -= item.width + ITEM_GAP;
+        }
+    }
+
+    for ix in 0..items.len() {
+        if !items[ix].separator {
+            continue;
+        }
+
+        let group = items[ix].group;
+        let is_visible_item =
+            |i: &usize| items[*i].group == group && !items[*i].separator && visible[*i];
+        let has_before = (0..ix).any(|i| is_visible_item(&i));
+        let next = (ix + 1..items.len()).find(|i| items[*i].group == group && !items[*i].separator);
+        let has_after = next.is_some_and(|i| visible[i]);
+        visible[ix] = has_before && has_after;
+    }
+
+    visible
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::px;
+
+    use super::{ItemLayout, ToolbarGroup, visible_items};
+
+    fn item(width: f32, collapsible: bool) -> ItemLayout {
+        ItemLayout {
+            group: ToolbarGroup::Left,
+            width: px(width),
+            separator: false,
+            collapsible,
+        }
+    }
+
+    fn separator() -> ItemLayout {
+        ItemLayout {
+            group: ToolbarGroup::Left,
+            width: px(9.),
+            separator: true,
+            collapsible: false,
+        }
+    }
+
+    #[test]
+    fn test_visible_items() {
+        let items = vec![
+            item(20., false),
+            separator(),
+            item(40., true),
+            item(40., true),
+            separator(),
+            item(40., true),
+        ];
+        // The total width with the gaps is 182.
+        assert_eq!(visible_items(&items, px(300.), px(20.)), vec![true; 6]);
+        // Not measured yet.
+        assert_eq!(visible_items(&items, px(0.), px(20.)), vec![true; 6]);
+
+        assert_eq!(
+            visible_items(&items, px(180.), px(20.)),
+            vec![true, true, true, true, false, false]
+        );
+        assert_eq!(
+            visible_items(&items, px(120.), px(20.)),
+            vec![true, true, true, false, false, false]
+        );
+        assert_eq!(
+            visible_items(&items, px(60.), px(20.)),
+            vec![true, false, false, false, false, false]
+        );
+    }
+
+    #[test]
+    fn test_visible_items_separator() {
+        let items = vec![item(40., true), separator(), item(20., false)];
+        assert_eq!(
+            visible_items(&items, px(70.), px(20.)),
+            vec![false, false, true]
+        );
+
+        // The separator in other group is not affected.
+        let mut right = item(20., false);
+        right.group = ToolbarGroup::Right;
+        let items = vec![item(20., false), separator(), item(40., true), right];
+        assert_eq!(
+            visible_items(&items, px(80.), px(20.)),
+            vec![true, false, false, true]
+        );
+    }
+}
@@ -1,26 +1,62 @@
-use std::{cell::RefCell, ops::Range, rc::Rc};
+use std::{
+    any::Any,
+    cell::RefCell,
+    cmp::Ordering,
+    collections::HashSet,
+    ops::Range,
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
 
 use gpui::{
-    App, Context, ElementId, Entity, FocusHandle, InteractiveElement as _, IntoElement, KeyBinding,
-    ListSizingBehavior, MouseButton, ParentElement, Render, RenderOnce, SharedString,
-    StyleRefinement, Styled, UniformListScrollHandle, Window, div, prelude::FluentBuilder as _,
-    uniform_list,
+    Animation, AnyElement, App, AppContext as _, Context, DragMoveEvent, ElementId, Entity,
+    EntityId, EventEmitter, FocusHandle, Focusable, InteractiveElement as _, IntoElement,
+    KeyBinding, KeyDownEvent, ListSizingBehavior, Modifiers, MouseButton, MouseDownEvent,
+    ParentElement, Pixels, Point, Render, RenderOnce, SharedString, StyleRefinement, Styled,
+    Subscription, Task, Transformation, UniformListScrollHandle, Window, actions, div, ease_in_out,
+    percentage, prelude::FluentBuilder as _, px, uniform_list,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    StyledExt,
+    ActiveTheme as _, Disableable as _, Icon, IconName, Sizable as _, StyledExt,
     actions::{Confirm, SelectDown, SelectLeft, SelectRight, SelectUp},
+    animation::MotionExt as _,
+    checkbox::Checkbox,
+    input::{self, InputEvent, InputState},
     list::ListItem,
-    scroll::ScrollableElement,
+    scroll::{ScrollableElement, ScrollbarHandle as _},
+    spinner::Spinner,
 };
 
+actions!(
+    tree,
+    [
+        ExtendSelectionUp,
+        ExtendSelectionDown,
+        SelectFirst,
+        SelectLast
+    ]
+);
+
 const CONTEXT: &str = "Tree";
+const EXPAND_ANIMATION_DURATION: Duration = Duration::from_millis(150);
+const CHEVRON_ANIMATION_DURATION: Duration = Duration::from_millis(150);
+const SCROLL_ANIMATION_DURATION: Duration = Duration::from_millis(150);
 pub(crate) fn init(cx: &mut App) {
     cx.bind_keys([
         KeyBinding::new("up", SelectUp, Some(CONTEXT)),
         KeyBinding::new("down", SelectDown, Some(CONTEXT)),
+        KeyBinding::new("shift-up", ExtendSelectionUp, Some(CONTEXT)),
+        KeyBinding::new("shift-down", ExtendSelectionDown, Some(CONTEXT)),
         KeyBinding::new("left", SelectLeft, Some(CONTEXT)),
         KeyBinding::new("right", SelectRight, Some(CONTEXT)),
+        KeyBinding::new("home", SelectFirst, Some(CONTEXT)),
+        KeyBinding::new("end", SelectLast, Some(CONTEXT)),
+        KeyBinding::new("enter", Confirm { secondary: false }, Some(CONTEXT)),
     ]);
 }
 
@@ -56,6 +92,10 @@ where
 struct TreeItemState {
     expanded: bool,
     disabled: bool,
+    /// The children are not loaded yet, see [`TreeItem::lazy`].
+    lazy: bool,
+    loading: bool,
+    load_error: Option<SharedString>,
 }
 
 /// A tree item with a label, children, and an expanded state.
@@ -64,6 +104,7 @@ pub struct TreeItem {
     pub id: SharedString,
     pub label: SharedString,
     pub children: Vec<TreeItem>,
+    data: Option<Arc<dyn Any + Send + Sync>>,
     state: Rc<RefCell<TreeItemState>>,
 }
 
@@ -72,6 +113,14 @@ pub struct TreeItem {
 pub struct TreeEntry {
     item: TreeItem,
     depth: usize,
+    /// The byte ranges of the label matched by the text filter or the search.
+    match_ranges: Vec<Range<usize>>,
+    /// Whether this entry is shown expanded to reveal the filter matched children.
+    filter_expanded: bool,
+    /// Whether this entry is collapsed by the user to hide the filter matched children.
+    filter_collapsed: bool,
+    /// The input to edit the label, only set when rendering the renaming entry.
+    rename_input: Option<Entity<InputState>>,
 }
 
 impl TreeEntry {
@@ -87,8 +136,9 @@ impl TreeEntry {
         self.depth
     }
 
+    /// Whether this item is at the top level of the tree.
     #[inline]
-    fn is_root(&self) -> bool {
+    pub fn is_root(&self) -> bool {
         self.depth == 0
     }
 
@@ -99,15 +149,42 @@ impl TreeEntry {
     }
 
     /// Return true if the item is expanded.
+    ///
+    /// When the tree is filtered, the ancestors of the matched items are expanded,
+    /// until collapsed by the user.
     #[inline]
     pub fn is_expanded(&self) -> bool {
-        self.item.is_expanded()
+        !self.filter_collapsed && (self.filter_expanded || self.item.is_expanded())
     }
 
     #[inline]
     pub fn is_disabled(&self) -> bool {
         self.item.is_disabled()
     }
+
+    /// Return true if the children of this item are loading.
+    #[inline]
+    pub fn is_loading(&self) -> bool {
+        self.item.is_loading()
+    }
+
+    /// Returns the input to edit the label if this entry is renaming, see [`TreeState::begin_rename`].
+    ///
+    /// Render it (e.g. `Input::new(input)`) in place of the label in the render closure.
+    #[inline]
+    pub fn rename_input(&self) -> Option<&Entity<InputState>> {
+        self.rename_input.as_ref()
+    }
+
+    /// The byte ranges of the label matched by the text filter or the [`TreeState::search`],
+    /// use to highlight the matched text.
+    ///
+    /// Returns an empty slice if there is no filter or search, or this entry is only an ancestor
+    /// of the matched items.
+    #[inline]
+    pub fn match_ranges(&self) -> &[Range<usize>] {
+        &self.match_ranges
+    }
 }
 
 impl TreeItem {
@@ -126,9 +203,13 @@ impl TreeItem {
             id: id.into(),
             label: label.into(),
             children: Vec::new(),
+            data: None,
             state: Rc::new(RefCell::new(TreeItemState {
                 expanded: false,
                 disabled: false,
+                lazy: false,
+                loading: false,
+                load_error: None,
             })),
         }
     }
@@ -157,10 +238,60 @@ impl TreeItem {
         self
     }
 
-    /// Whether this item is a folder (has children).
+    /// Set true to mark this item has children that are not loaded yet.
+    ///
+    /// The item is shown as a folder, and the children will be loaded by the
+    /// [`TreeState::set_children_loader`] when it is first expanded.
+    pub fn lazy(self, lazy: bool) -> Self {
+        self.state.borrow_mut().lazy = lazy;
+        self
+    }
+
+    /// Attach the user data to this tree item, e.g. the file size, mime type or VCS status.
+    ///
+    /// Use [`TreeItem::data_as`] to get it back in the render closure or event handlers.
+    ///
+    /// NOTE: The data is not included in the [`TreeItemSnapshot`].
+    ///
+    /// ```ignore
+    /// TreeItem::new("src/main.rs", "main.rs").data(Arc::new(FileInfo { size: 1024 }))
+    /// ```
+    pub fn data(mut self, data: Arc<dyn Any + Send + Sync>) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Returns the user data of this tree item downcast to `T`.
+    ///
+    /// Returns `None` if there is no data or the data is not a `T`.
+    #[inline]
+    pub fn data_as<T: Any>(&self) -> Option<&T> {
+        self.data.as_ref()?.downcast_ref::<T>()
+    }
+
+    /// Whether this item is a folder (has children or the [`TreeItem::lazy`] children to load).
     #[inline]
     pub fn is_folder(&self) -> bool {
-        self.children.len() > 0
+        self.children.len() > 0 || self.is_lazy()
+    }
+
+    /// Return true if the children of this item are not loaded yet, see [`TreeItem::lazy`].
+    #[inline]
+    pub fn is_lazy(&self) -> bool {
+        self.state.borrow().lazy
+    }
+
+    /// Return true if the children of this item are loading.
+    #[inline]
+    pub fn is_loading(&self) -> bool {
+        self.state.borrow().loading
+    }
+
+    /// Returns the error message of the last failed children loading, see [`TreeState::set_children_loader`].
+    ///
+    /// The error is cleared when retrying to load by expanding the item again.
+    pub fn load_error(&self) -> Option<SharedString> {
+        self.state.borrow().load_error.clone()
     }
 
     /// Return true if the item is disabled.
@@ -175,13 +306,384 @@ impl TreeItem {
     }
 }
 
+/// The mode of the text filter to match the tree items.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FilterMode {
+    /// Match items that contain the query (case-insensitive), non-matches are hidden in place,
+    /// the ancestors of the matched items are kept to show the tree structure.
+    #[default]
+    Substring,
+    /// Match items by fuzzy subsequence, the matched items are flattened and the best matches
+    /// are listed first.
+    Fuzzy,
+}
+
+/// The built-in sort orders of the tree items, see [`TreeState::set_sort_by`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortBy {
+    /// Sort by the label in ascending order (case-insensitive).
+    #[default]
+    NameAsc,
+    /// Sort the folders before the files, then by the label in ascending order (case-insensitive).
+    FoldersFirst,
+}
+
+impl SortBy {
+    /// Compare the two items by this sort order.
+    pub fn compare(&self, a: &TreeItem, b: &TreeItem) -> Ordering {
+        let by_name = || {
+            a.label
+                .to_lowercase()
+                .cmp(&b.label.to_lowercase())
+                .then_with(|| a.label.cmp(&b.label))
+        };
+
+        match self {
+            Self::NameAsc => by_name(),
+            Self::FoldersFirst => b.is_folder().cmp(&a.is_folder()).then_with(by_name),
+        }
+    }
+}
+
+/// The selection behavior when the selected item is removed, see [`TreeState::remove_item`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SelectionFallback {
+    /// Select the next sibling, else the previous sibling, else the parent.
+    #[default]
+    Neighbor,
+    /// Select the parent, or clear the selection if the removed item is a root.
+    Parent,
+    /// Clear the selection.
+    Clear,
+}
+
+/// Events emitted by the [`TreeState`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TreeEvent {
+    /// The item is activated by double-click or pressing `enter` on the selected item.
+    ///
+    /// This is the primary action of the item, e.g. open the file. The folder is also toggled
+    /// before it emitted.
+    Activated { id: SharedString },
+    /// The label of the item is renamed by the inline editor, see [`TreeState::begin_rename`].
+    ///
+    /// The item label is already updated, subscribe it to perform the actual rename, e.g. the file.
+    Renamed {
+        id: SharedString,
+        old_label: SharedString,
+        new_label: SharedString,
+    },
+    /// The item is dropped on the target item by drag and drop, see [`TreeState::set_draggable`].
+    ///
+    /// The items are not moved, handle this event to move them, e.g. the files.
+    Moved {
+        dragged_id: SharedString,
+        target_id: SharedString,
+        position: DropPosition,
+    },
+    /// The item is checked or unchecked by the checkbox, see [`Tree::checkable`].
+    ///
+    /// The descendants and ancestors are already updated, use [`TreeState::checked_ids`] to get
+    /// all the checked items.
+    CheckChanged { id: SharedString, checked: bool },
+}
+
+/// The checked state of a tree item, see [`TreeState::check_state`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    Unchecked,
+    Checked,
+    /// Only some of the descendants are checked.
+    Indeterminate,
+}
+
+/// The drop position relative to the target item, see [`TreeEvent::Moved`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropPosition {
+    /// Before the target item, as its previous sibling.
+    Before,
+    /// After the target item, as its next sibling.
+    After,
+    /// Into the target folder, as its child.
+    Into,
+}
+
+impl DropPosition {
+    /// Returns the drop position by the ratio of the mouse y in the target row (0.0 is the top).
+    ///
+    /// The middle half of a folder row is to drop into it.
+    fn from_ratio(ratio: f32, is_folder: bool) -> Self {
+        if is_folder {
+            if ratio < 0.25 {
+                Self::Before
+            } else if ratio > 0.75 {
+                Self::After
+            } else {
+                Self::Into
+            }
+        } else if ratio < 0.5 {
+            Self::Before
+        } else {
+            Self::After
+        }
+    }
+}
+
+/// The dragging tree item.
+#[derive(Clone)]
+struct DragTreeItem {
+    state_id: EntityId,
+    id: SharedString,
+    label: SharedString,
+}
+
+impl Render for DragTreeItem {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .id("drag-tree-item")
+            .cursor_grab()
+            .py_1()
+            .px_3()
+            .max_w_48()
+            .overflow_hidden()
+            .whitespace_nowrap()
+            .text_ellipsis()
+            .text_sm()
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded(cx.theme().radius)
+            .bg(cx.theme().background)
+            .opacity(0.75)
+            .child(self.label.clone())
+    }
+}
+
+/// The scroll position anchored to an item, see [`TreeState::preserve_scroll_on_update`].
+struct ScrollAnchor {
+    id: SharedString,
+    /// The scrolled distance from the top of the anchor row.
+    offset_in_row: Pixels,
+    row_height: Pixels,
+    /// The scroll offset before updating.
+    offset: Point<Pixels>,
+}
+
+/// The smooth scrolling to an entry, stepped on each frame, see [`TreeState::scroll_to_entry`].
+struct ScrollAnimation {
+    from: Pixels,
+    to: Pixels,
+    start: Instant,
+}
+
+/// The inline editor of the renaming item.
+struct RenameState {
+    id: SharedString,
+    input: Entity<InputState>,
+    _subscription: Subscription,
+}
+
+/// An item in the path from the root, without the children, see [`TreeState::item_path`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreePathItem {
+    pub id: SharedString,
+    pub label: SharedString,
+    /// Whether the item has children or lazy children, see [`TreeItem::is_folder`].
+    pub is_folder: bool,
+}
+
+impl From<&TreeItem> for TreePathItem {
+    fn from(item: &TreeItem) -> Self {
+        Self {
+            id: item.id.clone(),
+            label: item.label.clone(),
+            is_folder: item.is_folder(),
+        }
+    }
+}
+
+/// A serializable snapshot of a [`TreeItem`] with its children.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreeItemSnapshot {
+    pub id: SharedString,
+    pub label: SharedString,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TreeItemSnapshot>,
+    #[serde(default)]
+    pub expanded: bool,
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default)]
+    pub lazy: bool,
+}
+
+impl From<&TreeItem> for TreeItemSnapshot {
+    fn from(item: &TreeItem) -> Self {
+        Self {
+            id: item.id.clone(),
+            label: item.label.clone(),
+            children: item.children.iter().map(Into::into).collect(),
+            expanded: item.is_expanded(),
+            disabled: item.is_disabled(),
+            lazy: item.is_lazy(),
+        }
+    }
+}
+
+impl From<TreeItemSnapshot> for TreeItem {
+    fn from(snapshot: TreeItemSnapshot) -> Self {
+        TreeItem::new(snapshot.id, snapshot.label)
+            .expanded(snapshot.expanded)
+            .disabled(snapshot.disabled)
+            .lazy(snapshot.lazy)
+            .children(snapshot.children.into_iter().map(Into::into))
+    }
+}
+
+/// A serializable snapshot of the [`TreeState`], include the items, expanded state,
+/// selection, text filter and scroll offset.
+///
+/// Use [`TreeState::snapshot`] to create and [`TreeState::restore`] to restore,
+/// for example to save and restore the tree in a session, or to assert the tree in tests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreeSnapshot {
+    pub items: Vec<TreeItemSnapshot>,
+    /// The id of the selected item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected_id: Option<SharedString>,
+    #[serde(default)]
+    pub filter_query: SharedString,
+    #[serde(default)]
+    pub filter_mode: FilterMode,
+    #[serde(default)]
+    pub scroll_offset: Point<Pixels>,
+}
+
+type ChildrenLoader = Rc<dyn Fn(&TreeItem, &mut App) -> Task<Result<Vec<TreeItem>>>>;
+type ContextMenuHandler = Rc<dyn Fn(usize, &TreeEntry, Point<Pixels>, &mut Window, &mut App)>;
+
 /// State for managing tree items.
 pub struct TreeState {
     focus_handle: FocusHandle,
+    items: Vec<TreeItem>,
+    /// The total number of items, including all descendants.
+    item_count: usize,
     entries: Vec<TreeEntry>,
+    filter_query: SharedString,
+    filter_mode: FilterMode,
+    filter: Option<Rc<dyn Fn(&TreeItem) -> bool>>,
+    /// The ids of the filter expanded folders collapsed by the user, reset when the filter changed.
+    filter_collapsed_ids: HashSet<SharedString>,
+    search_query: SharedString,
+    /// The ids of the items matched by the search, in the tree order.
+    search_matches: Vec<SharedString>,
+    /// The id of the active search match.
+    search_active: Option<SharedString>,
+    sort: Option<Rc<dyn Fn(&TreeItem, &TreeItem) -> Ordering>>,
     scroll_handle: UniformListScrollHandle,
+    /// The most recently selected index.
     selected_ix: Option<usize>,
+    /// The ids of all the selected items, include the `selected_ix`.
+    selected_ids: HashSet<SharedString>,
+    /// The id of the item to start the range selection.
+    selection_anchor: Option<SharedString>,
+    selection_fallback: SelectionFallback,
+    children_loader: Option<ChildrenLoader>,
+    renaming: Option<RenameState>,
+    /// Keep the scroll position anchored to the top visible item on [`TreeState::set_items`].
+    preserve_scroll: bool,
+    draggable: bool,
+    /// The index of the entry under the dragging item, and the drop position.
+    drop_target: Option<(usize, DropPosition)>,
     render_item: Rc<dyn Fn(usize, &TreeEntry, bool, &mut Window, &mut App) -> ListItem>,
+    chevron: Option<TreeChevron>,
+    context_menu_handler: Option<ContextMenuHandler>,
+    checkable: bool,
+    checked_ids: HashSet<SharedString>,
+    animations: bool,
+    /// The id of the folder whose children are animating in, and the generation of the animation.
+    expanding: Option<(SharedString, usize)>,
+    expand_generation: usize,
+    _expand_task: Task<()>,
+    /// The id of the folder whose chevron is rotating, and the generation of the animation.
+    chevron_animation: Option<(SharedString, usize)>,
+    _chevron_task: Task<()>,
+    scroll_animation: Option<ScrollAnimation>,
+    /// The number of the entries in the last layout, to measure the row height.
+    rendered_len: usize,
+}
+
+/// The chevron before the folder items to indicate the expanded state, see [`Tree::chevron_icons`].
+#[derive(Clone)]
+struct TreeChevron {
+    collapsed: Icon,
+    expanded: Icon,
+    /// Rotate the collapsed icon 90° with animation instead of swapping icons.
+    animated: bool,
+}
+
+impl Default for TreeChevron {
+    fn default() -> Self {
+        Self {
+            collapsed: IconName::ChevronRight.into(),
+            expanded: IconName::ChevronDown.into(),
+            animated: false,
+        }
+    }
+}
+
+impl TreeChevron {
+    /// Render the chevron, the `animation` is the generation of the rotation animation if playing.
+    fn render(&self, entry: &TreeEntry, animation: Option<usize>, cx: &App) -> AnyElement {
+        let container = div()
+            .flex_shrink_0()
+            .size_4()
+            .flex()
+            .items_center()
+            .justify_center();
+        if !entry.is_folder() {
+            return container.into_any_element();
+        }
+
+        let expanded = entry.is_expanded();
+        let color = cx.theme().muted_foreground;
+        if entry.is_loading() {
+            return container
+                .child(Spinner::new().xsmall().color(color))
+                .into_any_element();
+        }
+        if !self.animated {
+            let icon = if expanded {
+                self.expanded.clone()
+            } else {
+                self.collapsed.clone()
+            };
+            return container
+                .child(icon.xsmall().text_color(color))
+                .into_any_element();
+        }
+
+        let icon = self.collapsed.clone().xsmall().text_color(color);
+        let Some(generation) = animation else {
+            return container
+                .child(icon.when(expanded, |this| {
+                    this.transform(Transformation::rotate(percentage(0.25)))
+                }))
+                .into_any_element();
+        };
+
+        let id = entry.item().id.clone();
+        container
+            .child(icon.with_motion(
+                ElementId::NamedInteger(format!("tree-chevron-{}", id).into(), generation as u64),
+                Animation::new(CHEVRON_ANIMATION_DURATION).with_easing(ease_in_out),
+                move |this, delta| {
+                    let turn = if expanded { delta } else { 1. - delta };
+                    this.transform(Transformation::rotate(percentage(turn * 0.25)))
+                },
+                cx,
+            ))
+            .into_any_element()
+    }
 }
 
 impl TreeState {
@@ -189,280 +691,2681 @@ impl TreeState {
     pub fn new(cx: &mut App) -> Self {
         Self {
             selected_ix: None,
+            selected_ids: HashSet::new(),
+            selection_anchor: None,
             focus_handle: cx.focus_handle(),
             scroll_handle: UniformListScrollHandle::default(),
+            items: Vec::new(),
+            item_count: 0,
             entries: Vec::new(),
+            filter_query: SharedString::default(),
+            filter_mode: FilterMode::default(),
+            filter: None,
+            filter_collapsed_ids: HashSet::new(),
+            search_query: SharedString::default(),
+            search_matches: Vec::new(),
+            search_active: None,
+            sort: None,
+            selection_fallback: SelectionFallback::default(),
+            children_loader: None,
+            renaming: None,
+            preserve_scroll: false,
+            draggable: false,
+            drop_target: None,
             render_item: Rc::new(|_, _, _, _, _| ListItem::new(0)),
+            chevron: None,
+            context_menu_handler: None,
+            checkable: false,
+            checked_ids: HashSet::new(),
+            animations: false,
+            expanding: None,
+            expand_generation: 0,
+            _expand_task: Task::ready(()),
+            chevron_animation: None,
+            _chevron_task: Task::ready(()),
+            scroll_animation: None,
+            rendered_len: 0,
         }
     }
 
     /// Set the tree items.
     pub fn items(mut self, items: impl Into<Vec<TreeItem>>) -> Self {
-        let items = items.into();
-        self.entries.clear();
-        for item in items.into_iter() {
-            self.add_entry(item, 0);
-        }
+        self.items = items.into();
+        self.sort_items();
+        self.rebuild_entries();
         self
     }
 
-    /// Set the tree items.
-    pub fn set_items(&mut self, items: impl Into<Vec<TreeItem>>, cx: &mut Context<Self>) {
-        let items = items.into();
-        self.entries.clear();
-        for item in items.into_iter() {
-            self.add_entry(item, 0);
-        }
-        self.selected_ix = None;
-        cx.notify();
+    /// Returns the ids of the checked items, include the checked folders, see [`Tree::checkable`].
+    pub fn checked_ids(&self) -> HashSet<String> {
+        self.checked_ids.iter().map(|id| id.to_string()).collect()
     }
 
-    /// Get the currently selected index, if any.
-    pub fn selected_index(&self) -> Option<usize> {
-        self.selected_ix
+    /// Returns the [`CheckState`] of the item.
+    ///
+    /// A folder is [`CheckState::Indeterminate`] if only some of its descendants are checked.
+    pub fn check_state(&self, item: &TreeItem) -> CheckState {
+        if self.checked_ids.contains(&item.id) {
+            CheckState::Checked
+        } else if any_item(&item.children, &|it| self.checked_ids.contains(&it.id)) {
+            CheckState::Indeterminate
+        } else {
+            CheckState::Unchecked
+        }
     }
 
-    /// Set the selected index, or `None` to clear selection.
-    pub fn set_selected_index(&mut self, ix: Option<usize>, cx: &mut Context<Self>) {
-        self.selected_ix = ix;
+    /// Check or uncheck the item by id, and emit [`TreeEvent::CheckChanged`].
+    ///
+    /// The checked state is propagated to all the descendants, include the collapsed ones and
+    /// the lazy children loaded later. The ancestors are checked if all their children are checked.
+    pub fn set_checked(&mut self, id: &str, checked: bool, cx: &mut Context<Self>) {
+        let mut ancestors = vec![];
+        if !find_ancestors(&self.items, id, &mut ancestors) {
+            return;
+        }
+        let siblings = ancestors
+            .last()
+            .map(|parent| &parent.children)
+            .unwrap_or(&self.items);
+        let Some(item) = siblings.iter().find(|item| item.id.as_ref() == id) else {
+            return;
+        };
+
+        let mut ids = vec![item.id.clone()];
+        collect_ids(&item.children, &mut ids);
+        for id in ids {
+            if checked {
+                self.checked_ids.insert(id);
+            } else {
+                self.checked_ids.remove(&id);
+            }
+        }
+
+        // Update the ancestors from the nearest one.
+        for ancestor in ancestors.iter().rev() {
+            let all_checked = ancestor
+                .children
+                .iter()
+                .all(|child| self.checked_ids.contains(&child.id));
+            if all_checked {
+                self.checked_ids.insert(ancestor.id.clone());
+            } else {
+                self.checked_ids.remove(&ancestor.id);
+            }
+        }
+
+        let id = item.id.clone();
+        cx.emit(TreeEvent::CheckChanged { id, checked });
         cx.notify();
     }
 
-    pub fn scroll_to_item(&mut self, ix: usize, strategy: gpui::ScrollStrategy) {
-        self.scroll_handle.scroll_to_item(ix, strategy);
+    fn render_checkbox(&self, entry: &TreeEntry, cx: &mut Context<Self>) -> impl IntoElement {
+        let id = entry.item.id.clone();
+        let check_state = self.check_state(&entry.item);
+        let state = cx.entity();
+
+        div()
+            .flex_shrink_0()
+            // Avoid to select and toggle the item.
+            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+            .child(
+                Checkbox::new(SharedString::from(format!("tree-checkbox-{}", id)))
+                    .small()
+                    .tab_stop(false)
+                    .checked(check_state == CheckState::Checked)
+                    .indeterminate(check_state == CheckState::Indeterminate)
+                    .disabled(entry.is_disabled())
+                    .on_click(move |checked, _, cx| {
+                        state.update(cx, |state, cx| state.set_checked(&id, *checked, cx));
+                    }),
+            )
     }
 
-    /// Get the currently selected entry, if any.
-    pub fn selected_entry(&self) -> Option<&TreeEntry> {
-        self.selected_ix.and_then(|ix| self.entries.get(ix))
+    /// Set the comparator to sort the items, the children are sorted recursively.
+    ///
+    /// The items are sorted now and on the later updates, include the children loaded by the
+    /// [`Self::set_children_loader`]. The selection and expanded state are kept.
+    ///
+    /// See also [`Self::set_sort_by`] to use the built-in [`SortBy`].
+    pub fn set_sort<F>(&mut self, compare: F, cx: &mut Context<Self>)
+    where
+        F: Fn(&TreeItem, &TreeItem) -> Ordering + 'static,
+    {
+        self.sort = Some(Rc::new(compare));
+        self.sort_items();
+        self.rebuild_entries();
+        cx.notify();
     }
 
-    fn add_entry(&mut self, item: TreeItem, depth: usize) {
-        self.entries.push(TreeEntry {
-            item: item.clone(),
-            depth,
-        });
-        if item.is_expanded() {
-            for child in &item.children {
-                self.add_entry(child.clone(), depth + 1);
-            }
-        }
+    /// Set the built-in [`SortBy`] to sort the items, see [`Self::set_sort`].
+    pub fn set_sort_by(&mut self, sort_by: SortBy, cx: &mut Context<Self>) {
+        self.set_sort(move |a, b| sort_by.compare(a, b), cx);
     }
 
-    fn toggle_expand(&mut self, ix: usize) {
-        let Some(entry) = self.entries.get_mut(ix) else {
-            return;
-        };
-        if !entry.is_folder() {
-            return;
+    fn sort_items(&mut self) {
+        if let Some(sort) = self.sort.clone() {
+            sort_items_by(&mut self.items, sort.as_ref());
         }
+    }
 
-        entry.item.state.borrow_mut().expanded = !entry.is_expanded();
-        self.rebuild_entries();
+    /// Set true to keep the scroll position when the items are updated by [`Self::set_items`],
+    /// default is false.
+    ///
+    /// The scroll position is anchored to the top visible item by id, so it keeps the user's place
+    /// even if the items before it are added or removed, e.g. refreshing by a file watcher.
+    pub fn preserve_scroll_on_update(mut self, preserve: bool) -> Self {
+        self.preserve_scroll = preserve;
+        self
     }
 
-    fn rebuild_entries(&mut self) {
-        let root_items: Vec<TreeItem> = self
-            .entries
-            .iter()
-            .filter(|e| e.is_root())
-            .map(|e| e.item.clone())
-            .collect();
-        self.entries.clear();
-        for item in root_items.into_iter() {
-            self.add_entry(item, 0);
-        }
+    /// Set true to animate the children in on expand, and smooth scroll on the keyboard navigation
+    /// and [`Self::reveal`], default is false.
+    ///
+    /// The animations are skipped when the [`crate::Theme::reduced_motion`] is enabled.
+    /// A new expand or scroll replaces the running one, so the rapid toggling never queues up.
+    pub fn animations(mut self, animations: bool) -> Self {
+        self.animations = animations;
+        self
     }
 
-    fn on_action_confirm(&mut self, _: &Confirm, _: &mut Window, cx: &mut Context<Self>) {
-        if let Some(selected_ix) = self.selected_ix {
-            if let Some(entry) = self.entries.get(selected_ix) {
-                if entry.is_folder() {
-                    self.toggle_expand(selected_ix);
-                    cx.notify();
-                }
-            }
-        }
+    fn animations_enabled(&self, cx: &App) -> bool {
+        self.animations && !cx.theme().reduced_motion
     }
 
-    fn on_action_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
-        if let Some(selected_ix) = self.selected_ix {
-            if let Some(entry) = self.entries.get(selected_ix) {
-                if entry.is_folder() && entry.is_expanded() {
-                    self.toggle_expand(selected_ix);
-                    cx.notify();
-                }
-            }
+    /// Set the tree items.
+    pub fn set_items(&mut self, items: impl Into<Vec<TreeItem>>, cx: &mut Context<Self>) {
+        let scroll_anchor = if self.preserve_scroll {
+            self.scroll_anchor()
+        } else {
+            None
+        };
+
+        self.items = items.into();
+        self.sort_items();
+        self.rebuild_entries();
+        self.select_single(None);
+        if let Some(anchor) = scroll_anchor {
+            self.restore_scroll_anchor(anchor);
         }
+        cx.notify();
     }
 
-    fn on_action_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
-        if let Some(selected_ix) = self.selected_ix {
-            if let Some(entry) = self.entries.get(selected_ix) {
-                if entry.is_folder() && !entry.is_expanded() {
-                    self.toggle_expand(selected_ix);
-                    cx.notify();
-                }
-            }
+    /// Returns the height of the rows measured by the last layout.
+    ///
+    /// The entries may be rebuilt after the layout, so measure by the number of the rendered
+    /// entries, the rows are in the same height.
+    fn row_height(&self) -> Option<Pixels> {
+        if self.rendered_len == 0 {
+            return None;
         }
+
+        let height = self.scroll_handle.content_size().height / self.rendered_len as f32;
+        (height > px(0.)).then_some(height)
     }
 
-    fn on_action_up(&mut self, _: &SelectUp, _: &mut Window, cx: &mut Context<Self>) {
-        let mut selected_ix = self.selected_ix.unwrap_or(0);
+    /// Returns the top visible item to anchor the scroll position.
+    fn scroll_anchor(&self) -> Option<ScrollAnchor> {
+        let row_height = self.row_height()?;
+        let offset = self.scroll_handle.offset();
+        let scroll_top = -offset.y;
+        let ix = (scroll_top / row_height).floor().max(0.) as usize;
 
-        if selected_ix > 0 {
-            selected_ix = selected_ix - 1;
-        } else {
-            selected_ix = self.entries.len().saturating_sub(1);
-        }
+        Some(ScrollAnchor {
+            id: self.entries.get(ix)?.item.id.clone(),
+            offset_in_row: scroll_top - row_height * ix as f32,
+            row_height,
+            offset,
+        })
+    }
 
-        self.selected_ix = Some(selected_ix);
-        self.scroll_handle
-            .scroll_to_item(selected_ix, gpui::ScrollStrategy::Top);
-        cx.notify();
+    /// Scroll to the anchor item, or keep the previous offset if the item is gone.
+    fn restore_scroll_anchor(&mut self, anchor: ScrollAnchor) {
+        let mut offset = anchor.offset;
+        if let Some(ix) = self
+            .entries
+            .iter()
+            .position(|entry| entry.item.id == anchor.id)
+        {
+            offset.y = -(anchor.row_height * ix as f32 + anchor.offset_in_row);
+        }
+        self.scroll_handle.set_offset(offset);
     }
 
-    fn on_action_down(&mut self, _: &SelectDown, _: &mut Window, cx: &mut Context<Self>) {
-        let mut selected_ix = self.selected_ix.unwrap_or(0);
-        if selected_ix + 1 < self.entries.len() {
-            selected_ix = selected_ix + 1;
-        } else {
-            selected_ix = 0;
+    /// Remove the item with the given id and its descendants, returns the removed item.
+    ///
+    /// If the selected item (or its ancestor) is removed, the selection is moved by the
+    /// [`SelectionFallback`], see [`Self::set_selection_fallback`].
+    pub fn remove_item(&mut self, id: &str, cx: &mut Context<Self>) -> Option<TreeItem> {
+        let had_selection = self.selected_ix.is_some();
+        let (item, neighbor_id, parent_id) = remove_item_by_id(&mut self.items, id, None)?;
+        self.rebuild_entries();
+
+        if had_selection && self.selected_ix.is_none() {
+            let fallback_id = match self.selection_fallback {
+                SelectionFallback::Neighbor => neighbor_id.or(parent_id),
+                SelectionFallback::Parent => parent_id,
+                SelectionFallback::Clear => None,
+            };
+            let fallback_ix = fallback_id
+                .and_then(|id| self.entries.iter().position(|entry| entry.item.id == id));
+            self.select_single(fallback_ix);
         }
 
-        self.selected_ix = Some(selected_ix);
-        self.scroll_handle
-            .scroll_to_item(selected_ix, gpui::ScrollStrategy::Bottom);
         cx.notify();
+        Some(item)
     }
 
-    fn on_entry_click(&mut self, ix: usize, _: &mut Window, cx: &mut Context<Self>) {
-        self.selected_ix = Some(ix);
-        self.toggle_expand(ix);
-        cx.notify();
+    /// Set the selection behavior when the selected item is removed by [`Self::remove_item`],
+    /// default is [`SelectionFallback::Neighbor`].
+    pub fn set_selection_fallback(&mut self, fallback: SelectionFallback) {
+        self.selection_fallback = fallback;
     }
-}
-
-impl Render for TreeState {
-    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let render_item = self.render_item.clone();
 
-        div().id("tree-state").size_full().relative().child(
+    /// Set the loader to load the children of the [`TreeItem::lazy`] items when first expanded.
+    ///
+    /// A spinner is shown on the item while loading. If the loader returns an error, the item is
+    /// collapsed and keeps lazy, expand it again to retry, see [`TreeItem::load_error`].
+    ///
+    /// ```ignore
+    /// state.set_children_loader(|item, cx| {
+    ///     let path = PathBuf::from(item.id.as_ref());
+    ///     let entries = cx.background_spawn(async move { read_dir(&path) });
+    ///     cx.spawn(async move |_| {
+    ///         let entries = entries.await?;
+    ///         Ok(entries
+    ///             .into_iter()
+    ///             .map(|entry| TreeItem::new(entry.path, entry.name).lazy(entry.is_dir))
+    ///             .collect())
+    ///     })
+    /// });
+    /// ```
+    pub fn set_children_loader<F>(&mut self, loader: F)
+    where
+        F: Fn(&TreeItem, &mut App) -> Task<Result<Vec<TreeItem>>> + 'static,
+    {
+        self.children_loader = Some(Rc::new(loader));
+    }
+
+    /// Begin to rename the visible item with the given id by an inline editor.
+    ///
+    /// The filename stem (excluding the extension) of the label is selected. Press `enter` or
+    /// click elsewhere to commit, press `escape` to cancel. A [`TreeEvent::Renamed`] is emitted
+    /// when the label is changed.
+    ///
+    /// The render closure should render the [`TreeEntry::rename_input`] in place of the label.
+    pub fn begin_rename(&mut self, id: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.commit_rename(window, cx);
+        let Some(ix) = self
+            .entries
+            .iter()
+            .position(|entry| entry.item.id.as_ref() == id)
+        else {
+            return;
+        };
+
+        let label = self.entries[ix].item.label.clone();
+        let stem_range = rename_stem_range(&label);
+        let input = cx.new(|cx| InputState::new(window, cx).default_value(label));
+        input.update(cx, |input, cx| {
+            input.set_selection(stem_range, window, cx);
+            input.focus(window, cx);
+        });
+        let _subscription = cx.subscribe_in(
+            &input,
+            window,
+            |this, _, event: &InputEvent, window, cx| match event {
+                InputEvent::PressEnter { .. } => {
+                    this.commit_rename(window, cx);
+                    this.focus_handle.focus(window);
+                }
+                InputEvent::Blur => this.commit_rename(window, cx),
+                _ => {}
+            },
+        );
+
+        self.renaming = Some(RenameState {
+            id: self.entries[ix].item.id.clone(),
+            input,
+            _subscription,
+        });
+        self.select_single(Some(ix));
+        self.scroll_to_entry(ix, gpui::ScrollStrategy::Center, cx);
+        cx.notify();
+    }
+
+    /// Commit the renaming label, the empty or unchanged label is ignored.
+    pub fn commit_rename(&mut self, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(renaming) = self.renaming.take() else {
+            return;
+        };
+        cx.notify();
+
+        let new_label: SharedString = renaming.input.read(cx).value().trim().to_string().into();
+        let Some(item) = find_item_mut(&mut self.items, &|item| item.id == renaming.id) else {
+            return;
+        };
+        if new_label.is_empty() || new_label == item.label {
+            return;
+        }
+
+        let old_label = std::mem::replace(&mut item.label, new_label.clone());
+        self.rebuild_entries();
+        cx.emit(TreeEvent::Renamed {
+            id: renaming.id,
+            old_label,
+            new_label,
+        });
+    }
+
+    /// Cancel the renaming, the label is not changed.
+    pub fn cancel_rename(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.renaming.take().is_some() {
+            self.focus_handle.focus(window);
+            cx.notify();
+        }
+    }
+
+    /// Returns the id of the renaming item, see [`Self::begin_rename`].
+    pub fn renaming_id(&self) -> Option<&SharedString> {
+        self.renaming.as_ref().map(|renaming| &renaming.id)
+    }
+
+    /// Set true to allow to drag the items and drop them before, after or into the other items,
+    /// default is false.
+    ///
+    /// A [`TreeEvent::Moved`] is emitted on drop, dropping a folder into itself or its descendants
+    /// is rejected.
+    pub fn set_draggable(&mut self, draggable: bool, cx: &mut Context<Self>) {
+        self.draggable = draggable;
+        self.drop_target = None;
+        cx.notify();
+    }
+
+    /// Return true if the dragged item can be dropped to the target item, the target must not be
+    /// the dragged item itself or its descendants.
+    fn can_drop(&self, dragged_id: &str, target_id: &str) -> bool {
+        let mut ancestors = vec![];
+        if !find_ancestors(&self.items, target_id, &mut ancestors) {
+            return false;
+        }
+
+        target_id != dragged_id && ancestors.iter().all(|item| item.id.as_ref() != dragged_id)
+    }
+
+    fn on_drag_move_over(
+        &mut self,
+        ix: usize,
+        event: &DragMoveEvent<DragTreeItem>,
+        cx: &mut Context<Self>,
+    ) {
+        let drag = event.drag(cx);
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+
+        let drop_target = if drag.state_id == cx.entity_id()
+            && event.bounds.contains(&event.event.position)
+            && self.can_drop(&drag.id, &entry.item.id)
+        {
+            let ratio = (event.event.position.y - event.bounds.top()) / event.bounds.size.height;
+            Some((ix, DropPosition::from_ratio(ratio, entry.is_folder())))
+        } else if self
+            .drop_target
+            .is_some_and(|(target_ix, _)| target_ix != ix)
+        {
+            // Keep the drop target of the other row.
+            return;
+        } else {
+            None
+        };
+
+        if self.drop_target != drop_target {
+            self.drop_target = drop_target;
+            cx.notify();
+        }
+    }
+
+    fn on_drop_item(&mut self, ix: usize, drag: &DragTreeItem, cx: &mut Context<Self>) {
+        let drop_target = self.drop_target.take();
+        cx.notify();
+
+        let Some((target_ix, position)) = drop_target else {
+            return;
+        };
+        let Some(target_id) = self.entries.get(ix).map(|entry| entry.item.id.clone()) else {
+            return;
+        };
+        if target_ix != ix
+            || drag.state_id != cx.entity_id()
+            || !self.can_drop(&drag.id, &target_id)
+        {
+            return;
+        }
+
+        cx.emit(TreeEvent::Moved {
+            dragged_id: drag.id.clone(),
+            target_id,
+            position,
+        });
+    }
+
+    /// Set the text filter to only show the items that label matches the query.
+    ///
+    /// The match behavior is decided by [`FilterMode`], see [`Self::set_filter_mode`].
+    ///
+    /// Set an empty query to clear the filter, the expand/collapse state of items is kept.
+    pub fn set_text_filter(&mut self, query: impl Into<SharedString>, cx: &mut Context<Self>) {
+        self.filter_query = query.into();
+        self.filter_collapsed_ids.clear();
+        self.rebuild_entries();
+        cx.notify();
+    }
+
+    /// Get the current text filter query.
+    pub fn text_filter(&self) -> &SharedString {
+        &self.filter_query
+    }
+
+    /// Set the [`FilterMode`] of the text filter, default is [`FilterMode::Substring`].
+    pub fn set_filter_mode(&mut self, mode: FilterMode, cx: &mut Context<Self>) {
+        self.filter_mode = mode;
+        self.filter_collapsed_ids.clear();
+        self.rebuild_entries();
+        cx.notify();
+    }
+
+    /// Get the current [`FilterMode`].
+    pub fn filter_mode(&self) -> FilterMode {
+        self.filter_mode
+    }
+
+    /// Set a predicate to only show the items that match it, e.g. filter by the file type.
+    ///
+    /// Like the text filter, the ancestors of the matched items are kept and shown expanded,
+    /// the expand/collapse state of items is restored after clearing by [`Self::clear_filter`].
+    ///
+    /// If both the predicate and the text filter are set, the items must match both.
+    ///
+    /// ```ignore
+    /// state.set_filter(|item| item.label.ends_with(".rs"), cx);
+    /// ```
+    pub fn set_filter<F>(&mut self, predicate: F, cx: &mut Context<Self>)
+    where
+        F: Fn(&TreeItem) -> bool + 'static,
+    {
+        self.filter = Some(Rc::new(predicate));
+        self.filter_collapsed_ids.clear();
+        self.rebuild_entries();
+        cx.notify();
+    }
+
+    /// Clear the filter predicate set by [`Self::set_filter`], the text filter is kept.
+    pub fn clear_filter(&mut self, cx: &mut Context<Self>) {
+        if self.filter.take().is_some() {
+            self.filter_collapsed_ids.clear();
+            self.rebuild_entries();
+            cx.notify();
+        }
+    }
+
+    /// Search the items that label contains the query (case-insensitive), to cycle through the
+    /// matches by [`Self::search_next`] and [`Self::search_prev`], returns the number of matches.
+    ///
+    /// Unlike [`Self::set_text_filter`], the non-matched items are kept in place, and the items in
+    /// the collapsed folders are matched too. The matched text is in [`TreeEntry::match_ranges`].
+    ///
+    /// Set an empty query to clear the search.
+    pub fn search(&mut self, query: impl Into<SharedString>, cx: &mut Context<Self>) -> usize {
+        self.search_query = query.into();
+        self.search_active = None;
+        self.rebuild_entries();
+        cx.notify();
+        self.search_matches.len()
+    }
+
+    /// Get the current search query.
+    pub fn search_query(&self) -> &SharedString {
+        &self.search_query
+    }
+
+    /// Get the number of the items matched by the search.
+    pub fn search_match_count(&self) -> usize {
+        self.search_matches.len()
+    }
+
+    /// Get the index of the active search match, `None` if no match is selected by
+    /// [`Self::search_next`] or [`Self::search_prev`] yet.
+    pub fn active_search_match(&self) -> Option<usize> {
+        let id = self.search_active.as_ref()?;
+        self.search_matches
+            .iter()
+            .position(|match_id| match_id == id)
+    }
+
+    /// Reveal and select the next search match, wrapping around to the first match at the end.
+    ///
+    /// Returns the index of the active match, `None` if there is no match.
+    pub fn search_next(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Option<usize> {
+        self.cycle_search(true, window, cx)
+    }
+
+    /// Reveal and select the previous search match, wrapping around to the last match at the start.
+    ///
+    /// Returns the index of the active match, `None` if there is no match.
+    pub fn search_prev(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Option<usize> {
+        self.cycle_search(false, window, cx)
+    }
+
+    /// Reveal the next or previous search match, the matches hidden by the filter are skipped.
+    fn cycle_search(
+        &mut self,
+        forward: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<usize> {
+        let count = self.search_matches.len();
+        let current = self.active_search_match();
+        for step in 1..=count {
+            let ix = match current {
+                Some(current) if forward => (current + step) % count,
+                Some(current) => (current + count - step) % count,
+                None if forward => step - 1,
+                None => count - step,
+            };
+
+            let id = self.search_matches[ix].clone();
+            if self.reveal(&id, window, cx) {
+                self.search_active = Some(id);
+                return Some(ix);
+            }
+        }
+
+        None
+    }
+
+    /// Take a [`TreeSnapshot`] of the current state.
+    pub fn snapshot(&self) -> TreeSnapshot {
+        TreeSnapshot {
+            items: self.items.iter().map(Into::into).collect(),
+            selected_id: self.selected_entry().map(|entry| entry.item.id.clone()),
+            filter_query: self.filter_query.clone(),
+            filter_mode: self.filter_mode,
+            scroll_offset: self.scroll_handle.offset(),
+        }
+    }
+
+    /// Restore the state from a [`TreeSnapshot`].
+    ///
+    /// The selection will be cleared if the selected item is not visible.
+    pub fn restore(&mut self, snapshot: TreeSnapshot, cx: &mut Context<Self>) {
+        self.items = snapshot.items.into_iter().map(Into::into).collect();
+        self.sort_items();
+        self.filter_query = snapshot.filter_query;
+        self.filter_mode = snapshot.filter_mode;
+        self.filter_collapsed_ids.clear();
+        self.select_single(None);
+        self.rebuild_entries();
+        let selected_ix = snapshot
+            .selected_id
+            .and_then(|id| self.entries.iter().position(|entry| entry.item.id == id));
+        self.select_single(selected_ix);
+        self.scroll_handle.set_offset(snapshot.scroll_offset);
+        cx.notify();
+    }
+
+    /// Get the number of visible rows, with the collapsed and filtered out items excluded.
+    pub fn visible_row_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Get the total number of items, including all collapsed and filtered out descendants.
+    pub fn total_item_count(&self) -> usize {
+        self.item_count
+    }
+
+    /// Get the currently selected index, if any.
+    ///
+    /// If multiple items are selected, this is the most recently selected one.
+    pub fn selected_index(&self) -> Option<usize> {
+        self.selected_ix
+    }
+
+    /// Set the selected index, or `None` to clear selection.
+    ///
+    /// This will clear the other selected items.
+    pub fn set_selected_index(&mut self, ix: Option<usize>, cx: &mut Context<Self>) {
+        self.select_single(ix);
+        cx.notify();
+    }
+
+    /// Get the indices of all the selected entries in the visible order.
+    ///
+    /// The items hidden in the collapsed folders are not selected.
+    pub fn selected_indices(&self) -> Vec<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| self.selected_ids.contains(&entry.item.id))
+            .map(|(ix, _)| ix)
+            .collect()
+    }
+
+    /// Get all the selected entries in the visible order.
+    pub fn selected_entries(&self) -> Vec<&TreeEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| self.selected_ids.contains(&entry.item.id))
+            .collect()
+    }
+
+    /// Return true if the entry at the given index is selected.
+    pub fn is_selected(&self, ix: usize) -> bool {
+        self.entries
+            .get(ix)
+            .is_some_and(|entry| self.selected_ids.contains(&entry.item.id))
+    }
+
+    /// Toggle the selection of the entry at the given index, and keep the other selected items,
+    /// like Ctrl/Cmd-click.
+    pub fn toggle_selected_index(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let Some(id) = self.entries.get(ix).map(|entry| entry.item.id.clone()) else {
+            return;
+        };
+
+        if self.selected_ids.remove(&id) {
+            // Move the selected index to the nearest selected row left.
+            if self.selected_ix == Some(ix) {
+                self.selected_ix = self
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, entry)| self.selected_ids.contains(&entry.item.id))
+                    .map(|(ix, _)| ix)
+                    .min_by_key(|selected_ix| selected_ix.abs_diff(ix));
+            }
+        } else {
+            self.selected_ids.insert(id.clone());
+            self.selected_ix = Some(ix);
+        }
+        self.selection_anchor = Some(id);
+        cx.notify();
+    }
+
+    /// Select the entries from the selection anchor to the given index, like Shift-click.
+    ///
+    /// The anchor is the last single selected or toggled item.
+    pub fn extend_selection_to(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if ix >= self.entries.len() {
+            return;
+        }
+
+        let anchor_ix = self
+            .selection_anchor
+            .as_ref()
+            .and_then(|id| self.entries.iter().position(|entry| &entry.item.id == id))
+            .or(self.selected_ix)
+            .unwrap_or(ix);
+        if self.selection_anchor.is_none() {
+            self.selection_anchor = Some(self.entries[anchor_ix].item.id.clone());
+        }
+
+        self.selected_ids = self.entries[anchor_ix.min(ix)..=anchor_ix.max(ix)]
+            .iter()
+            .filter(|entry| !entry.is_disabled())
+            .map(|entry| entry.item.id.clone())
+            .collect();
+        self.selected_ix = Some(ix);
+        cx.notify();
+    }
+
+    /// Select only the entry at the given index, and set it as the selection anchor.
+    fn select_single(&mut self, ix: Option<usize>) {
+        let id = ix
+            .and_then(|ix| self.entries.get(ix))
+            .map(|entry| entry.item.id.clone());
+
+        self.selected_ix = ix;
+        self.selected_ids = id.iter().cloned().collect();
+        self.selection_anchor = id;
+    }
+
+    /// Reveal the item with the given id, the ancestors are expanded, then select it and scroll
+    /// it into the center of the viewport.
+    ///
+    /// Returns false if the item does not exist or is hidden by the filter.
+    pub fn reveal(&mut self, id: &str, _: &mut Window, cx: &mut Context<Self>) -> bool {
+        let mut ancestors = vec![];
+        if !find_ancestors(&self.items, id, &mut ancestors) {
+            return false;
+        }
+
+        for item in ancestors {
+            item.state.borrow_mut().expanded = true;
+        }
+        self.rebuild_entries();
+        cx.notify();
+
+        let Some(ix) = self
+            .entries
+            .iter()
+            .position(|entry| entry.item.id.as_ref() == id)
+        else {
+            return false;
+        };
+        self.select_single(Some(ix));
+        self.scroll_to_entry(ix, gpui::ScrollStrategy::Center, cx);
+        true
+    }
+
+    /// Returns the path of the item with the given id, from the root item to the item itself,
+    /// e.g. to show a [`crate::breadcrumb::Breadcrumb`] of the selected item.
+    ///
+    /// Returns an empty vec if the item does not exist.
+    pub fn item_path(&self, id: &str) -> Vec<TreePathItem> {
+        let mut path = vec![];
+        if !find_ancestors(&self.items, id, &mut path) {
+            return vec![];
+        }
+
+        let siblings = path
+            .last()
+            .map(|parent| &parent.children)
+            .unwrap_or(&self.items);
+        if let Some(item) = siblings.iter().find(|item| item.id.as_ref() == id) {
+            path.push(item);
+        }
+        path.into_iter().map(TreePathItem::from).collect()
+    }
+
+    /// Expand all the folders, the [`TreeItem::lazy`] items are not loaded.
+    pub fn expand_all(&mut self, cx: &mut Context<Self>) {
+        set_expanded_recursive(&self.items, true);
+        self.rebuild_entries();
+        cx.notify();
+    }
+
+    /// Collapse all the folders.
+    pub fn collapse_all(&mut self, cx: &mut Context<Self>) {
+        set_expanded_recursive(&self.items, false);
+        self.rebuild_entries();
+        cx.notify();
+    }
+
+    pub fn scroll_to_item(&mut self, ix: usize, strategy: gpui::ScrollStrategy) {
+        self.scroll_handle.scroll_to_item(ix, strategy);
+    }
+
+    /// Scroll the entry into view, smoothly if the [`Self::animations`] is enabled.
+    fn scroll_to_entry(
+        &mut self,
+        ix: usize,
+        strategy: gpui::ScrollStrategy,
+        cx: &mut Context<Self>,
+    ) {
+        let row_height = self.row_height().filter(|_| self.animations_enabled(cx));
+        let Some(row_height) = row_height else {
+            self.scroll_animation = None;
+            self.scroll_handle.scroll_to_item(ix, strategy);
+            return;
+        };
+
+        // The max offset of the last layout is stale if the entries are rebuilt since then,
+        // so compute it from the current entries.
+        let viewport_height = self
+            .scroll_handle
+            .0
+            .borrow()
+            .base_handle
+            .bounds()
+            .size
+            .height;
+        let max_scroll = (row_height * self.entries.len() as f32 - viewport_height).max(px(0.));
+        let from = -self.scroll_handle.offset().y;
+        let Some(to) = scroll_target(
+            from,
+            viewport_height,
+            max_scroll,
+            row_height * ix as f32,
+            row_height,
+            strategy,
+        ) else {
+            return;
+        };
+
+        // Replace the running scroll animation, start from the current offset.
+        self.scroll_animation = Some(ScrollAnimation {
+            from,
+            to,
+            start: Instant::now(),
+        });
+        cx.notify();
+    }
+
+    /// Move the scroll offset by the running scroll animation, and request the next frame
+    /// until it's done.
+    fn step_scroll_animation(&mut self, window: &mut Window) {
+        let Some(animation) = self.scroll_animation.as_ref() else {
+            return;
+        };
+
+        let progress = (animation.start.elapsed().as_secs_f32()
+            / SCROLL_ANIMATION_DURATION.as_secs_f32())
+        .min(1.);
+        let mut offset = self.scroll_handle.offset();
+        offset.y = -(animation.from + (animation.to - animation.from) * ease_out_cubic(progress));
+        self.scroll_handle.set_offset(offset);
+        if progress < 1. {
+            window.request_animation_frame();
+        } else {
+            self.scroll_animation = None;
+        }
+    }
+
+    /// Get the currently selected entry, if any.
+    pub fn selected_entry(&self) -> Option<&TreeEntry> {
+        self.selected_ix.and_then(|ix| self.entries.get(ix))
+    }
+
+    fn add_entry(&mut self, item: TreeItem, depth: usize) {
+        let search_range = substring_match(&self.search_query, &item.label);
+        self.entries.push(TreeEntry {
+            item: item.clone(),
+            depth,
+            match_ranges: search_range.into_iter().collect(),
+            filter_expanded: false,
+            filter_collapsed: false,
+            rename_input: None,
+        });
+        if item.is_expanded() {
+            for child in &item.children {
+                self.add_entry(child.clone(), depth + 1);
+            }
+        }
+    }
+
+    /// Add the entry if the item or any of its descendants matches the query and the filter.
+    ///
+    /// Returns true if the entry was added.
+    fn add_filtered_entry(&mut self, item: &TreeItem, depth: usize, query: &str) -> bool {
+        let ix = self.entries.len();
+        let match_range = substring_match(query, &item.label);
+        let matched = (query.is_empty() || match_range.is_some()) && self.filter_matches(item);
+        let highlight_range =
+            match_range.or_else(|| substring_match(&self.search_query, &item.label));
+        self.entries.push(TreeEntry {
+            item: item.clone(),
+            depth,
+            match_ranges: highlight_range.into_iter().collect(),
+            filter_expanded: false,
+            filter_collapsed: false,
+            rename_input: None,
+        });
+
+        let mut has_matched_child = false;
+        for child in &item.children {
+            has_matched_child |= self.add_filtered_entry(child, depth + 1, query);
+        }
+
+        if !matched && !has_matched_child {
+            self.entries.truncate(ix);
+            return false;
+        }
+
+        if has_matched_child && self.filter_collapsed_ids.contains(&item.id) {
+            self.entries.truncate(ix + 1);
+            self.entries[ix].filter_collapsed = true;
+        } else {
+            self.entries[ix].filter_expanded = has_matched_child;
+        }
+        true
+    }
+
+    /// Return true if the item matches the filter predicate, or there is no filter.
+    fn filter_matches(&self, item: &TreeItem) -> bool {
+        self.filter.as_ref().is_none_or(|filter| filter(item))
+    }
+
+    fn toggle_expand(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        if !entry.is_folder() {
+            return;
+        }
+
+        let item = entry.item.clone();
+        let filter_toggled = entry.filter_expanded || entry.filter_collapsed;
+        if self
+            .chevron
+            .as_ref()
+            .is_some_and(|chevron| chevron.animated)
+        {
+            self.start_chevron_animation(item.id.clone(), cx);
+        }
+        // The filter matched children are revealed regardless of the expanded state of the item,
+        // so only toggle them until the filter changed.
+        if filter_toggled {
+            if !self.filter_collapsed_ids.remove(&item.id) {
+                self.filter_collapsed_ids.insert(item.id.clone());
+            }
+            self.rebuild_entries();
+            return;
+        }
+
+        let expanded = !item.is_expanded();
+        item.state.borrow_mut().expanded = expanded;
+        if expanded {
+            if self.animations_enabled(cx) {
+                self.start_expand_animation(item.id.clone(), cx);
+            }
+            self.load_children(item, cx);
+        }
+        self.rebuild_entries();
+    }
+
+    /// Animate the children of the folder in, the previous animation is finished immediately.
+    fn start_expand_animation(&mut self, id: SharedString, cx: &mut Context<Self>) {
+        self.expand_generation = self.expand_generation.wrapping_add(1);
+        self.expanding = Some((id, self.expand_generation));
+
+        // Clear after the animation, to avoid replay it when the rows are scrolled into view again.
+        self._expand_task = cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(EXPAND_ANIMATION_DURATION)
+                .await;
+            _ = this.update(cx, |this, _| this.expanding = None);
+        });
+    }
+
+    /// Rotate the chevron of the folder, the previous animation is finished immediately.
+    fn start_chevron_animation(&mut self, id: SharedString, cx: &mut Context<Self>) {
+        self.expand_generation = self.expand_generation.wrapping_add(1);
+        self.chevron_animation = Some((id, self.expand_generation));
+
+        // Clear after the animation, to avoid replay it when the rows are scrolled into view again.
+        self._chevron_task = cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(CHEVRON_ANIMATION_DURATION)
+                .await;
+            _ = this.update(cx, |this, _| this.chevron_animation = None);
+        });
+    }
+
+    /// Returns the range of the entries animating in, and the generation of the animation.
+    fn expanding_range(&self) -> Option<(Range<usize>, usize)> {
+        let (id, generation) = self.expanding.as_ref()?;
+        let parent_ix = self.entries.iter().position(|entry| &entry.item.id == id)?;
+        let depth = self.entries[parent_ix].depth;
+        let end = self.entries[parent_ix + 1..]
+            .iter()
+            .position(|entry| entry.depth <= depth)
+            .map_or(self.entries.len(), |len| parent_ix + 1 + len);
+        Some((parent_ix + 1..end, *generation))
+    }
+
+    /// Load the children of the lazy item by the children loader.
+    fn load_children(&mut self, item: TreeItem, cx: &mut Context<Self>) {
+        let Some(loader) = self.children_loader.clone() else {
+            return;
+        };
+        {
+            let mut state = item.state.borrow_mut();
+            if !state.lazy || state.loading {
+                return;
+            }
+            state.loading = true;
+            state.load_error = None;
+        }
+
+        let task = loader(&item, cx);
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            _ = this.update(cx, |this, cx| {
+                {
+                    let mut state = item.state.borrow_mut();
+                    state.loading = false;
+                    match result {
+                        Ok(children) => {
+                            // The item may be removed or replaced during loading.
+                            let target = find_item_mut(&mut this.items, &|it| {
+                                Rc::ptr_eq(&it.state, &item.state)
+                            });
+                            if let Some(target) = target {
+                                target.children = children;
+                                if let Some(sort) = this.sort.clone() {
+                                    sort_items_by(&mut target.children, sort.as_ref());
+                                }
+                                // The loaded children of a checked folder are checked too.
+                                if this.checked_ids.contains(&target.id) {
+                                    let mut ids = vec![];
+                                    collect_ids(&target.children, &mut ids);
+                                    this.checked_ids.extend(ids);
+                                }
+                                state.lazy = false;
+                            }
+                        }
+                        Err(err) => {
+                            state.load_error = Some(err.to_string().into());
+                            state.expanded = false;
+                        }
+                    }
+                }
+                this.rebuild_entries();
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Rebuild the flat entries from the tree items, and keep the selected item.
+    fn rebuild_entries(&mut self) {
+        let selected_id = self.selected_entry().map(|entry| entry.item.id.clone());
+        let items = self.items.clone();
+        let query = self.filter_query.clone();
+
+        self.item_count = count_items(&items);
+        self.search_matches.clear();
+        collect_search_matches(&items, &self.search_query, &mut self.search_matches);
+        if let Some(id) = &self.search_active {
+            if !self.search_matches.contains(id) {
+                self.search_active = None;
+            }
+        }
+        self.entries.clear();
+        if query.is_empty() && self.filter.is_none() {
+            for item in items.into_iter() {
+                self.add_entry(item, 0);
+            }
+        } else {
+            match self.filter_mode {
+                FilterMode::Fuzzy if !query.is_empty() => {
+                    let mut matches = vec![];
+                    collect_fuzzy_matches(&items, &query, &mut matches);
+                    matches.retain(|(_, item)| self.filter_matches(item));
+                    // Stable sort to keep the tree order for the same score and label length.
+                    matches.sort_by(|(a, a_item), (b, b_item)| {
+                        b.score
+                            .cmp(&a.score)
+                            .then(a_item.label.len().cmp(&b_item.label.len()))
+                    });
+                    self.entries = matches
+                        .into_iter()
+                        .map(|(m, item)| TreeEntry {
+                            item,
+                            depth: 0,
+                            match_ranges: m.ranges,
+                            filter_expanded: false,
+                            filter_collapsed: false,
+                            rename_input: None,
+                        })
+                        .collect();
+                }
+                _ => {
+                    for item in items.iter() {
+                        self.add_filtered_entry(item, 0, &query);
+                    }
+                }
+            }
+        }
+
+        self.selected_ix =
+            selected_id.and_then(|id| self.entries.iter().position(|entry| entry.item.id == id));
+        // Keep only the visible items selected, the items in collapsed folders are unselected.
+        if !self.selected_ids.is_empty() {
+            let visible_ids: HashSet<&SharedString> =
+                self.entries.iter().map(|entry| &entry.item.id).collect();
+            self.selected_ids.retain(|id| visible_ids.contains(id));
+        }
+    }
+
+    fn on_action_confirm(&mut self, _: &Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(selected_ix) = self.selected_ix {
+            if let Some(entry) = self.entries.get(selected_ix) {
+                if entry.is_disabled() {
+                    return;
+                }
+                if entry.is_folder() {
+                    self.toggle_expand(selected_ix, cx);
+                    cx.notify();
+                }
+                self.activate(selected_ix, cx);
+            }
+        }
+    }
+
+    /// Emit the [`TreeEvent::Activated`] of the entry at the index.
+    fn activate(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if let Some(entry) = self.entries.get(ix) {
+            cx.emit(TreeEvent::Activated {
+                id: entry.item.id.clone(),
+            });
+        }
+    }
+
+    /// Select only the entry at the index, and scroll it into view.
+    fn select_and_scroll(
+        &mut self,
+        ix: usize,
+        strategy: gpui::ScrollStrategy,
+        cx: &mut Context<Self>,
+    ) {
+        self.select_single(Some(ix));
+        self.scroll_to_entry(ix, strategy, cx);
+    }
+
+    /// Returns the index of the parent entry of the entry at the index.
+    fn parent_index(&self, ix: usize) -> Option<usize> {
+        let depth = self.entries.get(ix)?.depth;
+        self.entries[..ix]
+            .iter()
+            .rposition(|entry| entry.depth < depth)
+    }
+
+    /// Collapse the selected folder, or move the selection to the parent.
+    fn on_action_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(selected_ix) = self.selected_ix else {
+            return;
+        };
+        let Some(entry) = self.entries.get(selected_ix) else {
+            return;
+        };
+
+        if entry.is_folder() && entry.is_expanded() {
+            self.toggle_expand(selected_ix, cx);
+        } else if let Some(parent_ix) = self.parent_index(selected_ix) {
+            self.select_and_scroll(parent_ix, gpui::ScrollStrategy::Top, cx);
+        }
+        cx.notify();
+    }
+
+    /// Expand the selected folder, or move the selection to the first child.
+    fn on_action_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(selected_ix) = self.selected_ix else {
+            return;
+        };
+        let Some(entry) = self.entries.get(selected_ix) else {
+            return;
+        };
+        if !entry.is_folder() {
+            return;
+        }
+
+        if !entry.is_expanded() {
+            self.toggle_expand(selected_ix, cx);
+        } else if self
+            .entries
+            .get(selected_ix + 1)
+            .is_some_and(|child| child.depth > entry.depth)
+        {
+            self.select_and_scroll(selected_ix + 1, gpui::ScrollStrategy::Bottom, cx);
+        }
+        cx.notify();
+    }
+
+    fn on_action_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        self.select_and_scroll(0, gpui::ScrollStrategy::Top, cx);
+        cx.notify();
+    }
+
+    fn on_action_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        self.select_and_scroll(self.entries.len() - 1, gpui::ScrollStrategy::Bottom, cx);
+        cx.notify();
+    }
+
+    /// Returns the index of the next enabled entry after the selected one (wrapping around),
+    /// whose label starts with the char, case-insensitive.
+    fn type_ahead_index(&self, c: char) -> Option<usize> {
+        let len = self.entries.len();
+        let start = self.selected_ix.map_or(0, |ix| ix + 1);
+        (0..len).map(|i| (start + i) % len).find(|&ix| {
+            let entry = &self.entries[ix];
+            !entry.is_disabled()
+                && entry
+                    .item
+                    .label
+                    .chars()
+                    .next()
+                    .is_some_and(|first| chars_eq_ignore_case(first, c))
+        })
+    }
+
+    /// Type-ahead, typing a char jumps to the next entry whose label starts with the char.
+    fn on_key_down(&mut self, event: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if self.renaming.is_some() || !self.focus_handle.is_focused(window) {
+            return;
+        }
+
+        let keystroke = &event.keystroke;
+        let modifiers = keystroke.modifiers;
+        if modifiers.control || modifiers.alt || modifiers.platform || modifiers.function {
+            return;
+        }
+        let Some(c) = keystroke.key_char.as_ref().and_then(|text| {
+            let mut chars = text.chars();
+            chars.next().filter(|_| chars.next().is_none())
+        }) else {
+            return;
+        };
+        if c.is_whitespace() || c.is_control() {
+            return;
+        }
+
+        if let Some(ix) = self.type_ahead_index(c) {
+            self.select_and_scroll(ix, gpui::ScrollStrategy::Center, cx);
+            cx.stop_propagation();
+            cx.notify();
+        }
+    }
+
+    fn on_action_up(&mut self, _: &SelectUp, _: &mut Window, cx: &mut Context<Self>) {
+        let mut selected_ix = self.selected_ix.unwrap_or(0);
+
+        if selected_ix > 0 {
+            selected_ix = selected_ix - 1;
+        } else {
+            selected_ix = self.entries.len().saturating_sub(1);
+        }
+
+        self.select_and_scroll(selected_ix, gpui::ScrollStrategy::Top, cx);
+        cx.notify();
+    }
+
+    fn on_action_down(&mut self, _: &SelectDown, _: &mut Window, cx: &mut Context<Self>) {
+        let mut selected_ix = self.selected_ix.unwrap_or(0);
+        if selected_ix + 1 < self.entries.len() {
+            selected_ix = selected_ix + 1;
+        } else {
+            selected_ix = 0;
+        }
+
+        self.select_and_scroll(selected_ix, gpui::ScrollStrategy::Bottom, cx);
+        cx.notify();
+    }
+
+    fn on_action_extend_up(
+        &mut self,
+        _: &ExtendSelectionUp,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(selected_ix) = self.selected_ix else {
+            return;
+        };
+        if selected_ix > 0 {
+            self.extend_selection_to(selected_ix - 1, cx);
+            self.scroll_to_entry(selected_ix - 1, gpui::ScrollStrategy::Top, cx);
+        }
+    }
+
+    fn on_action_extend_down(
+        &mut self,
+        _: &ExtendSelectionDown,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(selected_ix) = self.selected_ix else {
+            return;
+        };
+        if selected_ix + 1 < self.entries.len() {
+            self.extend_selection_to(selected_ix + 1, cx);
+            self.scroll_to_entry(selected_ix + 1, gpui::ScrollStrategy::Bottom, cx);
+        }
+    }
+
+    fn on_action_escape(&mut self, _: &input::Escape, window: &mut Window, cx: &mut Context<Self>) {
+        if self.renaming.is_none() {
+            cx.propagate();
+            return;
+        }
+
+        self.cancel_rename(window, cx);
+    }
+
+    fn on_entry_secondary_click(
+        &mut self,
+        ix: usize,
+        position: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(renaming) = self.renaming.as_ref() {
+            if self
+                .entries
+                .get(ix)
+                .is_some_and(|entry| entry.item.id == renaming.id)
+            {
+                return;
+            }
+            self.commit_rename(window, cx);
+        }
+        // Keep the multiple selection if the entry is in it, like the file explorers.
+        if !self.is_selected(ix) {
+            self.select_single(Some(ix));
+        }
+        self.focus_handle.focus(window);
+        cx.notify();
+
+        let Some(handler) = self.context_menu_handler.clone() else {
+            return;
+        };
+        let Some(entry) = self.entries.get(ix).cloned() else {
+            return;
+        };
+        window.defer(cx, move |window, cx| {
+            handler(ix, &entry, position, window, cx);
+        });
+    }
+
+    fn on_entry_click(
+        &mut self,
+        ix: usize,
+        modifiers: &Modifiers,
+        click_count: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(renaming) = self.renaming.as_ref() {
+            if self
+                .entries
+                .get(ix)
+                .is_some_and(|entry| entry.item.id == renaming.id)
+            {
+                return;
+            }
+            self.commit_rename(window, cx);
+        }
+
+        if modifiers.shift {
+            self.extend_selection_to(ix, cx);
+            return;
+        }
+        if modifiers.secondary() {
+            self.toggle_selected_index(ix, cx);
+            return;
+        }
+
+        self.select_single(Some(ix));
+        // The first click has toggled the folder, keep it for the double-click.
+        if click_count >= 2 {
+            self.activate(ix, cx);
+            cx.notify();
+            return;
+        }
+        self.toggle_expand(ix, cx);
+        cx.notify();
+    }
+}
+
+/// The result of [`fuzzy_match`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FuzzyMatch {
+    /// The match score, higher is better.
+    pub(crate) score: i32,
+    /// The matched byte ranges in the text, contiguous chars are merged into one range.
+    pub(crate) ranges: Vec<Range<usize>>,
+}
+
+#[inline]
+fn chars_eq_ignore_case(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+/// Find the first case-insensitive substring match of the query in the text.
+///
+/// Returns the matched byte range in the text.
+pub(crate) fn substring_match(query: &str, text: &str) -> Option<Range<usize>> {
+    if query.is_empty() {
+        return None;
+    }
+
+    for (start, _) in text.char_indices() {
+        let mut end = start;
+        let mut text_chars = text[start..].chars();
+        let matched = query.chars().all(|q| match text_chars.next() {
+            Some(c) if chars_eq_ignore_case(c, q) => {
+                end += c.len_utf8();
+                true
+            }
+            _ => false,
+        });
+
+        if matched {
+            return Some(start..end);
+        }
+    }
+
+    None
+}
+
+/// Fuzzy match the query as a case-insensitive subsequence of the text.
+///
+/// The score prefers contiguous matches, and matches at the word starts
+/// (the first char, after a separator or a camelCase boundary).
+///
+/// Returns None if the query is not a subsequence of the text.
+pub(crate) fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    const CONTIGUOUS_BONUS: i32 = 5;
+    const WORD_START_BONUS: i32 = 3;
+
+    let query: Vec<char> = query.chars().collect();
+    if query.is_empty() {
+        return None;
+    }
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+
+    let is_word_start = |ix: usize| -> bool {
+        if ix == 0 {
+            return true;
+        }
+        let prev = chars[ix - 1].1;
+        let cur = chars[ix].1;
+        !prev.is_alphanumeric() || (prev.is_lowercase() && cur.is_uppercase())
+    };
+
+    // Greedy match the rest of the query from the start position of the first query char,
+    // optionally prefer the next word start char to the first matched char.
+    let match_from = |start: usize, prefer_word_start: bool| -> Option<Vec<usize>> {
+        let mut positions = vec![start];
+        let mut ix = start + 1;
+        for q in query.iter().skip(1) {
+            let rest = ix..chars.len();
+            let next = rest
+                .clone()
+                .find(|&i| {
+                    prefer_word_start
+                        && chars_eq_ignore_case(chars[i].1, *q)
+                        && (i == ix || is_word_start(i))
+                })
+                .or_else(|| rest.clone().find(|&i| chars_eq_ignore_case(chars[i].1, *q)))?;
+            positions.push(next);
+            ix = next + 1;
+        }
+        Some(positions)
+    };
+
+    // Only decide whether it matches by the plain subsequence, the word start preference is
+    // only for the score and the matched positions.
+    let first = (0..chars.len()).find(|&i| chars_eq_ignore_case(chars[i].1, query[0]))?;
+    match_from(first, false)?;
+
+    let mut best: Option<(i32, Vec<usize>)> = None;
+    for start in first..chars.len() {
+        if !chars_eq_ignore_case(chars[start].1, query[0]) {
+            continue;
+        }
+
+        let Some(positions) = match_from(start, true).or_else(|| match_from(start, false)) else {
+            // The plain match from a later start position can't match either.
+            break;
+        };
+
+        let mut score = 0;
+        for (i, &pos) in positions.iter().enumerate() {
+            score += 1;
+            if i > 0 && positions[i - 1] + 1 == pos {
+                score += CONTIGUOUS_BONUS;
+            }
+            if is_word_start(pos) {
+                score += WORD_START_BONUS;
+            }
+        }
+        // Penalty for the leading unmatched chars.
+        score -= (start as i32).min(3);
+
+        if best
+            .as_ref()
+            .is_none_or(|(best_score, _)| score > *best_score)
+        {
+            best = Some((score, positions));
+        }
+    }
+
+    let (score, positions) = best?;
+    let mut ranges: Vec<Range<usize>> = vec![];
+    for pos in positions {
+        let (offset, c) = chars[pos];
+        match ranges.last_mut() {
+            Some(last) if last.end == offset => last.end = offset + c.len_utf8(),
+            _ => ranges.push(offset..offset + c.len_utf8()),
+        }
+    }
+
+    Some(FuzzyMatch { score, ranges })
+}
+
+/// Remove the item with the given id from the items recursively.
+///
+/// Returns the removed item, the id of its neighbor sibling (next first, else previous)
+/// and the id of its parent.
+fn remove_item_by_id(
+    items: &mut Vec<TreeItem>,
+    id: &str,
+    parent_id: Option<&SharedString>,
+) -> Option<(TreeItem, Option<SharedString>, Option<SharedString>)> {
+    if let Some(ix) = items.iter().position(|item| item.id.as_ref() == id) {
+        let item = items.remove(ix);
+        let neighbor_id = items
+            .get(ix)
+            .or_else(|| ix.checked_sub(1).and_then(|ix| items.get(ix)))
+            .map(|item| item.id.clone());
+        return Some((item, neighbor_id, parent_id.cloned()));
+    }
+
+    items.iter_mut().find_map(|item| {
+        let parent_id = item.id.clone();
+        remove_item_by_id(&mut item.children, id, Some(&parent_id))
+    })
+}
+
+/// Find the first item that matches the predicate in the items recursively.
+fn find_item_mut<'a>(
+    items: &'a mut [TreeItem],
+    predicate: &dyn Fn(&TreeItem) -> bool,
+) -> Option<&'a mut TreeItem> {
+    items.iter_mut().find_map(|item| {
+        if predicate(item) {
+            Some(item)
+        } else {
+            find_item_mut(&mut item.children, predicate)
+        }
+    })
+}
+
+/// Returns the byte range of the filename stem (excluding the extension) to select for renaming,
+/// the whole label is selected if there is no extension, e.g. `.gitignore`.
+fn rename_stem_range(label: &str) -> Range<usize> {
+    match label.rfind('.') {
+        Some(ix) if ix > 0 => 0..ix,
+        _ => 0..label.len(),
+    }
+}
+
+/// Find the item with the given id, and collect its ancestors from the root.
+///
+/// Returns false if the item is not found.
+fn find_ancestors<'a>(items: &'a [TreeItem], id: &str, ancestors: &mut Vec<&'a TreeItem>) -> bool {
+    for item in items {
+        if item.id.as_ref() == id {
+            return true;
+        }
+
+        ancestors.push(item);
+        if find_ancestors(&item.children, id, ancestors) {
+            return true;
+        }
+        ancestors.pop();
+    }
+
+    false
+}
+
+/// Collect the ids of the items and their descendants.
+fn collect_ids(items: &[TreeItem], ids: &mut Vec<SharedString>) {
+    for item in items {
+        ids.push(item.id.clone());
+        collect_ids(&item.children, ids);
+    }
+}
+
+/// Returns true if any of the items or their descendants matches the predicate.
+fn any_item(items: &[TreeItem], predicate: &dyn Fn(&TreeItem) -> bool) -> bool {
+    items
+        .iter()
+        .any(|item| predicate(item) || any_item(&item.children, predicate))
+}
+
+/// Sort the items and their children recursively by the comparator, the sort is stable.
+fn sort_items_by(items: &mut [TreeItem], compare: &dyn Fn(&TreeItem, &TreeItem) -> Ordering) {
+    items.sort_by(|a, b| compare(a, b));
+    for item in items.iter_mut() {
+        sort_items_by(&mut item.children, compare);
+    }
+}
+
+fn ease_out_cubic(delta: f32) -> f32 {
+    1. - (1. - delta).powi(3)
+}
+
+/// Returns the scroll top to show the item by the strategy, or `None` if no need to scroll.
+///
+/// The `Center` strategy always centers the item, the others only scroll the item into the view.
+fn scroll_target(
+    scroll_top: Pixels,
+    viewport_height: Pixels,
+    max_scroll: Pixels,
+    item_top: Pixels,
+    item_height: Pixels,
+    strategy: gpui::ScrollStrategy,
+) -> Option<Pixels> {
+    let item_bottom = item_top + item_height;
+    let target = match strategy {
+        gpui::ScrollStrategy::Center => item_top - (viewport_height - item_height) / 2.,
+        _ if item_top < scroll_top => item_top,
+        _ if item_bottom > scroll_top + viewport_height => item_bottom - viewport_height,
+        _ => return None,
+    };
+    let target = target.min(max_scroll).max(px(0.));
+    (target != scroll_top).then_some(target)
+}
+
+/// Set the expanded state of all the items that have children.
+fn set_expanded_recursive(items: &[TreeItem], expanded: bool) {
+    for item in items {
+        if !item.children.is_empty() {
+            item.state.borrow_mut().expanded = expanded;
+            set_expanded_recursive(&item.children, expanded);
+        }
+    }
+}
+
+fn count_items(items: &[TreeItem]) -> usize {
+    items
+        .iter()
+        .map(|item| 1 + count_items(&item.children))
+        .sum()
+}
+
+/// Collect the ids of all the items (include the collapsed descendants) that label contains
+/// the query, in the tree order.
+fn collect_search_matches(items: &[TreeItem], query: &str, ids: &mut Vec<SharedString>) {
+    if query.is_empty() {
+        return;
+    }
+
+    for item in items {
+        if substring_match(query, &item.label).is_some() {
+            ids.push(item.id.clone());
+        }
+        collect_search_matches(&item.children, query, ids);
+    }
+}
+
+/// Collect all the items (include the collapsed descendants) that fuzzy match the query.
+fn collect_fuzzy_matches(
+    items: &[TreeItem],
+    query: &str,
+    matches: &mut Vec<(FuzzyMatch, TreeItem)>,
+) {
+    for item in items {
+        if let Some(m) = fuzzy_match(query, &item.label) {
+            matches.push((m, item.clone()));
+        }
+        collect_fuzzy_matches(&item.children, query, matches);
+    }
+}
+
+impl EventEmitter<TreeEvent> for TreeState {}
+
+impl Focusable for TreeState {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for TreeState {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.step_scroll_animation(window);
+        self.rendered_len = self.entries.len();
+        let render_item = self.render_item.clone();
+        let chevron = self.chevron.clone();
+        let drop_target = self.drop_target.filter(|_| cx.has_active_drag());
+        let expanding = self.expanding_range();
+
+        div().id("tree-state").size_full().relative().child(
             uniform_list("entries", self.entries.len(), {
                 cx.processor(move |state, visible_range: Range<usize>, window, cx| {
                     let mut items = Vec::with_capacity(visible_range.len());
                     for ix in visible_range {
-                        let entry = &state.entries[ix];
-                        let selected = Some(ix) == state.selected_ix;
-                        let item = (render_item)(ix, entry, selected, window, cx);
+                        let renaming_entry;
+                        let entry = match state.renaming.as_ref() {
+                            Some(renaming) if renaming.id == state.entries[ix].item.id => {
+                                renaming_entry = TreeEntry {
+                                    rename_input: Some(renaming.input.clone()),
+                                    ..state.entries[ix].clone()
+                                };
+                                &renaming_entry
+                            }
+                            _ => &state.entries[ix],
+                        };
+                        let selected = state.is_selected(ix);
+                        let mut item = (render_item)(ix, entry, selected, window, cx);
+                        if state.checkable {
+                            item = item.prefix_child(state.render_checkbox(entry, cx));
+                        }
+                        if let Some(chevron) = chevron.as_ref() {
+                            let animation = state
+                                .chevron_animation
+                                .as_ref()
+                                .filter(|(id, _)| id == &entry.item.id)
+                                .map(|(_, generation)| *generation);
+                            item = item.prefix_child(chevron.render(entry, animation, cx));
+                        } else if entry.is_loading() {
+                            item = item.suffix(|_, cx| {
+                                Spinner::new().xsmall().color(cx.theme().muted_foreground)
+                            });
+                        }
+
+                        let draggable = state.draggable
+                            && !entry.is_disabled()
+                            && entry.rename_input().is_none();
+                        let drop_position = drop_target
+                            .filter(|(target_ix, _)| *target_ix == ix)
+                            .map(|(_, position)| position);
+
+                        let el = div()
+                            .id(ix)
+                            .relative()
+                            .child(item.disabled(entry.item().is_disabled()).selected(selected))
+                            .when(!entry.item().is_disabled(), |this| {
+                                this.on_mouse_down(
+                                    MouseButton::Right,
+                                    cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                                        this.on_entry_secondary_click(
+                                            ix,
+                                            event.position,
+                                            window,
+                                            cx,
+                                        );
+                                    }),
+                                )
+                                .on_mouse_down(
+                                    MouseButton::Left,
+                                    cx.listener({
+                                        move |this, event: &MouseDownEvent, window, cx| {
+                                            this.on_entry_click(
+                                                ix,
+                                                &event.modifiers,
+                                                event.click_count,
+                                                window,
+                                                cx,
+                                            );
+                                        }
+                                    }),
+                                )
+                            })
+                            .when(draggable, |this| {
+                                let drag = DragTreeItem {
+                                    state_id: cx.entity_id(),
+                                    id: entry.item.id.clone(),
+                                    label: entry.item.label.clone(),
+                                };
+                                this.on_drag(drag, |drag, _, _, cx| {
+                                    cx.stop_propagation();
+                                    cx.new(|_| drag.clone())
+                                })
+                                .on_drag_move(cx.listener(
+                                    move |this, event: &DragMoveEvent<DragTreeItem>, _, cx| {
+                                        this.on_drag_move_over(ix, event, cx);
+                                    },
+                                ))
+                                .on_drop(cx.listener(
+                                    move |this, drag: &DragTreeItem, _, cx| {
+                                        this.on_drop_item(ix, drag, cx);
+                                    },
+                                ))
+                            })
+                            .when_some(drop_position, |this, position| {
+                                let indicator = div()
+                                    .absolute()
+                                    .left_0()
+                                    .right_0()
+                                    .h(px(2.))
+                                    .bg(cx.theme().drag_border);
+                                match position {
+                                    DropPosition::Before => this.child(indicator.top_0()),
+                                    DropPosition::After => this.child(indicator.bottom_0()),
+                                    DropPosition::Into => this.child(
+                                        div()
+                                            .absolute()
+                                            .inset_0()
+                                            .border_1()
+                                            .border_color(cx.theme().drag_border)
+                                            .bg(cx.theme().drop_target),
+                                    ),
+                                }
+                            });
+
+                        let el = match expanding.as_ref() {
+                            Some((range, generation)) if range.contains(&ix) => el.with_motion(
+                                ElementId::NamedInteger(
+                                    format!("tree-expand-{}", ix).into(),
+                                    *generation as u64,
+                                ),
+                                Animation::new(EXPAND_ANIMATION_DURATION)
+                                    .with_easing(ease_out_cubic),
+                                |this, delta| this.opacity(delta).top(px(-8.) * (1. - delta)),
+                                cx,
+                            ),
+                            _ => el.into_any_element(),
+                        };
+                        items.push(el)
+                    }
+
+                    items
+                })
+            })
+            .flex_grow()
+            .size_full()
+            .track_scroll(&self.scroll_handle)
+            .with_sizing_behavior(ListSizingBehavior::Auto)
+            .into_any_element(),
+        )
+    }
+}
+
+/// A tree view element that displays hierarchical data.
+#[derive(IntoElement)]
+pub struct Tree {
+    id: ElementId,
+    state: Entity<TreeState>,
+    style: StyleRefinement,
+    render_item: Rc<dyn Fn(usize, &TreeEntry, bool, &mut Window, &mut App) -> ListItem>,
+    chevron: Option<TreeChevron>,
+    context_menu_handler: Option<ContextMenuHandler>,
+    checkable: bool,
+}
+
+impl Tree {
+    pub fn new<R>(state: &Entity<TreeState>, render_item: R) -> Self
+    where
+        R: Fn(usize, &TreeEntry, bool, &mut Window, &mut App) -> ListItem + 'static,
+    {
+        Self {
+            id: ElementId::Name(format!("tree-{}", state.entity_id()).into()),
+            state: state.clone(),
+            style: StyleRefinement::default(),
+            render_item: Rc::new(move |ix, item, selected, window, app| {
+                render_item(ix, item, selected, window, app)
+            }),
+            chevron: None,
+            context_menu_handler: None,
+            checkable: false,
+        }
+    }
+
+    /// Set true to show a checkbox before the items, default is false.
+    ///
+    /// Checking a folder checks all its descendants, and a folder is indeterminate when only some
+    /// of its descendants are checked, see [`TreeState::set_checked`].
+    pub fn checkable(mut self, checkable: bool) -> Self {
+        self.checkable = checkable;
+        self
+    }
+
+    /// Set the handler to be called when right-clicking on an item, with the index, the entry and
+    /// the mouse position, e.g. to show a context menu of the item at the position.
+    ///
+    /// The item is selected before the handler called, unless it's already in the selection.
+    pub fn on_context_menu<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(usize, &TreeEntry, Point<Pixels>, &mut Window, &mut App) + 'static,
+    {
+        self.context_menu_handler = Some(Rc::new(handler));
+        self
+    }
+
+    /// Show a chevron before the items with the given icons to indicate the expanded state of the folders,
+    /// the leaf items will have a same width space to keep aligned.
+    pub fn chevron_icons(mut self, collapsed: impl Into<Icon>, expanded: impl Into<Icon>) -> Self {
+        let chevron = self.chevron.get_or_insert_default();
+        chevron.collapsed = collapsed.into();
+        chevron.expanded = expanded.into();
+        self
+    }
+
+    /// Set true to rotate the collapsed chevron icon 90° with animation on expand, instead of swapping the icons.
+    ///
+    /// The [`IconName::ChevronRight`] will be used if [`Self::chevron_icons`] is not set.
+    pub fn animated_chevron(mut self, animated: bool) -> Self {
+        self.chevron.get_or_insert_default().animated = animated;
+        self
+    }
+}
+
+impl Styled for Tree {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for Tree {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let focus_handle = self.state.read(cx).focus_handle.clone();
+        let scroll_handle = self.state.read(cx).scroll_handle.clone();
+
+        self.state.update(cx, |state, _| {
+            state.render_item = self.render_item;
+            state.chevron = self.chevron;
+            state.context_menu_handler = self.context_menu_handler;
+            state.checkable = self.checkable;
+        });
+
+        div()
+            .id(self.id)
+            .key_context(CONTEXT)
+            .track_focus(&focus_handle)
+            .on_action(window.listener_for(&self.state, TreeState::on_action_confirm))
+            .on_action(window.listener_for(&self.state, TreeState::on_action_left))
+            .on_action(window.listener_for(&self.state, TreeState::on_action_right))
+            .on_action(window.listener_for(&self.state, TreeState::on_action_up))
+            .on_action(window.listener_for(&self.state, TreeState::on_action_down))
+            .on_action(window.listener_for(&self.state, TreeState::on_action_first))
+            .on_action(window.listener_for(&self.state, TreeState::on_action_last))
+            .on_action(window.listener_for(&self.state, TreeState::on_action_extend_up))
+            .on_action(window.listener_for(&self.state, TreeState::on_action_extend_down))
+            .on_action(window.listener_for(&self.state, TreeState::on_action_escape))
+            .on_key_down(window.listener_for(&self.state, TreeState::on_key_down))
+            .size_full()
+            .child(self.state)
+            .refine_style(&self.style)
+            .vertical_scrollbar(&scroll_handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::{
+        FilterMode, SelectionFallback, TreeSnapshot, TreeState, fuzzy_match, rename_stem_range,
+        scroll_target, substring_match,
+    };
+    use gpui::AppContext as _;
+
+    fn assert_entries(entries: &Vec<super::TreeEntry>, expected: &str) {
+        let actual: Vec<String> = entries
+            .iter()
+            .map(|e| {
+                let mut s = String::new();
+                s.push_str(&"    ".repeat(e.depth));
+                s.push_str(e.item().label.as_str());
+                s
+            })
+            .collect();
+        let actual = actual.join("\n");
+        assert_eq!(actual.trim(), expected.trim());
+    }
+
+    #[gpui::test]
+    fn test_tree_entry(cx: &mut gpui::TestAppContext) {
+        use super::TreeItem;
 
-                        let el = div()
-                            .id(ix)
-                            .child(item.disabled(entry.item().is_disabled()).selected(selected))
-                            .when(!entry.item().is_disabled(), |this| {
-                                this.on_mouse_down(
-                                    MouseButton::Left,
-                                    cx.listener({
-                                        move |this, _, window, cx| {
-                                            this.on_entry_click(ix, window, cx);
-                                        }
-                                    }),
-                                )
-                            });
+        let items = vec![
+            TreeItem::new("src", "src")
+                .expanded(true)
+                .child(
+                    TreeItem::new("src/ui", "ui")
+                        .expanded(true)
+                        .child(TreeItem::new("src/ui/button.rs", "button.rs"))
+                        .child(TreeItem::new("src/ui/icon.rs", "icon.rs"))
+                        .child(TreeItem::new("src/ui/mod.rs", "mod.rs")),
+                )
+                .child(TreeItem::new("src/lib.rs", "lib.rs")),
+            TreeItem::new("Cargo.toml", "Cargo.toml"),
+            TreeItem::new("Cargo.lock", "Cargo.lock").disabled(true),
+            TreeItem::new("README.md", "README.md"),
+        ];
+
+        let state = cx.new(|cx| TreeState::new(cx).items(items));
+        state.update(cx, |state, cx| {
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                src
+                    ui
+                        button.rs
+                        icon.rs
+                        mod.rs
+                    lib.rs
+                Cargo.toml
+                Cargo.lock
+                README.md
+                "#
+                },
+            );
+
+            let entry = state.entries.get(0).unwrap();
+            assert_eq!(entry.depth(), 0);
+            assert_eq!(entry.is_root(), true);
+            assert_eq!(entry.is_folder(), true);
+            assert_eq!(entry.is_expanded(), true);
+
+            let entry = state.entries.get(1).unwrap();
+            assert_eq!(entry.depth(), 1);
+            assert_eq!(entry.is_root(), false);
+            assert_eq!(entry.is_folder(), true);
+            assert_eq!(entry.is_expanded(), true);
+            assert_eq!(entry.item().label.as_str(), "ui");
+
+            state.toggle_expand(1, cx);
+            let entry = state.entries.get(1).unwrap();
+            assert_eq!(entry.is_expanded(), false);
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                src
+                    ui
+                    lib.rs
+                Cargo.toml
+                Cargo.lock
+                README.md
+                "#
+                },
+            );
+        })
+    }
+
+    #[test]
+    fn test_item_data() {
+        use super::TreeItem;
+        use std::sync::Arc;
+
+        #[derive(Debug, PartialEq)]
+        struct FileInfo {
+            size: usize,
+        }
+
+        let item = TreeItem::new("src/main.rs", "main.rs").data(Arc::new(FileInfo { size: 1024 }));
+        assert_eq!(item.data_as::<FileInfo>(), Some(&FileInfo { size: 1024 }));
+        assert_eq!(item.data_as::<String>(), None);
+        assert_eq!(
+            item.clone().data_as::<FileInfo>().map(|info| info.size),
+            Some(1024)
+        );
+        assert_eq!(TreeItem::new("src", "src").data_as::<FileInfo>(), None);
+    }
+
+    #[test]
+    fn test_substring_match() {
+        assert_eq!(substring_match("ICON", "icon.rs"), Some(0..4));
+        assert_eq!(substring_match("rs", "icon.rs"), Some(5..7));
+        assert_eq!(substring_match("读取", "文件读取.rs"), Some(6..12));
+        assert_eq!(substring_match("foo", "icon.rs"), None);
+        assert_eq!(substring_match("", "icon.rs"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        let m = fuzzy_match("btn", "button.rs").unwrap();
+        assert_eq!(m.ranges, vec![0..1, 2..3, 5..6]);
+
+        let m = fuzzy_match("rs", "button.rs").unwrap();
+        assert_eq!(m.ranges, vec![7..9]);
+
+        assert_eq!(fuzzy_match("dm", "mod.rs"), None);
+        assert_eq!(fuzzy_match("", "mod.rs"), None);
+
+        // Contiguous and word start matches have higher score.
+        let contiguous = fuzzy_match("mod", "mod.rs").unwrap();
+        let word_starts = fuzzy_match("mod", "my_old_doc").unwrap();
+        let scattered = fuzzy_match("mod", "memo_pad").unwrap();
+        assert_eq!(word_starts.ranges, vec![0..1, 3..4, 7..8]);
+        assert!(contiguous.score > word_starts.score);
+        assert!(word_starts.score > scattered.score);
+
+        let m = fuzzy_match("tc", "TreeCell").unwrap();
+        assert_eq!(m.ranges, vec![0..1, 4..5]);
+
+        // The word start preference doesn't skip the plain subsequence match.
+        let m = fuzzy_match("abc", "axbcx_b").unwrap();
+        assert_eq!(m.ranges, vec![0..1, 2..4]);
+    }
+
+    #[test]
+    fn test_rename_stem_range() {
+        assert_eq!(rename_stem_range("button.rs"), 0..6);
+        assert_eq!(rename_stem_range("archive.tar.gz"), 0..11);
+        assert_eq!(rename_stem_range("Makefile"), 0..8);
+        assert_eq!(rename_stem_range(".gitignore"), 0..10);
+        assert_eq!(rename_stem_range("文件.md"), 0..6);
+    }
+
+    #[test]
+    fn test_scroll_target() {
+        use gpui::{ScrollStrategy, px};
+
+        let target = |scroll_top: f32, item_top: f32, strategy| {
+            scroll_target(
+                px(scroll_top),
+                px(100.),
+                px(400.),
+                px(item_top),
+                px(20.),
+                strategy,
+            )
+        };
+
+        // Visible items are not scrolled.
+        assert_eq!(target(0., 40., ScrollStrategy::Top), None);
+        assert_eq!(target(0., 80., ScrollStrategy::Bottom), None);
+        // Scroll the item into the view.
+        assert_eq!(target(100., 40., ScrollStrategy::Top), Some(px(40.)));
+        assert_eq!(target(0., 120., ScrollStrategy::Bottom), Some(px(40.)));
+        // Center the item, clamped to the scroll range.
+        assert_eq!(target(0., 200., ScrollStrategy::Center), Some(px(160.)));
+        assert_eq!(target(100., 20., ScrollStrategy::Center), Some(px(0.)));
+        assert_eq!(target(0., 480., ScrollStrategy::Center), Some(px(400.)));
+        assert_eq!(target(160., 200., ScrollStrategy::Center), None);
+    }
+
+    #[gpui::test]
+    fn test_text_filter(cx: &mut gpui::TestAppContext) {
+        use super::TreeItem;
+
+        let items = vec![
+            TreeItem::new("src", "src")
+                .expanded(true)
+                .child(
+                    TreeItem::new("src/ui", "ui")
+                        .child(TreeItem::new("src/ui/button.rs", "button.rs"))
+                        .child(TreeItem::new("src/ui/icon.rs", "icon.rs"))
+                        .child(TreeItem::new("src/ui/mod.rs", "mod.rs")),
+                )
+                .child(TreeItem::new("src/lib.rs", "lib.rs")),
+            TreeItem::new("Cargo.toml", "Cargo.toml"),
+            TreeItem::new("README.md", "README.md"),
+        ];
+
+        let state = cx.new(|cx| TreeState::new(cx).items(items));
+        state.update(cx, |state, cx| {
+            assert_eq!(state.total_item_count(), 8);
+            assert_eq!(state.visible_row_count(), 5);
+
+            state.set_selected_index(Some(1), cx);
+            state.set_text_filter("MOD", cx);
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                src
+                    ui
+                        mod.rs
+                "#
+                },
+            );
+            assert_eq!(state.entries[1].is_expanded(), true);
+            assert_eq!(state.entries[1].item().is_expanded(), false);
+            assert!(state.entries[1].match_ranges().is_empty());
+            assert_eq!(state.entries[2].match_ranges(), &[0..3]);
+            // The selected item is kept.
+            assert_eq!(state.selected_index(), Some(1));
+            assert_eq!(state.visible_row_count(), 3);
+            assert_eq!(state.total_item_count(), 8);
+
+            // The filter expanded folder can be collapsed, without changing the item state.
+            state.toggle_expand(1, cx);
+            assert_eq!(state.visible_row_count(), 2);
+            assert_eq!(state.entries[1].is_expanded(), false);
+            assert_eq!(state.entries[1].item().is_expanded(), false);
+            state.toggle_expand(1, cx);
+            assert_eq!(state.visible_row_count(), 3);
+            assert_eq!(state.entries[1].is_expanded(), true);
+
+            state.set_filter_mode(FilterMode::Fuzzy, cx);
+            state.set_text_filter("rs", cx);
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                mod.rs
+                lib.rs
+                icon.rs
+                button.rs
+                "#
+                },
+            );
+            assert_eq!(state.selected_index(), None);
+
+            state.set_text_filter("", cx);
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                src
+                    ui
+                    lib.rs
+                Cargo.toml
+                README.md
+                "#
+                },
+            );
+        })
+    }
+
+    #[gpui::test]
+    fn test_filter_predicate(cx: &mut gpui::TestAppContext) {
+        use super::TreeItem;
+
+        let items = vec![
+            TreeItem::new("src", "src")
+                .child(
+                    TreeItem::new("src/ui", "ui")
+                        .child(TreeItem::new("src/ui/button.rs", "button.rs"))
+                        .child(TreeItem::new("src/ui/icon.svg", "icon.svg")),
+                )
+                .child(TreeItem::new("src/lib.rs", "lib.rs")),
+            TreeItem::new("README.md", "README.md"),
+        ];
+
+        let state = cx.new(|cx| TreeState::new(cx).items(items));
+        state.update(cx, |state, cx| {
+            state.set_filter(|item| item.label.ends_with(".rs"), cx);
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                src
+                    ui
+                        button.rs
+                    lib.rs
+                "#
+                },
+            );
+            assert!(state.entries[0].is_expanded());
+            assert!(!state.entries[0].item().is_expanded());
+
+            // Combined with the text filter.
+            state.set_text_filter("lib", cx);
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                src
+                    lib.rs
+                "#
+                },
+            );
+            state.set_filter_mode(FilterMode::Fuzzy, cx);
+            state.set_text_filter("bt", cx);
+            assert_entries(&state.entries, "button.rs");
+
+            // The collapsed state is restored.
+            state.set_text_filter("", cx);
+            state.clear_filter(cx);
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                src
+                README.md
+                "#
+                },
+            );
+        });
+    }
+
+    #[gpui::test]
+    fn test_begin_rename_focus(cx: &mut gpui::TestAppContext) {
+        use gpui::Focusable as _;
+
+        use super::TreeItem;
+
+        cx.update(|cx| crate::init(cx));
+        let items = vec![TreeItem::new("README.md", "README.md")];
+        let state = cx.new(|cx| TreeState::new(cx).items(items));
+        let window = cx.add_empty_window();
+        window.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                state.begin_rename("README.md", window, cx);
+                let input = state.renaming.as_ref().unwrap().input.clone();
+                assert!(input.focus_handle(cx).is_focused(window));
+            });
+        });
+    }
+
+    #[gpui::test]
+    fn test_reveal(cx: &mut gpui::TestAppContext) {
+        use super::TreeItem;
+
+        let items = vec![
+            TreeItem::new("src", "src")
+                .child(
+                    TreeItem::new("src/ui", "ui")
+                        .child(TreeItem::new("src/ui/button.rs", "button.rs")),
+                )
+                .child(TreeItem::new("src/lib.rs", "lib.rs")),
+            TreeItem::new("README.md", "README.md"),
+        ];
+
+        let state = cx.new(|cx| TreeState::new(cx).items(items));
+        let window = cx.add_empty_window();
+        window.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                assert!(state.reveal("src/ui/button.rs", window, cx));
+                assert_entries(
+                    &state.entries,
+                    indoc! {
+                        r#"
+                    src
+                        ui
+                            button.rs
+                        lib.rs
+                    README.md
+                    "#
+                    },
+                );
+                assert_eq!(state.selected_index(), Some(2));
+                assert!(!state.reveal("not-exist", window, cx));
+                assert_eq!(state.selected_index(), Some(2));
+
+                let path = state.item_path("src/ui/button.rs");
+                assert_eq!(
+                    path.iter().map(|item| item.id.as_ref()).collect::<Vec<_>>(),
+                    vec!["src", "src/ui", "src/ui/button.rs"]
+                );
+                assert!(path[1].is_folder);
+                assert!(!path[2].is_folder);
+                assert_eq!(state.item_path("README.md").len(), 1);
+                assert!(state.item_path("not-exist").is_empty());
+
+                state.collapse_all(cx);
+                assert_entries(
+                    &state.entries,
+                    indoc! {
+                        r#"
+                    src
+                    README.md
+                    "#
+                    },
+                );
+                assert_eq!(state.selected_index(), None);
+
+                state.expand_all(cx);
+                assert_eq!(state.visible_row_count(), 5);
+            });
+        });
+    }
+
+    #[test]
+    fn test_drop_position() {
+        use super::DropPosition;
+
+        assert_eq!(DropPosition::from_ratio(0.1, true), DropPosition::Before);
+        assert_eq!(DropPosition::from_ratio(0.5, true), DropPosition::Into);
+        assert_eq!(DropPosition::from_ratio(0.9, true), DropPosition::After);
+        assert_eq!(DropPosition::from_ratio(0.4, false), DropPosition::Before);
+        assert_eq!(DropPosition::from_ratio(0.6, false), DropPosition::After);
+    }
+
+    #[gpui::test]
+    fn test_can_drop(cx: &mut gpui::TestAppContext) {
+        use super::TreeItem;
+
+        let items = vec![
+            TreeItem::new("src", "src")
+                .child(TreeItem::new("src/ui", "ui").child(TreeItem::new("src/ui/a.rs", "a.rs")))
+                .child(TreeItem::new("src/lib.rs", "lib.rs")),
+            TreeItem::new("docs", "docs"),
+        ];
+
+        let state = cx.new(|cx| TreeState::new(cx).items(items));
+        state.update(cx, |state, _| {
+            assert!(state.can_drop("src/ui/a.rs", "docs"));
+            assert!(state.can_drop("src/ui", "src/lib.rs"));
+            assert!(state.can_drop("docs", "src/ui/a.rs"));
+            // Into itself or its descendants
+            assert!(!state.can_drop("src", "src"));
+            assert!(!state.can_drop("src", "src/ui"));
+            assert!(!state.can_drop("src", "src/ui/a.rs"));
+            assert!(!state.can_drop("src", "not-exist"));
+        });
+    }
+
+    #[gpui::test]
+    fn test_search(cx: &mut gpui::TestAppContext) {
+        use super::TreeItem;
+
+        let items = vec![
+            TreeItem::new("src", "src")
+                .child(TreeItem::new("src/main.rs", "main.rs"))
+                .child(TreeItem::new("src/lib.rs", "lib.rs")),
+            TreeItem::new("Cargo.toml", "Cargo.toml"),
+            TreeItem::new("README.md", "README.md"),
+        ];
+
+        let state = cx.new(|cx| TreeState::new(cx).items(items));
+        let window = cx.add_empty_window();
+        window.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                // The matches in the collapsed folder are counted.
+                assert_eq!(state.search("RS", cx), 2);
+                assert_eq!(state.active_search_match(), None);
+                assert_eq!(state.visible_row_count(), 3);
 
-                        items.push(el)
-                    }
+                assert_eq!(state.search_next(window, cx), Some(0));
+                assert_entries(
+                    &state.entries,
+                    "src\n    main.rs\n    lib.rs\nCargo.toml\nREADME.md",
+                );
+                assert_eq!(state.selected_index(), Some(1));
+                assert_eq!(state.entries[1].match_ranges(), &[5..7]);
+                assert!(state.entries[3].match_ranges().is_empty());
 
-                    items
-                })
+                assert_eq!(state.search_next(window, cx), Some(1));
+                assert_eq!(state.selected_index(), Some(2));
+                // Wrap around at the ends.
+                assert_eq!(state.search_next(window, cx), Some(0));
+                assert_eq!(state.selected_index(), Some(1));
+                assert_eq!(state.search_prev(window, cx), Some(1));
+                assert_eq!(state.selected_index(), Some(2));
+                assert_eq!(state.active_search_match(), Some(1));
+
+                // The matches hidden by the filter are skipped.
+                state.set_text_filter("main", cx);
+                assert_eq!(state.search_next(window, cx), Some(0));
+                assert_eq!(state.search_next(window, cx), Some(0));
+                state.set_text_filter("", cx);
+
+                assert_eq!(state.search("", cx), 0);
+                assert_eq!(state.search_next(window, cx), None);
+                assert!(state.entries.iter().all(|e| e.match_ranges().is_empty()));
             })
-            .flex_grow()
-            .size_full()
-            .track_scroll(&self.scroll_handle)
-            .with_sizing_behavior(ListSizingBehavior::Auto)
-            .into_any_element(),
-        )
+        });
     }
-}
 
-/// A tree view element that displays hierarchical data.
-#[derive(IntoElement)]
-pub struct Tree {
-    id: ElementId,
-    state: Entity<TreeState>,
-    style: StyleRefinement,
-    render_item: Rc<dyn Fn(usize, &TreeEntry, bool, &mut Window, &mut App) -> ListItem>,
-}
+    #[gpui::test]
+    fn test_keyboard_navigation(cx: &mut gpui::TestAppContext) {
+        use super::{SelectFirst, SelectLast, TreeItem};
+        use crate::actions::{SelectLeft, SelectRight};
 
-impl Tree {
-    pub fn new<R>(state: &Entity<TreeState>, render_item: R) -> Self
-    where
-        R: Fn(usize, &TreeEntry, bool, &mut Window, &mut App) -> ListItem + 'static,
-    {
-        Self {
-            id: ElementId::Name(format!("tree-{}", state.entity_id()).into()),
-            state: state.clone(),
-            style: StyleRefinement::default(),
-            render_item: Rc::new(move |ix, item, selected, window, app| {
-                render_item(ix, item, selected, window, app)
-            }),
-        }
+        let items = vec![
+            TreeItem::new("src", "src")
+                .child(TreeItem::new("src/main.rs", "main.rs"))
+                .child(TreeItem::new("src/lib.rs", "lib.rs")),
+            TreeItem::new("Cargo.toml", "Cargo.toml"),
+            TreeItem::new("Cargo.lock", "Cargo.lock").disabled(true),
+            TreeItem::new("README.md", "README.md"),
+        ];
+
+        let state = cx.new(|cx| TreeState::new(cx).items(items));
+        let window = cx.add_empty_window();
+        window.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                state.on_action_last(&SelectLast, window, cx);
+                assert_eq!(state.selected_index(), Some(3));
+                state.on_action_first(&SelectFirst, window, cx);
+                assert_eq!(state.selected_index(), Some(0));
+
+                // Right expands the folder, then moves to the first child.
+                state.on_action_right(&SelectRight, window, cx);
+                assert_entries(
+                    &state.entries,
+                    "src\n    main.rs\n    lib.rs\nCargo.toml\nCargo.lock\nREADME.md",
+                );
+                assert_eq!(state.selected_index(), Some(0));
+                state.on_action_right(&SelectRight, window, cx);
+                assert_eq!(state.selected_index(), Some(1));
+
+                // Left moves to the parent, then collapses the folder.
+                state.on_action_left(&SelectLeft, window, cx);
+                assert_eq!(state.selected_index(), Some(0));
+                assert!(state.entries[0].is_expanded());
+                state.on_action_left(&SelectLeft, window, cx);
+                assert_eq!(state.selected_index(), Some(0));
+                assert!(!state.entries[0].is_expanded());
+                assert_eq!(state.visible_row_count(), 4);
+
+                // Type-ahead to the next enabled entry, wrapping around.
+                assert_eq!(state.type_ahead_index('c'), Some(1));
+                state.set_selected_index(Some(1), cx);
+                assert_eq!(state.type_ahead_index('C'), Some(1));
+                assert_eq!(state.type_ahead_index('r'), Some(3));
+                state.set_selected_index(Some(3), cx);
+                assert_eq!(state.type_ahead_index('s'), Some(0));
+                assert_eq!(state.type_ahead_index('x'), None);
+            });
+        });
     }
-}
 
-impl Styled for Tree {
-    fn style(&mut self) -> &mut StyleRefinement {
-        &mut self.style
+    #[gpui::test]
+    fn test_activate(cx: &mut gpui::TestAppContext) {
+        use super::{TreeEvent, TreeItem};
+        use crate::actions::Confirm;
+        use gpui::SharedString;
+        use std::{cell::RefCell, rc::Rc};
+
+        let items = vec![
+            TreeItem::new("src", "src").child(TreeItem::new("src/lib.rs", "lib.rs")),
+            TreeItem::new("README.md", "README.md"),
+            TreeItem::new("LICENSE", "LICENSE").disabled(true),
+        ];
+
+        let state = cx.new(|cx| TreeState::new(cx).items(items));
+        let activated = Rc::new(RefCell::new(vec![]));
+        cx.update(|cx| {
+            let activated = activated.clone();
+            cx.subscribe(&state, move |_, event: &TreeEvent, _| {
+                if let TreeEvent::Activated { id } = event {
+                    activated.borrow_mut().push(id.clone());
+                }
+            })
+            .detach();
+        });
+
+        let window = cx.add_empty_window();
+        window.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                let confirm = Confirm { secondary: false };
+
+                state.set_selected_index(Some(0), cx);
+                state.on_action_confirm(&confirm, window, cx);
+                assert_entries(&state.entries, "src\n    lib.rs\nREADME.md\nLICENSE");
+
+                state.set_selected_index(Some(2), cx);
+                state.on_action_confirm(&confirm, window, cx);
+                // The disabled item is not activated.
+                state.set_selected_index(Some(3), cx);
+                state.on_action_confirm(&confirm, window, cx);
+            });
+        });
+        cx.run_until_parked();
+
+        assert_eq!(
+            *activated.borrow(),
+            vec![SharedString::from("src"), SharedString::from("README.md")]
+        );
     }
-}
 
-impl RenderOnce for Tree {
-    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
-        let focus_handle = self.state.read(cx).focus_handle.clone();
-        let scroll_handle = self.state.read(cx).scroll_handle.clone();
+    #[gpui::test]
+    fn test_secondary_click(cx: &mut gpui::TestAppContext) {
+        use super::TreeItem;
+        use gpui::{point, px};
 
-        self.state
-            .update(cx, |state, _| state.render_item = self.render_item);
+        let items = vec![
+            TreeItem::new("a.rs", "a.rs"),
+            TreeItem::new("b.rs", "b.rs"),
+            TreeItem::new("c.rs", "c.rs"),
+        ];
 
-        div()
-            .id(self.id)
-            .key_context(CONTEXT)
-            .track_focus(&focus_handle)
-            .on_action(window.listener_for(&self.state, TreeState::on_action_confirm))
-            .on_action(window.listener_for(&self.state, TreeState::on_action_left))
-            .on_action(window.listener_for(&self.state, TreeState::on_action_right))
-            .on_action(window.listener_for(&self.state, TreeState::on_action_up))
-            .on_action(window.listener_for(&self.state, TreeState::on_action_down))
-            .size_full()
-            .child(self.state)
-            .refine_style(&self.style)
-            .vertical_scrollbar(&scroll_handle)
+        let state = cx.new(|cx| TreeState::new(cx).items(items));
+        let window = cx.add_empty_window();
+        window.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                let position = point(px(10.), px(10.));
+
+                // Select the not selected item.
+                state.on_entry_secondary_click(1, position, window, cx);
+                assert_eq!(state.selected_indices(), vec![1]);
+
+                // Keep the multiple selection.
+                state.toggle_selected_index(2, cx);
+                state.on_entry_secondary_click(2, position, window, cx);
+                assert_eq!(state.selected_indices(), vec![1, 2]);
+
+                state.on_entry_secondary_click(0, position, window, cx);
+                assert_eq!(state.selected_indices(), vec![0]);
+            });
+        });
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
+    #[gpui::test]
+    fn test_sort(cx: &mut gpui::TestAppContext) {
+        use super::{SortBy, TreeItem};
 
-    use super::TreeState;
-    use gpui::AppContext as _;
+        let items = vec![
+            TreeItem::new("b.rs", "b.rs"),
+            TreeItem::new("src", "src")
+                .expanded(true)
+                .child(TreeItem::new("src/main.rs", "main.rs"))
+                .child(TreeItem::new("src/Lib.rs", "Lib.rs")),
+            TreeItem::new("a.rs", "a.rs"),
+        ];
 
-    fn assert_entries(entries: &Vec<super::TreeEntry>, expected: &str) {
-        let actual: Vec<String> = entries
-            .iter()
-            .map(|e| {
-                let mut s = String::new();
-                s.push_str(&"    ".repeat(e.depth));
-                s.push_str(e.item().label.as_str());
-                s
-            })
-            .collect();
-        let actual = actual.join("\n");
-        assert_eq!(actual.trim(), expected.trim());
+        let state = cx.new(|cx| TreeState::new(cx).items(items));
+        state.update(cx, |state, cx| {
+            state.set_selected_index(Some(2), cx);
+            state.set_sort_by(SortBy::NameAsc, cx);
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                a.rs
+                b.rs
+                src
+                    Lib.rs
+                    main.rs
+                "#
+                },
+            );
+            // The selection is kept by id.
+            assert_eq!(
+                state.selected_entry().map(|entry| entry.item().id.as_ref()),
+                Some("src/main.rs")
+            );
+
+            state.set_sort_by(SortBy::FoldersFirst, cx);
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                src
+                    Lib.rs
+                    main.rs
+                a.rs
+                b.rs
+                "#
+                },
+            );
+
+            // Sort the new items.
+            state.set_sort(|a, b| b.label.cmp(&a.label), cx);
+            state.set_items(vec![TreeItem::new("x", "x"), TreeItem::new("y", "y")], cx);
+            assert_entries(&state.entries, "y\nx");
+        });
     }
 
     #[gpui::test]
-    fn test_tree_entry(cx: &mut gpui::TestAppContext) {
+    fn test_snapshot_restore(cx: &mut gpui::TestAppContext) {
         use super::TreeItem;
 
         let items = vec![
@@ -470,65 +3373,323 @@ mod tests {
                 .expanded(true)
                 .child(
                     TreeItem::new("src/ui", "ui")
-                        .expanded(true)
                         .child(TreeItem::new("src/ui/button.rs", "button.rs"))
-                        .child(TreeItem::new("src/ui/icon.rs", "icon.rs"))
                         .child(TreeItem::new("src/ui/mod.rs", "mod.rs")),
                 )
                 .child(TreeItem::new("src/lib.rs", "lib.rs")),
-            TreeItem::new("Cargo.toml", "Cargo.toml"),
-            TreeItem::new("Cargo.lock", "Cargo.lock").disabled(true),
-            TreeItem::new("README.md", "README.md"),
+            TreeItem::new("Cargo.toml", "Cargo.toml").disabled(true),
         ];
 
         let state = cx.new(|cx| TreeState::new(cx).items(items));
-        state.update(cx, |state, _| {
+        let snapshot = state.update(cx, |state, cx| {
+            state.toggle_expand(1, cx);
+            state.set_selected_index(Some(3), cx);
+            state.set_filter_mode(FilterMode::Fuzzy, cx);
+            state.snapshot()
+        });
+
+        assert_eq!(snapshot.selected_id.as_deref(), Some("src/ui/mod.rs"));
+        assert_eq!(snapshot.filter_mode, FilterMode::Fuzzy);
+        assert_eq!(snapshot.items[0].expanded, true);
+        assert_eq!(snapshot.items[0].children[0].expanded, true);
+        assert_eq!(snapshot.items[1].disabled, true);
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let snapshot: TreeSnapshot = serde_json::from_str(&json).unwrap();
+
+        let restored = cx.new(|cx| TreeState::new(cx));
+        restored.update(cx, |restored, cx| {
+            restored.restore(snapshot.clone(), cx);
             assert_entries(
-                &state.entries,
+                &restored.entries,
                 indoc! {
                     r#"
                 src
                     ui
                         button.rs
-                        icon.rs
                         mod.rs
                     lib.rs
                 Cargo.toml
-                Cargo.lock
-                README.md
                 "#
                 },
             );
+            assert_eq!(restored.selected_index(), Some(3));
+            assert_eq!(restored.entries[5].is_disabled(), true);
+            assert_eq!(restored.snapshot(), snapshot);
+        });
+    }
 
-            let entry = state.entries.get(0).unwrap();
-            assert_eq!(entry.depth(), 0);
-            assert_eq!(entry.is_root(), true);
-            assert_eq!(entry.is_folder(), true);
-            assert_eq!(entry.is_expanded(), true);
+    #[gpui::test]
+    fn test_remove_item(cx: &mut gpui::TestAppContext) {
+        use super::TreeItem;
 
-            let entry = state.entries.get(1).unwrap();
-            assert_eq!(entry.depth(), 1);
-            assert_eq!(entry.is_root(), false);
-            assert_eq!(entry.is_folder(), true);
-            assert_eq!(entry.is_expanded(), true);
-            assert_eq!(entry.item().label.as_str(), "ui");
+        let items = vec![
+            TreeItem::new("src", "src")
+                .expanded(true)
+                .child(TreeItem::new("src/a.rs", "a.rs"))
+                .child(TreeItem::new("src/b.rs", "b.rs"))
+                .child(TreeItem::new("src/c.rs", "c.rs")),
+            TreeItem::new("Cargo.toml", "Cargo.toml"),
+        ];
 
-            state.toggle_expand(1);
-            let entry = state.entries.get(1).unwrap();
-            assert_eq!(entry.is_expanded(), false);
+        let state = cx.new(|cx| TreeState::new(cx).items(items));
+        state.update(cx, |state, cx| {
+            let selected_id = |state: &TreeState| {
+                state
+                    .selected_entry()
+                    .map(|entry| entry.item().id.to_string())
+            };
+
+            // Next sibling
+            state.set_selected_index(Some(2), cx);
+            let removed = state.remove_item("src/b.rs", cx).unwrap();
+            assert_eq!(removed.id.as_ref(), "src/b.rs");
+            assert_eq!(selected_id(state).as_deref(), Some("src/c.rs"));
+            assert_eq!(state.total_item_count(), 4);
+
+            // Previous sibling
+            state.remove_item("src/c.rs", cx);
+            assert_eq!(selected_id(state).as_deref(), Some("src/a.rs"));
+
+            // Parent
+            state.remove_item("src/a.rs", cx);
+            assert_eq!(selected_id(state).as_deref(), Some("src"));
+
+            // Removing an unselected item keeps the selection.
+            assert!(state.remove_item("Cargo.toml", cx).is_some());
+            assert_eq!(selected_id(state).as_deref(), Some("src"));
+            assert!(state.remove_item("not-exist", cx).is_none());
+
+            state.set_items(
+                vec![
+                    TreeItem::new("src", "src")
+                        .expanded(true)
+                        .child(TreeItem::new("src/a.rs", "a.rs"))
+                        .child(TreeItem::new("src/b.rs", "b.rs")),
+                ],
+                cx,
+            );
+            state.set_selection_fallback(SelectionFallback::Parent);
+            state.set_selected_index(Some(1), cx);
+            state.remove_item("src/a.rs", cx);
+            assert_eq!(selected_id(state).as_deref(), Some("src"));
+
+            state.set_selection_fallback(SelectionFallback::Clear);
+            state.set_selected_index(Some(1), cx);
+            state.remove_item("src/b.rs", cx);
+            assert_eq!(state.selected_index(), None);
+        });
+    }
+
+    #[gpui::test]
+    fn test_chevron_animation(cx: &mut gpui::TestAppContext) {
+        use super::{CHEVRON_ANIMATION_DURATION, TreeChevron, TreeItem};
+
+        let items = vec![TreeItem::new("src", "src").child(TreeItem::new("src/a.rs", "a.rs"))];
+        let state = cx.new(|cx| {
+            let mut state = TreeState::new(cx).items(items);
+            state.chevron = Some(TreeChevron {
+                animated: true,
+                ..Default::default()
+            });
+            state
+        });
+
+        // The animation is started once by the toggle, and cleared after the duration.
+        state.update(cx, |state, cx| {
+            state.toggle_expand(0, cx);
+            assert_eq!(
+                state.chevron_animation.as_ref().map(|(id, _)| id.as_ref()),
+                Some("src")
+            );
+        });
+        cx.executor().advance_clock(CHEVRON_ANIMATION_DURATION);
+        cx.run_until_parked();
+        state.update(cx, |state, _| assert!(state.chevron_animation.is_none()));
+    }
+
+    #[gpui::test]
+    fn test_multi_selection(cx: &mut gpui::TestAppContext) {
+        use super::TreeItem;
+
+        let items = vec![
+            TreeItem::new("src", "src")
+                .expanded(true)
+                .child(TreeItem::new("src/a.rs", "a.rs"))
+                .child(TreeItem::new("src/b.rs", "b.rs")),
+            TreeItem::new("tests", "tests").child(TreeItem::new("tests/a.rs", "a.rs")),
+            TreeItem::new("Cargo.toml", "Cargo.toml"),
+        ];
+
+        let state = cx.new(|cx| TreeState::new(cx).items(items));
+        state.update(cx, |state, cx| {
+            state.set_selected_index(Some(1), cx);
+            assert_eq!(state.selected_indices(), vec![1]);
+
+            // Ctrl/Cmd-click
+            state.toggle_selected_index(4, cx);
+            assert_eq!(state.selected_indices(), vec![1, 4]);
+            assert_eq!(state.selected_index(), Some(4));
+            state.toggle_selected_index(1, cx);
+            assert_eq!(state.selected_indices(), vec![4]);
+            assert_eq!(state.selected_index(), Some(4));
+            // Toggling off the selected index moves it to another selected row.
+            state.toggle_selected_index(2, cx);
+            state.toggle_selected_index(2, cx);
+            assert_eq!(state.selected_indices(), vec![4]);
+            assert_eq!(state.selected_index(), Some(4));
+            state.toggle_selected_index(4, cx);
+            assert_eq!(state.selected_index(), None);
+            state.toggle_selected_index(4, cx);
+
+            // Shift-click from the anchor (the last toggled item), the hidden children of
+            // the collapsed `tests` folder are not selected.
+            state.extend_selection_to(2, cx);
+            assert_eq!(state.selected_indices(), vec![2, 3, 4]);
+            assert_eq!(state.selected_index(), Some(2));
+            let ids: Vec<_> = state
+                .selected_entries()
+                .iter()
+                .map(|entry| entry.item().id.to_string())
+                .collect();
+            assert_eq!(ids, vec!["src/b.rs", "tests", "Cargo.toml"]);
+
+            // The single-select API clears the other selections.
+            state.set_selected_index(Some(0), cx);
+            assert_eq!(state.selected_indices(), vec![0]);
+
+            // The selected items in the collapsed folder are unselected.
+            state.extend_selection_to(2, cx);
+            assert_eq!(state.selected_indices(), vec![0, 1, 2]);
+            state.toggle_expand(0, cx);
+            assert_eq!(state.selected_indices(), vec![0]);
+        });
+    }
+
+    #[gpui::test]
+    fn test_checked(cx: &mut gpui::TestAppContext) {
+        use super::{CheckState, TreeItem};
+        use gpui::Task;
+
+        let items = vec![
+            TreeItem::new("src", "src")
+                .child(TreeItem::new("src/main.rs", "main.rs"))
+                .child(
+                    TreeItem::new("src/ui", "ui")
+                        .child(TreeItem::new("src/ui/button.rs", "button.rs"))
+                        .child(TreeItem::new("src/ui/icon.rs", "icon.rs")),
+                ),
+            TreeItem::new("docs", "docs").lazy(true),
+        ];
+
+        let state = cx.new(|cx| {
+            let mut state = TreeState::new(cx).items(items);
+            state.set_children_loader(|item, _| {
+                let id = format!("{}/README.md", item.id);
+                Task::ready(Ok(vec![TreeItem::new(id, "README.md")]))
+            });
+            state
+        });
+
+        let check_state = |state: &TreeState, id: &str| {
+            let item = super::find_item_mut(&mut state.items.clone(), &|it| it.id.as_ref() == id)
+                .cloned()
+                .unwrap();
+            state.check_state(&item)
+        };
+
+        state.update(cx, |state, cx| {
+            // Check a collapsed folder checks all the descendants.
+            state.set_checked("src", true, cx);
+            assert_eq!(state.checked_ids().len(), 5);
+            assert_eq!(check_state(state, "src"), CheckState::Checked);
+
+            // Uncheck a child makes the ancestors indeterminate.
+            state.set_checked("src/ui/icon.rs", false, cx);
+            assert!(!state.checked_ids().contains("src/ui"));
+            assert!(!state.checked_ids().contains("src"));
+            assert_eq!(check_state(state, "src/ui"), CheckState::Indeterminate);
+            assert_eq!(check_state(state, "src"), CheckState::Indeterminate);
+            assert_eq!(check_state(state, "src/main.rs"), CheckState::Checked);
+
+            // Check all the children checks the ancestors.
+            state.set_checked("src/ui/icon.rs", true, cx);
+            assert_eq!(check_state(state, "src"), CheckState::Checked);
+
+            state.set_checked("src", false, cx);
+            assert!(state.checked_ids().is_empty());
+            assert_eq!(check_state(state, "src"), CheckState::Unchecked);
+
+            // The lazy children of a checked folder are checked after loaded.
+            state.set_checked("docs", true, cx);
+            state.toggle_expand(1, cx);
+        });
+        cx.run_until_parked();
+        state.update(cx, |state, _| {
+            assert!(state.checked_ids().contains("docs/README.md"));
+            assert_eq!(check_state(state, "docs"), CheckState::Checked);
+        });
+    }
+
+    #[gpui::test]
+    fn test_lazy_children(cx: &mut gpui::TestAppContext) {
+        use super::TreeItem;
+        use gpui::Task;
+
+        let items = vec![
+            TreeItem::new("src", "src").lazy(true),
+            TreeItem::new("broken", "broken").lazy(true),
+        ];
+
+        let state = cx.new(|cx| {
+            let mut state = TreeState::new(cx).items(items);
+            state.set_children_loader(|item, _| {
+                if item.id.as_ref() == "broken" {
+                    return Task::ready(Err(anyhow::anyhow!("permission denied")));
+                }
+                let id = format!("{}/lib.rs", item.id);
+                Task::ready(Ok(vec![TreeItem::new(id, "lib.rs")]))
+            });
+            state
+        });
+
+        state.update(cx, |state, cx| {
+            assert!(state.entries[0].is_folder());
+            assert_eq!(state.total_item_count(), 2);
+
+            state.toggle_expand(0, cx);
+            assert!(state.entries[0].is_loading());
+            assert!(state.entries[0].is_expanded());
+        });
+        cx.run_until_parked();
+        state.update(cx, |state, cx| {
             assert_entries(
                 &state.entries,
                 indoc! {
                     r#"
                 src
-                    ui
                     lib.rs
-                Cargo.toml
-                Cargo.lock
-                README.md
+                broken
                 "#
                 },
             );
-        })
+            assert!(!state.entries[0].is_loading());
+            assert!(!state.entries[0].item().is_lazy());
+
+            state.toggle_expand(2, cx);
+        });
+        cx.run_until_parked();
+        state.update(cx, |state, _| {
+            let entry = &state.entries[2];
+            assert!(!entry.is_loading());
+            assert!(!entry.is_expanded());
+            // Keep lazy to retry.
+            assert!(entry.is_folder());
+            assert!(entry.item().is_lazy());
+            assert_eq!(
+                entry.item().load_error().as_deref(),
+                Some("permission denied")
+            );
+        });
     }
 }
@@ -1,7 +1,7 @@
 use crate::{
     Placement, Root, dialog::Dialog, input::InputState, notification::Notification, sheet::Sheet,
 };
-use gpui::{App, Entity, Window};
+use gpui::{App, Entity, Task, Window};
 use std::rc::Rc;
 
 /// Extension trait for [`Window`] to add dialog, sheet .. functionality.
@@ -27,6 +27,26 @@ pub trait WindowExt: Sized {
     where
         F: Fn(Dialog, &mut Window, &mut App) -> Dialog + 'static;
 
+    /// Opens a Dialog and returns a task of the user's choice, e.g. with [`Dialog::confirm`].
+    ///
+    /// The task resolves to `true` when confirmed by the ok button or the `enter` key, and
+    /// `false` when canceled by the cancel button, the `escape` key or clicking the overlay.
+    ///
+    /// ```ignore
+    /// let confirmed = window.open_confirm_dialog(cx, |dialog, _, _| {
+    ///     dialog.confirm().title("Delete").child("Are you sure to delete this file?")
+    /// });
+    /// cx.spawn(async move |cx| {
+    ///     if confirmed.await {
+    ///         // delete the file
+    ///     }
+    /// })
+    /// .detach();
+    /// ```
+    fn open_confirm_dialog<F>(&mut self, cx: &mut App, build: F) -> Task<bool>
+    where
+        F: Fn(Dialog, &mut Window, &mut App) -> Dialog + 'static;
+
     /// Return true, if there is an active Dialog.
     fn has_active_dialog(&mut self, cx: &mut App) -> bool;
 
@@ -95,6 +115,19 @@ impl WindowExt for Window {
         })
     }
 
+    fn open_confirm_dialog<F>(&mut self, cx: &mut App, build: F) -> Task<bool>
+    where
+        F: Fn(Dialog, &mut Window, &mut App) -> Dialog + 'static,
+    {
+        let (tx, rx) = smol::channel::bounded(1);
+        self.open_dialog(cx, move |dialog, window, cx| {
+            build(dialog, window, cx).result_sender(tx.clone())
+        });
+
+        // The sender is dropped when the dialog is closed without a choice.
+        cx.background_spawn(async move { rx.recv().await.unwrap_or(false) })
+    }
+
     #[inline]
     fn has_active_dialog(&mut self, cx: &mut App) -> bool {
         Root::read(self, cx).active_dialogs.len() > 0